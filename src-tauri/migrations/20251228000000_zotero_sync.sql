@@ -0,0 +1,29 @@
+-- migrations/20251228000000_zotero_sync.sql
+
+-- A paper that is kept in sync with an item in the user's Zotero library
+CREATE TABLE IF NOT EXISTS zotero_links (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    item_key TEXT NOT NULL UNIQUE,
+    item_type TEXT NOT NULL,
+    -- Zotero's version of the item as of the last sync
+    version INTEGER NOT NULL,
+    -- JSON snapshot of the synced fields as of the last sync; a paper that no longer
+    -- matches it has been edited locally
+    synced_fields TEXT NOT NULL,
+    -- The paper's notes are synced with one child note
+    note_key TEXT UNIQUE,
+    note_version INTEGER,
+    synced_note TEXT,
+    -- Remote versions that clashed with local edits, kept until the user picks a side
+    conflict_version INTEGER,
+    note_conflict_version INTEGER,
+    synced_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Single row; tied to the account it was synced with
+CREATE TABLE IF NOT EXISTS zotero_sync_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    user_id INTEGER NOT NULL,
+    library_version INTEGER NOT NULL DEFAULT 0,
+    last_synced_at TIMESTAMP
+);
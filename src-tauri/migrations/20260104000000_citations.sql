@@ -0,0 +1,28 @@
+-- migrations/20260104000000_citations.sql
+
+-- Citation data looked up from Semantic Scholar, one row per paper
+CREATE TABLE IF NOT EXISTS citation_metrics (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    s2_paper_id TEXT NOT NULL,
+    citation_count INTEGER NOT NULL,
+    influential_citation_count INTEGER NOT NULL,
+    reference_count INTEGER NOT NULL,
+    fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Works a paper cites ('reference') and works citing it ('citation'), in the order
+-- Semantic Scholar lists them
+CREATE TABLE IF NOT EXISTS citation_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('reference', 'citation')),
+    position INTEGER NOT NULL,
+    s2_paper_id TEXT,
+    title TEXT NOT NULL,
+    authors TEXT,
+    year INTEGER,
+    doi TEXT,
+    arxiv_id TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_citation_entries_paper ON citation_entries(paper_id, kind, position);
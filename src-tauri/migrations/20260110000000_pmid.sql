@@ -0,0 +1,6 @@
+-- migrations/20260110000000_pmid.sql
+
+-- PubMed identifier for biomedical papers
+ALTER TABLE papers ADD COLUMN pmid TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_papers_pmid ON papers(pmid);
@@ -0,0 +1,13 @@
+-- migrations/20260111000000_paper_links.sql
+
+-- Citations between papers that are both in the library. `source` records where the
+-- link came from so each source can be rebuilt without touching the others.
+CREATE TABLE IF NOT EXISTS paper_links (
+    citing_paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    cited_paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (citing_paper_id, cited_paper_id, source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_links_cited ON paper_links(cited_paper_id);
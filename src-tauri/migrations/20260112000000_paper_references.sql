@@ -0,0 +1,18 @@
+-- migrations/20260112000000_paper_references.sql
+
+-- Bibliography entries parsed out of a paper's PDF, in the order they appear.
+-- matched_paper_id is set once an entry has been matched to a paper in the library.
+CREATE TABLE IF NOT EXISTS paper_references (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    raw TEXT NOT NULL,
+    title TEXT,
+    authors TEXT,
+    year INTEGER,
+    doi TEXT,
+    arxiv_id TEXT,
+    matched_paper_id INTEGER REFERENCES papers(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_references_paper ON paper_references(paper_id, position);
@@ -0,0 +1,7 @@
+-- migrations/20260113000000_cite_key.sql
+
+-- BibTeX-style citation key (smith2021attention). Assigned once on import and kept even
+-- if the metadata changes later, so existing LaTeX documents keep resolving.
+ALTER TABLE papers ADD COLUMN cite_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_papers_cite_key ON papers(cite_key);
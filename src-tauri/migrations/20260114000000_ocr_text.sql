@@ -0,0 +1,11 @@
+-- migrations/20260114000000_ocr_text.sql
+
+-- Text recognised from image-only PDFs. It's also indexed in papers_fts in place of the
+-- (empty) extracted text, so scanned papers turn up in full-text search.
+CREATE TABLE IF NOT EXISTS paper_ocr (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    language TEXT NOT NULL,
+    page_count INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
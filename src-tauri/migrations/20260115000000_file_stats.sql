@@ -0,0 +1,7 @@
+-- migrations/20260115000000_file_stats.sql
+
+-- Read from the PDF on import. page_count and pdf_version stay NULL when the file doesn't
+-- parse, which usually means a truncated download.
+ALTER TABLE papers ADD COLUMN page_count INTEGER;
+ALTER TABLE papers ADD COLUMN file_size INTEGER;
+ALTER TABLE papers ADD COLUMN pdf_version TEXT;
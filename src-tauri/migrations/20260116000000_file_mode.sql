@@ -0,0 +1,8 @@
+-- migrations/20260116000000_file_mode.sql
+
+-- 'stored': the PDF was copied into the app's papers folder and the app owns it.
+-- 'linked': pdf_path points at the user's original file, which is never moved or deleted.
+ALTER TABLE papers ADD COLUMN file_mode TEXT NOT NULL DEFAULT 'stored';
+
+-- How PDFs picked up from a watched folder are imported
+ALTER TABLE watched_folders ADD COLUMN file_mode TEXT NOT NULL DEFAULT 'stored';
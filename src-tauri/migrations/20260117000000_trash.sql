@@ -0,0 +1,7 @@
+-- migrations/20260117000000_trash.sql
+
+-- Set when a paper is moved to the Trash. Trashed papers keep their files and metadata
+-- until the Trash is emptied.
+ALTER TABLE papers ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_papers_deleted_at ON papers(deleted_at);
@@ -0,0 +1,10 @@
+-- migrations/20260118000000_operation_journal.sql
+
+-- Undoable operations, newest last. undo_data is JSON holding what the operation changed,
+-- as it was before; only the most recent entries are kept.
+CREATE TABLE IF NOT EXISTS operation_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    description TEXT NOT NULL,
+    undo_data TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,10 @@
+-- migrations/20260119000000_smart_collections.sql
+
+-- Collections whose papers are whatever currently matches a saved query rather than a
+-- fixed list. `query` is JSON: tags, year range, text query, reading status and so on.
+CREATE TABLE IF NOT EXISTS smart_collections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
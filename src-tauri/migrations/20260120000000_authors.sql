@@ -0,0 +1,25 @@
+-- migrations/20260120000000_authors.sql
+
+-- One row per person, parsed from papers.authors, which stays the displayed source of truth.
+-- paper_authors keeps the byline order.
+CREATE TABLE IF NOT EXISTS authors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS paper_authors (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (paper_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_authors_author ON paper_authors(author_id);
+
+-- Spellings merged into another author, e.g. "J. Smith" -> John Smith, so papers that use
+-- them keep landing on the merged author
+CREATE TABLE IF NOT EXISTS author_aliases (
+    name TEXT PRIMARY KEY COLLATE NOCASE,
+    author_id INTEGER NOT NULL REFERENCES authors(id) ON DELETE CASCADE
+);
@@ -0,0 +1,23 @@
+-- migrations/20260121000000_venues.sql
+
+-- Canonical journals and conferences. papers.journal keeps the string as printed; venue_id
+-- points at the venue it was matched to. `key` is the name lowercased with punctuation
+-- dropped, so "Proc. IEEE" and "Proc IEEE" are the same venue.
+CREATE TABLE IF NOT EXISTS venues (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    key TEXT NOT NULL UNIQUE,
+    -- e.g. "PNAS" for Proceedings of the National Academy of Sciences
+    abbreviation TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Other spellings that lead to a venue: abbreviations and the names of merged venues
+CREATE TABLE IF NOT EXISTS venue_aliases (
+    key TEXT PRIMARY KEY,
+    venue_id INTEGER NOT NULL REFERENCES venues(id) ON DELETE CASCADE
+);
+
+ALTER TABLE papers ADD COLUMN venue_id INTEGER REFERENCES venues(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_papers_venue ON papers(venue_id);
@@ -0,0 +1,7 @@
+-- migrations/20260122000000_open_tracking.sql
+
+-- Updated whenever the viewer loads the paper's PDF, for "Continue reading"
+ALTER TABLE papers ADD COLUMN last_opened_at DATETIME;
+ALTER TABLE papers ADD COLUMN open_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_papers_last_opened_at ON papers(last_opened_at);
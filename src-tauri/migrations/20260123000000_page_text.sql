@@ -0,0 +1,10 @@
+-- migrations/20260123000000_page_text.sql
+
+-- Extracted (or OCR) text one row per page, so a search hit can say which page it is on.
+-- papers_fts keeps the whole text and still decides which papers match and how they rank.
+CREATE VIRTUAL TABLE IF NOT EXISTS pages_fts USING fts5(
+    content,
+    paper_id UNINDEXED,
+    page UNINDEXED,
+    tokenize = 'porter unicode61'
+);
@@ -0,0 +1,10 @@
+-- migrations/20260124000000_saved_searches.sql
+
+-- Named queries in the advanced search syntax (see query.rs), kept in the sidebar. Unlike a
+-- smart collection the query is the text the user typed.
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    query TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
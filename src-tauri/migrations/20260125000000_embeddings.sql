@@ -0,0 +1,14 @@
+-- migrations/20260125000000_embeddings.sql
+
+-- Sentence embeddings for semantic search, one per chunk of a paper's text; chunk 0 is the
+-- title and abstract. Only builds with the semantic-search feature fill this in. `vector`
+-- holds little-endian f32s, and `model` names the model that made it, since vectors from
+-- different models can't be compared.
+CREATE TABLE IF NOT EXISTS paper_embeddings (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    chunk INTEGER NOT NULL,
+    model TEXT NOT NULL,
+    text TEXT NOT NULL,
+    vector BLOB NOT NULL,
+    PRIMARY KEY (paper_id, chunk)
+);
@@ -0,0 +1,11 @@
+-- migrations/20260126000000_summaries.sql
+
+-- Summaries written by a language model on request, one per paper. `key_points` is a JSON
+-- array of strings.
+CREATE TABLE IF NOT EXISTS summaries (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    summary TEXT NOT NULL,
+    key_points TEXT NOT NULL DEFAULT '[]',
+    model TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
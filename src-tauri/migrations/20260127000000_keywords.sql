@@ -0,0 +1,9 @@
+-- migrations/20260127000000_keywords.sql
+
+-- Key phrases extracted from each paper's text at import, offered as tag suggestions
+CREATE TABLE IF NOT EXISTS paper_keywords (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    keyword TEXT NOT NULL,
+    score REAL NOT NULL,
+    PRIMARY KEY (paper_id, keyword)
+);
@@ -0,0 +1,22 @@
+-- migrations/20260128000000_paper_keywords.sql
+
+-- Keywords the authors or the publisher gave the paper, comma separated, from Crossref or the
+-- "Keywords:" line of the first page. The ones extracted from the text are in paper_keywords.
+ALTER TABLE papers ADD COLUMN keywords TEXT;
+
+-- FTS5 tables can't gain columns, so the index is rebuilt with the abstract and keywords.
+-- New columns go last so snippet() and bm25() keep addressing title and content by position.
+CREATE VIRTUAL TABLE papers_fts_new USING fts5(
+    title,
+    content,
+    abstract,
+    keywords,
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO papers_fts_new (rowid, title, content, abstract, keywords)
+SELECT f.rowid, f.title, f.content, p.abstract, p.keywords
+FROM papers_fts f LEFT JOIN papers p ON p.id = f.rowid;
+
+DROP TABLE papers_fts;
+ALTER TABLE papers_fts_new RENAME TO papers_fts;
@@ -0,0 +1,21 @@
+-- migrations/20260129000000_arxiv_versions.sql
+
+-- Earlier PDFs of a paper, kept when its file is replaced by a newer version
+CREATE TABLE IF NOT EXISTS paper_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    pdf_path TEXT NOT NULL,
+    file_hash TEXT,
+    -- What the file was, e.g. "arXiv 1706.03762v5"
+    label TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_paper_revisions_paper ON paper_revisions(paper_id, created_at);
+
+-- The newest arXiv version seen for each paper with an arXiv id, and when arXiv was last asked
+CREATE TABLE IF NOT EXISTS arxiv_versions (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    latest_id TEXT,
+    checked_at TIMESTAMP NOT NULL
+);
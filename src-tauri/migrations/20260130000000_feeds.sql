@@ -0,0 +1,34 @@
+-- migrations/20260130000000_feeds.sql
+
+-- RSS/Atom feeds of journals and arXiv categories, polled in the background
+CREATE TABLE IF NOT EXISTS feeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    last_polled_at TIMESTAMP,
+    -- Why the last poll failed; cleared by the next one that works
+    last_error TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The inbox: entries seen in feeds, until imported or dismissed. `guid` identifies the
+-- entry within its feed, so an entry that is still listed on the next poll isn't added twice.
+CREATE TABLE IF NOT EXISTS feed_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    feed_id INTEGER NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+    guid TEXT NOT NULL,
+    title TEXT NOT NULL,
+    -- Comma separated
+    authors TEXT,
+    link TEXT,
+    summary TEXT,
+    published TEXT,
+    doi TEXT,
+    arxiv_id TEXT,
+    paper_id INTEGER REFERENCES papers(id) ON DELETE SET NULL,
+    dismissed BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (feed_id, guid)
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_entries_feed ON feed_entries(feed_id, created_at);
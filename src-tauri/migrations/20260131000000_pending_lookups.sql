@@ -0,0 +1,16 @@
+-- migrations/20260131000000_pending_lookups.sql
+
+-- Metadata lookups that couldn't reach Crossref or arXiv, retried in the background with a
+-- growing delay until they go through
+CREATE TABLE IF NOT EXISTS pending_lookups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    -- 'doi' or 'arxiv'
+    kind TEXT NOT NULL,
+    identifier TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (paper_id, kind)
+);
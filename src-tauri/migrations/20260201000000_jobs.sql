@@ -0,0 +1,23 @@
+-- migrations/20260201000000_jobs.sql
+
+-- Long-running background work such as OCR over the library or rebuilding the search index.
+-- Rows outlive the app, so what ran and how it ended is still listed after a restart.
+CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- e.g. 'ocr' or 'search_index'; one job of a kind runs at a time
+    kind TEXT NOT NULL,
+    label TEXT NOT NULL,
+    -- 'queued', 'running', 'done', 'failed', 'cancelled', or 'interrupted' when the app
+    -- quit while it was queued or running
+    status TEXT NOT NULL DEFAULT 'queued',
+    current INTEGER NOT NULL DEFAULT 0,
+    total INTEGER,
+    -- What the job is doing right now, then how it ended
+    message TEXT,
+    error TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP,
+    finished_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_created ON jobs(created_at);
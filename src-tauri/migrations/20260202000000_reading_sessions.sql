@@ -0,0 +1,14 @@
+-- migrations/20260202000000_reading_sessions.sql
+
+-- Time spent with a paper open in the viewer. A session without ended_at is still going;
+-- ones left open when the app quit are discarded at the next start, since when they really
+-- ended is unknown.
+CREATE TABLE IF NOT EXISTS reading_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ended_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_sessions_paper ON reading_sessions(paper_id);
+CREATE INDEX IF NOT EXISTS idx_reading_sessions_started ON reading_sessions(started_at);
@@ -0,0 +1,15 @@
+-- migrations/20260203000000_outlines.sql
+
+-- A PDF's table of contents, read from its outline (bookmarks) at import. Entries are
+-- stored flat in document order; `depth` says how deep each one is nested, 0 being top level.
+-- `page` is 1-based and NULL when the entry points nowhere usable; `y` is the offset from
+-- the top of the page in points when the destination gives one.
+CREATE TABLE IF NOT EXISTS outline_entries (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    depth INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    page INTEGER,
+    y REAL,
+    PRIMARY KEY (paper_id, position)
+);
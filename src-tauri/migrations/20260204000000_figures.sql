@@ -0,0 +1,20 @@
+-- migrations/20260204000000_figures.sql
+
+-- Images pulled out of a paper's PDF for the figure browser. The files live under
+-- <app data>/figures/<paper id>/; `file_name` is relative to that folder. A paper with a
+-- row in figure_scans has been scanned, whether or not anything was found.
+CREATE TABLE IF NOT EXISTS figures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    page INTEGER NOT NULL,
+    file_name TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_figures_paper ON figures(paper_id, page);
+
+CREATE TABLE IF NOT EXISTS figure_scans (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    scanned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+-- migrations/20260205000000_paper_pages.sql
+
+-- Each page's text as PDFium lays it out, with the position of every word, so a search
+-- inside a paper can highlight its hits without parsing the PDF again. `words` is a JSON
+-- array of [start, end, x, y, width, height]: char offsets into `text` and a box in points
+-- from the page's top-left corner. Unlike pages_fts, which is built for ranking, the text
+-- here is exactly what is on the page.
+CREATE TABLE IF NOT EXISTS paper_pages (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    page INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    words TEXT NOT NULL DEFAULT '[]',
+    PRIMARY KEY (paper_id, page)
+);
@@ -0,0 +1,15 @@
+-- migrations/20260206000000_view_states.sql
+
+-- How the viewer last showed each paper, restored when it is opened again. Scroll offsets
+-- are fractions of the document's scrollable width and height, so they survive a change
+-- of window size.
+CREATE TABLE IF NOT EXISTS view_states (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    page INTEGER NOT NULL DEFAULT 1,
+    zoom REAL NOT NULL DEFAULT 1.0,
+    fit TEXT,
+    scroll_top REAL NOT NULL DEFAULT 0,
+    scroll_left REAL NOT NULL DEFAULT 0,
+    view_mode TEXT NOT NULL DEFAULT 'continuous',
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,22 @@
+-- migrations/20260207000000_custom_fields.sql
+
+-- Fields a library defines for itself ("grant", "experiment", ...) and their values per
+-- paper. `options` is a JSON array of the allowed values of an enum field. Values are text:
+-- numbers as written by Rust's f64 formatting, dates as YYYY-MM-DD, so both sort and compare
+-- as text where it matters.
+CREATE TABLE IF NOT EXISTS custom_fields (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'enum')),
+    options TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS custom_values (
+    paper_id INTEGER NOT NULL REFERENCES papers(id) ON DELETE CASCADE,
+    field_id INTEGER NOT NULL REFERENCES custom_fields(id) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    PRIMARY KEY (paper_id, field_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_values_field ON custom_values(field_id, value);
@@ -0,0 +1,14 @@
+-- migrations/20260208000000_item_types.sql
+
+-- What kind of work a paper is, and the fields that only some kinds have: an ISBN and
+-- publisher for books, the awarding or issuing institution for theses and reports.
+ALTER TABLE papers ADD COLUMN item_type TEXT NOT NULL DEFAULT 'article'
+    CHECK (item_type IN ('article', 'book', 'thesis', 'preprint', 'report'));
+ALTER TABLE papers ADD COLUMN isbn TEXT;
+ALTER TABLE papers ADD COLUMN publisher TEXT;
+ALTER TABLE papers ADD COLUMN institution TEXT;
+
+-- arXiv papers that never got a venue are preprints
+UPDATE papers SET item_type = 'preprint' WHERE arxiv_id IS NOT NULL AND journal IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_papers_item_type ON papers(item_type);
@@ -0,0 +1,14 @@
+-- migrations/20260209000000_metadata_matches.sql
+
+-- What resolve_missing_metadata found for papers whose title is only their file name. A
+-- paper with a row here is not looked up again. `metadata` is the best Crossref candidate
+-- as JSON; pending rows are the review queue of matches too uncertain to apply on their own.
+CREATE TABLE IF NOT EXISTS metadata_matches (
+    paper_id INTEGER PRIMARY KEY REFERENCES papers(id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('pending', 'applied', 'rejected', 'unmatched')),
+    confidence REAL,
+    metadata TEXT,
+    checked_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_metadata_matches_status ON metadata_matches(status);
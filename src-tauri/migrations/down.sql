@@ -1,5 +0,0 @@
--- migrations/20251221_0001_initial/down.sql
-
-DROP TABLE IF EXISTS cards;
-DROP TABLE IF EXISTS excerpts;
-DROP TABLE IF EXISTS papers;
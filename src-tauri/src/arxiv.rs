@@ -0,0 +1,194 @@
+// src-tauri/src/arxiv.rs
+
+use crate::crossref::WorkMetadata;
+use serde::Deserialize;
+
+// Answers the API; probed to tell whether arXiv can be reached at all
+pub const API_HOST: &str = "https://export.arxiv.org";
+const API_BASE: &str = "https://export.arxiv.org/api/query";
+const PDF_BASE: &str = "https://arxiv.org/pdf";
+
+pub struct ArxivEntry {
+    // Versioned id, e.g. 1706.03762v7
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub r#abstract: String,
+    pub categories: Vec<String>,
+    pub year: Option<i64>,
+    pub doi: Option<String>,
+    pub journal_ref: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Feed {
+    #[serde(default)]
+    entry: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    id: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    published: Option<String>,
+    #[serde(default)]
+    author: Vec<Author>,
+    #[serde(default)]
+    category: Vec<Category>,
+    doi: Option<String>,
+    journal_ref: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Category {
+    #[serde(rename = "@term")]
+    term: String,
+}
+
+// Accepts 2106.01234, 2106.01234v2, arXiv:2106.01234, hep-th/9901001 and abs/pdf URLs
+pub fn parse_id(input: &str) -> Option<String> {
+    let mut id = input.trim();
+    for prefix in ["https://", "http://", "www.", "export.", "arxiv.org/", "abs/", "pdf/"] {
+        id = id.strip_prefix(prefix).unwrap_or(id);
+    }
+    if id.get(..6).is_some_and(|prefix| prefix.eq_ignore_ascii_case("arxiv:")) {
+        id = &id[6..];
+    }
+    let id = id.trim_end_matches('/').trim_end_matches(".pdf");
+
+    let (base, version) = match id.rfind('v') {
+        Some(pos) if pos + 1 < id.len() && id[pos + 1..].bytes().all(|b| b.is_ascii_digit()) => {
+            (&id[..pos], &id[pos..])
+        }
+        _ => (id, ""),
+    };
+
+    let is_new_style = base
+        .split_once('.')
+        .is_some_and(|(yymm, num)| {
+            yymm.len() == 4
+                && (4..=5).contains(&num.len())
+                && yymm.bytes().chain(num.bytes()).all(|b| b.is_ascii_digit())
+        });
+    let is_old_style = base.split_once('/').is_some_and(|(archive, num)| {
+        !archive.is_empty()
+            && archive.bytes().all(|b| b.is_ascii_alphabetic() || b == b'-' || b == b'.')
+            && num.len() == 7
+            && num.bytes().all(|b| b.is_ascii_digit())
+    });
+
+    (is_new_style || is_old_style).then(|| format!("{}{}", base, version))
+}
+
+// Splits 1706.03762v7 into 1706.03762 and 7; an id without a version has None
+pub fn split_version(id: &str) -> (&str, Option<u32>) {
+    match id.rsplit_once('v') {
+        Some((base, version)) if !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()) => {
+            (base, version.parse().ok())
+        }
+        _ => (id, None),
+    }
+}
+
+impl From<ArxivEntry> for WorkMetadata {
+    fn from(entry: ArxivEntry) -> Self {
+        WorkMetadata {
+            title: entry.title,
+            authors: (!entry.authors.is_empty()).then(|| entry.authors.join(", ")),
+            journal: entry.journal_ref,
+            year: entry.year,
+            doi: entry.doi,
+            r#abstract: Some(entry.r#abstract).filter(|a| !a.is_empty()),
+            ..Default::default()
+        }
+    }
+}
+
+pub async fn fetch_entry(client: &reqwest::Client, id: &str) -> Result<ArxivEntry, String> {
+    let entry = fetch_feed(client, id, 1)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("arXiv paper not found: {}", id))?;
+
+    Ok(ArxivEntry {
+        id: entry_id(&entry).to_string(),
+        title: collapse_whitespace(&entry.title),
+        authors: entry.author.into_iter().map(|a| collapse_whitespace(&a.name)).collect(),
+        r#abstract: collapse_whitespace(&entry.summary),
+        categories: entry.category.into_iter().map(|c| c.term).collect(),
+        year: entry
+            .published
+            .and_then(|p| p.get(..4).and_then(|y| y.parse().ok())),
+        doi: entry.doi,
+        journal_ref: entry.journal_ref.map(|j| collapse_whitespace(&j)),
+    })
+}
+
+// The current versioned id of each of `ids`, which are looked up without their version.
+// Ids arXiv doesn't know are left out.
+pub async fn fetch_latest_versions(client: &reqwest::Client, ids: &[&str]) -> Result<Vec<String>, String> {
+    let id_list: Vec<&str> = ids.iter().map(|id| split_version(id).0).collect();
+    let entries = fetch_feed(client, &id_list.join(","), id_list.len()).await?;
+    Ok(entries.iter().map(|entry| entry_id(entry).to_string()).collect())
+}
+
+async fn fetch_feed(client: &reqwest::Client, id_list: &str, max_results: usize) -> Result<Vec<Entry>, String> {
+    let max_results = max_results.to_string();
+    let body = client
+        .get(API_BASE)
+        .query(&[("id_list", id_list), ("max_results", max_results.as_str())])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("arXiv request failed: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("arXiv request failed: {}", e))?;
+
+    let feed: Feed = quick_xml::de::from_str(&body)
+        .map_err(|e| format!("Unexpected arXiv response: {}", e))?;
+
+    // Unknown ids come back as an entry pointing at the API error page
+    Ok(feed.entry.into_iter().filter(|e| !e.id.contains("/api/errors")).collect())
+}
+
+// Entry ids are abs URLs, e.g. http://arxiv.org/abs/1706.03762v7
+fn entry_id(entry: &Entry) -> &str {
+    entry.id.rsplit_once("/abs/").map_or(entry.id.as_str(), |(_, id)| id)
+}
+
+pub fn pdf_url(id: &str) -> String {
+    format!("{}/{}", PDF_BASE, id)
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ids() {
+        assert_eq!(parse_id("arXiv:2106.01234v2"), Some("2106.01234v2".to_string()));
+        assert_eq!(parse_id("https://arxiv.org/pdf/2106.01234.pdf"), Some("2106.01234".to_string()));
+        assert_eq!(parse_id("hep-th/9901001"), Some("hep-th/9901001".to_string()));
+        assert_eq!(parse_id("arxiv:"), None);
+    }
+
+    #[test]
+    fn ignores_non_ascii_text() {
+        for input in ["café👍x", "👍👍👍", "arxiv👍2106.01234", "naïve:2106.01234", "Ü"] {
+            assert_eq!(parse_id(input), None, "{}", input);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+// src-tauri/src/arxiv_updates.rs
+
+// Checks papers imported from arXiv for newer versions in the background. Each paper is asked
+// about at most once a day, many to a request, and versions that weren't seen before are
+// announced with "arxiv:update_available". Updating keeps the old PDF as a revision.
+
+use crate::arxiv;
+use crate::db::{self, revisions::ArxivUpdate, Paper};
+use crate::downloads::{DownloadRequest, Downloads};
+use crate::import;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const RECHECK_HOURS: i64 = 24;
+const BATCH_SIZE: i64 = 50;
+// arXiv asks API clients to wait three seconds between requests
+const REQUEST_DELAY: Duration = Duration::from_secs(3);
+
+pub fn start(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    tauri::async_runtime::spawn(check_loop(handle, pool, http));
+}
+
+async fn check_loop(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    // Leave startup alone
+    let start = tokio::time::Instant::now() + Duration::from_secs(60);
+    let mut tick = tokio::time::interval_at(start, CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+        if let Err(e) = check_due(&handle, &pool, &http).await {
+            tracing::warn!("arXiv version check failed: {}", e);
+        }
+    }
+}
+
+// Checks every paper that is due and returns the updates that turned up
+pub async fn check_due(handle: &AppHandle, pool: &SqlitePool, http: &reqwest::Client) -> Result<Vec<ArxivUpdate>, String> {
+    let known = db::revisions::get_arxiv_updates(pool).await?;
+
+    loop {
+        let batch = db::revisions::get_arxiv_ids_to_check(pool, RECHECK_HOURS, BATCH_SIZE).await?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let ids: Vec<&str> = batch.iter().map(|(_, id)| id.as_str()).collect();
+        let latest = arxiv::fetch_latest_versions(http, &ids).await?;
+        for (paper_id, id) in &batch {
+            let base = arxiv::split_version(id).0;
+            let found = latest.iter().find(|l| arxiv::split_version(l).0 == base);
+            db::revisions::save_arxiv_check(pool, *paper_id, found.map(String::as_str)).await?;
+        }
+        tokio::time::sleep(REQUEST_DELAY).await;
+    }
+
+    let updates: Vec<ArxivUpdate> = db::revisions::get_arxiv_updates(pool)
+        .await?
+        .into_iter()
+        .filter(|u| !known.iter().any(|k| k.paper_id == u.paper_id && k.latest_id == u.latest_id))
+        .collect();
+    for update in &updates {
+        let _ = handle.emit("arxiv:update_available", update);
+    }
+    Ok(updates)
+}
+
+// Replaces the paper's PDF with the newest arXiv version. The old file stays on disk and is
+// listed among the paper's revisions; the new one always goes into app storage.
+pub async fn update_version(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    http: &reqwest::Client,
+    downloads: &Downloads,
+    paper_id: i64,
+) -> Result<Paper, String> {
+    let paper = db::get_paper(pool, paper_id).await?;
+    let current = paper.arxiv_id.clone().filter(|id| !id.is_empty()).ok_or("The paper has no arXiv id")?;
+
+    // Asked again rather than trusting the last check, which may be a day old
+    let latest = arxiv::fetch_latest_versions(http, &[current.as_str()])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("arXiv paper not found: {}", current))?;
+    db::revisions::save_arxiv_check(pool, paper_id, Some(&latest)).await?;
+    if latest == current {
+        return Err(format!("{} is already the newest version", current));
+    }
+
+    let papers_dir = import::papers_dir(handle)?;
+    let dest = import::unique_destination(&papers_dir, &format!("{}.pdf", latest.replace('/', "_")));
+    downloads
+        .fetch(DownloadRequest {
+            url: &arxiv::pdf_url(&latest),
+            dest: &dest,
+            label: format!("arXiv {}", latest),
+            https_only: false,
+            cancel: None,
+        })
+        .await?;
+
+    if let Err(e) = replace_file(pool, &paper, &current, &latest, &dest).await {
+        let _ = fs::remove_file(&dest);
+        return Err(e);
+    }
+    Ok(db::get_paper(pool, paper_id).await?)
+}
+
+async fn replace_file(pool: &SqlitePool, paper: &Paper, current: &str, latest: &str, dest: &Path) -> Result<(), String> {
+    if !paper.pdf_path.is_empty() {
+        let label = format!("arXiv {}", current);
+        db::revisions::add_revision(pool, paper.id, &paper.pdf_path, paper.file_hash.as_deref(), &label).await?;
+    }
+    db::set_arxiv_id(pool, paper.id, latest).await?;
+    import::attach_pdf(pool, paper.id, dest).await
+}
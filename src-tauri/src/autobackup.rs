@@ -0,0 +1,130 @@
+// src-tauri/src/autobackup.rs
+
+use crate::backup::{self, BackupInfo};
+use crate::config::{self, BackupSchedule};
+use crate::import;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// Automatic backups are named paper-master-20261015-143000.zip (UTC), so the newest
+// one, and with it the last backup time, can be read straight from the folder
+const FILE_PREFIX: &str = "paper-master-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize)]
+pub struct BackupStatus {
+    pub schedule: BackupSchedule,
+    pub last_backup_at: Option<String>,
+    pub last_backup_path: Option<String>,
+    pub next_backup_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+pub struct AutoBackup {
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl AutoBackup {
+    // The schedule is re-read from the config on every check, so changes apply without a restart
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Self {
+        let last_error: Arc<Mutex<Option<String>>> = Arc::default();
+        tauri::async_runtime::spawn(schedule_loop(handle, pool, last_error.clone()));
+        AutoBackup { last_error }
+    }
+
+    pub fn status(&self, handle: &AppHandle) -> BackupStatus {
+        let schedule = config::load(handle).backup;
+        let last = schedule.folder.as_deref().and_then(|folder| list_backups(folder).into_iter().next());
+        let next = schedule.enabled.then(|| match &last {
+            Some((created, _)) => *created + ChronoDuration::hours(schedule.interval_hours as i64),
+            None => Utc::now(),
+        });
+
+        BackupStatus {
+            last_backup_at: last.as_ref().map(|(created, _)| created.to_rfc3339()),
+            last_backup_path: last.map(|(_, path)| path.display().to_string()),
+            next_backup_at: next.map(|next| next.to_rfc3339()),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+            schedule,
+        }
+    }
+}
+
+async fn schedule_loop(handle: AppHandle, pool: SqlitePool, last_error: Arc<Mutex<Option<String>>>) {
+    // Leave startup alone; an overdue backup can wait a minute
+    let start = tokio::time::Instant::now() + CHECK_INTERVAL;
+    let mut tick = tokio::time::interval_at(start, CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+
+        let schedule = config::load(&handle).backup;
+        let Some(folder) = schedule.folder.filter(|_| schedule.enabled) else {
+            continue;
+        };
+
+        let due = match list_backups(&folder).first() {
+            Some((created, _)) => Utc::now() - *created >= ChronoDuration::hours(schedule.interval_hours as i64),
+            None => true,
+        };
+        if !due {
+            continue;
+        }
+
+        let result = run_backup(&handle, &pool, &folder, schedule.keep).await;
+        if let Ok(mut last_error) = last_error.lock() {
+            *last_error = result.as_ref().err().cloned();
+        }
+        match result {
+            Ok(info) => {
+                tracing::info!("Automatic backup written to {}", info.path);
+                let _ = handle.emit("backup:created", &info);
+            }
+            Err(e) => tracing::warn!("Automatic backup failed: {}", e),
+        }
+    }
+}
+
+async fn run_backup(handle: &AppHandle, pool: &SqlitePool, folder: &Path, keep: usize) -> Result<BackupInfo, String> {
+    fs::create_dir_all(folder).map_err(|e| format!("Failed to create backup folder: {}", e))?;
+    let papers_dir = import::papers_dir(handle)?;
+
+    let file_name = format!("{}{}.zip", FILE_PREFIX, Utc::now().format(TIMESTAMP_FORMAT));
+    let info = backup::create_backup(pool, &papers_dir, &folder.join(file_name)).await?;
+
+    for (_, old) in list_backups(folder).into_iter().skip(keep.max(1)) {
+        if let Err(e) = fs::remove_file(&old) {
+            tracing::warn!("Failed to remove old backup {}: {}", old.display(), e);
+        }
+    }
+
+    Ok(info)
+}
+
+// Automatic backups in `folder`, newest first. Anything else in the folder is left alone.
+fn list_backups(folder: &Path) -> Vec<(DateTime<Utc>, PathBuf)> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<(DateTime<Utc>, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let stamp = name.to_str()?.strip_prefix(FILE_PREFIX)?.strip_suffix(".zip")?;
+            let created = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?;
+            Some((created.and_utc(), entry.path()))
+        })
+        .collect();
+
+    backups.sort_by_key(|(created, _)| std::cmp::Reverse(*created));
+    backups
+}
@@ -0,0 +1,301 @@
+// src-tauri/src/backup.rs
+
+// A backup is a zip holding a snapshot of the database, every file in the papers
+// directory, and a manifest recording where those papers lived when it was made.
+
+use crate::db;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DB_ENTRY: &str = "paper_master.db";
+const PAPERS_PREFIX: &str = "papers/";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    app_version: String,
+    created_at: String,
+    // pdf_path values in the database start with this
+    papers_dir: String,
+}
+
+#[derive(Serialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub papers: usize,
+    pub size_bytes: u64,
+}
+
+pub async fn create_backup(pool: &SqlitePool, papers_dir: &Path, dest: &Path) -> Result<BackupInfo, String> {
+    // VACUUM INTO gives a consistent copy even while the app keeps writing
+    let snapshot = dest.with_extension("db.tmp");
+    let _ = fs::remove_file(&snapshot);
+    db::copy_database(pool, &snapshot).await?;
+
+    let manifest = Manifest {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        papers_dir: papers_dir.to_str().ok_or("Invalid papers directory")?.to_string(),
+    };
+
+    let papers_dir = papers_dir.to_path_buf();
+    let dest = dest.to_path_buf();
+    let snapshot_path = snapshot.clone();
+    let result = tokio::task::spawn_blocking(move || write_archive(&dest, &snapshot_path, &papers_dir, &manifest))
+        .await
+        .map_err(|e| format!("Backup aborted: {}", e))?;
+
+    let _ = fs::remove_file(&snapshot);
+    result
+}
+
+fn write_archive(dest: &Path, snapshot: &Path, papers_dir: &Path, manifest: &Manifest) -> Result<BackupInfo, String> {
+    // Write next to the destination and rename at the end so a failed backup never
+    // replaces a good one
+    let partial = dest.with_extension("zip.partial");
+    let file = fs::File::create(&partial).map_err(|e| format!("Failed to create backup: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // PDFs are already compressed
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let result = (|| -> Result<usize, String> {
+        let err = |e: &dyn std::fmt::Display| format!("Failed to write backup: {}", e);
+
+        zip.start_file(MANIFEST_NAME, deflated).map_err(|e| err(&e))?;
+        let manifest = serde_json::to_vec_pretty(manifest).map_err(|e| err(&e))?;
+        zip.write_all(&manifest).map_err(|e| err(&e))?;
+
+        zip.start_file(DB_ENTRY, deflated).map_err(|e| err(&e))?;
+        let mut db_file = fs::File::open(snapshot).map_err(|e| err(&e))?;
+        io::copy(&mut db_file, &mut zip).map_err(|e| err(&e))?;
+
+        let mut papers = 0;
+        for entry in walkdir::WalkDir::new(papers_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(papers_dir).map_err(|e| err(&e))?;
+            // Zip entries always use forward slashes
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            zip.start_file(format!("{}{}", PAPERS_PREFIX, name), stored).map_err(|e| err(&e))?;
+            let mut file = fs::File::open(entry.path()).map_err(|e| err(&e))?;
+            io::copy(&mut file, &mut zip).map_err(|e| err(&e))?;
+            papers += 1;
+        }
+
+        Ok(papers)
+    })();
+
+    let finished = result.and_then(|papers| {
+        zip.finish()
+            .map_err(|e| format!("Failed to write backup: {}", e))?;
+        Ok(papers)
+    });
+
+    let papers = match finished {
+        Ok(papers) => papers,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+
+    fs::rename(&partial, dest).map_err(|e| format!("Failed to save backup: {}", e))?;
+    let size_bytes = fs::metadata(dest).map(|m| m.len()).unwrap_or_default();
+
+    Ok(BackupInfo {
+        path: dest.display().to_string(),
+        papers,
+        size_bytes,
+    })
+}
+
+// An unpacked, checked backup waiting to replace the live library
+pub struct StagedRestore {
+    staging: PathBuf,
+}
+
+// Unpacks the backup next to the papers directory and prepares its database for this
+// machine. Nothing in the live library is touched yet.
+pub async fn stage_restore(archive: &Path, papers_dir: &Path) -> Result<StagedRestore, String> {
+    let data_dir = papers_dir.parent().ok_or("Invalid papers directory")?;
+    let staged = StagedRestore {
+        staging: data_dir.join("restore-staging"),
+    };
+    let _ = fs::remove_dir_all(&staged.staging);
+    fs::create_dir_all(staged.staging.join("papers")).map_err(|e| format!("Failed to prepare restore: {}", e))?;
+
+    let archive = archive.to_path_buf();
+    let staging_dir = staged.staging.clone();
+    let manifest = tokio::task::spawn_blocking(move || extract_archive(&archive, &staging_dir))
+        .await
+        .map_err(|e| format!("Restore aborted: {}", e))??;
+
+    // Opening the staged copy also runs any migrations it is missing
+    let pool = db::init_db(&staged.staging.join(DB_ENTRY), 1).await?;
+    let new_prefix = papers_dir.to_str().ok_or("Invalid papers directory")?;
+    let rebased = db::rebase_pdf_paths(&pool, &manifest.papers_dir, new_prefix).await;
+    pool.close().await;
+    rebased?;
+
+    Ok(staged)
+}
+
+impl StagedRestore {
+    // The pool on the live database must be closed before calling this
+    pub fn swap(self, db_path: &Path, papers_dir: &Path) -> Result<(), String> {
+        swap_into_place(&self.staging.join(DB_ENTRY), db_path, &self.staging.join("papers"), papers_dir)
+    }
+}
+
+impl Drop for StagedRestore {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
+
+fn extract_archive(archive: &Path, staging: &Path) -> Result<Manifest, String> {
+    let file = fs::File::open(archive).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a valid backup: {}", e))?;
+
+    let manifest: Manifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| "Not a paper-master backup: manifest is missing".to_string())?;
+        let mut contents = String::new();
+        entry
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("Failed to read backup: {}", e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+
+    if zip.by_name(DB_ENTRY).is_err() {
+        return Err("Not a paper-master backup: database is missing".to_string());
+    }
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| format!("Failed to read backup: {}", e))?;
+        // enclosed_name rejects absolute paths and ../ tricks
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let is_wanted = name == Path::new(DB_ENTRY) || name.starts_with("papers");
+        if entry.is_dir() || !is_wanted {
+            continue;
+        }
+
+        let out_path = staging.join(&name);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to extract backup: {}", e))?;
+        }
+        let mut out = fs::File::create(&out_path).map_err(|e| format!("Failed to extract backup: {}", e))?;
+        io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to extract backup: {}", e))?;
+    }
+
+    Ok(manifest)
+}
+
+// Moves the current files aside, moves the staged ones in, and puts the old ones back
+// if anything fails half way
+fn swap_into_place(staged_db: &Path, db_path: &Path, staged_papers: &Path, papers_dir: &Path) -> Result<(), String> {
+    let old_db = sibling(db_path, "before-restore");
+    let old_papers = sibling(papers_dir, "before-restore");
+    let _ = fs::remove_file(&old_db);
+    let _ = fs::remove_dir_all(&old_papers);
+
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+    }
+
+    let had_db = db_path.exists();
+    let had_papers = papers_dir.exists();
+    if had_db {
+        fs::rename(db_path, &old_db).map_err(|e| format!("Failed to restore: {}", e))?;
+    }
+    if had_papers {
+        if let Err(e) = fs::rename(papers_dir, &old_papers) {
+            if had_db {
+                let _ = fs::rename(&old_db, db_path);
+            }
+            return Err(format!("Failed to restore: {}", e));
+        }
+    }
+
+    let swapped = move_path(staged_db, db_path).and_then(|_| move_path(staged_papers, papers_dir));
+    if let Err(e) = swapped {
+        let _ = fs::remove_file(db_path);
+        let _ = fs::remove_dir_all(papers_dir);
+        if had_db {
+            let _ = fs::rename(&old_db, db_path);
+        }
+        if had_papers {
+            let _ = fs::rename(&old_papers, papers_dir);
+        }
+        return Err(format!("Failed to restore: {}", e));
+    }
+
+    let _ = fs::remove_file(&old_db);
+    let _ = fs::remove_dir_all(&old_papers);
+    Ok(())
+}
+
+// rename fails across drives (a custom database path may live elsewhere), so fall back to
+// copying. A copy that fails partway is removed again, so the files are never split between
+// the two places: on error everything is still at `from`. Once the copy is complete, what
+// can't be deleted from `from` is left there.
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let existed = to.exists();
+    if let Err(e) = copy_path(from, to) {
+        if from.is_dir() {
+            let _ = fs::remove_dir_all(to);
+            if existed {
+                let _ = fs::create_dir(to);
+            }
+        } else {
+            let _ = fs::remove_file(to);
+        }
+        return Err(e);
+    }
+
+    let removed = if from.is_dir() { fs::remove_dir_all(from) } else { fs::remove_file(from) };
+    if let Err(e) = removed {
+        tracing::warn!("Copied {} to {} but couldn't remove the original: {}", from.display(), to.display(), e);
+    }
+    Ok(())
+}
+
+fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    for entry in walkdir::WalkDir::new(from) {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from).map_err(io::Error::other)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    path.with_file_name(name)
+}
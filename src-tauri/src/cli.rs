@@ -0,0 +1,159 @@
+// src-tauri/src/cli.rs
+
+// The library from scripts, without the window:
+//
+//     paper-master import a.pdf b.pdf        imports PDFs like dropping them on the window
+//     paper-master export-bibtex out.bib     the whole library; "-" writes to stdout
+//     paper-master search "query"            matching papers, one per line: id, year, title
+//
+// The app is built without opening its windows, so the same data directory, settings and
+// import pipeline are used, and the command runs on the library database directly. That is
+// safe while the app is open too; it just won't see the new papers until it reloads.
+
+use crate::config;
+use crate::db::{self, NewPaper};
+use crate::formats::bibtex;
+use crate::import::{self, DuplicatePolicy};
+use sqlx::SqlitePool;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const USAGE: &str = "Usage:
+  paper-master import <file.pdf>...
+  paper-master export-bibtex <out.bib | ->
+  paper-master search <query>";
+
+pub enum Command {
+    Import(Vec<PathBuf>),
+    ExportBibtex(String),
+    Search(String),
+}
+
+// None when the arguments aren't a command, i.e. the app should open as usual
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (name, rest) = args.split_first()?;
+    let command = match name.as_str() {
+        "import" if !rest.is_empty() => Ok(Command::Import(rest.iter().map(PathBuf::from).collect())),
+        "export-bibtex" => match rest {
+            [dest] => Ok(Command::ExportBibtex(dest.clone())),
+            _ => Err("export-bibtex takes one destination".to_string()),
+        },
+        "search" if !rest.is_empty() => Ok(Command::Search(rest.join(" "))),
+        "import" | "search" => Err(format!("{} needs an argument", name)),
+        "help" | "--help" | "-h" => Err(String::new()),
+        _ => return None,
+    };
+    Some(command)
+}
+
+// Runs the command and returns the process exit code
+pub fn run(mut context: tauri::Context<tauri::Wry>, command: Result<Command, String>) -> i32 {
+    attach_console();
+    let command = match command {
+        Ok(command) => command,
+        Err(e) if e.is_empty() => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    context.config_mut().app.windows.clear();
+    let app = match tauri::Builder::default().build(context) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Failed to start: {}", e);
+            return 1;
+        }
+    };
+    let handle = app.handle().clone();
+    let result = tauri::async_runtime::block_on(async move {
+        let pool = open_library(&handle).await?;
+        let result = execute(&handle, &pool, command).await;
+        pool.close().await;
+        result
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+async fn open_library(handle: &AppHandle) -> Result<SqlitePool, String> {
+    let db_path = db::database_path(handle)?;
+    let pool_size = config::load(handle).database_pool_size.unwrap_or(db::DEFAULT_POOL_SIZE);
+    Ok(db::init_db(&db_path, pool_size).await?)
+}
+
+async fn execute(handle: &AppHandle, pool: &SqlitePool, command: Command) -> Result<(), String> {
+    match command {
+        Command::Import(paths) => {
+            let mut failed = 0;
+            for path in &paths {
+                let is_pdf = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+                let result = if !path.is_file() {
+                    Err("File not found".to_string())
+                } else if !is_pdf {
+                    Err("Not a PDF".to_string())
+                } else {
+                    import::import_file(handle, pool, path, NewPaper::default(), DuplicatePolicy::Reject).await
+                };
+                match result {
+                    Ok(imported) => println!("{}\t{}", imported.paper.id, imported.paper.title),
+                    Err(e) => {
+                        failed += 1;
+                        eprintln!("{}: {}", path.display(), e);
+                    }
+                }
+            }
+            match failed {
+                0 => Ok(()),
+                _ => Err(format!("{} of {} files not imported", failed, paths.len())),
+            }
+        }
+        Command::ExportBibtex(dest) => {
+            let mut papers = db::get_all_papers(pool).await?;
+            // Oldest first so citation key suffixes match exports from the app
+            papers.sort_by_key(|p| p.id);
+            let bibliography = bibtex::export(&papers);
+            if dest == "-" {
+                print!("{}", bibliography);
+                return Ok(());
+            }
+            std::fs::write(&dest, bibliography).map_err(|e| format!("Failed to write {}: {}", dest, e))?;
+            eprintln!("Exported {} papers to {}", papers.len(), dest);
+            Ok(())
+        }
+        Command::Search(query) => {
+            for result in db::search_papers(pool, &query, 50).await? {
+                let year = result.year.map(|y| y.to_string()).unwrap_or_default();
+                println!("{}\t{}\t{}", result.id, year, result.title);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Release builds on Windows are GUI programs with no console of their own; borrow the one
+// the command was typed in so the output shows up there
+fn attach_console() {
+    #[cfg(windows)]
+    {
+        const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+        }
+        // SAFETY: takes no pointers; fails harmlessly when there is no parent console
+        unsafe {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
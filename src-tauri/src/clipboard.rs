@@ -0,0 +1,100 @@
+// src-tauri/src/clipboard.rs
+
+// Opt-in watcher for DOIs and arXiv ids copied anywhere, e.g. from a reference list in the
+// browser. The clipboard is polled; new text holding an identifier the library doesn't have
+// yet is announced with "clipboard:identifier", and the frontend offers to import it with
+// import_from_doi or import_from_arxiv.
+
+use crate::arxiv;
+use crate::config;
+use crate::db::{self, lookups::LookupKind};
+use crate::references;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Longer text is a copied page or document rather than a reference
+const MAX_TEXT_LEN: usize = 2000;
+
+#[derive(Serialize, Clone)]
+pub struct ClipboardIdentifier {
+    pub kind: LookupKind,
+    pub identifier: String,
+}
+
+pub struct ClipboardWatcher {
+    enabled: AtomicBool,
+}
+
+impl ClipboardWatcher {
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Arc<Self> {
+        let watcher = Arc::new(ClipboardWatcher {
+            enabled: AtomicBool::new(config::load(&handle).watch_clipboard),
+        });
+        tauri::async_runtime::spawn(watch_loop(watcher.clone(), handle, pool));
+        watcher
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+async fn watch_loop(watcher: Arc<ClipboardWatcher>, handle: AppHandle, pool: SqlitePool) {
+    let mut tick = tokio::time::interval(POLL_INTERVAL);
+    // None until the first read after watching starts, so whatever was copied before isn't offered
+    let mut last: Option<String> = None;
+
+    loop {
+        tick.tick().await;
+        if !watcher.is_enabled() {
+            last = None;
+            continue;
+        }
+        // Fails while the clipboard holds an image or nothing at all
+        let text = handle.clipboard().read_text().unwrap_or_default();
+        if last.replace(text.clone()).is_none_or(|previous| previous == text) {
+            continue;
+        }
+
+        let Some(found) = find_identifier(&text) else {
+            continue;
+        };
+        let (doi, arxiv_id) = match found.kind {
+            LookupKind::Doi => (Some(found.identifier.as_str()), None),
+            LookupKind::Arxiv => (None, Some(found.identifier.as_str())),
+        };
+        match db::find_matching_paper(&pool, doi, arxiv_id, None, None).await {
+            Ok(None) => {
+                let _ = handle.emit("clipboard:identifier", &found);
+            }
+            Ok(Some(_)) => {}
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+}
+
+// The first DOI in the text, otherwise an arXiv id: marked as one ("arXiv:2106.01234", an
+// arxiv.org link) or copied on its own
+fn find_identifier(text: &str) -> Option<ClipboardIdentifier> {
+    let text = text.trim();
+    if text.is_empty() || text.len() > MAX_TEXT_LEN {
+        return None;
+    }
+
+    if let Some(doi) = references::find_doi(text) {
+        return Some(ClipboardIdentifier { kind: LookupKind::Doi, identifier: doi });
+    }
+    references::find_arxiv_id(text)
+        .or_else(|| (!text.contains(char::is_whitespace)).then(|| arxiv::parse_id(text)).flatten())
+        .map(|id| ClipboardIdentifier { kind: LookupKind::Arxiv, identifier: id })
+}
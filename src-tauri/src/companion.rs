@@ -0,0 +1,442 @@
+// src-tauri/src/companion.rs
+
+// A small HTTP server on 127.0.0.1 that the browser extension saves papers through. Off
+// unless the user turns it on. Requests other than /ping must carry the token shown in the
+// settings as "Authorization: Bearer <token>"; the token lives in the keychain.
+//
+//     GET  /ping                                    finds the app, no token needed
+//     POST /save      {"url", "doi", "arxiv_id", "title"}, at least one of the first three
+//     POST /save-pdf?url=...&doi=...&title=...      the PDF itself as the body
+//
+// Saves go through the same import as the app's own commands (see save_from_companion in
+// main.rs) and are answered with the new paper as {"paper": ...}, failures with {"error": ...}.
+
+use crate::config::{self, CompanionSettings};
+use crate::error::AppError;
+use crate::import;
+use crate::secrets;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tauri::http::StatusCode;
+use tauri::AppHandle;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+const TOKEN_SECRET: &str = "companion-token";
+// Request line and headers
+const MAX_HEAD_LEN: usize = 16 * 1024;
+const MAX_JSON_LEN: u64 = 64 * 1024;
+const MAX_PDF_LEN: u64 = 512 * 1024 * 1024;
+// For the whole request line and headers, then for each read of the body, so a client that
+// stops sending doesn't hold its connection open
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// What the extension sent to /save, or the query of /save-pdf
+#[derive(Deserialize, Default)]
+pub struct SaveRequest {
+    // A direct PDF link, or the page the paper is on
+    pub url: Option<String>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub title: Option<String>,
+}
+
+pub enum Save {
+    Link(SaveRequest),
+    // An uploaded PDF, already written to `file` in the papers folder under a temporary name
+    Pdf { file: PathBuf, file_name: String, request: SaveRequest },
+}
+
+#[derive(Serialize)]
+pub struct CompanionStatus {
+    pub settings: CompanionSettings,
+    // For pasting into the extension; None until the endpoint is first turned on
+    pub token: Option<String>,
+    pub running: bool,
+    pub last_error: Option<String>,
+}
+
+struct Server {
+    stop: CancellationToken,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+pub struct Companion {
+    handle: AppHandle,
+    server: tokio::sync::Mutex<Option<Server>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Companion {
+    pub fn start(handle: AppHandle) -> Arc<Self> {
+        let companion = Arc::new(Companion {
+            handle,
+            server: tokio::sync::Mutex::new(None),
+            last_error: Arc::default(),
+        });
+        let starting = companion.clone();
+        tauri::async_runtime::spawn(async move { starting.restart().await });
+        companion
+    }
+
+    // Stops the server and, if it is enabled, starts it again with the current settings
+    pub async fn restart(&self) {
+        let mut server = self.server.lock().await;
+        if let Some(old) = server.take() {
+            old.stop.cancel();
+            // The port is only free again once the old listener is gone
+            let _ = old.task.await;
+        }
+        self.set_error(None);
+
+        let settings = config::load(&self.handle).companion;
+        if !settings.enabled {
+            return;
+        }
+        let result = async {
+            let token = ensure_token()?;
+            let listener = TcpListener::bind(("127.0.0.1", settings.port))
+                .await
+                .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+            Ok::<_, String>((token, listener))
+        }
+        .await;
+
+        match result {
+            Ok((token, listener)) => {
+                let stop = CancellationToken::new();
+                let task = tauri::async_runtime::spawn(serve(self.handle.clone(), listener, token, settings.port, stop.clone()));
+                *server = Some(Server { stop, task });
+                tracing::info!("Browser extension endpoint listening on 127.0.0.1:{}", settings.port);
+            }
+            Err(e) => {
+                tracing::warn!("{}", e);
+                self.set_error(Some(e));
+            }
+        }
+    }
+
+    pub async fn status(&self) -> Result<CompanionStatus, String> {
+        Ok(CompanionStatus {
+            settings: config::load(&self.handle).companion,
+            token: secrets::get(TOKEN_SECRET)?,
+            running: self.server.lock().await.is_some(),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+        })
+    }
+
+    // The old token stops working right away
+    pub async fn regenerate_token(&self) -> Result<(), String> {
+        secrets::set(TOKEN_SECRET, &new_token())?;
+        self.restart().await;
+        Ok(())
+    }
+
+    fn set_error(&self, error: Option<String>) {
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = error;
+        }
+    }
+}
+
+fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn ensure_token() -> Result<String, String> {
+    match secrets::get(TOKEN_SECRET)? {
+        Some(token) => Ok(token),
+        None => {
+            let token = new_token();
+            secrets::set(TOKEN_SECRET, &token)?;
+            Ok(token)
+        }
+    }
+}
+
+async fn serve(handle: AppHandle, listener: TcpListener, token: String, port: u16, stop: CancellationToken) {
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Browser extension endpoint: {}", e);
+                    continue;
+                }
+            },
+            _ = stop.cancelled() => break,
+        };
+
+        let handle = handle.clone();
+        let token = token.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = handle_connection(&handle, stream, &token, port).await {
+                tracing::warn!("Browser extension request failed: {}", e);
+            }
+        });
+    }
+}
+
+struct Head {
+    method: String,
+    // With the query
+    target: String,
+    // Names lowercased
+    headers: Vec<(String, String)>,
+}
+
+impl Head {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn content_length(&self) -> Result<u64, Failure> {
+        self.header("content-length")
+            .and_then(|len| len.trim().parse().ok())
+            .ok_or((StatusCode::LENGTH_REQUIRED, "Content-Length is required".to_string()))
+    }
+}
+
+type Failure = (StatusCode, String);
+
+async fn handle_connection(handle: &AppHandle, mut stream: TcpStream, token: &str, port: u16) -> Result<(), String> {
+    let (head, body_start) = tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream))
+        .await
+        .map_err(|_| "Timed out waiting for the request".to_string())??;
+    let (status, body) = match respond(handle, &mut stream, &head, body_start, token, port).await {
+        Ok(body) => (StatusCode::OK, body),
+        Err((status, message)) => {
+            tracing::warn!("{} {}: {}", head.method, head.target, message);
+            (status, serde_json::json!({ "error": message }))
+        }
+    };
+    write_response(&mut stream, status, &body).await
+}
+
+// The request line and headers, and whatever of the body arrived with them
+async fn read_head(stream: &mut TcpStream) -> Result<(Head, Vec<u8>), String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("Connection closed before the request was complete".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+
+        let mut headers = [httparse::EMPTY_HEADER; 32];
+        let mut request = httparse::Request::new(&mut headers);
+        match request.parse(&buffer).map_err(|e| format!("Malformed request: {}", e))? {
+            httparse::Status::Complete(len) => {
+                let head = Head {
+                    method: request.method.unwrap_or_default().to_string(),
+                    target: request.path.unwrap_or_default().to_string(),
+                    headers: request
+                        .headers
+                        .iter()
+                        .map(|h| (h.name.to_ascii_lowercase(), String::from_utf8_lossy(h.value).into_owned()))
+                        .collect(),
+                };
+                return Ok((head, buffer[len..].to_vec()));
+            }
+            httparse::Status::Partial if buffer.len() > MAX_HEAD_LEN => {
+                return Err("Request headers too large".to_string());
+            }
+            httparse::Status::Partial => {}
+        }
+    }
+}
+
+async fn respond(
+    handle: &AppHandle,
+    stream: &mut TcpStream,
+    head: &Head,
+    body_start: Vec<u8>,
+    token: &str,
+    port: u16,
+) -> Result<serde_json::Value, Failure> {
+    // Only pages that were told our address may talk to us, not any site a DNS record points here
+    let host = head.header("host").unwrap_or_default();
+    if host != format!("127.0.0.1:{}", port) && host != format!("localhost:{}", port) {
+        return Err((StatusCode::FORBIDDEN, format!("Unexpected Host: {}", host)));
+    }
+
+    let url = reqwest::Url::parse(&format!("http://localhost{}", head.target))
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("Bad request target: {}", head.target)))?;
+    match (head.method.as_str(), url.path()) {
+        // CORS preflight for the Authorization header; the token is what keeps others out
+        ("OPTIONS", _) => return Ok(serde_json::Value::Null),
+        ("GET", "/ping") => {
+            return Ok(serde_json::json!({
+                "app": handle.package_info().name,
+                "version": handle.package_info().version.to_string(),
+            }));
+        }
+        ("POST", "/save") | ("POST", "/save-pdf") => {}
+        (_, "/ping") | (_, "/save") | (_, "/save-pdf") => {
+            return Err((StatusCode::METHOD_NOT_ALLOWED, format!("{} is not supported here", head.method)));
+        }
+        (_, path) => return Err((StatusCode::NOT_FOUND, format!("No such endpoint: {}", path))),
+    }
+
+    let authorized = head
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| bool::from(given.trim().as_bytes().ct_eq(token.as_bytes())));
+    if !authorized {
+        return Err((StatusCode::UNAUTHORIZED, "Missing or wrong token".to_string()));
+    }
+
+    let save = if url.path() == "/save" {
+        let len = head.content_length()?;
+        if len > MAX_JSON_LEN {
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, "Request too large".to_string()));
+        }
+        let body = read_body(stream, body_start, len).await?;
+        let request: SaveRequest = serde_json::from_slice(&body)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid request: {}", e)))?;
+        if [&request.url, &request.doi, &request.arxiv_id]
+            .iter()
+            .all(|v| v.as_deref().is_none_or(|v| v.trim().is_empty()))
+        {
+            return Err((StatusCode::BAD_REQUEST, "Send a url, doi or arxiv_id".to_string()));
+        }
+        Save::Link(request)
+    } else {
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let request = SaveRequest {
+            url: query("url"),
+            doi: query("doi"),
+            arxiv_id: query("arxiv_id"),
+            title: query("title"),
+        };
+        let len = head.content_length()?;
+        if len > MAX_PDF_LEN {
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, "PDF too large".to_string()));
+        }
+        let file_name = request
+            .url
+            .as_deref()
+            .and_then(|u| reqwest::Url::parse(u).ok())
+            .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+            .filter(|name| name.to_lowercase().ends_with(".pdf"))
+            .unwrap_or_else(|| "paper.pdf".to_string());
+        let file = receive_pdf(handle, stream, body_start, len).await?;
+        Save::Pdf { file, file_name, request }
+    };
+
+    let paper = crate::save_from_companion(handle, save).await.map_err(|e| (status_for(&e), e.to_string()))?;
+    Ok(serde_json::json!({ "paper": paper }))
+}
+
+async fn read_body(stream: &mut TcpStream, mut body: Vec<u8>, len: u64) -> Result<Vec<u8>, Failure> {
+    fill(stream, &mut body, len).await?;
+    body.truncate(len as usize);
+    Ok(body)
+}
+
+// Reads until `buffer` holds at least `len` bytes
+async fn fill(stream: &mut TcpStream, buffer: &mut Vec<u8>, len: u64) -> Result<(), Failure> {
+    let mut chunk = [0u8; 4096];
+    while (buffer.len() as u64) < len {
+        let read = read_some(stream, &mut chunk)
+            .await
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to read request: {}", e)))?;
+        if read == 0 {
+            return Err((StatusCode::BAD_REQUEST, "The request was cut off".to_string()));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok(())
+}
+
+// A read that gives up on a client that stops sending
+async fn read_some(stream: &mut TcpStream, buffer: &mut [u8]) -> std::io::Result<usize> {
+    tokio::time::timeout(READ_TIMEOUT, stream.read(buffer))
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "the client stopped sending"))?
+}
+
+// Streams the body into the papers folder, so a large PDF is never held in memory
+async fn receive_pdf(handle: &AppHandle, stream: &mut TcpStream, mut start: Vec<u8>, len: u64) -> Result<PathBuf, Failure> {
+    // Enough of the body to see the signature, however little of it came with the headers
+    fill(stream, &mut start, len.min(4)).await?;
+    start.truncate(len as usize);
+    if !start.starts_with(b"%PDF") {
+        return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, "The body is not a PDF".to_string()));
+    }
+
+    let internal = |e: String| (StatusCode::INTERNAL_SERVER_ERROR, e);
+    let papers_dir = import::papers_dir(handle).map_err(internal)?;
+    let path = papers_dir.join(format!(".upload-{}.pdf", uuid::Uuid::new_v4()));
+
+    let result = async {
+        let mut file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| internal(format!("Failed to save PDF: {}", e)))?;
+        file.write_all(&start).await.map_err(|e| internal(format!("Failed to save PDF: {}", e)))?;
+        let mut remaining = len - start.len() as u64;
+        let mut chunk = vec![0u8; 64 * 1024];
+        while remaining > 0 {
+            let wanted = remaining.min(chunk.len() as u64) as usize;
+            let read = read_some(stream, &mut chunk[..wanted])
+                .await
+                .map_err(|e| (StatusCode::BAD_REQUEST, format!("Failed to receive PDF: {}", e)))?;
+            if read == 0 {
+                return Err((StatusCode::BAD_REQUEST, "The PDF was cut off".to_string()));
+            }
+            file.write_all(&chunk[..read]).await.map_err(|e| internal(format!("Failed to save PDF: {}", e)))?;
+            remaining -= read as u64;
+        }
+        file.flush().await.map_err(|e| internal(format!("Failed to save PDF: {}", e)))?;
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => Ok(path),
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&path).await;
+            Err(e)
+        }
+    }
+}
+
+fn status_for(error: &AppError) -> StatusCode {
+    match error {
+        AppError::Validation(_) => StatusCode::BAD_REQUEST,
+        AppError::NotFound(_) => StatusCode::NOT_FOUND,
+        AppError::Conflict(_) => StatusCode::CONFLICT,
+        AppError::Network(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: StatusCode, body: &serde_json::Value) -> Result<(), String> {
+    let body = if body.is_null() { Vec::new() } else { body.to_string().into_bytes() };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Connection: close\r\n\r\n",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or_default(),
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.write_all(&body).await.map_err(|e| e.to_string())?;
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,276 @@
+// src-tauri/src/config.rs
+
+// Settings that must be known before the database is opened live in a JSON file
+// next to the app's other config rather than in the database itself.
+
+use crate::db::FileMode;
+use crate::import::DuplicatePolicy;
+use crate::logging::LogLevel;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    // Overrides the default database location in the app data directory
+    pub database_path: Option<PathBuf>,
+    // Connections kept open to the database; None uses db::DEFAULT_POOL_SIZE
+    pub database_pool_size: Option<u32>,
+    // Where imported PDFs are copied; None is <app local data>/papers. Changed with
+    // set_papers_dir, which moves the papers already stored along with it.
+    pub papers_dir: Option<PathBuf>,
+    pub import: ImportSettings,
+    pub backup: BackupSchedule,
+    pub zotero: ZoteroSyncSettings,
+    pub file_naming: FileNaming,
+    pub summarizer: SummarizerSettings,
+    pub network: NetworkSettings,
+    pub log_level: LogLevel,
+    pub maintenance: MaintenanceSchedule,
+    // Offer to import DOIs and arXiv ids as they are copied; see clipboard.rs
+    pub watch_clipboard: bool,
+    pub companion: CompanionSettings,
+    // Commands run on library events; see hooks.rs
+    pub hooks: Vec<Hook>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BackupSchedule {
+    pub enabled: bool,
+    pub folder: Option<PathBuf>,
+    pub interval_hours: u64,
+    // Older automatic backups beyond this many are deleted
+    pub keep: usize,
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        BackupSchedule {
+            enabled: false,
+            folder: None,
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+// Used by the import commands when they aren't told otherwise
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ImportSettings {
+    pub file_mode: FileMode,
+    pub on_duplicate: DuplicatePolicy,
+}
+
+// The local endpoint browser extensions save papers through; see companion.rs. Its token is
+// kept in the keychain.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CompanionSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for CompanionSettings {
+    fn default() -> Self {
+        CompanionSettings {
+            enabled: false,
+            port: 27182,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    #[default]
+    PaperImported,
+    PaperUpdated,
+    PaperDeleted,
+    NotesSaved,
+}
+
+// `command` is run by the shell (sh, or cmd on Windows), so it can be a one-liner as well
+// as a script's path
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Hook {
+    pub event: HookEvent,
+    pub command: String,
+    pub enabled: bool,
+}
+
+impl Default for Hook {
+    fn default() -> Self {
+        Hook {
+            event: HookEvent::default(),
+            command: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+// VACUUM and ANALYZE every `interval_days`; see maintenance.rs
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MaintenanceSchedule {
+    pub enabled: bool,
+    pub interval_days: u64,
+}
+
+impl Default for MaintenanceSchedule {
+    fn default() -> Self {
+        MaintenanceSchedule {
+            enabled: false,
+            interval_days: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ZoteroSyncSettings {
+    pub enabled: bool,
+    // The key itself is in the keychain (see zotero_sync::API_KEY_SECRET)
+    pub has_api_key: bool,
+    // Where older versions kept the key; only read, to move it to the keychain
+    #[serde(skip_serializing)]
+    pub api_key: Option<String>,
+    // Looked up from the API key when it is saved
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub interval_minutes: u64,
+}
+
+impl Default for ZoteroSyncSettings {
+    fn default() -> Self {
+        ZoteroSyncSettings {
+            enabled: false,
+            has_api_key: false,
+            api_key: None,
+            user_id: None,
+            username: None,
+            interval_minutes: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct FileNaming {
+    // Off keeps the original file names
+    pub rename_on_edit: bool,
+    // Placeholders: {title}, {year}, {first_author}, {journal}, {cite_key}
+    pub pattern: String,
+}
+
+impl Default for FileNaming {
+    fn default() -> Self {
+        FileNaming {
+            rename_on_edit: false,
+            pattern: "{year} - {first_author} - {title}".to_string(),
+        }
+    }
+}
+
+// Off unless the user turns it on, since it sends paper text to another service. The API key
+// is kept in the keychain, not here; see secrets.rs.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SummarizerSettings {
+    pub enabled: bool,
+    pub provider: SummarizerProvider,
+    // Base URL; None uses the provider's usual one
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SummarizerProvider {
+    // Any server with the OpenAI chat completions API, e.g. OpenAI itself, LM Studio or vLLM
+    #[serde(rename = "openai")]
+    OpenAi,
+    // Runs locally
+    #[default]
+    Ollama,
+}
+
+impl SummarizerProvider {
+    pub fn default_endpoint(self) -> &'static str {
+        match self {
+            SummarizerProvider::OpenAi => "https://api.openai.com/v1",
+            SummarizerProvider::Ollama => "http://localhost:11434",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct NetworkSettings {
+    // Sent along with Crossref lookups so they are served from its "polite" pool; Unpaywall
+    // won't answer without one
+    pub contact_email: Option<String>,
+    pub proxy: ProxySettings,
+    // PEM file with extra root certificates, for networks that inspect TLS traffic
+    pub ca_certificate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    None,
+    // Whatever the HTTP_PROXY / HTTPS_PROXY environment variables say
+    #[default]
+    System,
+    Manual,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    // A host name, or a URL to pick the scheme, e.g. https://proxy.example.edu
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    // The password is kept in the keychain
+    pub username: Option<String>,
+    // Comma separated hosts reached directly
+    pub no_proxy: Option<String>,
+}
+
+fn config_path(handle: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))?;
+    Ok(config_dir.join("config.json"))
+}
+
+// A missing or unreadable config falls back to the defaults
+pub fn load(handle: &AppHandle) -> AppConfig {
+    let Ok(path) = config_path(handle) else {
+        return AppConfig::default();
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid config {}: {}", path.display(), e);
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    }
+}
+
+pub fn save(handle: &AppHandle, config: &AppConfig) -> Result<(), String> {
+    let path = config_path(handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| format!("Failed to save config: {}", e))
+}
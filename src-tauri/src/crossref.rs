@@ -0,0 +1,229 @@
+// src-tauri/src/crossref.rs
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+const API_BASE: &str = "https://api.crossref.org/works";
+
+// Crossref throttles clients that send requests in quick succession; searches in particular
+// are expensive for it. Requests from the whole app are spaced at least this far apart.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+static NEXT_REQUEST: Mutex<Option<Instant>> = Mutex::const_new(None);
+
+// Lookup result shared by the metadata providers; the frontend can hand it back to apply_metadata
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct WorkMetadata {
+    pub title: String,
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    pub doi: Option<String>,
+    pub r#abstract: Option<String>,
+    // Comma separated
+    pub keywords: Option<String>,
+    // A full-text PDF the publisher deposited with Crossref; often only for subscribers
+    pub pdf_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WorkResponse {
+    message: Work,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    message: SearchMessage,
+}
+
+#[derive(Deserialize)]
+struct SearchMessage {
+    #[serde(default)]
+    items: Vec<Work>,
+}
+
+#[derive(Deserialize)]
+struct Work {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(default)]
+    author: Vec<Author>,
+    #[serde(rename = "container-title", default)]
+    container_title: Vec<String>,
+    issued: Option<DateParts>,
+    #[serde(rename = "published-print")]
+    published_print: Option<DateParts>,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<String>,
+    #[serde(default)]
+    subject: Vec<String>,
+    #[serde(default)]
+    link: Vec<Link>,
+}
+
+#[derive(Deserialize)]
+struct Link {
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(rename = "content-type")]
+    content_type: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    given: Option<String>,
+    family: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DateParts {
+    #[serde(rename = "date-parts", default)]
+    date_parts: Vec<Vec<Option<i64>>>,
+}
+
+impl DateParts {
+    fn year(&self) -> Option<i64> {
+        self.date_parts.first()?.first().copied().flatten()
+    }
+}
+
+impl From<Work> for WorkMetadata {
+    fn from(work: Work) -> Self {
+        let authors: Vec<String> = work
+            .author
+            .into_iter()
+            .filter_map(|a| match (a.given, a.family, a.name) {
+                (Some(given), Some(family), _) => Some(format!("{} {}", given, family)),
+                (None, Some(family), _) => Some(family),
+                (_, None, name) => name,
+            })
+            .collect();
+
+        WorkMetadata {
+            title: work.title.into_iter().next().unwrap_or_default(),
+            authors: (!authors.is_empty()).then(|| authors.join(", ")),
+            journal: work.container_title.into_iter().next(),
+            year: work
+                .issued
+                .as_ref()
+                .and_then(DateParts::year)
+                .or_else(|| work.published_print.as_ref().and_then(DateParts::year)),
+            doi: Some(work.doi.to_lowercase()),
+            r#abstract: work.abstract_text.map(|a| strip_tags(&a)),
+            keywords: (!work.subject.is_empty()).then(|| work.subject.join(", ")),
+            pdf_url: work
+                .link
+                .into_iter()
+                .find(|l| l.content_type.as_deref() == Some("application/pdf"))
+                .map(|l| l.url),
+        }
+    }
+}
+
+// `mailto` is the user's contact address; with it Crossref serves the request from its
+// "polite" pool, which is faster and more reliable than the anonymous one
+pub async fn fetch_by_doi(client: &reqwest::Client, doi: &str, mailto: Option<&str>) -> Result<WorkMetadata, String> {
+    let doi = normalize_doi(doi).ok_or_else(|| format!("Not a valid DOI: {}", doi))?;
+
+    let mut url = reqwest::Url::parse(API_BASE).map_err(|e| e.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| "Invalid Crossref URL".to_string())?
+        .push(&doi);
+
+    let response = send(client.get(url), mailto).await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("DOI not found on Crossref: {}", doi));
+    }
+
+    let work: WorkResponse = response
+        .error_for_status()
+        .map_err(|e| format!("Crossref request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Crossref response: {}", e))?;
+
+    Ok(work.message.into())
+}
+
+// Works matching a title, possibly half-remembered, with authors or year mixed in; best match first
+pub async fn search(
+    client: &reqwest::Client,
+    query: &str,
+    rows: usize,
+    mailto: Option<&str>,
+) -> Result<Vec<WorkMetadata>, String> {
+    let rows = rows.clamp(1, 50).to_string();
+    let request = client.get(API_BASE).query(&[("query.bibliographic", query), ("rows", rows.as_str())]);
+
+    let results: SearchResponse = send(request, mailto)
+        .await?
+        .error_for_status()
+        .map_err(|e| format!("Crossref search failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Crossref response: {}", e))?;
+
+    Ok(results
+        .message
+        .items
+        .into_iter()
+        .map(WorkMetadata::from)
+        .filter(|work| !work.title.is_empty())
+        .collect())
+}
+
+async fn send(request: reqwest::RequestBuilder, mailto: Option<&str>) -> Result<reqwest::Response, String> {
+    let request = match mailto.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(mailto) => request.query(&[("mailto", mailto)]),
+        None => request,
+    };
+
+    {
+        let mut next = NEXT_REQUEST.lock().await;
+        if let Some(at) = *next {
+            tokio::time::sleep_until(at).await;
+        }
+        *next = Some(Instant::now() + MIN_REQUEST_INTERVAL);
+    }
+
+    let response = request.send().await.map_err(|e| format!("Crossref request failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err("Crossref is limiting requests right now; try again in a minute".to_string());
+    }
+    Ok(response)
+}
+
+// Accepts bare DOIs as well as doi: prefixes and doi.org URLs
+pub fn normalize_doi(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let lower = trimmed.to_lowercase();
+    let start = ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/", "doi:"]
+        .iter()
+        .find(|prefix| lower.starts_with(*prefix))
+        .map_or(0, |prefix| prefix.len());
+    let doi = trimmed[start..].trim();
+
+    (doi.starts_with("10.") && doi.contains('/')).then(|| doi.to_string())
+}
+
+// Crossref abstracts are JATS XML fragments; feed summaries are HTML
+pub fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,112 +1,1734 @@
 // src-tauri/src/db.rs
 
-use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
-use std::path::PathBuf;
-use tauri::AppHandle;
+use crate::config;
+use crate::error::AppError;
+use crate::query;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{migrate::Migrator, Connection, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Transaction};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
 use sqlx::FromRow;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, FromRow)]
+pub mod annotations;
+pub mod authors;
+pub mod citations;
+pub mod collections;
+pub mod custom_fields;
+pub mod embeddings;
+pub mod feeds;
+pub mod figures;
+pub mod fuzzy;
+pub mod jobs;
+pub mod journal;
+pub mod keywords;
+mod legacy;
+pub mod links;
+pub mod lookups;
+pub mod metadata_matches;
+pub mod notes;
+pub mod ocr;
+pub mod pages;
+pub mod outline;
+pub mod reading;
+pub mod references;
+pub mod revisions;
+pub mod saved_searches;
+pub mod similar;
+pub mod smart_collections;
+pub mod stats;
+pub mod summaries;
+pub mod tags;
+pub mod venues;
+pub mod view_states;
+pub mod zotero;
+
+#[derive(Serialize, FromRow, Clone)]
 pub struct Paper 
 {
     pub id: i64,
     pub title: String,
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    // The canonical venue `journal` was matched to
+    pub venue_id: Option<i64>,
+    pub year: Option<i64>,
+    pub item_type: ItemType,
+    // Books
+    pub isbn: Option<String>,
+    pub publisher: Option<String>,
+    // The university of a thesis or the issuer of a report
+    pub institution: Option<String>,
     pub pdf_path: String,
+    pub tags: Option<String>,
+    pub notes: Option<String>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub pmid: Option<String>,
+    pub r#abstract: Option<String>,
+    // Comma separated, as given by the authors or publisher
+    pub keywords: Option<String>,
+    pub cite_key: Option<String>,
+    pub file_hash: Option<String>,
+    pub page_count: Option<i64>,
+    // Bytes
+    pub file_size: Option<i64>,
+    // From the header, e.g. "1.7"
+    pub pdf_version: Option<String>,
+    pub file_mode: FileMode,
+    pub reading_status: ReadingStatus,
+    pub last_read_page: Option<i64>,
+    pub last_opened_at: Option<String>,
+    pub open_count: i64,
+    pub starred: bool,
+    pub rating: Option<i64>,
+    pub priority: Priority,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    // Set while the paper is in the Trash
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ReadingStatus {
+    #[default]
+    Unread,
+    Reading,
+    Read,
+}
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ItemType {
+    // Journal and conference papers alike
+    #[default]
+    Article,
+    Book,
+    Thesis,
+    Preprint,
+    Report,
+}
+
+impl ItemType {
+    pub fn parse(name: &str) -> Option<ItemType> {
+        match name.trim().to_lowercase().as_str() {
+            "article" => Some(ItemType::Article),
+            "book" => Some(ItemType::Book),
+            "thesis" => Some(ItemType::Thesis),
+            "preprint" => Some(ItemType::Preprint),
+            "report" => Some(ItemType::Report),
+            _ => None,
+        }
+    }
+}
+
+// Whether the app owns the PDF (a copy in the papers folder) or only points at the user's file
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum FileMode {
+    #[default]
+    Stored,
+    Linked,
+}
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[repr(i64)]
+pub enum Priority {
+    #[default]
+    None = 0,
+    Low = 1,
+    Medium = 2,
+    High = 3,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    #[default]
+    Added,
+    Updated,
+    Title,
+    Year,
+    Rating,
+    Priority,
+    Authors,
+    Journal,
+    Pages,
+    FileSize,
+}
+
+impl SortBy {
+    fn column(self) -> &'static str {
+        match self {
+            SortBy::Added => "created_at",
+            SortBy::Updated => "updated_at",
+            SortBy::Title => "title COLLATE NOCASE",
+            SortBy::Year => "year",
+            SortBy::Rating => "rating",
+            SortBy::Priority => "priority",
+            SortBy::Authors => "authors COLLATE NOCASE",
+            SortBy::Journal => "journal COLLATE NOCASE",
+            SortBy::Pages => "page_count",
+            SortBy::FileSize => "file_size",
+        }
+    }
+
+    // Text reads A-Z, everything else newest/highest first
+    fn descending_by_default(self) -> bool {
+        !matches!(self, SortBy::Title | SortBy::Authors | SortBy::Journal)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct SortKey {
+    pub field: SortBy,
+    // None uses the field's natural direction
+    pub descending: Option<bool>,
+}
+
+// `tags` is the paper's tag names joined with ", "
+const PAPER_COLUMNS: &str = "id, title, authors, journal, venue_id, year, item_type, isbn, publisher, institution, pdf_path, \
+    (SELECT group_concat(t.name, ', ') FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.paper_id = papers.id) AS tags, \
+    notes, doi, arxiv_id, pmid, abstract, keywords, cite_key, file_hash, page_count, file_size, pdf_version, file_mode, reading_status, last_read_page, last_opened_at, open_count, starred, rating, priority, created_at, updated_at, deleted_at";
+
+#[derive(Serialize, FromRow)]
+pub struct WatchedFolder {
+    pub id: i64,
+    pub path: String,
+    pub recursive: bool,
+    pub file_mode: FileMode,
     pub created_at: Option<String>,
 }
 
+#[derive(Default)]
+pub struct NewPaper {
+    pub title: String,
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    pub item_type: ItemType,
+    pub isbn: Option<String>,
+    pub publisher: Option<String>,
+    pub institution: Option<String>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub pmid: Option<String>,
+    pub r#abstract: Option<String>,
+    // Comma separated, as given by the authors or publisher
+    pub keywords: Option<String>,
+    // Comma separated tag names
+    pub tags: Option<String>,
+    pub pdf_path: String,
+    pub file_hash: Option<String>,
+    pub page_count: Option<i64>,
+    pub file_size: Option<i64>,
+    pub pdf_version: Option<String>,
+    pub file_mode: FileMode,
+    // Key to keep if it's still free, e.g. from an imported .bib file
+    pub cite_key: Option<String>,
+}
 
+// Fields left as None keep their current value
+#[derive(Deserialize, Default)]
+pub struct PaperUpdate {
+    pub title: Option<String>,
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    pub item_type: Option<ItemType>,
+    pub isbn: Option<String>,
+    pub publisher: Option<String>,
+    pub institution: Option<String>,
+    // Comma separated; replaces all of the paper's tags
+    pub tags: Option<String>,
+    pub notes: Option<String>,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub r#abstract: Option<String>,
+    pub keywords: Option<String>,
+}
 
-pub async fn init_db(app_handle: &AppHandle) -> Result<SqlitePool, String> {
-    println!("Initializing database...");
+// The same change for every selected paper; fields left as None keep each paper's value
+#[derive(Deserialize, Default)]
+pub struct BulkUpdate {
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    pub reading_status: Option<ReadingStatus>,
+    pub priority: Option<Priority>,
+    pub starred: Option<bool>,
+    pub rating: Option<i64>,
+    // Comma separated; added to or taken off each paper's existing tags
+    pub add_tags: Option<String>,
+    pub remove_tags: Option<String>,
+}
 
-    let current_dir = std::env::current_dir()
-        .map_err(|e| format!("Failed to get current dir: {}", e))?;
+#[derive(Serialize)]
+pub struct BulkUpdateResult {
+    pub paper_id: i64,
+    // The paper after the update, None when it failed
+    pub paper: Option<Paper>,
+    pub error: Option<String>,
+}
 
-    let db_path = current_dir.join("paper_master.db");
-    let clean_path = clean_windows_path(&db_path);
-    let forward_slashes = clean_path.to_str().unwrap().replace("\\", "/");
-    let db_url = format!("file:{}?mode=rwc", forward_slashes);
+#[derive(Deserialize, Default)]
+pub struct PaperFilter {
+    pub collection_id: Option<i64>,
+    // Every spelling of the venue, unlike `journal`
+    pub venue_id: Option<i64>,
+    pub status: Option<ReadingStatus>,
+    pub item_type: Option<ItemType>,
+    pub starred: Option<bool>,
+    // Papers must carry every one of these tags
+    pub tags: Option<Vec<String>>,
+    // Inclusive; papers without a year are left out once either bound is set
+    pub year_from: Option<i64>,
+    pub year_to: Option<i64>,
+    // Case-insensitive substring of the journal name
+    pub journal: Option<String>,
+    // Words to find in the title or PDF text, or a substring of the authors
+    pub query: Option<String>,
+    // Advanced search syntax, e.g. `author:smith year:2019..2022 -tag:survey`; see query.rs
+    pub search: Option<String>,
+    // Applied in order, with id as the final tie-breaker; newest first when empty
+    pub sort: Option<Vec<SortKey>>,
+    // One page of results for virtualized lists; everything when both are None
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    // Lists the Trash instead; trashed papers are left out otherwise
+    pub trashed: Option<bool>,
+}
 
+#[derive(Serialize)]
+pub struct SchemaVersion {
+    // Latest applied migration, None on a database that was never migrated
+    pub version: Option<i64>,
+    pub description: Option<String>,
+    pub installed_on: Option<String>,
+    // Newest migration bundled with this build
+    pub latest: i64,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct SearchResult {
+    pub id: i64,
+    pub title: String,
+    pub authors: Option<String>,
+    pub year: Option<i64>,
+    pub pdf_path: String,
+    pub snippet: String,
+    pub rank: f64,
+    // Best hits in the PDF text; empty when only the title matched
+    #[sqlx(skip)]
+    pub matches: Vec<PageMatch>,
+    // Found by a title resembling the query rather than containing it; has no snippet
+    #[sqlx(skip)]
+    pub fuzzy: bool,
+}
+
+#[derive(Serialize)]
+pub struct PageMatch {
+    // 1-based
+    pub page: i64,
+    // Text around the hit, with the hit in <mark>
+    pub snippet: String,
+    // Where the hit is in the page's text, in UTF-16 code units like JavaScript string indices
+    pub start: usize,
+    pub end: usize,
+}
+
+
+
+const DB_FILE_NAME: &str = "paper_master.db";
+
+// The configured override, or paper_master.db in the app data directory
+pub fn database_path(handle: &AppHandle) -> Result<PathBuf, AppError> {
+    if let Some(path) = config::load(handle).database_path {
+        return Ok(path);
+    }
+
+    let app_data_dir = handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| AppError::Other(format!("Path resolve error: {}", e)))?;
+    fs::create_dir_all(&app_data_dir).map_err(|e| AppError::Io(format!("Failed to create data directory: {}", e)))?;
+
+    let db_path = app_data_dir.join(DB_FILE_NAME);
+    move_legacy_database(&db_path)?;
+    Ok(db_path)
+}
+
+// Early versions kept the database in whatever directory the app was launched from
+fn move_legacy_database(db_path: &Path) -> Result<(), AppError> {
+    if db_path.exists() {
+        return Ok(());
+    }
+    let Ok(current_dir) = std::env::current_dir() else {
+        return Ok(());
+    };
+    let old_path = current_dir.join(DB_FILE_NAME);
+    if !old_path.is_file() || old_path == db_path {
+        return Ok(());
+    }
+
+    tracing::info!("Moving database from {} to {}", old_path.display(), db_path.display());
+    // WAL and shared-memory files must travel with the database
+    for suffix in ["", "-wal", "-shm"] {
+        let from = PathBuf::from(format!("{}{}", old_path.display(), suffix));
+        let to = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+        if !from.exists() {
+            continue;
+        }
+        // rename fails across drives, so fall back to copying
+        if fs::rename(&from, &to).is_err() {
+            fs::copy(&from, &to).map_err(|e| AppError::Io(format!("Failed to move database: {}", e)))?;
+            let _ = fs::remove_file(&from);
+        }
+    }
+
+    Ok(())
+}
+
+// Used unless the config sets another pool size
+pub const DEFAULT_POOL_SIZE: u32 = 5;
+// How long a write waits for another connection's transaction before failing with SQLITE_BUSY
+const BUSY_TIMEOUT_MS: u32 = 5000;
+
+pub async fn init_db(db_path: &Path, max_connections: u32) -> Result<SqlitePool, AppError> {
+    tracing::info!("Initializing database at {}...", db_path.display());
+
+    let options = SqliteConnectOptions::new()
+        .filename(clean_windows_path(db_path))
+        .create_if_missing(true);
+
+    // WAL lets readers carry on while a background job writes. busy_timeout and foreign_keys
+    // are per connection, so they are set on every connection the pool opens.
     let pool = match SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
+        .max_connections(max_connections.max(1))
+        .after_connect(|conn, _meta| {
+            Box::pin(async move {
+                for pragma in [
+                    "PRAGMA journal_mode = WAL".to_string(),
+                    format!("PRAGMA busy_timeout = {}", BUSY_TIMEOUT_MS),
+                    "PRAGMA foreign_keys = ON".to_string(),
+                ] {
+                    sqlx::query(&pragma).execute(&mut *conn).await?;
+                }
+                Ok(())
+            })
+        })
+        .connect_with(options)
         .await
     {
         Ok(pool) => {
-            println!("Connected to DB!");
-            init_tables(&pool).await?;
+            tracing::info!("Connected to DB!");
+            run_migrations(&pool).await?;
             pool
         }
         Err(e) => {
-            println!("All attempts failed: {}", e);
-            return Err(format!("Database connection failed: {}", e));
+            tracing::error!("All attempts failed: {}", e);
+            return Err(AppError::Db(format!("Database connection failed: {}", e)));
         }
     };
 
     Ok(pool)
 }
 
-fn clean_windows_path(path: &PathBuf) -> PathBuf {
+fn clean_windows_path(path: &Path) -> PathBuf {
     let path_str = path.to_str().unwrap_or("");
-    if path_str.starts_with(r"\\?\") {
-        PathBuf::from(&path_str[4..])
-    } else {
-        path.clone()
+    match path_str.strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path.to_path_buf(),
     }
 }
 
-async fn init_tables(pool: &SqlitePool) -> Result<(), String> {
+// Writes a consistent copy of the open database to `dest`
+pub async fn copy_database(pool: &SqlitePool, dest: &Path) -> Result<(), AppError> {
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest.to_str().ok_or_else(|| AppError::Validation("Invalid database path".to_string()))?)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to copy database: {}", e)))?;
+    Ok(())
+}
+
+// Points pdf_path values under `old_prefix` at `new_prefix`, e.g. after restoring a
+// backup made on another machine
+pub async fn rebase_pdf_paths(pool: &SqlitePool, old_prefix: &str, new_prefix: &str) -> Result<u64, AppError> {
+    let mut tx = pool.begin().await?;
+    let moved = rebase_pdf_paths_in(&mut tx, old_prefix, new_prefix).await?;
+    tx.commit().await?;
+    Ok(moved)
+}
+
+// rebase_pdf_paths inside a caller's transaction
+pub async fn rebase_pdf_paths_in(
+    conn: &mut SqliteConnection,
+    old_prefix: &str,
+    new_prefix: &str,
+) -> Result<u64, AppError> {
+    if old_prefix == new_prefix {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        "UPDATE papers SET pdf_path = ? || substr(pdf_path, length(?) + 1) WHERE substr(pdf_path, 1, length(?)) = ?",
+    )
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update file paths: {}", e)))?;
+    // Earlier versions of the papers' PDFs live in the same folder
     sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS papers (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL,
-            authors TEXT,
-            journal TEXT,
-            year INTEGER,
-            pdf_path TEXT NOT NULL,
-            tags TEXT,
-            notes TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#
+        "UPDATE paper_revisions SET pdf_path = ? || substr(pdf_path, length(?) + 1) WHERE substr(pdf_path, 1, length(?)) = ?",
     )
-    .execute(pool)
+    .bind(new_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .bind(old_prefix)
+    .execute(&mut *conn)
     .await
-    .map_err(|e| format!("Failed to create table: {}", e))?;
+    .map_err(|e| AppError::Db(format!("Failed to update file paths: {}", e)))?;
 
-    Ok(())
+    Ok(result.rows_affected())
+}
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+async fn run_migrations(pool: &SqlitePool) -> Result<(), AppError> {
+    legacy::prepare(pool).await?;
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to migrate database: {}", e)))?;
+    legacy::finish(pool).await
+}
+
+pub async fn get_schema_version(pool: &SqlitePool) -> Result<SchemaVersion, AppError> {
+    let applied: Option<(i64, String, Option<String>)> = sqlx::query_as(
+        "SELECT version, description, installed_on FROM _sqlx_migrations WHERE success = 1 ORDER BY version DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to read schema version: {}", e)))?;
+
+    let (version, description, installed_on) = match applied {
+        Some((version, description, installed_on)) => (Some(version), Some(description), installed_on),
+        None => (None, None, None),
+    };
+
+    Ok(SchemaVersion {
+        version,
+        description,
+        installed_on,
+        latest: MIGRATOR.iter().map(|m| m.version).max().unwrap_or_default(),
+    })
 }
 
 pub async fn insert_paper(
     pool: &SqlitePool,
-    title: &str,
-    pdf_path: &str,
-) -> Result<i64, String> {
+    paper: &NewPaper,
+) -> Result<i64, AppError> {
     let result = sqlx::query(
         r#"
-        INSERT INTO papers (title, pdf_path)
-        VALUES (?, ?)
+        INSERT INTO papers (title, authors, journal, year, item_type, isbn, publisher, institution, doi, arxiv_id,
+                            pmid, abstract, keywords, pdf_path, file_hash, page_count, file_size, pdf_version, file_mode)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#
     )
-    .bind(title)
-    .bind(pdf_path)
+    .bind(&paper.title)
+    .bind(&paper.authors)
+    .bind(&paper.journal)
+    .bind(paper.year)
+    .bind(paper.item_type)
+    .bind(&paper.isbn)
+    .bind(&paper.publisher)
+    .bind(&paper.institution)
+    .bind(&paper.doi)
+    .bind(&paper.arxiv_id)
+    .bind(&paper.pmid)
+    .bind(&paper.r#abstract)
+    .bind(&paper.keywords)
+    .bind(&paper.pdf_path)
+    .bind(&paper.file_hash)
+    .bind(paper.page_count)
+    .bind(paper.file_size)
+    .bind(&paper.pdf_version)
+    .bind(paper.file_mode)
     .execute(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    ?;
+
+    let id = result.last_insert_rowid();
+    if let Some(names) = &paper.tags {
+        tags::set_paper_tags(pool, id, &tags::split_tags(names)).await?;
+    }
+    authors::set_paper_authors(pool, id, paper.authors.as_deref()).await?;
+    venues::set_paper_venue(pool, id, paper.journal.as_deref()).await?;
 
-    Ok(result.last_insert_rowid())
+    Ok(id)
 }
 
 
 
-pub async fn get_all_papers(pool: &SqlitePool) -> Result<Vec<Paper>, String>
+pub async fn get_all_papers(pool: &SqlitePool) -> Result<Vec<Paper>, AppError>
 {
     let papers = sqlx::query_as::<_,Paper>(
-        "SELECT id, title, pdf_path, created_at FROM papers ORDER BY created_at DESC"
-    ).fetch_all(pool).await.map_err(|e| format!("Faled to fetch the papers: {}",e))?;
+        &format!("SELECT {} FROM papers WHERE deleted_at IS NULL ORDER BY created_at DESC", PAPER_COLUMNS)
+    ).fetch_all(pool).await.map_err(|e| AppError::Db(format!("Faled to fetch the papers: {}",e)))?;
+
+    Ok(papers)
+}
+
+pub async fn get_papers_filtered(pool: &SqlitePool, filter: &PaperFilter) -> Result<Vec<Paper>, AppError> {
+    let mut builder = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM papers WHERE 1 = 1", PAPER_COLUMNS));
+    push_filter_conditions(&mut builder, filter)?;
+
+    let default_sort = [SortKey { field: SortBy::default(), descending: None }];
+    let sort = filter.sort.as_deref().filter(|keys| !keys.is_empty()).unwrap_or(&default_sort);
+    // Only column names from SortBy are pushed as SQL. Unrated/undated papers go last
+    // either way; id keeps ties stable.
+    builder.push(" ORDER BY ");
+    for key in sort {
+        let descending = key.descending.unwrap_or(key.field.descending_by_default());
+        builder.push(format!(
+            "{} {} NULLS LAST, ",
+            key.field.column(),
+            if descending { "DESC" } else { "ASC" }
+        ));
+    }
+    builder.push("id DESC");
+
+    // SQLite needs a LIMIT before an OFFSET; -1 means no limit
+    if filter.limit.is_some() || filter.offset.is_some() {
+        builder
+            .push(" LIMIT ")
+            .push_bind(filter.limit.unwrap_or(-1))
+            .push(" OFFSET ")
+            .push_bind(filter.offset.unwrap_or(0));
+    }
+
+    builder
+        .build_query_as::<Paper>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch the papers: {}", e)))
+}
+
+// Number of papers get_papers_filtered returns for `filter`, ignoring limit and offset
+pub async fn count_papers(pool: &SqlitePool, filter: &PaperFilter) -> Result<i64, AppError> {
+    let mut builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM papers WHERE 1 = 1");
+    push_filter_conditions(&mut builder, filter)?;
+
+    builder
+        .build_query_scalar::<i64>()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to count papers: {}", e)))
+}
+
+fn push_filter_conditions(builder: &mut QueryBuilder<'_, Sqlite>, filter: &PaperFilter) -> Result<(), AppError> {
+    if filter.trashed.unwrap_or(false) {
+        builder.push(" AND deleted_at IS NOT NULL");
+    } else {
+        builder.push(" AND deleted_at IS NULL");
+    }
+    if let Some(collection_id) = filter.collection_id {
+        builder
+            .push(" AND id IN (SELECT paper_id FROM paper_collections WHERE collection_id = ")
+            .push_bind(collection_id)
+            .push(")");
+    }
+
+    if let Some(venue_id) = filter.venue_id {
+        builder.push(" AND venue_id = ").push_bind(venue_id);
+    }
+
+    if let Some(status) = filter.status {
+        builder.push(" AND reading_status = ").push_bind(status);
+    }
+
+    if let Some(item_type) = filter.item_type {
+        builder.push(" AND item_type = ").push_bind(item_type);
+    }
+
+    if let Some(starred) = filter.starred {
+        builder.push(" AND starred = ").push_bind(starred);
+    }
+
+    let names = filter
+        .tags
+        .as_deref()
+        .map(|names| tags::split_tags(&names.join(",")))
+        .unwrap_or_default();
+    if !names.is_empty() {
+        builder.push(
+            " AND id IN (SELECT pt.paper_id FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for name in &names {
+            separated.push_bind(name.clone());
+        }
+        separated.push_unseparated(") GROUP BY pt.paper_id HAVING COUNT(*) = ");
+        builder.push_bind(names.len() as i64).push(")");
+    }
+
+    if let Some(year_from) = filter.year_from {
+        builder.push(" AND year >= ").push_bind(year_from);
+    }
+
+    if let Some(year_to) = filter.year_to {
+        builder.push(" AND year <= ").push_bind(year_to);
+    }
+
+    if let Some(journal) = filter.journal.as_deref().map(str::trim).filter(|j| !j.is_empty()) {
+        builder
+            .push(" AND journal LIKE ")
+            .push_bind(like_pattern(journal))
+            .push(" ESCAPE '\\'");
+    }
+
+    if let Some(query) = filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        builder
+            .push(" AND (id IN (SELECT rowid FROM papers_fts WHERE papers_fts MATCH ")
+            .push_bind(fts_query(query))
+            .push(") OR authors LIKE ")
+            .push_bind(like_pattern(query))
+            .push(" ESCAPE '\\')");
+    }
+
+    if let Some(search) = filter.search.as_deref() {
+        query::push_conditions(builder, &query::parse(search)?);
+    }
+    Ok(())
+}
+
+// %text% with LIKE's wildcards escaped; LIKE is case-insensitive for ASCII
+pub fn like_pattern(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+pub async fn get_paper(pool: &SqlitePool, id: i64) -> Result<Paper, AppError> {
+    sqlx::query_as::<_, Paper>(&format!("SELECT {} FROM papers WHERE id = ?", PAPER_COLUMNS))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch paper: {}", e)))?
+    .ok_or_else(|| AppError::NotFound(format!("Paper {} not found", id)))
+}
+
+pub async fn find_paper_by_hash(pool: &SqlitePool, file_hash: &str) -> Result<Option<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!("SELECT {} FROM papers WHERE file_hash = ? AND deleted_at IS NULL LIMIT 1", PAPER_COLUMNS))
+        .bind(file_hash)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to look up file hash: {}", e)))
+}
+
+pub async fn find_paper_by_path(pool: &SqlitePool, pdf_path: &str) -> Result<Option<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!("SELECT {} FROM papers WHERE pdf_path = ? LIMIT 1", PAPER_COLUMNS))
+        .bind(pdf_path)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to look up file: {}", e)))
+}
+
+pub async fn find_paper_by_pmid(pool: &SqlitePool, pmid: &str) -> Result<Option<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!("SELECT {} FROM papers WHERE pmid = ? LIMIT 1", PAPER_COLUMNS))
+        .bind(pmid)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to look up PMID: {}", e)))
+}
+
+// A paper outside the Trash sharing any of the identifiers, checked in this order
+pub async fn find_matching_paper(
+    pool: &SqlitePool,
+    doi: Option<&str>,
+    arxiv_id: Option<&str>,
+    pmid: Option<&str>,
+    file_hash: Option<&str>,
+) -> Result<Option<Paper>, AppError> {
+    let candidates = [
+        ("doi = ? COLLATE NOCASE", doi),
+        ("arxiv_id = ?", arxiv_id),
+        ("pmid = ?", pmid),
+        ("file_hash = ?", file_hash),
+    ];
+    for (condition, value) in candidates {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            continue;
+        };
+        let found = sqlx::query_as::<_, Paper>(&format!(
+            "SELECT {} FROM papers WHERE {} AND deleted_at IS NULL LIMIT 1",
+            PAPER_COLUMNS, condition
+        ))
+        .bind(value)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to look up paper: {}", e)))?;
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
+pub async fn get_papers_missing_hash(pool: &SqlitePool) -> Result<Vec<(i64, String)>, AppError> {
+    sqlx::query_as("SELECT id, pdf_path FROM papers WHERE file_hash IS NULL AND pdf_path != ''")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+// Newest first, so fresh imports get their thumbnails before a backlog of old papers
+pub async fn get_pdf_paths(pool: &SqlitePool) -> Result<Vec<(i64, String)>, AppError> {
+    sqlx::query_as("SELECT id, pdf_path FROM papers WHERE pdf_path != '' ORDER BY id DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+pub async fn set_pdf_path(pool: &SqlitePool, id: i64, pdf_path: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE papers SET pdf_path = ? WHERE id = ?")
+        .bind(pdf_path)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update PDF path: {}", e)))?;
+    Ok(())
+}
 
+pub async fn set_file_mode(pool: &SqlitePool, id: i64, file_mode: FileMode) -> Result<(), AppError> {
+    sqlx::query("UPDATE papers SET file_mode = ? WHERE id = ?")
+        .bind(file_mode)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update file mode: {}", e)))?;
+    Ok(())
+}
+
+pub async fn set_arxiv_id(pool: &SqlitePool, id: i64, arxiv_id: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE papers SET arxiv_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(arxiv_id)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update arXiv id: {}", e)))?;
+    Ok(())
+}
+
+pub async fn set_file_hash(pool: &SqlitePool, id: i64, file_hash: &str) -> Result<(), AppError> {
+    sqlx::query("UPDATE papers SET file_hash = ? WHERE id = ?")
+        .bind(file_hash)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to store file hash: {}", e)))?;
+    Ok(())
+}
+
+pub async fn set_file_stats(
+    pool: &SqlitePool,
+    id: i64,
+    page_count: Option<i64>,
+    file_size: i64,
+    pdf_version: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE papers SET page_count = ?, file_size = ?, pdf_version = ? WHERE id = ?")
+        .bind(page_count)
+        .bind(file_size)
+        .bind(pdf_version)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to store file stats: {}", e)))?;
+    Ok(())
+}
+
+pub async fn get_papers_missing_cite_key(pool: &SqlitePool) -> Result<Vec<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!("SELECT {} FROM papers WHERE cite_key IS NULL ORDER BY id", PAPER_COLUMNS))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+// Keys equal to `base` or starting with it
+pub async fn get_cite_keys_like(pool: &SqlitePool, base: &str) -> Result<Vec<String>, AppError> {
+    sqlx::query_scalar("SELECT cite_key FROM papers WHERE substr(cite_key, 1, length(?)) = ?")
+        .bind(base)
+        .bind(base)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch citation keys: {}", e)))
+}
+
+// Returns false if another paper already has the key
+pub async fn set_cite_key(pool: &SqlitePool, id: i64, cite_key: &str) -> Result<bool, AppError> {
+    match sqlx::query("UPDATE papers SET cite_key = ? WHERE id = ?")
+        .bind(cite_key)
+        .bind(id)
+        .execute(pool)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(false),
+        Err(e) => Err(AppError::Db(format!("Failed to store citation key: {}", e))),
+    }
+}
+
+// Papers come back in the order the ids were given; unknown ids are skipped
+pub async fn get_papers_by_ids(pool: &SqlitePool, ids: &[i64]) -> Result<Vec<Paper>, AppError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM papers WHERE id IN (", PAPER_COLUMNS));
+    let mut separated = builder.separated(", ");
+    for id in ids {
+        separated.push_bind(id);
+    }
+    separated.push_unseparated(")");
+
+    let mut papers = builder
+        .build_query_as::<Paper>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))?;
+
+    papers.sort_by_key(|p| ids.iter().position(|id| *id == p.id));
     Ok(papers)
 }
+
+pub async fn update_paper(
+    pool: &SqlitePool,
+    id: i64,
+    changes: &PaperUpdate,
+) -> Result<(), AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE papers SET
+            title = COALESCE(?, title),
+            authors = COALESCE(?, authors),
+            journal = COALESCE(?, journal),
+            year = COALESCE(?, year),
+            item_type = COALESCE(?, item_type),
+            isbn = COALESCE(?, isbn),
+            publisher = COALESCE(?, publisher),
+            institution = COALESCE(?, institution),
+            notes = COALESCE(?, notes),
+            doi = COALESCE(?, doi),
+            pmid = COALESCE(?, pmid),
+            abstract = COALESCE(?, abstract),
+            keywords = COALESCE(?, keywords),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#
+    )
+    .bind(&changes.title)
+    .bind(&changes.authors)
+    .bind(&changes.journal)
+    .bind(changes.year)
+    .bind(changes.item_type)
+    .bind(&changes.isbn)
+    .bind(&changes.publisher)
+    .bind(&changes.institution)
+    .bind(&changes.notes)
+    .bind(&changes.doi)
+    .bind(&changes.pmid)
+    .bind(&changes.r#abstract)
+    .bind(&changes.keywords)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update paper: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+
+    if let Some(names) = &changes.tags {
+        tags::set_paper_tags(pool, id, &tags::split_tags(names)).await?;
+    }
+
+    if let Some(names) = &changes.authors {
+        authors::set_paper_authors(pool, id, Some(names)).await?;
+    }
+
+    if let Some(journal) = &changes.journal {
+        venues::set_paper_venue(pool, id, Some(journal)).await?;
+    }
+
+    if changes.title.is_some() || changes.r#abstract.is_some() || changes.keywords.is_some() {
+        sqlx::query(
+            "UPDATE papers_fts SET (title, abstract, keywords) = \
+             (SELECT title, abstract, keywords FROM papers WHERE id = ?) WHERE rowid = ?",
+        )
+        .bind(id)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update search index: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+// Applies `changes` to every paper in one transaction. A paper that fails is rolled back on
+// its own and reported in its result; the rest are still saved.
+pub async fn update_papers_bulk(
+    pool: &SqlitePool,
+    ids: &[i64],
+    changes: &BulkUpdate,
+) -> Result<Vec<BulkUpdateResult>, AppError> {
+    let add_tags = changes.add_tags.as_deref().map(tags::split_tags).unwrap_or_default();
+    let remove_tags = changes.remove_tags.as_deref().map(tags::split_tags).unwrap_or_default();
+
+    let mut tx = pool.begin().await?;
+    let mut errors = Vec::with_capacity(ids.len());
+    for id in ids {
+        // A nested transaction is a savepoint, so only this paper's changes are undone on error
+        let mut savepoint = tx.begin().await?;
+        let result = update_paper_in(&mut savepoint, *id, changes, &add_tags, &remove_tags).await;
+        match result {
+            Ok(()) => savepoint.commit().await?,
+            Err(_) => savepoint.rollback().await?,
+        }
+        errors.push(result.err().map(|e| e.to_string()));
+    }
+    tx.commit().await?;
+
+    let updated: Vec<i64> = ids.iter().zip(&errors).filter(|(_, e)| e.is_none()).map(|(id, _)| *id).collect();
+    let mut papers: HashMap<i64, Paper> = get_papers_by_ids(pool, &updated)
+        .await?
+        .into_iter()
+        .map(|paper| (paper.id, paper))
+        .collect();
+    Ok(ids
+        .iter()
+        .zip(errors)
+        .map(|(id, error)| BulkUpdateResult { paper_id: *id, paper: papers.remove(id), error })
+        .collect())
+}
+
+async fn update_paper_in(
+    tx: &mut Transaction<'_, Sqlite>,
+    id: i64,
+    changes: &BulkUpdate,
+    add_tags: &[String],
+    remove_tags: &[String],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to update paper: {}", e));
+    let result = sqlx::query(
+        r#"
+        UPDATE papers SET
+            authors = COALESCE(?, authors),
+            journal = COALESCE(?, journal),
+            year = COALESCE(?, year),
+            reading_status = COALESCE(?, reading_status),
+            priority = COALESCE(?, priority),
+            starred = COALESCE(?, starred),
+            rating = COALESCE(?, rating),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#
+    )
+    .bind(&changes.authors)
+    .bind(&changes.journal)
+    .bind(changes.year)
+    .bind(changes.reading_status)
+    .bind(changes.priority)
+    .bind(changes.starred)
+    .bind(changes.rating)
+    .bind(id)
+    .execute(&mut **tx)
+    .await
+    .map_err(err)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+
+    if let Some(names) = &changes.authors {
+        authors::set_paper_authors_in(tx, id, Some(names)).await.map_err(err)?;
+    }
+    if let Some(journal) = &changes.journal {
+        venues::set_paper_venue_in(tx, id, Some(journal)).await.map_err(err)?;
+    }
+
+    for name in add_tags {
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(name)
+            .execute(&mut **tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT ?, id FROM tags WHERE name = ?")
+            .bind(id)
+            .bind(name)
+            .execute(&mut **tx)
+            .await
+            .map_err(err)?;
+    }
+    for name in remove_tags {
+        sqlx::query("DELETE FROM paper_tags WHERE paper_id = ? AND tag_id IN (SELECT id FROM tags WHERE name = ?)")
+            .bind(id)
+            .bind(name)
+            .execute(&mut **tx)
+            .await
+            .map_err(err)?;
+    }
+
+    Ok(())
+}
+
+pub async fn set_reading_status(pool: &SqlitePool, id: i64, status: ReadingStatus) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE papers SET reading_status = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(status)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update reading status: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+    Ok(())
+}
+
+// None clears the rating
+pub async fn set_rating(pool: &SqlitePool, id: i64, rating: Option<i64>) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE papers SET rating = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(rating)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update rating: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+    Ok(())
+}
+
+pub async fn set_priority(pool: &SqlitePool, id: i64, priority: Priority) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE papers SET priority = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(priority)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update priority: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+    Ok(())
+}
+
+// Returns the new value
+pub async fn toggle_star(pool: &SqlitePool, id: i64) -> Result<bool, AppError> {
+    let starred: Option<(bool,)> = sqlx::query_as(
+        "UPDATE papers SET starred = NOT starred, updated_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING starred",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update star: {}", e)))?;
+
+    starred
+        .map(|(starred,)| starred)
+        .ok_or_else(|| AppError::NotFound(format!("Paper {} not found", id)))
+}
+
+// Opening a paper somewhere past the start marks an unread paper as being read
+pub async fn set_reading_position(pool: &SqlitePool, id: i64, page: i64) -> Result<(), AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE papers SET
+            last_read_page = ?,
+            reading_status = CASE WHEN reading_status = 'unread' AND ? > 1 THEN 'reading' ELSE reading_status END
+        WHERE id = ?
+        "#
+    )
+    .bind(page)
+    .bind(page)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save reading position: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+    Ok(())
+}
+
+// The viewer asks for a PDF in several range requests and may reload it, so loads within
+// a minute of the last one count as the same open
+pub async fn record_open(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE papers SET
+            open_count = open_count + CASE
+                WHEN last_opened_at IS NULL OR last_opened_at < datetime('now', '-1 minute') THEN 1 ELSE 0 END,
+            last_opened_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#
+    )
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to record open: {}", e)))?;
+    Ok(())
+}
+
+// Most recently opened first, for "Continue reading"
+pub async fn get_recent_papers(pool: &SqlitePool, limit: i64) -> Result<Vec<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!(
+        "SELECT {} FROM papers WHERE last_opened_at IS NOT NULL AND deleted_at IS NULL \
+         ORDER BY last_opened_at DESC, id DESC LIMIT ?",
+        PAPER_COLUMNS
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch recent papers: {}", e)))
+}
+
+// `trashed` moves the paper to the Trash (true) or back out of it (false)
+pub async fn set_trashed(pool: &SqlitePool, id: i64, trashed: bool) -> Result<(), AppError> {
+    let result = sqlx::query(
+        "UPDATE papers SET deleted_at = CASE WHEN ? THEN COALESCE(deleted_at, CURRENT_TIMESTAMP) END WHERE id = ?",
+    )
+    .bind(trashed)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update Trash: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+    Ok(())
+}
+
+// Trashed papers, or only those trashed at least `older_than_days` days ago
+pub async fn get_trashed_papers(pool: &SqlitePool, older_than_days: Option<i64>) -> Result<Vec<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!(
+        "SELECT {} FROM papers WHERE deleted_at IS NOT NULL AND (? IS NULL OR deleted_at <= datetime('now', '-' || ? || ' days')) ORDER BY deleted_at",
+        PAPER_COLUMNS
+    ))
+    .bind(older_than_days)
+    .bind(older_than_days)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch the Trash: {}", e)))
+}
+
+pub async fn delete_paper(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query("DELETE FROM papers WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", id)));
+    }
+
+    sqlx::query("DELETE FROM paper_collections WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_tags WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_authors WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM annotations WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM note_revisions WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM citation_metrics WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM citation_entries WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_links WHERE citing_paper_id = ? OR cited_paper_id = ?")
+        .bind(id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_references WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("UPDATE paper_references SET matched_paper_id = NULL WHERE matched_paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_ocr WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    // The Zotero item is left alone; deletions are not synced
+    sqlx::query("DELETE FROM zotero_links WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM papers_fts WHERE rowid = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update search index: {}", e)))?;
+
+    sqlx::query("DELETE FROM pages_fts WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update search index: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_embeddings WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update search index: {}", e)))?;
+
+    sqlx::query("DELETE FROM summaries WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_keywords WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM paper_revisions WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM arxiv_versions WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    sqlx::query("DELETE FROM pending_lookups WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete paper: {}", e)))?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub struct PaperText {
+    pub title: String,
+    pub r#abstract: Option<String>,
+    // The indexed PDF or OCR text
+    pub content: Option<String>,
+}
+
+pub async fn get_paper_text(pool: &SqlitePool, paper_id: i64) -> Result<PaperText, AppError> {
+    let (title, r#abstract, content): (String, Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT p.title, p.abstract, f.content FROM papers p \
+         LEFT JOIN papers_fts f ON f.rowid = p.id WHERE p.id = ?",
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to read paper text: {}", e)))?
+    .ok_or_else(|| AppError::NotFound(format!("Paper {} not found", paper_id)))?;
+    Ok(PaperText { title, r#abstract, content })
+}
+
+// `pages` is the text of each page in order, empty for a paper without a PDF
+pub async fn index_paper_text(
+    pool: &SqlitePool,
+    id: i64,
+    title: &str,
+    pages: &[String],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to update search index: {}", e));
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM papers_fts WHERE rowid = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    sqlx::query(
+        "INSERT INTO papers_fts (rowid, title, content, abstract, keywords) \
+         SELECT ?, ?, ?, abstract, keywords FROM papers WHERE id = ?",
+    )
+    .bind(id)
+    .bind(title)
+    .bind(pages.join("\n\n"))
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(err)?;
+
+    sqlx::query("DELETE FROM pages_fts WHERE paper_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    for (index, content) in pages.iter().enumerate() {
+        sqlx::query("INSERT INTO pages_fts (content, paper_id, page) VALUES (?, ?, ?)")
+            .bind(content)
+            .bind(id)
+            .bind(index as i64 + 1)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct IndexHealth {
+    // Papers in the library, the Trash included
+    pub papers: i64,
+    // Papers without a row in the full-text index; "Rebuild search index" adds them
+    pub unindexed: i64,
+    // Index rows left behind by papers that no longer exist
+    pub orphaned: i64,
+    // Papers with a PDF but no per-page text, so search hits can't name a page
+    pub missing_pages: i64,
+    // What FTS5's own consistency check found wrong, None when both indexes pass
+    pub integrity_error: Option<String>,
+}
+
+pub async fn get_index_health(pool: &SqlitePool) -> Result<IndexHealth, AppError> {
+    let count = |sql: &'static str| async move {
+        sqlx::query_scalar::<_, i64>(sql)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to check search index: {}", e)))
+    };
+
+    let mut integrity_errors = Vec::new();
+    for table in ["papers_fts", "pages_fts"] {
+        let check = format!("INSERT INTO {0} ({0}) VALUES ('integrity-check')", table);
+        if let Err(e) = sqlx::query(&check).execute(pool).await {
+            integrity_errors.push(format!("{}: {}", table, e));
+        }
+    }
+
+    Ok(IndexHealth {
+        papers: count("SELECT COUNT(*) FROM papers").await?,
+        unindexed: count("SELECT COUNT(*) FROM papers WHERE id NOT IN (SELECT rowid FROM papers_fts)").await?,
+        orphaned: count("SELECT COUNT(*) FROM papers_fts WHERE rowid NOT IN (SELECT id FROM papers)").await?,
+        missing_pages: count(
+            "SELECT COUNT(*) FROM papers WHERE pdf_path != '' AND id NOT IN (SELECT paper_id FROM pages_fts)",
+        )
+        .await?,
+        integrity_error: (!integrity_errors.is_empty()).then(|| integrity_errors.join("; ")),
+    })
+}
+
+// Papers that have a PDF but no per-page text yet, e.g. indexed before pages were stored
+pub async fn get_papers_missing_pages(pool: &SqlitePool) -> Result<Vec<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!(
+        "SELECT {} FROM papers WHERE pdf_path != '' AND id NOT IN (SELECT paper_id FROM pages_fts)",
+        PAPER_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+pub async fn search_papers(
+    pool: &SqlitePool,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchResult>, AppError> {
+    let match_expr = fts_query(query);
+    if match_expr.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut results = sqlx::query_as::<_, SearchResult>(
+        r#"
+        SELECT p.id, p.title, p.authors, p.year, p.pdf_path,
+               snippet(papers_fts, 1, ?, ?, '…', 16) AS snippet,
+               bm25(papers_fts, 10.0, 1.0, 3.0, 5.0) AS rank
+        FROM papers_fts
+        JOIN papers p ON p.id = papers_fts.rowid
+        WHERE papers_fts MATCH ? AND p.deleted_at IS NULL
+        ORDER BY rank
+        LIMIT ?
+        "#
+    )
+    .bind(MATCH_OPEN)
+    .bind(MATCH_CLOSE)
+    .bind(&match_expr)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Search failed: {}", e)))?;
+
+    for result in &mut results {
+        result.snippet = marked_snippet(&result.snippet);
+        result.matches = find_page_matches(pool, result.id, &match_expr, Some(MATCHES_PER_RESULT)).await?;
+    }
+
+    // Fill up with titles that are close, so typos still find the paper
+    let wanted = (limit.max(0) as usize).saturating_sub(results.len());
+    if wanted > 0 {
+        let found: Vec<i64> = results.iter().map(|r| r.id).collect();
+        results.extend(fuzzy::search_titles(pool, query, &found, wanted).await?);
+    }
+    Ok(results)
+}
+
+const MATCHES_PER_RESULT: usize = 3;
+// Characters of context on each side of a hit
+const MATCH_CONTEXT: usize = 60;
+// Marks FTS5 puts around matched terms; control characters don't turn up in extracted text
+const MATCH_OPEN: &str = "\u{2}";
+const MATCH_CLOSE: &str = "\u{3}";
+
+// Every hit of `query` in the paper's text, page by page, for jumping through them in the viewer
+pub async fn search_paper_pages(pool: &SqlitePool, paper_id: i64, query: &str) -> Result<Vec<PageMatch>, AppError> {
+    let match_expr = fts_query(query);
+    if match_expr.is_empty() {
+        return Ok(Vec::new());
+    }
+    find_page_matches(pool, paper_id, &match_expr, None).await
+}
+
+async fn find_page_matches(
+    pool: &SqlitePool,
+    paper_id: i64,
+    match_expr: &str,
+    limit: Option<usize>,
+) -> Result<Vec<PageMatch>, AppError> {
+    // Best pages first when only a few are wanted, in page order otherwise
+    let order = if limit.is_some() { "rank" } else { "page" };
+    let pages: Vec<(i64, String)> = sqlx::query_as(&format!(
+        "SELECT page, highlight(pages_fts, 0, ?, ?) FROM pages_fts \
+         WHERE pages_fts MATCH ? AND paper_id = ? ORDER BY {}",
+        order
+    ))
+    .bind(MATCH_OPEN)
+    .bind(MATCH_CLOSE)
+    .bind(match_expr)
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Search failed: {}", e)))?;
+
+    let mut matches = Vec::new();
+    for (page, marked) in pages {
+        for m in marked_matches(page, &marked) {
+            if limit.is_some_and(|limit| matches.len() >= limit) {
+                return Ok(matches);
+            }
+            matches.push(m);
+        }
+    }
+    Ok(matches)
+}
+
+// Splits text returned by highlight() back into the page text and one PageMatch per marked hit
+fn marked_matches(page: i64, marked: &str) -> Vec<PageMatch> {
+    let open = MATCH_OPEN.chars().next().unwrap_or_default();
+    let close = MATCH_CLOSE.chars().next().unwrap_or_default();
+
+    let mut text: Vec<char> = Vec::with_capacity(marked.len());
+    // (start, end) in chars of `text`
+    let mut hits: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+    for c in marked.chars() {
+        if c == open {
+            start = Some(text.len());
+        } else if c == close {
+            if let Some(start) = start.take() {
+                hits.push((start, text.len()));
+            }
+        } else {
+            text.push(c);
+        }
+    }
+
+    page_matches(page, &text, hits)
+}
+
+// One PageMatch per (start, end) hit, given in chars of `text`
+pub(crate) fn page_matches(page: i64, text: &[char], hits: Vec<(usize, usize)>) -> Vec<PageMatch> {
+    let utf16_offset = |chars: &[char]| chars.iter().map(|c| c.len_utf16()).sum::<usize>();
+    // Line breaks and runs of spaces from the PDF layout become single spaces
+    let collapse = |chars: &[char]| {
+        let mut out = String::with_capacity(chars.len());
+        for (i, c) in chars.iter().enumerate() {
+            if !c.is_whitespace() {
+                out.push(*c);
+            } else if i == 0 || !chars[i - 1].is_whitespace() {
+                out.push(' ');
+            }
+        }
+        out
+    };
+    hits.into_iter()
+        .map(|(start, end)| {
+            let from = start.saturating_sub(MATCH_CONTEXT);
+            let to = (end + MATCH_CONTEXT).min(text.len());
+            // The page text is whatever the PDF holds, and the snippet is shown as HTML
+            let snippet = format!(
+                "{}{}<mark>{}</mark>{}{}",
+                if from > 0 { "…" } else { "" },
+                escape_html(collapse(&text[from..start]).trim_start()),
+                escape_html(&collapse(&text[start..end])),
+                escape_html(collapse(&text[end..to]).trim_end()),
+                if to < text.len() { "…" } else { "" },
+            );
+            PageMatch {
+                page,
+                snippet,
+                start: utf16_offset(&text[..start]),
+                end: utf16_offset(&text[..end]),
+            }
+        })
+        .collect()
+}
+
+// The text of a snippet() escaped for showing as HTML, with its hits in <mark>
+fn marked_snippet(snippet: &str) -> String {
+    escape_html(snippet).replace(MATCH_OPEN, "<mark>").replace(MATCH_CLOSE, "</mark>")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// Quote every term so user input can't be parsed as FTS5 syntax
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub async fn add_watched_folder(
+    pool: &SqlitePool,
+    path: &str,
+    recursive: bool,
+    file_mode: FileMode,
+) -> Result<WatchedFolder, AppError> {
+    sqlx::query_as::<_, WatchedFolder>(
+        "INSERT INTO watched_folders (path, recursive, file_mode) VALUES (?, ?, ?) \
+         RETURNING id, path, recursive, file_mode, created_at"
+    )
+    .bind(path)
+    .bind(recursive)
+    .bind(file_mode)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict(format!("Already watching {}", path))
+        }
+        e => AppError::Db(format!("Failed to add watched folder: {}", e)),
+    })
+}
+
+pub async fn remove_watched_folder(pool: &SqlitePool, id: i64) -> Result<WatchedFolder, AppError> {
+    sqlx::query_as::<_, WatchedFolder>(
+        "DELETE FROM watched_folders WHERE id = ? RETURNING id, path, recursive, file_mode, created_at"
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to remove watched folder: {}", e)))?
+    .ok_or_else(|| AppError::NotFound(format!("Watched folder {} not found", id)))
+}
+
+pub async fn get_watched_folders(pool: &SqlitePool) -> Result<Vec<WatchedFolder>, AppError> {
+    sqlx::query_as::<_, WatchedFolder>(
+        "SELECT id, path, recursive, file_mode, created_at FROM watched_folders ORDER BY path"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch watched folders: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_page_text_around_marks() {
+        let text: Vec<char> = "a <script>alert(1)</script> & b".chars().collect();
+        let matches = page_matches(1, &text, vec![(3, 9)]);
+        assert_eq!(matches[0].snippet, "a &lt;<mark>script</mark>&gt;alert(1)&lt;/script&gt; &amp; b");
+        assert_eq!((matches[0].start, matches[0].end), (3, 9));
+    }
+
+    #[test]
+    fn escapes_snippets_around_marks() {
+        let snippet = format!("<img src=x onerror=alert(1)> {}deep{} & learning", MATCH_OPEN, MATCH_CLOSE);
+        assert_eq!(marked_snippet(&snippet), "&lt;img src=x onerror=alert(1)&gt; <mark>deep</mark> &amp; learning");
+    }
+}
@@ -0,0 +1,151 @@
+// src-tauri/src/db/annotations.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+// Rectangle coordinates are in PDF points on the given page, origin top-left
+#[derive(Serialize, FromRow)]
+pub struct Annotation {
+    pub id: i64,
+    pub paper_id: i64,
+    pub page: i64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub selected_text: Option<String>,
+    pub color: String,
+    pub comment: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NewAnnotation {
+    pub paper_id: i64,
+    pub page: i64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub selected_text: Option<String>,
+    pub color: Option<String>,
+    pub comment: Option<String>,
+}
+
+// Fields left as None keep their current value
+#[derive(Deserialize, Default)]
+pub struct AnnotationUpdate {
+    pub page: Option<i64>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub color: Option<String>,
+    pub comment: Option<String>,
+}
+
+const DEFAULT_COLOR: &str = "#ffeb3b";
+
+const ANNOTATION_COLUMNS: &str =
+    "id, paper_id, page, x, y, width, height, selected_text, color, comment, created_at, updated_at";
+
+pub async fn get_annotation(pool: &SqlitePool, id: i64) -> Result<Annotation, AppError> {
+    sqlx::query_as::<_, Annotation>(&format!("SELECT {} FROM annotations WHERE id = ?", ANNOTATION_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch annotation: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Annotation {} not found", id)))
+}
+
+pub async fn get_annotations(pool: &SqlitePool, paper_id: i64) -> Result<Vec<Annotation>, AppError> {
+    sqlx::query_as::<_, Annotation>(&format!(
+        "SELECT {} FROM annotations WHERE paper_id = ? ORDER BY page, y, x",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch annotations: {}", e)))
+}
+
+pub async fn add_annotation(pool: &SqlitePool, annotation: &NewAnnotation) -> Result<Annotation, AppError> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO annotations (paper_id, page, x, y, width, height, selected_text, color, comment)
+        SELECT id, ?, ?, ?, ?, ?, ?, ?, ? FROM papers WHERE id = ?
+        "#
+    )
+    .bind(annotation.page)
+    .bind(annotation.x)
+    .bind(annotation.y)
+    .bind(annotation.width)
+    .bind(annotation.height)
+    .bind(&annotation.selected_text)
+    .bind(annotation.color.as_deref().unwrap_or(DEFAULT_COLOR))
+    .bind(&annotation.comment)
+    .bind(annotation.paper_id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to add annotation: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", annotation.paper_id)));
+    }
+
+    get_annotation(pool, result.last_insert_rowid()).await
+}
+
+pub async fn update_annotation(
+    pool: &SqlitePool,
+    id: i64,
+    changes: &AnnotationUpdate,
+) -> Result<Annotation, AppError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE annotations SET
+            page = COALESCE(?, page),
+            x = COALESCE(?, x),
+            y = COALESCE(?, y),
+            width = COALESCE(?, width),
+            height = COALESCE(?, height),
+            color = COALESCE(?, color),
+            comment = COALESCE(?, comment),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?
+        "#
+    )
+    .bind(changes.page)
+    .bind(changes.x)
+    .bind(changes.y)
+    .bind(changes.width)
+    .bind(changes.height)
+    .bind(&changes.color)
+    .bind(&changes.comment)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update annotation: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Annotation {} not found", id)));
+    }
+
+    get_annotation(pool, id).await
+}
+
+pub async fn delete_annotation(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM annotations WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete annotation: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Annotation {} not found", id)));
+    }
+
+    Ok(())
+}
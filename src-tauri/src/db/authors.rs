@@ -0,0 +1,188 @@
+// src-tauri/src/db/authors.rs
+
+use super::{journal, Paper, PAPER_COLUMNS};
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Author {
+    pub id: i64,
+    pub name: String,
+    pub paper_count: i64,
+    pub created_at: Option<String>,
+}
+
+const AUTHOR_SELECT: &str = r#"
+    SELECT a.id, a.name, a.created_at,
+           (SELECT COUNT(DISTINCT pa.paper_id) FROM paper_authors pa JOIN papers p ON p.id = pa.paper_id
+            WHERE pa.author_id = a.id AND p.deleted_at IS NULL) AS paper_count
+    FROM authors a
+"#;
+
+// Authors are stored "First Last, First Last"
+pub fn split_authors(authors: &str) -> Vec<String> {
+    authors
+        .split(',')
+        .map(|name| name.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+pub async fn get_author(pool: &SqlitePool, id: i64) -> Result<Author, AppError> {
+    sqlx::query_as::<_, Author>(&format!("{} WHERE a.id = ?", AUTHOR_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch author: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Author {} not found", id)))
+}
+
+// Authors of papers outside the Trash, by name
+pub async fn get_authors(pool: &SqlitePool) -> Result<Vec<Author>, AppError> {
+    sqlx::query_as::<_, Author>(&format!(
+        "SELECT * FROM ({}) WHERE paper_count > 0 ORDER BY name COLLATE NOCASE",
+        AUTHOR_SELECT
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch authors: {}", e)))
+}
+
+// Newest first
+pub async fn get_papers_by_author(pool: &SqlitePool, author_id: i64) -> Result<Vec<Paper>, AppError> {
+    sqlx::query_as::<_, Paper>(&format!(
+        "SELECT {} FROM papers WHERE deleted_at IS NULL \
+         AND id IN (SELECT paper_id FROM paper_authors WHERE author_id = ?) \
+         ORDER BY year DESC NULLS LAST, id DESC",
+        PAPER_COLUMNS
+    ))
+    .bind(author_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch the papers: {}", e)))
+}
+
+pub async fn set_paper_authors(pool: &SqlitePool, paper_id: i64, authors: Option<&str>) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    set_paper_authors_in(&mut tx, paper_id, authors)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update authors: {}", e)))?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// Re-parses the paper's byline into paper_authors, for use inside a caller's transaction
+pub async fn set_paper_authors_in(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    authors: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM paper_authors WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for (position, name) in split_authors(authors.unwrap_or_default()).iter().enumerate() {
+        let author_id = find_or_create(&mut *conn, name).await?;
+        sqlx::query("INSERT INTO paper_authors (paper_id, author_id, position) VALUES (?, ?, ?)")
+            .bind(paper_id)
+            .bind(author_id)
+            .bind(position as i64)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    // Authors nobody references any more, unless a merge pointed other spellings at them
+    sqlx::query(
+        "DELETE FROM authors WHERE id NOT IN (SELECT author_id FROM paper_authors) \
+         AND id NOT IN (SELECT author_id FROM author_aliases)",
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn find_or_create(conn: &mut SqliteConnection, name: &str) -> Result<i64, sqlx::Error> {
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT author_id FROM author_aliases WHERE name = ?1 UNION ALL SELECT id FROM authors WHERE name = ?1 LIMIT 1",
+    )
+    .bind(name)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar("INSERT INTO authors (name) VALUES (?) RETURNING id")
+        .bind(name)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+// Folds `merge_ids` into `keep_id`: their papers move over and their names become aliases,
+// so later imports spelling the name that way land on the kept author too. Journaled, so it
+// can be undone.
+pub async fn merge_authors(pool: &SqlitePool, keep_id: i64, merge_ids: &[i64]) -> Result<Author, AppError> {
+    let keep = get_author(pool, keep_id).await?;
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to merge authors: {}", e));
+
+    let mut tx = pool.begin().await?;
+    let merge = journal::snapshot_author_merge(&mut tx, keep_id, merge_ids).await.map_err(err)?;
+    for merge_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        let result = sqlx::query(
+            "INSERT OR REPLACE INTO author_aliases (name, author_id) SELECT name, ? FROM authors WHERE id = ?",
+        )
+        .bind(keep_id)
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Author {} not found", merge_id)));
+        }
+
+        sqlx::query("UPDATE author_aliases SET author_id = ? WHERE author_id = ?")
+            .bind(keep_id)
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("UPDATE paper_authors SET author_id = ? WHERE author_id = ?")
+            .bind(keep_id)
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("DELETE FROM authors WHERE id = ?")
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+    let merged = match merge.authors.as_slice() {
+        [author] => format!("\"{}\"", author.name),
+        authors => format!("{} authors", authors.len()),
+    };
+    let description = format!("Merge {} into \"{}\"", merged, keep.name);
+    journal::record_in(&mut tx, &description, &journal::UndoData::Authors(merge)).await?;
+    tx.commit().await?;
+
+    get_author(pool, keep_id).await
+}
+
+// Fills paper_authors for papers added before authors were normalized
+pub async fn backfill_authors(pool: &SqlitePool) -> Result<usize, AppError> {
+    let papers: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, authors FROM papers WHERE authors IS NOT NULL AND trim(authors) != '' \
+         AND id NOT IN (SELECT paper_id FROM paper_authors)",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))?;
+
+    for (id, authors) in &papers {
+        set_paper_authors(pool, *id, Some(authors)).await?;
+    }
+    Ok(papers.len())
+}
@@ -0,0 +1,143 @@
+// src-tauri/src/db/citations.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum CitationKind {
+    // A work this paper cites
+    Reference,
+    // A work that cites this paper
+    Citation,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct CitationMetrics {
+    pub paper_id: i64,
+    pub s2_paper_id: String,
+    pub citation_count: i64,
+    pub influential_citation_count: i64,
+    pub reference_count: i64,
+    pub fetched_at: Option<String>,
+}
+
+#[derive(Serialize, FromRow, Clone, Default)]
+pub struct CitedWork {
+    pub s2_paper_id: Option<String>,
+    pub title: String,
+    pub authors: Option<String>,
+    pub year: Option<i64>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CitationData {
+    // None until citations have been fetched for the paper
+    pub metrics: Option<CitationMetrics>,
+    pub references: Vec<CitedWork>,
+    pub citations: Vec<CitedWork>,
+}
+
+pub async fn get_citation_data(pool: &SqlitePool, paper_id: i64) -> Result<CitationData, AppError> {
+    let metrics = sqlx::query_as::<_, CitationMetrics>(
+        r#"
+        SELECT paper_id, s2_paper_id, citation_count, influential_citation_count, reference_count, fetched_at
+        FROM citation_metrics WHERE paper_id = ?
+        "#
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch citations: {}", e)))?;
+
+    Ok(CitationData {
+        metrics,
+        references: get_entries(pool, paper_id, CitationKind::Reference).await?,
+        citations: get_entries(pool, paper_id, CitationKind::Citation).await?,
+    })
+}
+
+async fn get_entries(pool: &SqlitePool, paper_id: i64, kind: CitationKind) -> Result<Vec<CitedWork>, AppError> {
+    sqlx::query_as::<_, CitedWork>(
+        r#"
+        SELECT s2_paper_id, title, authors, year, doi, arxiv_id FROM citation_entries
+        WHERE paper_id = ? AND kind = ?
+        ORDER BY position
+        "#
+    )
+    .bind(paper_id)
+    .bind(kind)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch citations: {}", e)))
+}
+
+// Replaces everything stored for the paper
+pub async fn save_citation_data(
+    pool: &SqlitePool,
+    metrics: &CitationMetrics,
+    references: &[CitedWork],
+    citations: &[CitedWork],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save citations: {}", e));
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO citation_metrics (paper_id, s2_paper_id, citation_count, influential_citation_count, reference_count)
+        SELECT id, ?, ?, ?, ? FROM papers WHERE id = ?
+        ON CONFLICT(paper_id) DO UPDATE SET
+            s2_paper_id = excluded.s2_paper_id,
+            citation_count = excluded.citation_count,
+            influential_citation_count = excluded.influential_citation_count,
+            reference_count = excluded.reference_count,
+            fetched_at = CURRENT_TIMESTAMP
+        "#
+    )
+    .bind(&metrics.s2_paper_id)
+    .bind(metrics.citation_count)
+    .bind(metrics.influential_citation_count)
+    .bind(metrics.reference_count)
+    .bind(metrics.paper_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(err)?;
+
+    sqlx::query("DELETE FROM citation_entries WHERE paper_id = ?")
+        .bind(metrics.paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    let entries = references
+        .iter()
+        .map(|work| (CitationKind::Reference, work))
+        .enumerate()
+        .chain(citations.iter().map(|work| (CitationKind::Citation, work)).enumerate());
+    for (position, (kind, work)) in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO citation_entries (paper_id, kind, position, s2_paper_id, title, authors, year, doi, arxiv_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(metrics.paper_id)
+        .bind(kind)
+        .bind(position as i64)
+        .bind(&work.s2_paper_id)
+        .bind(&work.title)
+        .bind(&work.authors)
+        .bind(work.year)
+        .bind(&work.doi)
+        .bind(&work.arxiv_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    }
+
+    Ok(tx.commit().await?)
+}
@@ -0,0 +1,161 @@
+// src-tauri/src/db/collections.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub paper_count: i64,
+    pub created_at: Option<String>,
+}
+
+const COLLECTION_SELECT: &str = r#"
+    SELECT c.id, c.name, c.parent_id, c.created_at,
+           (SELECT COUNT(*) FROM paper_collections pc JOIN papers p ON p.id = pc.paper_id
+            WHERE pc.collection_id = c.id AND p.deleted_at IS NULL) AS paper_count
+    FROM collections c
+"#;
+
+pub async fn get_collection(pool: &SqlitePool, id: i64) -> Result<Collection, AppError> {
+    sqlx::query_as::<_, Collection>(&format!("{} WHERE c.id = ?", COLLECTION_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch collection: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Collection {} not found", id)))
+}
+
+pub async fn get_collections(pool: &SqlitePool) -> Result<Vec<Collection>, AppError> {
+    sqlx::query_as::<_, Collection>(&format!("{} ORDER BY c.name COLLATE NOCASE", COLLECTION_SELECT))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch collections: {}", e)))
+}
+
+pub async fn find_collection(pool: &SqlitePool, name: &str, parent_id: Option<i64>) -> Result<Option<i64>, AppError> {
+    let found: Option<(i64,)> = sqlx::query_as("SELECT id FROM collections WHERE name = ? AND parent_id IS ? LIMIT 1")
+        .bind(name)
+        .bind(parent_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch collection: {}", e)))?;
+    Ok(found.map(|(id,)| id))
+}
+
+pub async fn create_collection(
+    pool: &SqlitePool,
+    name: &str,
+    parent_id: Option<i64>,
+) -> Result<Collection, AppError> {
+    if let Some(parent_id) = parent_id {
+        get_collection(pool, parent_id).await?;
+    }
+
+    let result = sqlx::query("INSERT INTO collections (name, parent_id) VALUES (?, ?)")
+        .bind(name)
+        .bind(parent_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to create collection: {}", e)))?;
+
+    get_collection(pool, result.last_insert_rowid()).await
+}
+
+pub async fn rename_collection(pool: &SqlitePool, id: i64, name: &str) -> Result<Collection, AppError> {
+    let result = sqlx::query("UPDATE collections SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to rename collection: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Collection {} not found", id)));
+    }
+
+    get_collection(pool, id).await
+}
+
+// Removes the collection and everything nested under it; the papers themselves stay
+pub async fn delete_collection(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let ids: Vec<(i64,)> = sqlx::query_as(
+        r#"
+        WITH RECURSIVE tree(id) AS (
+            SELECT id FROM collections WHERE id = ?
+            UNION ALL
+            SELECT c.id FROM collections c JOIN tree ON c.parent_id = tree.id
+        )
+        SELECT id FROM tree
+        "#
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to delete collection: {}", e)))?;
+
+    if ids.is_empty() {
+        return Err(AppError::NotFound(format!("Collection {} not found", id)));
+    }
+
+    for (collection_id,) in ids {
+        sqlx::query("DELETE FROM paper_collections WHERE collection_id = ?")
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to delete collection: {}", e)))?;
+        sqlx::query("DELETE FROM collections WHERE id = ?")
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to delete collection: {}", e)))?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// (paper_id, collection_id) for every paper filed in a collection
+pub async fn get_memberships(pool: &SqlitePool) -> Result<Vec<(i64, i64)>, AppError> {
+    sqlx::query_as("SELECT paper_id, collection_id FROM paper_collections ORDER BY paper_id, collection_id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch collection memberships: {}", e)))
+}
+
+pub async fn add_papers(pool: &SqlitePool, collection_id: i64, paper_ids: &[i64]) -> Result<(), AppError> {
+    get_collection(pool, collection_id).await?;
+
+    let mut tx = pool.begin().await?;
+    for paper_id in paper_ids {
+        sqlx::query("INSERT OR IGNORE INTO paper_collections (paper_id, collection_id) SELECT id, ? FROM papers WHERE id = ?")
+            .bind(collection_id)
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to add paper to collection: {}", e)))?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn remove_papers(pool: &SqlitePool, collection_id: i64, paper_ids: &[i64]) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    for paper_id in paper_ids {
+        sqlx::query("DELETE FROM paper_collections WHERE collection_id = ? AND paper_id = ?")
+            .bind(collection_id)
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to remove paper from collection: {}", e)))?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,276 @@
+// src-tauri/src/db/custom_fields.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum FieldType {
+    Text,
+    Number,
+    Date,
+    // One of a fixed list of options
+    Enum,
+}
+
+#[derive(Serialize, Clone)]
+pub struct CustomField {
+    pub id: i64,
+    pub name: String,
+    pub field_type: FieldType,
+    // Allowed values of an enum field, empty for the other types
+    pub options: Vec<String>,
+    pub paper_count: i64,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct CustomValue {
+    pub field_id: i64,
+    pub name: String,
+    pub field_type: FieldType,
+    pub value: String,
+}
+
+#[derive(FromRow)]
+struct FieldRow {
+    id: i64,
+    name: String,
+    field_type: FieldType,
+    options: String,
+    paper_count: i64,
+}
+
+impl From<FieldRow> for CustomField {
+    fn from(row: FieldRow) -> Self {
+        CustomField {
+            id: row.id,
+            name: row.name,
+            field_type: row.field_type,
+            options: serde_json::from_str(&row.options).unwrap_or_default(),
+            paper_count: row.paper_count,
+        }
+    }
+}
+
+const FIELD_SELECT: &str = r#"
+    SELECT f.id, f.name, f.field_type, f.options,
+           (SELECT COUNT(*) FROM custom_values v WHERE v.field_id = f.id) AS paper_count
+    FROM custom_fields f
+"#;
+
+// A name can be searched as `name:value`, so it is one word and doesn't shadow a built-in
+// search field
+pub fn validate_name(name: &str) -> Result<(), AppError> {
+    if name.is_empty() {
+        return Err(AppError::Validation("Field name can't be empty".to_string()));
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err(AppError::Validation(format!(
+            "Field name \"{}\" may only contain letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    if crate::query::FIELDS.iter().any(|f| f.eq_ignore_ascii_case(name)) {
+        return Err(AppError::Validation(format!("\"{}\" is already a search field", name)));
+    }
+    Ok(())
+}
+
+// The value as stored, or None to clear it. Blank values clear the field.
+pub fn normalize_value(field: &CustomField, value: &str) -> Result<Option<String>, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let normalized = match field.field_type {
+        FieldType::Text => value.to_string(),
+        FieldType::Number => value
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|n| n.to_string())
+            .ok_or_else(|| AppError::Validation(format!("{} must be a number, not \"{}\"", field.name, value)))?,
+        FieldType::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .map_err(|_| {
+                AppError::Validation(format!("{} must be a date like 2026-10-15, not \"{}\"", field.name, value))
+            })?,
+        FieldType::Enum => field
+            .options
+            .iter()
+            .find(|o| o.eq_ignore_ascii_case(value))
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Validation(format!("{} must be one of {}", field.name, field.options.join(", ")))
+            })?,
+    };
+    Ok(Some(normalized))
+}
+
+// Trimmed, without blanks or duplicates
+pub fn clean_options(options: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for option in options.iter().map(|o| o.trim()).filter(|o| !o.is_empty()) {
+        if !cleaned.iter().any(|c| c.eq_ignore_ascii_case(option)) {
+            cleaned.push(option.to_string());
+        }
+    }
+    cleaned
+}
+
+pub async fn get_fields(pool: &SqlitePool) -> Result<Vec<CustomField>, AppError> {
+    let rows = sqlx::query_as::<_, FieldRow>(&format!("{} ORDER BY f.name COLLATE NOCASE", FIELD_SELECT))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch custom fields: {}", e)))?;
+    Ok(rows.into_iter().map(CustomField::from).collect())
+}
+
+pub async fn get_field(pool: &SqlitePool, id: i64) -> Result<CustomField, AppError> {
+    sqlx::query_as::<_, FieldRow>(&format!("{} WHERE f.id = ?", FIELD_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch custom field: {}", e)))?
+        .map(CustomField::from)
+        .ok_or_else(|| AppError::NotFound(format!("Custom field {} not found", id)))
+}
+
+pub async fn find_field(pool: &SqlitePool, name: &str) -> Result<Option<CustomField>, AppError> {
+    let row = sqlx::query_as::<_, FieldRow>(&format!("{} WHERE f.name = ? COLLATE NOCASE", FIELD_SELECT))
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch custom field: {}", e)))?;
+    Ok(row.map(CustomField::from))
+}
+
+// Expects a validated name and cleaned options
+pub async fn create_field(
+    pool: &SqlitePool,
+    name: &str,
+    field_type: FieldType,
+    options: &[String],
+) -> Result<CustomField, AppError> {
+    let options = serde_json::to_string(options).map_err(|e| e.to_string())?;
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO custom_fields (name, field_type, options) VALUES (?, ?, ?) RETURNING id",
+    )
+    .bind(name)
+    .bind(field_type)
+    .bind(options)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => format!("There is already a field called {}", name),
+        e => format!("Failed to create custom field: {}", e),
+    })?;
+
+    get_field(pool, id).await
+}
+
+// Expects a validated name and cleaned options; the type can't be changed
+pub async fn update_field(
+    pool: &SqlitePool,
+    id: i64,
+    name: Option<&str>,
+    options: Option<&[String]>,
+) -> Result<CustomField, AppError> {
+    let options = options.map(serde_json::to_string).transpose().map_err(|e| e.to_string())?;
+    let result = sqlx::query(
+        "UPDATE custom_fields SET name = COALESCE(?, name), options = COALESCE(?, options) WHERE id = ?",
+    )
+    .bind(name)
+    .bind(options)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            format!("There is already a field called {}", name.unwrap_or_default())
+        }
+        e => format!("Failed to update custom field: {}", e),
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Custom field {} not found", id)));
+    }
+    get_field(pool, id).await
+}
+
+// Values of the field go with it
+pub async fn delete_field(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM custom_fields WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete custom field: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Custom field {} not found", id)));
+    }
+    Ok(())
+}
+
+// How many papers hold `value` in the field
+pub async fn count_values(pool: &SqlitePool, field_id: i64, value: &str) -> Result<i64, AppError> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM custom_values WHERE field_id = ? AND value = ?")
+        .bind(field_id)
+        .bind(value)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch custom values: {}", e)))
+}
+
+// The paper's values, by field name
+pub async fn get_values(pool: &SqlitePool, paper_id: i64) -> Result<Vec<CustomValue>, AppError> {
+    sqlx::query_as::<_, CustomValue>(
+        "SELECT v.field_id, f.name, f.field_type, v.value FROM custom_values v \
+         JOIN custom_fields f ON f.id = v.field_id WHERE v.paper_id = ? ORDER BY f.name COLLATE NOCASE",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch custom values: {}", e)))
+}
+
+// Every value in the library as (paper_id, field_id, value), for exports
+pub async fn get_all_values(pool: &SqlitePool) -> Result<Vec<(i64, i64, String)>, AppError> {
+    sqlx::query_as("SELECT paper_id, field_id, value FROM custom_values")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch custom values: {}", e)))
+}
+
+// Expects a value from normalize_value; None clears it
+pub async fn set_value(pool: &SqlitePool, paper_id: i64, field_id: i64, value: Option<&str>) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save custom value: {}", e));
+    match value {
+        Some(value) => {
+            let result = sqlx::query(
+                "INSERT INTO custom_values (paper_id, field_id, value) SELECT id, ?, ? FROM papers WHERE id = ? \
+                 ON CONFLICT(paper_id, field_id) DO UPDATE SET value = excluded.value",
+            )
+            .bind(field_id)
+            .bind(value)
+            .bind(paper_id)
+            .execute(pool)
+            .await
+            .map_err(err)?;
+            if result.rows_affected() == 0 {
+                return Err(AppError::NotFound(format!("Paper {} not found", paper_id)));
+            }
+        }
+        None => {
+            sqlx::query("DELETE FROM custom_values WHERE paper_id = ? AND field_id = ?")
+                .bind(paper_id)
+                .bind(field_id)
+                .execute(pool)
+                .await
+                .map_err(err)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// src-tauri/src/db/embeddings.rs
+
+use crate::error::AppError;
+use sqlx::SqlitePool;
+
+pub struct StoredChunk {
+    pub paper_id: i64,
+    pub text: String,
+    pub vector: Vec<f32>,
+}
+
+// Papers outside the Trash without vectors from `model`
+pub async fn get_papers_missing_embeddings(pool: &SqlitePool, model: &str) -> Result<Vec<(i64, String)>, AppError> {
+    sqlx::query_as(
+        "SELECT id, title FROM papers WHERE deleted_at IS NULL \
+         AND id NOT IN (SELECT paper_id FROM paper_embeddings WHERE model = ?) ORDER BY id",
+    )
+    .bind(model)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+// Replaces the paper's chunks, numbered in order
+pub async fn save_embeddings(
+    pool: &SqlitePool,
+    paper_id: i64,
+    model: &str,
+    chunks: &[(String, Vec<f32>)],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save embeddings: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    sqlx::query("DELETE FROM paper_embeddings WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    for (index, (text, vector)) in chunks.iter().enumerate() {
+        sqlx::query("INSERT INTO paper_embeddings (paper_id, chunk, model, text, vector) VALUES (?, ?, ?, ?, ?)")
+            .bind(paper_id)
+            .bind(index as i64)
+            .bind(model)
+            .bind(text)
+            .bind(to_blob(vector))
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await.map_err(err)
+}
+
+// Every chunk from `model`, including those of papers in the Trash. Search keeps these in
+// memory and leaves trashed papers out with get_searchable_ids.
+pub async fn get_embeddings(pool: &SqlitePool, model: &str) -> Result<Vec<StoredChunk>, AppError> {
+    let rows: Vec<(i64, String, Vec<u8>)> =
+        sqlx::query_as("SELECT paper_id, text, vector FROM paper_embeddings WHERE model = ?")
+            .bind(model)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to read embeddings: {}", e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(paper_id, text, blob)| StoredChunk { paper_id, text, vector: from_blob(&blob) })
+        .collect())
+}
+
+// Papers outside the Trash
+pub async fn get_searchable_ids(pool: &SqlitePool) -> Result<Vec<i64>, AppError> {
+    sqlx::query_scalar("SELECT id FROM papers WHERE deleted_at IS NULL")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+pub async fn clear_embeddings(pool: &SqlitePool) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM paper_embeddings")
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to clear embeddings: {}", e)))?;
+    Ok(())
+}
+
+pub async fn count_embedded_papers(pool: &SqlitePool, model: &str) -> Result<i64, AppError> {
+    sqlx::query_scalar("SELECT COUNT(DISTINCT paper_id) FROM paper_embeddings WHERE model = ?")
+        .bind(model)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to count embeddings: {}", e)))
+}
+
+fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
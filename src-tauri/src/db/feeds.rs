@@ -0,0 +1,210 @@
+// src-tauri/src/db/feeds.rs
+
+use crate::feeds::FeedItem;
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Feed {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    pub last_polled_at: Option<String>,
+    pub last_error: Option<String>,
+    // Entries neither imported nor dismissed
+    pub unread_count: i64,
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct FeedEntry {
+    pub id: i64,
+    pub feed_id: i64,
+    pub title: String,
+    pub authors: Option<String>,
+    pub link: Option<String>,
+    pub summary: Option<String>,
+    pub published: Option<String>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    // The library paper it was imported as
+    pub paper_id: Option<i64>,
+    pub dismissed: bool,
+    pub created_at: Option<String>,
+}
+
+const FEED_COLUMNS: &str = "id, url, title, last_polled_at, last_error, \
+    (SELECT COUNT(*) FROM feed_entries e WHERE e.feed_id = feeds.id AND e.paper_id IS NULL AND NOT e.dismissed) AS unread_count, \
+    created_at";
+
+const ENTRY_COLUMNS: &str =
+    "id, feed_id, title, authors, link, summary, published, doi, arxiv_id, paper_id, dismissed, created_at";
+
+pub async fn get_feeds(pool: &SqlitePool) -> Result<Vec<Feed>, AppError> {
+    sqlx::query_as(&format!("SELECT {} FROM feeds ORDER BY title COLLATE NOCASE", FEED_COLUMNS))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch feeds: {}", e)))
+}
+
+pub async fn get_feed(pool: &SqlitePool, id: i64) -> Result<Feed, AppError> {
+    sqlx::query_as(&format!("SELECT {} FROM feeds WHERE id = ?", FEED_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch feed: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Feed {} not found", id)))
+}
+
+// Feeds that weren't polled in the last `minutes`
+pub async fn get_feeds_due(pool: &SqlitePool, minutes: i64) -> Result<Vec<Feed>, AppError> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM feeds WHERE last_polled_at IS NULL OR last_polled_at < datetime('now', ?) ORDER BY id",
+        FEED_COLUMNS
+    ))
+    .bind(format!("-{} minutes", minutes))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch feeds: {}", e)))
+}
+
+pub async fn create_feed(pool: &SqlitePool, url: &str, title: &str) -> Result<Feed, AppError> {
+    let id: i64 = sqlx::query_scalar("INSERT INTO feeds (url, title) VALUES (?, ?) RETURNING id")
+        .bind(url)
+        .bind(title)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => format!("Already subscribed to {}", url),
+            e => format!("Failed to subscribe: {}", e),
+        })?;
+    get_feed(pool, id).await
+}
+
+pub async fn delete_feed(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM feed_entries WHERE feed_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to unsubscribe: {}", e)))?;
+
+    let result = sqlx::query("DELETE FROM feeds WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to unsubscribe: {}", e)))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Feed {} not found", id)));
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// `error` is None after a successful poll
+pub async fn set_polled(pool: &SqlitePool, id: i64, error: Option<&str>) -> Result<(), AppError> {
+    sqlx::query("UPDATE feeds SET last_polled_at = CURRENT_TIMESTAMP, last_error = ? WHERE id = ?")
+        .bind(error)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update feed: {}", e)))?;
+    Ok(())
+}
+
+// Adds the items the feed didn't have yet and returns how many that were
+pub async fn add_entries(pool: &SqlitePool, feed_id: i64, items: &[FeedItem]) -> Result<usize, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save feed entries: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    let mut added = 0;
+    for item in items {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO feed_entries (feed_id, guid, title, authors, link, summary, published, doi, arxiv_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(feed_id)
+        .bind(&item.guid)
+        .bind(&item.title)
+        .bind((!item.authors.is_empty()).then(|| item.authors.join(", ")))
+        .bind(&item.link)
+        .bind(&item.summary)
+        .bind(&item.published)
+        .bind(&item.doi)
+        .bind(&item.arxiv_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+        added += result.rows_affected() as usize;
+    }
+
+    tx.commit().await.map_err(err)?;
+    Ok(added)
+}
+
+// Newest first. `feed_id` None lists every feed; handled entries only with `include_handled`.
+pub async fn get_entries(
+    pool: &SqlitePool,
+    feed_id: Option<i64>,
+    include_handled: bool,
+    limit: i64,
+) -> Result<Vec<FeedEntry>, AppError> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM feed_entries WHERE (? IS NULL OR feed_id = ?) \
+         AND (? OR (paper_id IS NULL AND NOT dismissed)) ORDER BY created_at DESC, published DESC, id DESC LIMIT ?",
+        ENTRY_COLUMNS
+    ))
+    .bind(feed_id)
+    .bind(feed_id)
+    .bind(include_handled)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch feed entries: {}", e)))
+}
+
+pub async fn get_entry(pool: &SqlitePool, id: i64) -> Result<FeedEntry, AppError> {
+    sqlx::query_as(&format!("SELECT {} FROM feed_entries WHERE id = ?", ENTRY_COLUMNS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch feed entry: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Feed entry {} not found", id)))
+}
+
+pub async fn set_entry_paper(pool: &SqlitePool, id: i64, paper_id: i64) -> Result<(), AppError> {
+    sqlx::query("UPDATE feed_entries SET paper_id = ? WHERE id = ?")
+        .bind(paper_id)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update feed entry: {}", e)))?;
+    Ok(())
+}
+
+pub async fn dismiss_entries(pool: &SqlitePool, ids: &[i64]) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    for id in ids {
+        sqlx::query("UPDATE feed_entries SET dismissed = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to dismiss feed entry: {}", e)))?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+// Forgets dismissed entries older than `days`; by then they have long dropped out of their
+// feed and won't come back on the next poll
+pub async fn prune_entries(pool: &SqlitePool, days: i64) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM feed_entries WHERE dismissed AND created_at < datetime('now', ?)")
+        .bind(format!("-{} days", days))
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to prune feed entries: {}", e)))?;
+    Ok(())
+}
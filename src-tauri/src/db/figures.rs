@@ -0,0 +1,103 @@
+// src-tauri/src/db/figures.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow, Clone)]
+pub struct Figure {
+    pub id: i64,
+    pub paper_id: i64,
+    pub page: i64,
+    // Relative to the paper's figures folder
+    pub file_name: String,
+    // In pixels
+    pub width: i64,
+    pub height: i64,
+}
+
+pub struct NewFigure {
+    pub page: i64,
+    pub file_name: String,
+    pub width: i64,
+    pub height: i64,
+}
+
+// In page order
+pub async fn get_figures(pool: &SqlitePool, paper_id: i64) -> Result<Vec<Figure>, AppError> {
+    sqlx::query_as::<_, Figure>(
+        "SELECT id, paper_id, page, file_name, width, height FROM figures WHERE paper_id = ? ORDER BY page, id",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch figures: {}", e)))
+}
+
+pub async fn is_scanned(pool: &SqlitePool, paper_id: i64) -> Result<bool, AppError> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM figure_scans WHERE paper_id = ?)")
+        .bind(paper_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch figures: {}", e)))
+}
+
+// Papers with a PDF that haven't been scanned yet, as (id, pdf_path)
+pub async fn get_unscanned(pool: &SqlitePool) -> Result<Vec<(i64, String)>, AppError> {
+    sqlx::query_as(
+        "SELECT id, pdf_path FROM papers \
+         WHERE pdf_path != '' AND id NOT IN (SELECT paper_id FROM figure_scans) ORDER BY id DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
+
+// Replaces the paper's figures and marks it scanned. Does nothing if the paper is gone.
+pub async fn save_figures(pool: &SqlitePool, paper_id: i64, figures: &[NewFigure]) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save figures: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    sqlx::query("DELETE FROM figures WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    let scanned = sqlx::query(
+        "INSERT INTO figure_scans (paper_id) SELECT id FROM papers WHERE id = ? \
+         ON CONFLICT(paper_id) DO UPDATE SET scanned_at = CURRENT_TIMESTAMP",
+    )
+    .bind(paper_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(err)?;
+    if scanned.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    for figure in figures {
+        sqlx::query("INSERT INTO figures (paper_id, page, file_name, width, height) VALUES (?, ?, ?, ?, ?)")
+            .bind(paper_id)
+            .bind(figure.page)
+            .bind(&figure.file_name)
+            .bind(figure.width)
+            .bind(figure.height)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await.map_err(err)
+}
+
+// For when the paper's PDF has been replaced; the next scan starts from scratch
+pub async fn clear_figures(pool: &SqlitePool, paper_id: i64) -> Result<(), AppError> {
+    for table in ["figures", "figure_scans"] {
+        sqlx::query(&format!("DELETE FROM {} WHERE paper_id = ?", table))
+            .bind(paper_id)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to clear figures: {}", e)))?;
+    }
+    Ok(())
+}
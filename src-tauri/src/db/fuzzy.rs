@@ -0,0 +1,71 @@
+// src-tauri/src/db/fuzzy.rs
+
+// Typo-tolerant title matching for when full-text search comes up short, e.g. "atention is
+// all you ned". Titles are compared by the trigrams of their words, like PostgreSQL's pg_trgm.
+
+use super::SearchResult;
+use crate::error::AppError;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+// Share of the query's trigrams a title must contain
+const MIN_SCORE: f64 = 0.5;
+
+// "Attention!" -> {"  a", " at", "att", "tte", "ten", "ent", "nti", "tio", "ion", "on "}
+fn trigrams(text: &str) -> HashSet<String> {
+    let lowered = text.to_lowercase();
+    let mut grams = HashSet::new();
+    for word in lowered.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let padded: Vec<char> = format!("  {} ", word).chars().collect();
+        for window in padded.windows(3) {
+            grams.insert(window.iter().collect());
+        }
+    }
+    grams
+}
+
+// How much of the query turns up in the title, from 0 to 1; extra words in the title don't
+// count against it, so a few remembered words still find a long title
+fn score(query: &HashSet<String>, title: &str) -> f64 {
+    if query.is_empty() {
+        return 0.0;
+    }
+    let title = trigrams(title);
+    query.intersection(&title).count() as f64 / query.len() as f64
+}
+
+// Papers outside the Trash whose titles resemble `query`, best first, leaving out `exclude`.
+// They have no snippet, and their rank is 1 - score so they sort after full-text hits.
+pub async fn search_titles(
+    pool: &SqlitePool,
+    query: &str,
+    exclude: &[i64],
+    limit: usize,
+) -> Result<Vec<SearchResult>, AppError> {
+    let query = trigrams(query);
+    if query.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let papers = sqlx::query_as::<_, SearchResult>(
+        "SELECT id, title, authors, year, pdf_path, '' AS snippet, 0.0 AS rank \
+         FROM papers WHERE deleted_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Search failed: {}", e)))?;
+
+    let mut scored: Vec<(f64, SearchResult)> = papers
+        .into_iter()
+        .filter(|p| !exclude.contains(&p.id))
+        .map(|p| (score(&query, &p.title), p))
+        .filter(|(score, _)| *score >= MIN_SCORE)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(limit);
+
+    Ok(scored
+        .into_iter()
+        .map(|(score, result)| SearchResult { rank: 1.0 - score, fuzzy: true, ..result })
+        .collect())
+}
@@ -0,0 +1,138 @@
+// src-tauri/src/db/jobs.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+    Interrupted,
+}
+
+#[derive(Serialize, FromRow, Clone)]
+pub struct Job {
+    pub id: i64,
+    pub kind: String,
+    pub label: String,
+    pub status: JobStatus,
+    pub current: i64,
+    pub total: Option<i64>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub created_at: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+const JOB_COLUMNS: &str = "id, kind, label, status, current, total, message, error, created_at, started_at, finished_at";
+// Older finished jobs are pruned
+const KEEP_FINISHED: i64 = 200;
+
+pub async fn create_job(pool: &SqlitePool, kind: &str, label: &str) -> Result<Job, AppError> {
+    sqlx::query_as(&format!("INSERT INTO jobs (kind, label) VALUES (?, ?) RETURNING {}", JOB_COLUMNS))
+        .bind(kind)
+        .bind(label)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to create job: {}", e)))
+}
+
+// Newest first
+pub async fn get_jobs(pool: &SqlitePool, limit: i64) -> Result<Vec<Job>, AppError> {
+    sqlx::query_as(&format!("SELECT {} FROM jobs ORDER BY id DESC LIMIT ?", JOB_COLUMNS))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch jobs: {}", e)))
+}
+
+// Whether a job of `kind` is queued or running
+pub async fn is_kind_active(pool: &SqlitePool, kind: &str) -> Result<bool, AppError> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM jobs WHERE kind = ? AND status IN ('queued', 'running'))")
+        .bind(kind)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch jobs: {}", e)))
+}
+
+pub async fn set_running(pool: &SqlitePool, id: i64) -> Result<Job, AppError> {
+    sqlx::query_as(&format!(
+        "UPDATE jobs SET status = 'running', started_at = CURRENT_TIMESTAMP WHERE id = ? RETURNING {}",
+        JOB_COLUMNS
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update job: {}", e)))
+}
+
+pub async fn set_progress(
+    pool: &SqlitePool,
+    id: i64,
+    current: i64,
+    total: Option<i64>,
+    message: Option<&str>,
+) -> Result<Job, AppError> {
+    sqlx::query_as(&format!(
+        "UPDATE jobs SET current = ?, total = ?, message = ? WHERE id = ? RETURNING {}",
+        JOB_COLUMNS
+    ))
+    .bind(current)
+    .bind(total)
+    .bind(message)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update job: {}", e)))
+}
+
+pub async fn finish_job(
+    pool: &SqlitePool,
+    id: i64,
+    status: JobStatus,
+    message: Option<&str>,
+    error: Option<&str>,
+) -> Result<Job, AppError> {
+    let job = sqlx::query_as(&format!(
+        "UPDATE jobs SET status = ?, message = COALESCE(?, message), error = ?, finished_at = CURRENT_TIMESTAMP
+         WHERE id = ? RETURNING {}",
+        JOB_COLUMNS
+    ))
+    .bind(status)
+    .bind(message)
+    .bind(error)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to update job: {}", e)))?;
+
+    sqlx::query(
+        "DELETE FROM jobs WHERE status NOT IN ('queued', 'running')
+         AND id NOT IN (SELECT id FROM jobs ORDER BY id DESC LIMIT ?)",
+    )
+    .bind(KEEP_FINISHED)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to prune jobs: {}", e)))?;
+
+    Ok(job)
+}
+
+// Jobs the app was in the middle of when it last quit; their tasks died with it
+pub async fn mark_interrupted(pool: &SqlitePool) -> Result<u64, AppError> {
+    sqlx::query(
+        "UPDATE jobs SET status = 'interrupted', finished_at = CURRENT_TIMESTAMP
+         WHERE status IN ('queued', 'running')",
+    )
+    .execute(pool)
+    .await
+    .map(|r| r.rows_affected())
+    .map_err(|e| AppError::Db(format!("Failed to update jobs: {}", e)))
+}
@@ -0,0 +1,571 @@
+// src-tauri/src/db/journal.rs
+
+// Undo journal. Each undoable operation stores how things looked before it ran; undoing
+// writes that state back and removes the entry. Only the most recent JOURNAL_SIZE
+// operations are kept. Papers are also snapshotted after the operation, so undo only puts
+// back what it changed, and later notes, status or tag changes survive it.
+
+use super::{ItemType, Priority, ReadingStatus};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Transaction};
+
+const JOURNAL_SIZE: i64 = 50;
+
+#[derive(Serialize, FromRow)]
+pub struct Operation {
+    pub id: i64,
+    // Shown to the user, e.g. "Move \"Attention Is All You Need\" to Trash"
+    pub description: String,
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UndoData {
+    // `after` is missing from entries recorded before it existed; those restore everything
+    Papers {
+        papers: Vec<PaperSnapshot>,
+        #[serde(default)]
+        after: Vec<PaperSnapshot>,
+    },
+    // A deleted tag and the papers that carried it
+    Tag { id: i64, name: String, paper_ids: Vec<i64> },
+    // A deleted collection with its subcollections, parents first
+    Collections { collections: Vec<CollectionSnapshot>, memberships: Vec<(i64, i64)> },
+    Authors(AuthorMerge),
+    Venues(VenueMerge),
+}
+
+impl UndoData {
+    // Papers that undoing this touches
+    pub fn paper_ids(&self) -> Vec<i64> {
+        let mut ids: Vec<i64> = match self {
+            UndoData::Papers { papers, .. } => papers.iter().map(|p| p.id).collect(),
+            UndoData::Tag { paper_ids, .. } => paper_ids.clone(),
+            UndoData::Collections { memberships, .. } => memberships.iter().map(|(paper_id, _)| *paper_id).collect(),
+            UndoData::Authors(merge) => merge.links.iter().map(|(paper_id, _, _)| *paper_id).collect(),
+            UndoData::Venues(merge) => merge.papers.iter().map(|(paper_id, _)| *paper_id).collect(),
+        };
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+// The parts of a paper that edits, tagging, filing and trashing can change
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct PaperSnapshot {
+    pub id: i64,
+    pub title: String,
+    pub authors: Option<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    // Missing from entries recorded before item types existed
+    #[serde(default)]
+    pub item_type: ItemType,
+    #[serde(default)]
+    pub isbn: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub institution: Option<String>,
+    pub notes: Option<String>,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub r#abstract: Option<String>,
+    pub keywords: Option<String>,
+    pub reading_status: ReadingStatus,
+    pub starred: bool,
+    pub rating: Option<i64>,
+    pub priority: Priority,
+    pub deleted_at: Option<String>,
+    #[sqlx(skip)]
+    pub tag_ids: Vec<i64>,
+    #[sqlx(skip)]
+    pub collection_ids: Vec<i64>,
+}
+
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct CollectionSnapshot {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+// Authors merged into `keep_id`: their rows, the aliases that led to them or took their
+// names, and their places in bylines as (paper id, author id, position)
+#[derive(Serialize, Deserialize)]
+pub struct AuthorMerge {
+    pub keep_id: i64,
+    pub authors: Vec<AuthorSnapshot>,
+    pub aliases: Vec<(String, i64)>,
+    pub links: Vec<(i64, i64, i64)>,
+}
+
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct AuthorSnapshot {
+    pub id: i64,
+    pub name: String,
+    pub created_at: Option<String>,
+}
+
+// Venues merged into `keep_id`: their rows, the aliases that led to them or took their keys,
+// each paper's venue as (paper id, venue id), and the kept venue's own abbreviation
+#[derive(Serialize, Deserialize)]
+pub struct VenueMerge {
+    pub keep_id: i64,
+    pub keep_abbreviation: Option<String>,
+    pub venues: Vec<VenueSnapshot>,
+    pub aliases: Vec<(String, i64)>,
+    pub papers: Vec<(i64, i64)>,
+}
+
+#[derive(Serialize, Deserialize, FromRow)]
+pub struct VenueSnapshot {
+    pub id: i64,
+    pub name: String,
+    pub key: String,
+    pub abbreviation: Option<String>,
+    pub created_at: Option<String>,
+}
+
+pub async fn snapshot_papers(pool: &SqlitePool, paper_ids: &[i64]) -> Result<Vec<PaperSnapshot>, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to record undo state: {}", e));
+    let mut snapshots = Vec::with_capacity(paper_ids.len());
+
+    for id in paper_ids {
+        let snapshot: Option<PaperSnapshot> = sqlx::query_as(
+            r#"
+            SELECT id, title, authors, journal, year, item_type, isbn, publisher, institution, notes, doi, pmid, abstract, keywords,
+                   reading_status, starred, rating, priority, deleted_at
+            FROM papers WHERE id = ?
+            "#
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(err)?;
+        let Some(mut snapshot) = snapshot else {
+            continue;
+        };
+
+        snapshot.tag_ids = sqlx::query_scalar("SELECT tag_id FROM paper_tags WHERE paper_id = ?")
+            .bind(id)
+            .fetch_all(pool)
+            .await
+            .map_err(err)?;
+        snapshot.collection_ids = sqlx::query_scalar("SELECT collection_id FROM paper_collections WHERE paper_id = ?")
+            .bind(id)
+            .fetch_all(pool)
+            .await
+            .map_err(err)?;
+        snapshots.push(snapshot);
+    }
+
+    Ok(snapshots)
+}
+
+pub async fn snapshot_tag(pool: &SqlitePool, tag_id: i64) -> Result<UndoData, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to record undo state: {}", e));
+    let name: String = sqlx::query_scalar("SELECT name FROM tags WHERE id = ?")
+        .bind(tag_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(err)?
+        .ok_or_else(|| AppError::NotFound(format!("Tag {} not found", tag_id)))?;
+    let paper_ids = sqlx::query_scalar("SELECT paper_id FROM paper_tags WHERE tag_id = ?")
+        .bind(tag_id)
+        .fetch_all(pool)
+        .await
+        .map_err(err)?;
+
+    Ok(UndoData::Tag { id: tag_id, name, paper_ids })
+}
+
+pub async fn snapshot_collection(pool: &SqlitePool, collection_id: i64) -> Result<UndoData, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to record undo state: {}", e));
+    let collections: Vec<CollectionSnapshot> = sqlx::query_as(
+        r#"
+        WITH RECURSIVE tree(id, depth) AS (
+            SELECT id, 0 FROM collections WHERE id = ?
+            UNION ALL
+            SELECT c.id, tree.depth + 1 FROM collections c JOIN tree ON c.parent_id = tree.id
+        )
+        SELECT c.id, c.name, c.parent_id FROM collections c JOIN tree ON tree.id = c.id
+        ORDER BY tree.depth
+        "#
+    )
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    if collections.is_empty() {
+        return Err(AppError::NotFound(format!("Collection {} not found", collection_id)));
+    }
+
+    let mut memberships = Vec::new();
+    for collection in &collections {
+        let paper_ids: Vec<i64> = sqlx::query_scalar("SELECT paper_id FROM paper_collections WHERE collection_id = ?")
+            .bind(collection.id)
+            .fetch_all(pool)
+            .await
+            .map_err(err)?;
+        memberships.extend(paper_ids.into_iter().map(|paper_id| (paper_id, collection.id)));
+    }
+
+    Ok(UndoData::Collections { collections, memberships })
+}
+
+// Journals an operation on papers that has just run, from their snapshots before it
+pub async fn record_papers(pool: &SqlitePool, description: &str, before: Vec<PaperSnapshot>) -> Result<(), AppError> {
+    let ids: Vec<i64> = before.iter().map(|p| p.id).collect();
+    let after = snapshot_papers(pool, &ids).await?;
+    record(pool, description, &UndoData::Papers { papers: before, after }).await
+}
+
+// Before `merge_ids` are folded into `keep_id`, inside the merge's transaction
+pub async fn snapshot_author_merge(
+    conn: &mut SqliteConnection,
+    keep_id: i64,
+    merge_ids: &[i64],
+) -> Result<AuthorMerge, sqlx::Error> {
+    let (mut authors, mut aliases, mut links) = (Vec::new(), Vec::new(), Vec::new());
+    for merge_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        let author: Option<AuthorSnapshot> = sqlx::query_as("SELECT id, name, created_at FROM authors WHERE id = ?")
+            .bind(merge_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        let Some(author) = author else {
+            continue;
+        };
+        aliases.extend(
+            sqlx::query_as::<_, (String, i64)>("SELECT name, author_id FROM author_aliases WHERE author_id = ? OR name = ?")
+                .bind(merge_id)
+                .bind(&author.name)
+                .fetch_all(&mut *conn)
+                .await?,
+        );
+        links.extend(
+            sqlx::query_as::<_, (i64, i64, i64)>("SELECT paper_id, author_id, position FROM paper_authors WHERE author_id = ?")
+                .bind(merge_id)
+                .fetch_all(&mut *conn)
+                .await?,
+        );
+        authors.push(author);
+    }
+    Ok(AuthorMerge { keep_id, authors, aliases, links })
+}
+
+// Before `merge_ids` are folded into `keep_id`, inside the merge's transaction
+pub async fn snapshot_venue_merge(
+    conn: &mut SqliteConnection,
+    keep_id: i64,
+    merge_ids: &[i64],
+) -> Result<VenueMerge, sqlx::Error> {
+    let keep_abbreviation = sqlx::query_scalar("SELECT abbreviation FROM venues WHERE id = ?")
+        .bind(keep_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .flatten();
+    let (mut venues, mut aliases, mut papers) = (Vec::new(), Vec::new(), Vec::new());
+    for merge_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        let venue: Option<VenueSnapshot> =
+            sqlx::query_as("SELECT id, name, key, abbreviation, created_at FROM venues WHERE id = ?")
+                .bind(merge_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        let Some(venue) = venue else {
+            continue;
+        };
+        aliases.extend(
+            sqlx::query_as::<_, (String, i64)>("SELECT key, venue_id FROM venue_aliases WHERE venue_id = ? OR key = ?")
+                .bind(merge_id)
+                .bind(&venue.key)
+                .fetch_all(&mut *conn)
+                .await?,
+        );
+        papers.extend(
+            sqlx::query_as::<_, (i64, i64)>("SELECT id, venue_id FROM papers WHERE venue_id = ?")
+                .bind(merge_id)
+                .fetch_all(&mut *conn)
+                .await?,
+        );
+        venues.push(venue);
+    }
+    Ok(VenueMerge { keep_id, keep_abbreviation, venues, aliases, papers })
+}
+
+pub async fn record(pool: &SqlitePool, description: &str, undo: &UndoData) -> Result<(), AppError> {
+    let mut conn = pool.acquire().await?;
+    record_in(&mut conn, description, undo).await
+}
+
+// record inside a caller's transaction, so the operation and its entry are saved together
+pub async fn record_in(conn: &mut SqliteConnection, description: &str, undo: &UndoData) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to record operation: {}", e));
+    let undo = serde_json::to_string(undo).map_err(|e| AppError::Db(format!("Failed to record operation: {}", e)))?;
+
+    sqlx::query("INSERT INTO operation_journal (description, undo_data) VALUES (?, ?)")
+        .bind(description)
+        .bind(undo)
+        .execute(&mut *conn)
+        .await
+        .map_err(err)?;
+    sqlx::query("DELETE FROM operation_journal WHERE id NOT IN (SELECT id FROM operation_journal ORDER BY id DESC LIMIT ?)")
+        .bind(JOURNAL_SIZE)
+        .execute(&mut *conn)
+        .await
+        .map_err(err)?;
+    Ok(())
+}
+
+// Newest first
+pub async fn get_operations(pool: &SqlitePool) -> Result<Vec<Operation>, AppError> {
+    sqlx::query_as::<_, Operation>("SELECT id, description, created_at FROM operation_journal ORDER BY id DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch operations: {}", e)))
+}
+
+// Reverts the newest operation and drops it from the journal. None when there is nothing to undo.
+pub async fn undo_last(pool: &SqlitePool) -> Result<Option<(Operation, UndoData)>, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to undo: {}", e));
+    let mut tx = pool.begin().await?;
+
+    let last: Option<(i64, String, Option<String>, String)> =
+        sqlx::query_as("SELECT id, description, created_at, undo_data FROM operation_journal ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(err)?;
+    let Some((id, description, created_at, undo)) = last else {
+        return Ok(None);
+    };
+    let undo: UndoData = serde_json::from_str(&undo).map_err(|e| AppError::Db(format!("Failed to undo: {}", e)))?;
+
+    match &undo {
+        UndoData::Papers { papers, after } => {
+            for paper in papers {
+                let after = after.iter().find(|a| a.id == paper.id);
+                restore_paper(&mut tx, paper, after).await.map_err(err)?;
+            }
+        }
+        UndoData::Tag { id, name, paper_ids } => {
+            sqlx::query("INSERT INTO tags (id, name) VALUES (?, ?)")
+                .bind(id)
+                .bind(name)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Db(format!("Failed to restore tag \"{}\": {}", name, e)))?;
+            for paper_id in paper_ids {
+                sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT id, ? FROM papers WHERE id = ?")
+                    .bind(id)
+                    .bind(paper_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(err)?;
+            }
+        }
+        UndoData::Collections { collections, memberships } => {
+            for collection in collections {
+                sqlx::query("INSERT INTO collections (id, name, parent_id) VALUES (?, ?, ?)")
+                    .bind(collection.id)
+                    .bind(&collection.name)
+                    .bind(collection.parent_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| AppError::Db(format!("Failed to restore collection \"{}\": {}", collection.name, e)))?;
+            }
+            for (paper_id, collection_id) in memberships {
+                sqlx::query(
+                    "INSERT OR IGNORE INTO paper_collections (paper_id, collection_id) SELECT id, ? FROM papers WHERE id = ?",
+                )
+                .bind(collection_id)
+                .bind(paper_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(err)?;
+            }
+        }
+        UndoData::Authors(merge) => restore_authors(&mut tx, merge).await.map_err(err)?,
+        UndoData::Venues(merge) => restore_venues(&mut tx, merge).await.map_err(err)?,
+    }
+
+    sqlx::query("DELETE FROM operation_journal WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    tx.commit().await?;
+
+    Ok(Some((Operation { id, description, created_at }, undo)))
+}
+
+// Splits merged authors off again. Bylines edited since the merge keep their new authors.
+async fn restore_authors(tx: &mut Transaction<'_, Sqlite>, merge: &AuthorMerge) -> Result<(), sqlx::Error> {
+    for author in &merge.authors {
+        sqlx::query("INSERT INTO authors (id, name, created_at) VALUES (?, ?, ?)")
+            .bind(author.id)
+            .bind(&author.name)
+            .bind(&author.created_at)
+            .execute(&mut **tx)
+            .await?;
+        // The alias the merge made of the name
+        sqlx::query("DELETE FROM author_aliases WHERE name = ?")
+            .bind(&author.name)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for (name, author_id) in &merge.aliases {
+        sqlx::query("INSERT OR REPLACE INTO author_aliases (name, author_id) SELECT ?, id FROM authors WHERE id = ?")
+            .bind(name)
+            .bind(author_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for (paper_id, author_id, position) in &merge.links {
+        sqlx::query("UPDATE paper_authors SET author_id = ? WHERE paper_id = ? AND position = ? AND author_id = ?")
+            .bind(author_id)
+            .bind(paper_id)
+            .bind(position)
+            .bind(merge.keep_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+// Splits merged venues off again. Papers whose journal was edited since keep their new venue.
+async fn restore_venues(tx: &mut Transaction<'_, Sqlite>, merge: &VenueMerge) -> Result<(), sqlx::Error> {
+    for venue in &merge.venues {
+        sqlx::query("INSERT INTO venues (id, name, key, abbreviation, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(venue.id)
+            .bind(&venue.name)
+            .bind(&venue.key)
+            .bind(&venue.abbreviation)
+            .bind(&venue.created_at)
+            .execute(&mut **tx)
+            .await?;
+        // The alias the merge made of the key
+        sqlx::query("DELETE FROM venue_aliases WHERE key = ?")
+            .bind(&venue.key)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for (key, venue_id) in &merge.aliases {
+        sqlx::query("INSERT OR REPLACE INTO venue_aliases (key, venue_id) SELECT ?, id FROM venues WHERE id = ?")
+            .bind(key)
+            .bind(venue_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for (paper_id, venue_id) in &merge.papers {
+        sqlx::query("UPDATE papers SET venue_id = ? WHERE id = ? AND venue_id = ?")
+            .bind(venue_id)
+            .bind(paper_id)
+            .bind(merge.keep_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    sqlx::query("UPDATE venues SET abbreviation = ? WHERE id = ?")
+        .bind(&merge.keep_abbreviation)
+        .bind(merge.keep_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+// Puts back what the operation changed: the fields where `paper` and `after` differ, and the
+// tags and collections it added or removed. Without `after`, the whole snapshot. A paper
+// deleted permanently since stays deleted; tags and collections deleted since are skipped.
+async fn restore_paper(
+    tx: &mut Transaction<'_, Sqlite>,
+    paper: &PaperSnapshot,
+    after: Option<&PaperSnapshot>,
+) -> Result<(), sqlx::Error> {
+    macro_rules! changed {
+        ($field:ident) => {
+            after.is_none_or(|after| after.$field != paper.$field)
+        };
+    }
+    let mut update = QueryBuilder::<Sqlite>::new("UPDATE papers SET updated_at = CURRENT_TIMESTAMP");
+    macro_rules! restore {
+        ($($column:literal => $field:ident),* $(,)?) => {
+            $(
+                if changed!($field) {
+                    update.push(concat!(", ", $column, " = ")).push_bind(paper.$field.clone());
+                }
+            )*
+        };
+    }
+    restore!(
+        "title" => title, "authors" => authors, "journal" => journal, "year" => year,
+        "item_type" => item_type, "isbn" => isbn, "publisher" => publisher,
+        "institution" => institution, "notes" => notes, "doi" => doi, "pmid" => pmid,
+        "abstract" => r#abstract, "keywords" => keywords, "reading_status" => reading_status,
+        "starred" => starred, "rating" => rating, "priority" => priority, "deleted_at" => deleted_at,
+    );
+    let result = update.push(" WHERE id = ").push_bind(paper.id).build().execute(&mut **tx).await?;
+    if result.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    if changed!(title) || changed!(r#abstract) || changed!(keywords) {
+        sqlx::query(
+            "UPDATE papers_fts SET (title, abstract, keywords) = \
+             (SELECT title, abstract, keywords FROM papers WHERE id = ?) WHERE rowid = ?",
+        )
+        .bind(paper.id)
+        .bind(paper.id)
+        .execute(&mut **tx)
+        .await?;
+    }
+    if changed!(authors) {
+        super::authors::set_paper_authors_in(tx, paper.id, paper.authors.as_deref()).await?;
+    }
+    if changed!(journal) {
+        super::venues::set_paper_venue_in(tx, paper.id, paper.journal.as_deref()).await?;
+    }
+
+    let (tags_after, collections_after) = match after {
+        Some(after) => (after.tag_ids.as_slice(), after.collection_ids.as_slice()),
+        None => {
+            // Everything the paper has now counts as added
+            sqlx::query("DELETE FROM paper_tags WHERE paper_id = ?").bind(paper.id).execute(&mut **tx).await?;
+            sqlx::query("DELETE FROM paper_collections WHERE paper_id = ?").bind(paper.id).execute(&mut **tx).await?;
+            (&[][..], &[][..])
+        }
+    };
+    for tag_id in tags_after.iter().filter(|id| !paper.tag_ids.contains(id)) {
+        sqlx::query("DELETE FROM paper_tags WHERE paper_id = ? AND tag_id = ?")
+            .bind(paper.id)
+            .bind(tag_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for tag_id in paper.tag_ids.iter().filter(|id| !tags_after.contains(id)) {
+        sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT ?, id FROM tags WHERE id = ?")
+            .bind(paper.id)
+            .bind(tag_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for collection_id in collections_after.iter().filter(|id| !paper.collection_ids.contains(id)) {
+        sqlx::query("DELETE FROM paper_collections WHERE paper_id = ? AND collection_id = ?")
+            .bind(paper.id)
+            .bind(collection_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    for collection_id in paper.collection_ids.iter().filter(|id| !collections_after.contains(id)) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO paper_collections (paper_id, collection_id) SELECT ?, id FROM collections WHERE id = ?",
+        )
+        .bind(paper.id)
+        .bind(collection_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
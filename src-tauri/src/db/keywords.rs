@@ -0,0 +1,41 @@
+// src-tauri/src/db/keywords.rs
+
+use crate::keywords::Keyword;
+use crate::error::AppError;
+use sqlx::SqlitePool;
+
+// Highest score first
+pub async fn get_keywords(pool: &SqlitePool, paper_id: i64) -> Result<Vec<Keyword>, AppError> {
+    let rows: Vec<(String, f64)> =
+        sqlx::query_as("SELECT keyword, score FROM paper_keywords WHERE paper_id = ? ORDER BY score DESC, keyword")
+            .bind(paper_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch keywords: {}", e)))?;
+
+    Ok(rows.into_iter().map(|(phrase, score)| Keyword { phrase, score }).collect())
+}
+
+// Replaces the paper's keywords
+pub async fn save_keywords(pool: &SqlitePool, paper_id: i64, keywords: &[Keyword]) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save keywords: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    sqlx::query("DELETE FROM paper_keywords WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    for keyword in keywords {
+        sqlx::query("INSERT OR IGNORE INTO paper_keywords (paper_id, keyword, score) VALUES (?, ?, ?)")
+            .bind(paper_id)
+            .bind(&keyword.phrase)
+            .bind(keyword.score)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await.map_err(err)
+}
@@ -0,0 +1,89 @@
+// src-tauri/src/db/legacy.rs
+
+// Databases created before migrations existed were built with CREATE TABLE IF NOT EXISTS
+// plus ad-hoc ALTERs, so they may be missing columns the initial migration expects.
+
+use super::tags;
+use crate::error::AppError;
+use sqlx::SqlitePool;
+
+// Runs before the migrator: fills in columns so the initial migration's indexes apply
+pub(super) async fn prepare(pool: &SqlitePool) -> Result<(), AppError> {
+    if !table_exists(pool, "papers").await? || table_exists(pool, "_sqlx_migrations").await? {
+        return Ok(());
+    }
+
+    tracing::info!("Upgrading database from before schema migrations...");
+    ensure_column(pool, "papers", "doi", "TEXT").await?;
+    ensure_column(pool, "papers", "arxiv_id", "TEXT").await?;
+    ensure_column(pool, "papers", "abstract", "TEXT").await?;
+    ensure_column(pool, "papers", "file_hash", "TEXT").await?;
+    ensure_column(pool, "papers", "reading_status", "TEXT NOT NULL DEFAULT 'unread'").await?;
+    ensure_column(pool, "papers", "last_read_page", "INTEGER").await?;
+    ensure_column(pool, "papers", "starred", "BOOLEAN NOT NULL DEFAULT 0").await?;
+    ensure_column(pool, "papers", "rating", "INTEGER CHECK (rating BETWEEN 1 AND 5)").await?;
+    ensure_column(pool, "papers", "priority", "INTEGER NOT NULL DEFAULT 0").await?;
+
+    Ok(())
+}
+
+// Runs after the migrator: the old comma separated papers.tags column moves into the tag tables
+pub(super) async fn finish(pool: &SqlitePool) -> Result<(), AppError> {
+    if !column_exists(pool, "papers", "tags").await? {
+        return Ok(());
+    }
+
+    let rows: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, tags FROM papers WHERE tags IS NOT NULL AND tags != ''")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to migrate tags: {}", e)))?;
+
+    for (paper_id, names) in rows {
+        tags::set_paper_tags(pool, paper_id, &tags::split_tags(&names)).await?;
+    }
+
+    sqlx::query("ALTER TABLE papers DROP COLUMN tags")
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to migrate tags: {}", e)))?;
+
+    Ok(())
+}
+
+async fn table_exists(pool: &SqlitePool, table: &str) -> Result<bool, AppError> {
+    let (exists,): (bool,) =
+        sqlx::query_as("SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to inspect database: {}", e)))?;
+    Ok(exists)
+}
+
+async fn column_exists(pool: &SqlitePool, table: &str, column: &str) -> Result<bool, AppError> {
+    let (exists,): (bool,) =
+        sqlx::query_as("SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to inspect table {}: {}", table, e)))?;
+    Ok(exists)
+}
+
+async fn ensure_column(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), AppError> {
+    if !column_exists(pool, table, column).await? {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to add column {}.{}: {}", table, column, e)))?;
+    }
+
+    Ok(())
+}
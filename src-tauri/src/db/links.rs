@@ -0,0 +1,87 @@
+// src-tauri/src/db/links.rs
+
+use super::{get_papers_by_ids, Paper};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum LinkSource {
+    // Matched from the reference and citation lists fetched from Semantic Scholar
+    SemanticScholar,
+    // Matched from the bibliography extracted from the citing paper's PDF
+    Pdf,
+}
+
+// Papers in the library that `paper_id` cites
+pub async fn get_references(pool: &SqlitePool, paper_id: i64) -> Result<Vec<Paper>, AppError> {
+    let ids: Vec<(i64,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT p.id FROM paper_links l
+        JOIN papers p ON p.id = l.cited_paper_id
+        WHERE l.citing_paper_id = ? AND p.deleted_at IS NULL
+        ORDER BY p.year DESC, p.title
+        "#
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch references: {}", e)))?;
+
+    get_papers_by_ids(pool, &ids.into_iter().map(|(id,)| id).collect::<Vec<_>>()).await
+}
+
+// Papers in the library that cite `paper_id`
+pub async fn get_citing_papers(pool: &SqlitePool, paper_id: i64) -> Result<Vec<Paper>, AppError> {
+    let ids: Vec<(i64,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT p.id FROM paper_links l
+        JOIN papers p ON p.id = l.citing_paper_id
+        WHERE l.cited_paper_id = ? AND p.deleted_at IS NULL
+        ORDER BY p.year DESC, p.title
+        "#
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch citing papers: {}", e)))?;
+
+    get_papers_by_ids(pool, &ids.into_iter().map(|(id,)| id).collect::<Vec<_>>()).await
+}
+
+// Rebuilds the Semantic Scholar links for the whole library from the stored citation lists,
+// so papers added after another paper's lookup still get linked to it. Works are matched
+// to papers by DOI or arXiv id.
+pub async fn refresh_citation_links(pool: &SqlitePool) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to update citation links: {}", e));
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM paper_links WHERE source = ?")
+        .bind(LinkSource::SemanticScholar)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    // A 'reference' entry means the entry's paper cites the matched one; 'citation' the reverse
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO paper_links (citing_paper_id, cited_paper_id, source)
+        SELECT
+            CASE e.kind WHEN 'reference' THEN e.paper_id ELSE p.id END,
+            CASE e.kind WHEN 'reference' THEN p.id ELSE e.paper_id END,
+            ?
+        FROM citation_entries e
+        JOIN papers p ON (e.doi IS NOT NULL AND p.doi = e.doi COLLATE NOCASE)
+            OR (e.arxiv_id IS NOT NULL AND p.arxiv_id = e.arxiv_id)
+        WHERE p.id != e.paper_id
+        "#
+    )
+    .bind(LinkSource::SemanticScholar)
+    .execute(&mut *tx)
+    .await
+    .map_err(err)?;
+
+    Ok(tx.commit().await?)
+}
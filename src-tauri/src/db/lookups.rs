@@ -0,0 +1,99 @@
+// src-tauri/src/db/lookups.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum LookupKind {
+    Doi,
+    Arxiv,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct PendingLookup {
+    pub id: i64,
+    pub paper_id: i64,
+    pub kind: LookupKind,
+    pub identifier: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub created_at: Option<String>,
+}
+
+const LOOKUP_COLUMNS: &str = "id, paper_id, kind, identifier, attempts, last_error, next_attempt_at, created_at";
+
+// Queues a lookup for the paper, or makes an already queued one due again
+pub async fn queue_lookup(
+    pool: &SqlitePool,
+    paper_id: i64,
+    kind: LookupKind,
+    identifier: &str,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO pending_lookups (paper_id, kind, identifier) VALUES (?, ?, ?)
+         ON CONFLICT (paper_id, kind) DO UPDATE SET
+             identifier = excluded.identifier, attempts = 0, last_error = NULL, next_attempt_at = CURRENT_TIMESTAMP",
+    )
+    .bind(paper_id)
+    .bind(kind)
+    .bind(identifier)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| AppError::Db(format!("Failed to queue lookup: {}", e)))
+}
+
+pub async fn get_pending_lookups(pool: &SqlitePool) -> Result<Vec<PendingLookup>, AppError> {
+    sqlx::query_as(&format!("SELECT {} FROM pending_lookups ORDER BY created_at, id", LOOKUP_COLUMNS))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch pending lookups: {}", e)))
+}
+
+pub async fn get_due_lookups(pool: &SqlitePool) -> Result<Vec<PendingLookup>, AppError> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM pending_lookups WHERE next_attempt_at <= CURRENT_TIMESTAMP ORDER BY next_attempt_at, id",
+        LOOKUP_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch pending lookups: {}", e)))
+}
+
+// Counts the failed attempt and puts the next one `minutes` away
+pub async fn reschedule_lookup(pool: &SqlitePool, id: i64, error: &str, minutes: i64) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE pending_lookups
+         SET attempts = attempts + 1, last_error = ?, next_attempt_at = datetime('now', ?)
+         WHERE id = ?",
+    )
+    .bind(error)
+    .bind(format!("+{} minutes", minutes))
+    .bind(id)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| AppError::Db(format!("Failed to update lookup: {}", e)))
+}
+
+// Makes every queued lookup due now, e.g. once the connection is back
+pub async fn make_lookups_due(pool: &SqlitePool) -> Result<(), AppError> {
+    sqlx::query("UPDATE pending_lookups SET next_attempt_at = CURRENT_TIMESTAMP")
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| AppError::Db(format!("Failed to update lookups: {}", e)))
+}
+
+pub async fn delete_lookup(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM pending_lookups WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| AppError::Db(format!("Failed to delete lookup: {}", e)))
+}
@@ -0,0 +1,125 @@
+// src-tauri/src/db/metadata_matches.rs
+
+use crate::crossref::WorkMetadata;
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum MatchStatus {
+    // Waiting for the user to accept or reject it
+    Pending,
+    Applied,
+    Rejected,
+    // Crossref had nothing close enough
+    Unmatched,
+}
+
+#[derive(Serialize)]
+pub struct MetadataMatch {
+    pub paper_id: i64,
+    // The paper's current title, i.e. its file name
+    pub title: String,
+    pub pdf_path: String,
+    pub status: MatchStatus,
+    // 0 to 1
+    pub confidence: Option<f64>,
+    pub metadata: Option<WorkMetadata>,
+    pub checked_at: Option<String>,
+}
+
+type MatchRow = (i64, String, String, MatchStatus, Option<f64>, Option<String>, Option<String>);
+
+fn from_row((paper_id, title, pdf_path, status, confidence, metadata, checked_at): MatchRow) -> MetadataMatch {
+    MetadataMatch {
+        paper_id,
+        title,
+        pdf_path,
+        status,
+        confidence,
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        checked_at,
+    }
+}
+
+const MATCH_QUERY: &str = "SELECT m.paper_id, p.title, p.pdf_path, m.status, m.confidence, m.metadata, m.checked_at \
+     FROM metadata_matches m JOIN papers p ON p.id = m.paper_id";
+
+// The review queue, most confident first; papers in the Trash are left out
+pub async fn get_pending_matches(pool: &SqlitePool) -> Result<Vec<MetadataMatch>, AppError> {
+    let rows: Vec<MatchRow> = sqlx::query_as(&format!(
+        "{} WHERE m.status = 'pending' AND p.deleted_at IS NULL ORDER BY m.confidence DESC, m.paper_id",
+        MATCH_QUERY
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch metadata matches: {}", e)))?;
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+pub async fn get_match(pool: &SqlitePool, paper_id: i64) -> Result<Option<MetadataMatch>, AppError> {
+    let row: Option<MatchRow> = sqlx::query_as(&format!("{} WHERE m.paper_id = ?", MATCH_QUERY))
+        .bind(paper_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch metadata match: {}", e)))?;
+    Ok(row.map(from_row))
+}
+
+// Replaces whatever was recorded for the paper before
+pub async fn save_match(
+    pool: &SqlitePool,
+    paper_id: i64,
+    status: MatchStatus,
+    confidence: Option<f64>,
+    metadata: Option<&WorkMetadata>,
+) -> Result<(), AppError> {
+    let metadata = metadata
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| AppError::Db(format!("Failed to save metadata match: {}", e)))?;
+    sqlx::query(
+        "INSERT INTO metadata_matches (paper_id, status, confidence, metadata) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(paper_id) DO UPDATE SET status = excluded.status, confidence = excluded.confidence, \
+         metadata = excluded.metadata, checked_at = CURRENT_TIMESTAMP",
+    )
+    .bind(paper_id)
+    .bind(status)
+    .bind(confidence)
+    .bind(metadata)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| AppError::Db(format!("Failed to save metadata match: {}", e)))
+}
+
+pub async fn set_status(pool: &SqlitePool, paper_id: i64, status: MatchStatus) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE metadata_matches SET status = ? WHERE paper_id = ?")
+        .bind(status)
+        .bind(paper_id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update metadata match: {}", e)))?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("No metadata match for paper {}", paper_id)));
+    }
+    Ok(())
+}
+
+// Papers without a DOI or arXiv id that haven't been looked up yet, with their indexed text
+pub async fn get_unresolved_papers(pool: &SqlitePool) -> Result<Vec<(i64, String, String, Option<String>)>, AppError> {
+    sqlx::query_as(
+        r#"
+        SELECT p.id, p.title, p.pdf_path, f.content FROM papers p
+        LEFT JOIN papers_fts f ON f.rowid = p.id
+        WHERE p.doi IS NULL AND p.arxiv_id IS NULL AND p.deleted_at IS NULL
+          AND p.id NOT IN (SELECT paper_id FROM metadata_matches)
+        ORDER BY p.id
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
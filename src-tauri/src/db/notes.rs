@@ -0,0 +1,116 @@
+// src-tauri/src/db/notes.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Notes {
+    pub paper_id: i64,
+    pub markdown: String,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct NoteRevision {
+    pub id: i64,
+    pub paper_id: i64,
+    pub markdown: String,
+    pub created_at: Option<String>,
+}
+
+// Saves within this window of the latest revision update it instead of adding a new one,
+// so autosave doesn't leave a revision per keystroke
+const REVISION_WINDOW: &str = "-10 minutes";
+
+pub async fn get_notes(pool: &SqlitePool, paper_id: i64) -> Result<Notes, AppError> {
+    sqlx::query_as::<_, Notes>(
+        "SELECT id AS paper_id, COALESCE(notes, '') AS markdown, updated_at FROM papers WHERE id = ?",
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch notes: {}", e)))?
+    .ok_or_else(|| AppError::NotFound(format!("Paper {} not found", paper_id)))
+}
+
+pub async fn save_notes(pool: &SqlitePool, paper_id: i64, markdown: &str) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let current: Option<(Option<String>,)> = sqlx::query_as("SELECT notes FROM papers WHERE id = ?")
+        .bind(paper_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to save notes: {}", e)))?;
+
+    let Some((current,)) = current else {
+        return Err(AppError::NotFound(format!("Paper {} not found", paper_id)));
+    };
+    if current.as_deref().unwrap_or_default() == markdown {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE papers SET notes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+        .bind(markdown)
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to save notes: {}", e)))?;
+
+    let recent: Option<(i64,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM note_revisions
+        WHERE paper_id = ? AND created_at > datetime('now', ?)
+        ORDER BY id DESC LIMIT 1
+        "#
+    )
+    .bind(paper_id)
+    .bind(REVISION_WINDOW)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save notes: {}", e)))?;
+
+    match recent {
+        Some((revision_id,)) => {
+            sqlx::query("UPDATE note_revisions SET markdown = ? WHERE id = ?")
+                .bind(markdown)
+                .bind(revision_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Db(format!("Failed to save notes: {}", e)))?;
+        }
+        None => {
+            sqlx::query("INSERT INTO note_revisions (paper_id, markdown) VALUES (?, ?)")
+                .bind(paper_id)
+                .bind(markdown)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Db(format!("Failed to save notes: {}", e)))?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// Newest first
+pub async fn get_revisions(pool: &SqlitePool, paper_id: i64) -> Result<Vec<NoteRevision>, AppError> {
+    sqlx::query_as::<_, NoteRevision>(
+        "SELECT id, paper_id, markdown, created_at FROM note_revisions WHERE paper_id = ? ORDER BY id DESC",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch note revisions: {}", e)))
+}
+
+pub async fn get_revision(pool: &SqlitePool, id: i64) -> Result<NoteRevision, AppError> {
+    sqlx::query_as::<_, NoteRevision>(
+        "SELECT id, paper_id, markdown, created_at FROM note_revisions WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch note revision: {}", e)))?
+    .ok_or_else(|| AppError::NotFound(format!("Note revision {} not found", id)))
+}
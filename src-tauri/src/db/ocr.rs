@@ -0,0 +1,75 @@
+// src-tauri/src/db/ocr.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+// Between pages in `content`, as pdftotext does it. Text saved before pages were kept apart
+// uses blank lines and reads back as a single page.
+pub const PAGE_BREAK: char = '\x0c';
+
+#[derive(Serialize, FromRow, Clone)]
+pub struct OcrText {
+    pub paper_id: i64,
+    pub content: String,
+    // Tesseract language codes, e.g. "eng" or "deu+eng"
+    pub language: String,
+    pub page_count: i64,
+    pub created_at: Option<String>,
+}
+
+pub async fn get_ocr_text(pool: &SqlitePool, paper_id: i64) -> Result<Option<OcrText>, AppError> {
+    sqlx::query_as::<_, OcrText>(
+        "SELECT paper_id, content, language, page_count, created_at FROM paper_ocr WHERE paper_id = ?",
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch OCR text: {}", e)))
+}
+
+pub async fn save_ocr_text(
+    pool: &SqlitePool,
+    paper_id: i64,
+    content: &str,
+    language: &str,
+    page_count: i64,
+) -> Result<OcrText, AppError> {
+    sqlx::query_as::<_, OcrText>(
+        r#"
+        INSERT INTO paper_ocr (paper_id, content, language, page_count) VALUES (?, ?, ?, ?)
+        ON CONFLICT(paper_id) DO UPDATE SET
+            content = excluded.content,
+            language = excluded.language,
+            page_count = excluded.page_count,
+            created_at = CURRENT_TIMESTAMP
+        RETURNING paper_id, content, language, page_count, created_at
+        "#
+    )
+    .bind(paper_id)
+    .bind(content)
+    .bind(language)
+    .bind(page_count)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save OCR text: {}", e)))
+}
+
+// Papers with a PDF but (almost) no indexed text that haven't been through OCR yet,
+// as (id, title, pdf_path)
+pub async fn get_papers_needing_ocr(pool: &SqlitePool, min_chars: i64) -> Result<Vec<(i64, String, String)>, AppError> {
+    sqlx::query_as(
+        r#"
+        SELECT p.id, p.title, p.pdf_path FROM papers p
+        LEFT JOIN papers_fts f ON f.rowid = p.id
+        WHERE p.pdf_path != '' AND p.deleted_at IS NULL
+          AND length(trim(COALESCE(f.content, ''))) < ?
+          AND p.id NOT IN (SELECT paper_id FROM paper_ocr)
+        ORDER BY p.id
+        "#
+    )
+    .bind(min_chars)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))
+}
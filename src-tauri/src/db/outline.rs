@@ -0,0 +1,101 @@
+// src-tauri/src/db/outline.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+// One outline entry as read from the PDF, in document order
+#[derive(Debug, FromRow)]
+pub struct OutlineEntry {
+    // 0 for top-level sections
+    pub depth: i64,
+    pub title: String,
+    pub page: Option<i64>,
+    // Points from the top of the page
+    pub y: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct OutlineItem {
+    pub title: String,
+    pub page: Option<i64>,
+    pub y: Option<f64>,
+    pub children: Vec<OutlineItem>,
+}
+
+// Replaces whatever outline the paper had
+pub async fn save_outline(pool: &SqlitePool, paper_id: i64, entries: &[OutlineEntry]) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save outline: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    sqlx::query("DELETE FROM outline_entries WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    for (position, entry) in entries.iter().enumerate() {
+        sqlx::query("INSERT INTO outline_entries (paper_id, position, depth, title, page, y) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(paper_id)
+            .bind(position as i64)
+            .bind(entry.depth)
+            .bind(&entry.title)
+            .bind(entry.page)
+            .bind(entry.y)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await.map_err(err)
+}
+
+// Top-level sections with their subsections nested under them. Empty if the PDF has no outline.
+pub async fn get_outline(pool: &SqlitePool, paper_id: i64) -> Result<Vec<OutlineItem>, AppError> {
+    let entries: Vec<OutlineEntry> = sqlx::query_as(
+        "SELECT depth, title, page, y FROM outline_entries WHERE paper_id = ? ORDER BY position",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch outline: {}", e)))?;
+
+    Ok(nest(entries))
+}
+
+// Builds the tree from depths. An entry deeper than the one before it is taken as that
+// entry's child, however many levels it skips.
+fn nest(entries: Vec<OutlineEntry>) -> Vec<OutlineItem> {
+    // The chain of open items from the top level down; each is folded into its parent
+    // once an entry at the same or a shallower depth turns up
+    let mut stack: Vec<(i64, OutlineItem)> = Vec::new();
+    let mut roots = Vec::new();
+
+    for entry in entries {
+        while stack.last().is_some_and(|(depth, _)| *depth >= entry.depth) {
+            close_last(&mut stack, &mut roots);
+        }
+        stack.push((
+            entry.depth,
+            OutlineItem {
+                title: entry.title,
+                page: entry.page,
+                y: entry.y,
+                children: Vec::new(),
+            },
+        ));
+    }
+    while !stack.is_empty() {
+        close_last(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn close_last(stack: &mut Vec<(i64, OutlineItem)>, roots: &mut Vec<OutlineItem>) {
+    if let Some((_, item)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(item),
+            None => roots.push(item),
+        }
+    }
+}
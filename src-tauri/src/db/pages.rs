@@ -0,0 +1,206 @@
+// src-tauri/src/db/pages.rs
+
+use super::PageMatch;
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+// A page's text and where each word sits on it
+pub struct PageLayout {
+    // 1-based
+    pub page: i64,
+    pub text: String,
+    pub words: Vec<WordBox>,
+}
+
+// `start` and `end` are char offsets into the page text; the box is in points from the
+// page's top-left corner, like annotations
+#[derive(Clone, Copy)]
+pub struct WordBox {
+    pub start: usize,
+    pub end: usize,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Serialize)]
+pub struct MatchRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Serialize)]
+pub struct InPaperMatch {
+    pub page: i64,
+    // Text around the hit, with the hit in <mark>
+    pub snippet: String,
+    // In UTF-16 code units of the page text
+    pub start: usize,
+    pub end: usize,
+    // One per line the hit spans, covering whole words
+    pub rects: Vec<MatchRect>,
+}
+
+// Words whose boxes are this close vertically are on the same line
+const LINE_TOLERANCE: f64 = 2.0;
+
+pub async fn has_pages(pool: &SqlitePool, paper_id: i64) -> Result<bool, AppError> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM paper_pages WHERE paper_id = ?)")
+        .bind(paper_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch page text: {}", e)))
+}
+
+// Replaces the paper's cached pages
+pub async fn save_pages(pool: &SqlitePool, paper_id: i64, pages: &[PageLayout]) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save page text: {}", e));
+    let mut tx = pool.begin().await.map_err(err)?;
+
+    sqlx::query("DELETE FROM paper_pages WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    for page in pages {
+        let words: Vec<[f64; 6]> = page
+            .words
+            .iter()
+            .map(|w| [w.start as f64, w.end as f64, round(w.x), round(w.y), round(w.width), round(w.height)])
+            .collect();
+        let words =
+            serde_json::to_string(&words).map_err(|e| AppError::Db(format!("Failed to save page text: {}", e)))?;
+        sqlx::query("INSERT INTO paper_pages (paper_id, page, text, words) VALUES (?, ?, ?, ?)")
+            .bind(paper_id)
+            .bind(page.page)
+            .bind(&page.text)
+            .bind(words)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await.map_err(err)
+}
+
+// For when the paper's PDF has been replaced
+pub async fn clear_pages(pool: &SqlitePool, paper_id: i64) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM paper_pages WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| AppError::Db(format!("Failed to clear page text: {}", e)))
+}
+
+// Case-insensitive, with any run of whitespace in the query matching any run of whitespace
+// (line breaks included) in the page. In page order.
+pub async fn search_in_paper(pool: &SqlitePool, paper_id: i64, query: &str) -> Result<Vec<InPaperMatch>, AppError> {
+    let terms: Vec<Vec<char>> = query.split_whitespace().map(|t| t.chars().map(fold).collect()).collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pages: Vec<(i64, String, String)> =
+        sqlx::query_as("SELECT page, text, words FROM paper_pages WHERE paper_id = ? ORDER BY page")
+            .bind(paper_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Search failed: {}", e)))?;
+
+    let mut matches = Vec::new();
+    for (page, text, words) in pages {
+        let text: Vec<char> = text.chars().collect();
+        let hits = find_hits(&text, &terms);
+        if hits.is_empty() {
+            continue;
+        }
+        let words: Vec<WordBox> = serde_json::from_str::<Vec<[f64; 6]>>(&words)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|[start, end, x, y, width, height]| WordBox {
+                start: start as usize,
+                end: end as usize,
+                x,
+                y,
+                width,
+                height,
+            })
+            .collect();
+
+        let rects: Vec<Vec<MatchRect>> = hits.iter().map(|&(start, end)| hit_rects(&words, start, end)).collect();
+        for (m, rects) in super::page_matches(page, &text, hits).into_iter().zip(rects) {
+            let PageMatch { page, snippet, start, end } = m;
+            matches.push(InPaperMatch { page, snippet, start, end, rects });
+        }
+    }
+    Ok(matches)
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// (start, end) in chars of every non-overlapping hit
+fn find_hits(text: &[char], terms: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let mut hits = Vec::new();
+    let mut at = 0;
+    while at < text.len() {
+        match match_at(text, at, terms) {
+            Some(end) => {
+                hits.push((at, end));
+                at = end;
+            }
+            None => at += 1,
+        }
+    }
+    hits
+}
+
+// End of the match starting at `at`, if there is one
+fn match_at(text: &[char], at: usize, terms: &[Vec<char>]) -> Option<usize> {
+    let mut pos = at;
+    for (i, term) in terms.iter().enumerate() {
+        if i > 0 {
+            let gap = text[pos..].iter().take_while(|c| c.is_whitespace()).count();
+            if gap == 0 {
+                return None;
+            }
+            pos += gap;
+        }
+        let candidate = text.get(pos..pos + term.len())?;
+        if !candidate.iter().zip(term).all(|(&c, &t)| fold(c) == t) {
+            return None;
+        }
+        pos += term.len();
+    }
+    Some(pos)
+}
+
+// The boxes of the words the hit touches, merged line by line
+fn hit_rects(words: &[WordBox], start: usize, end: usize) -> Vec<MatchRect> {
+    let mut rects: Vec<MatchRect> = Vec::new();
+    for word in words.iter().filter(|w| w.start < end && w.end > start) {
+        match rects.last_mut() {
+            Some(rect) if (rect.y - word.y).abs() <= LINE_TOLERANCE => {
+                let right = (rect.x + rect.width).max(word.x + word.width);
+                let bottom = (rect.y + rect.height).max(word.y + word.height);
+                rect.x = rect.x.min(word.x);
+                rect.y = rect.y.min(word.y);
+                rect.width = right - rect.x;
+                rect.height = bottom - rect.y;
+            }
+            _ => rects.push(MatchRect { x: word.x, y: word.y, width: word.width, height: word.height }),
+        }
+    }
+    rects
+}
+
+// Hundredths of a point are plenty and keep the stored JSON small
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
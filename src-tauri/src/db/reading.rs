@@ -0,0 +1,117 @@
+// src-tauri/src/db/reading.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct ReadingSession {
+    pub id: i64,
+    pub paper_id: i64,
+    pub started_at: String,
+    // None while the session is going on
+    pub ended_at: Option<String>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct PaperReadingTime {
+    pub paper_id: i64,
+    pub title: String,
+    pub seconds: i64,
+    pub sessions: i64,
+    pub last_read_at: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct WeekReadingTime {
+    // Monday of the week, e.g. 2026-10-12
+    pub week_start: String,
+    pub seconds: i64,
+    // Distinct papers read that week
+    pub papers: i64,
+}
+
+#[derive(Serialize)]
+pub struct ReadingStats {
+    // Most time spent first
+    pub per_paper: Vec<PaperReadingTime>,
+    // Newest week first
+    pub per_week: Vec<WeekReadingTime>,
+}
+
+const SESSION_COLUMNS: &str = "id, paper_id, started_at, ended_at";
+const SESSION_SECONDS: &str = "CAST((julianday(ended_at) - julianday(started_at)) * 86400 AS INTEGER)";
+
+// Any session still open, on this paper or another, ends now; only one paper is read at a time
+pub async fn start_session(pool: &SqlitePool, paper_id: i64) -> Result<ReadingSession, AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("UPDATE reading_sessions SET ended_at = CURRENT_TIMESTAMP WHERE ended_at IS NULL")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to end reading session: {}", e)))?;
+
+    let session = sqlx::query_as(&format!(
+        "INSERT INTO reading_sessions (paper_id) VALUES (?) RETURNING {}",
+        SESSION_COLUMNS
+    ))
+    .bind(paper_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to start reading session: {}", e)))?;
+
+    tx.commit().await?;
+    Ok(session)
+}
+
+// Ending a session twice keeps the first end time. None if there is no such session.
+pub async fn end_session(pool: &SqlitePool, id: i64) -> Result<Option<ReadingSession>, AppError> {
+    sqlx::query_as(&format!(
+        "UPDATE reading_sessions SET ended_at = COALESCE(ended_at, CURRENT_TIMESTAMP) WHERE id = ? RETURNING {}",
+        SESSION_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to end reading session: {}", e)))
+}
+
+// Sessions the app was in the middle of when it last quit
+pub async fn discard_open_sessions(pool: &SqlitePool) -> Result<u64, AppError> {
+    sqlx::query("DELETE FROM reading_sessions WHERE ended_at IS NULL")
+        .execute(pool)
+        .await
+        .map(|r| r.rows_affected())
+        .map_err(|e| AppError::Db(format!("Failed to discard reading sessions: {}", e)))
+}
+
+// Finished sessions only. `weeks` limits the weekly breakdown to that many recent weeks.
+pub async fn get_reading_stats(pool: &SqlitePool, weeks: i64) -> Result<ReadingStats, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to compute reading time: {}", e));
+
+    let per_paper = sqlx::query_as::<_, PaperReadingTime>(&format!(
+        "SELECT s.paper_id, p.title, SUM({}) AS seconds, COUNT(*) AS sessions, MAX(s.ended_at) AS last_read_at \
+         FROM reading_sessions s JOIN papers p ON p.id = s.paper_id \
+         WHERE s.ended_at IS NOT NULL \
+         GROUP BY s.paper_id ORDER BY seconds DESC",
+        SESSION_SECONDS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    // 'weekday 0' moves forward to Sunday, six days back from there is the week's Monday
+    let per_week = sqlx::query_as::<_, WeekReadingTime>(&format!(
+        "SELECT date(started_at, 'weekday 0', '-6 days') AS week_start, SUM({}) AS seconds, \
+             COUNT(DISTINCT paper_id) AS papers \
+         FROM reading_sessions WHERE ended_at IS NOT NULL \
+         GROUP BY week_start ORDER BY week_start DESC LIMIT ?",
+        SESSION_SECONDS
+    ))
+    .bind(weeks)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    Ok(ReadingStats { per_paper, per_week })
+}
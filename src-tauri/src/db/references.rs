@@ -0,0 +1,120 @@
+// src-tauri/src/db/references.rs
+
+use super::links::LinkSource;
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow, Clone, Default, Debug)]
+pub struct ExtractedReference {
+    pub id: i64,
+    // The entry as it appears in the PDF, whitespace collapsed
+    pub raw: String,
+    pub title: Option<String>,
+    pub authors: Option<String>,
+    pub year: Option<i64>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub matched_paper_id: Option<i64>,
+}
+
+pub async fn get_extracted_references(pool: &SqlitePool, paper_id: i64) -> Result<Vec<ExtractedReference>, AppError> {
+    sqlx::query_as::<_, ExtractedReference>(
+        r#"
+        SELECT id, raw, title, authors, year, doi, arxiv_id, matched_paper_id FROM paper_references
+        WHERE paper_id = ?
+        ORDER BY position
+        "#
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch references: {}", e)))
+}
+
+// Replaces the paper's references, along with any citation links matched from the old ones
+pub async fn save_extracted_references(
+    pool: &SqlitePool,
+    paper_id: i64,
+    references: &[ExtractedReference],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save references: {}", e));
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM paper_references WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    sqlx::query("DELETE FROM paper_links WHERE citing_paper_id = ? AND source = ?")
+        .bind(paper_id)
+        .bind(LinkSource::Pdf)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    for (position, reference) in references.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO paper_references (paper_id, position, raw, title, authors, year, doi, arxiv_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(paper_id)
+        .bind(position as i64)
+        .bind(&reference.raw)
+        .bind(&reference.title)
+        .bind(&reference.authors)
+        .bind(reference.year)
+        .bind(&reference.doi)
+        .bind(&reference.arxiv_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+    }
+
+    Ok(tx.commit().await?)
+}
+
+// Stores (reference id, matched paper) pairs and rebuilds the paper's links from them
+pub async fn set_reference_matches(
+    pool: &SqlitePool,
+    paper_id: i64,
+    matches: &[(i64, Option<i64>)],
+) -> Result<(), AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to save reference matches: {}", e));
+    let mut tx = pool.begin().await?;
+
+    for (reference_id, matched_paper_id) in matches {
+        sqlx::query("UPDATE paper_references SET matched_paper_id = ? WHERE id = ? AND paper_id = ?")
+            .bind(matched_paper_id)
+            .bind(reference_id)
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    sqlx::query("DELETE FROM paper_links WHERE citing_paper_id = ? AND source = ?")
+        .bind(paper_id)
+        .bind(LinkSource::Pdf)
+        .execute(&mut *tx)
+        .await
+        .map_err(err)?;
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO paper_links (citing_paper_id, cited_paper_id, source)
+        SELECT DISTINCT paper_id, matched_paper_id, ? FROM paper_references
+        WHERE paper_id = ? AND matched_paper_id IS NOT NULL AND matched_paper_id != paper_id
+        "#
+    )
+    .bind(LinkSource::Pdf)
+    .bind(paper_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(err)?;
+
+    Ok(tx.commit().await?)
+}
@@ -0,0 +1,105 @@
+// src-tauri/src/db/revisions.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct PaperRevision {
+    pub id: i64,
+    pub paper_id: i64,
+    pub pdf_path: String,
+    pub file_hash: Option<String>,
+    pub label: String,
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, FromRow, Clone)]
+pub struct ArxivUpdate {
+    pub paper_id: i64,
+    pub title: String,
+    // The version the paper's PDF is
+    pub current_id: String,
+    pub latest_id: String,
+}
+
+// Newest first
+pub async fn get_revisions(pool: &SqlitePool, paper_id: i64) -> Result<Vec<PaperRevision>, AppError> {
+    sqlx::query_as(
+        "SELECT id, paper_id, pdf_path, file_hash, label, created_at FROM paper_revisions \
+         WHERE paper_id = ? ORDER BY created_at DESC, id DESC",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch revisions: {}", e)))
+}
+
+pub async fn add_revision(
+    pool: &SqlitePool,
+    paper_id: i64,
+    pdf_path: &str,
+    file_hash: Option<&str>,
+    label: &str,
+) -> Result<(), AppError> {
+    sqlx::query("INSERT INTO paper_revisions (paper_id, pdf_path, file_hash, label) VALUES (?, ?, ?, ?)")
+        .bind(paper_id)
+        .bind(pdf_path)
+        .bind(file_hash)
+        .bind(label)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to save revision: {}", e)))?;
+    Ok(())
+}
+
+// Papers with an arXiv id that weren't checked for a newer version in the last `hours`, oldest check first
+pub async fn get_arxiv_ids_to_check(pool: &SqlitePool, hours: i64, limit: i64) -> Result<Vec<(i64, String)>, AppError> {
+    sqlx::query_as(
+        "SELECT p.id, p.arxiv_id FROM papers p LEFT JOIN arxiv_versions v ON v.paper_id = p.id \
+         WHERE p.arxiv_id IS NOT NULL AND p.arxiv_id != '' AND p.deleted_at IS NULL \
+         AND (v.checked_at IS NULL OR v.checked_at < datetime('now', ?)) \
+         ORDER BY v.checked_at IS NOT NULL, v.checked_at, p.id LIMIT ?",
+    )
+    .bind(format!("-{} hours", hours))
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch arXiv papers: {}", e)))
+}
+
+// `latest_id` is None when arXiv didn't know the paper
+pub async fn save_arxiv_check(pool: &SqlitePool, paper_id: i64, latest_id: Option<&str>) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO arxiv_versions (paper_id, latest_id, checked_at) VALUES (?, ?, CURRENT_TIMESTAMP) \
+         ON CONFLICT(paper_id) DO UPDATE SET latest_id = COALESCE(excluded.latest_id, latest_id), \
+         checked_at = excluded.checked_at",
+    )
+    .bind(paper_id)
+    .bind(latest_id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save arXiv check: {}", e)))?;
+    Ok(())
+}
+
+// Papers whose PDF is an older arXiv version than the newest one seen
+pub async fn get_arxiv_updates(pool: &SqlitePool) -> Result<Vec<ArxivUpdate>, AppError> {
+    let rows: Vec<ArxivUpdate> = sqlx::query_as(
+        "SELECT p.id AS paper_id, p.title, p.arxiv_id AS current_id, v.latest_id FROM papers p \
+         JOIN arxiv_versions v ON v.paper_id = p.id \
+         WHERE v.latest_id IS NOT NULL AND v.latest_id != p.arxiv_id AND p.deleted_at IS NULL ORDER BY p.title COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch arXiv updates: {}", e)))?;
+
+    Ok(rows.into_iter().filter(is_newer).collect())
+}
+
+// Without a version in the paper's id, e.g. from a .bib file, there's no telling which one its PDF is
+fn is_newer(update: &ArxivUpdate) -> bool {
+    let (current_base, current) = crate::arxiv::split_version(&update.current_id);
+    let (latest_base, latest) = crate::arxiv::split_version(&update.latest_id);
+    current_base == latest_base && matches!((current, latest), (Some(current), Some(latest)) if latest > current)
+}
@@ -0,0 +1,113 @@
+// src-tauri/src/db/saved_searches.rs
+
+use super::{count_papers, PaperFilter};
+use crate::query;
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+#[derive(Serialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    // Advanced search syntax, see query.rs
+    pub query: String,
+    // Papers matching right now, not counting the Trash
+    pub result_count: i64,
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SavedSearchCount {
+    pub id: i64,
+    pub result_count: i64,
+}
+
+type Row = (i64, String, String, Option<String>);
+
+// The saved query narrows `filter`, which still decides sorting and paging. A search already
+// in the filter has to match as well.
+pub fn apply(query: &str, filter: PaperFilter) -> PaperFilter {
+    let search = match filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(extra) => format!("{} {}", query, extra),
+        None => query.to_string(),
+    };
+    PaperFilter { search: Some(search), ..filter }
+}
+
+pub async fn count_results(pool: &SqlitePool, query: &str) -> Result<i64, AppError> {
+    count_papers(pool, &apply(query, PaperFilter::default())).await
+}
+
+async fn to_saved_search(pool: &SqlitePool, (id, name, query, created_at): Row) -> Result<SavedSearch, AppError> {
+    // A query that no longer parses shows as empty instead of hiding the whole list
+    let result_count = count_results(pool, &query).await.unwrap_or_default();
+    Ok(SavedSearch { id, name, query, result_count, created_at })
+}
+
+pub async fn get_saved_search(pool: &SqlitePool, id: i64) -> Result<SavedSearch, AppError> {
+    let row: Row = sqlx::query_as("SELECT id, name, query, created_at FROM saved_searches WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch saved search: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Saved search {} not found", id)))?;
+    to_saved_search(pool, row).await
+}
+
+pub async fn get_saved_searches(pool: &SqlitePool) -> Result<Vec<SavedSearch>, AppError> {
+    let rows: Vec<Row> =
+        sqlx::query_as("SELECT id, name, query, created_at FROM saved_searches ORDER BY name COLLATE NOCASE")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch saved searches: {}", e)))?;
+
+    let mut searches = Vec::with_capacity(rows.len());
+    for row in rows {
+        searches.push(to_saved_search(pool, row).await?);
+    }
+    Ok(searches)
+}
+
+// Saving under a name that is already taken replaces that search's query
+pub async fn save_search(pool: &SqlitePool, name: &str, query: &str) -> Result<SavedSearch, AppError> {
+    query::parse(query)?;
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO saved_searches (name, query) VALUES (?, ?) \
+         ON CONFLICT(name) DO UPDATE SET query = excluded.query RETURNING id",
+    )
+    .bind(name)
+    .bind(query)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save search: {}", e)))?;
+
+    get_saved_search(pool, id).await
+}
+
+pub async fn delete_saved_search(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM saved_searches WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete saved search: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Saved search {} not found", id)));
+    }
+    Ok(())
+}
+
+pub async fn get_result_counts(pool: &SqlitePool) -> Result<Vec<SavedSearchCount>, AppError> {
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, query FROM saved_searches")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch saved searches: {}", e)))?;
+
+    let mut counts = Vec::with_capacity(rows.len());
+    for (id, query) in rows {
+        let result_count = count_results(pool, &query).await.unwrap_or_default();
+        counts.push(SavedSearchCount { id, result_count });
+    }
+    Ok(counts)
+}
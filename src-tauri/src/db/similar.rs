@@ -0,0 +1,144 @@
+// src-tauri/src/db/similar.rs
+
+// Papers about the same things as a given one, by TF-IDF cosine similarity over the title,
+// abstract and the start of the PDF text. It's computed on request from the search index, so
+// there is nothing extra to keep up to date.
+
+use super::{get_papers_by_ids, Paper};
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+// The opening pages (abstract, introduction) say the most about what a paper is about
+const TEXT_CHARS: i64 = 20_000;
+const TITLE_WEIGHT: f64 = 3.0;
+const ABSTRACT_WEIGHT: f64 = 2.0;
+// Below this papers only share common vocabulary
+const MIN_SCORE: f64 = 0.05;
+const SHARED_TERMS: usize = 5;
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was", "one", "our", "out",
+    "has", "have", "his", "how", "its", "may", "new", "now", "see", "two", "way", "who", "did", "get", "use", "used",
+    "using", "this", "that", "with", "from", "they", "them", "then", "than", "there", "their", "these", "those",
+    "which", "while", "where", "when", "what", "will", "would", "could", "should", "been", "being", "were", "into",
+    "also", "such", "only", "other", "more", "most", "some", "each", "both", "between", "over", "under", "about",
+    "after", "before", "through", "however", "thus", "here", "well", "very", "does", "paper", "papers", "show",
+    "shown", "shows", "results", "result", "based", "propose", "proposed", "approach", "method", "methods", "work",
+    "figure", "fig", "table", "section", "et", "al", "first", "second", "three", "via", "within", "without",
+];
+
+#[derive(Serialize)]
+pub struct SimilarPaper {
+    pub paper: Paper,
+    // Cosine similarity, from 0 to 1
+    pub score: f64,
+    // Word stems that weigh most in the match, to show why it was suggested
+    pub terms: Vec<String>,
+}
+
+type Row = (i64, String, Option<String>, Option<String>);
+
+// Up to `limit` papers outside the Trash, most similar first
+pub async fn get_similar_papers(pool: &SqlitePool, paper_id: i64, limit: usize) -> Result<Vec<SimilarPaper>, AppError> {
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT p.id, p.title, p.abstract, substr(f.content, 1, ?) \
+         FROM papers p LEFT JOIN papers_fts f ON f.rowid = p.id \
+         WHERE p.deleted_at IS NULL OR p.id = ?",
+    )
+    .bind(TEXT_CHARS)
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to read paper texts: {}", e)))?;
+
+    let documents: Vec<(i64, HashMap<String, f64>)> = rows
+        .into_iter()
+        .map(|(id, title, r#abstract, content)| {
+            let mut counts = HashMap::new();
+            add_terms(&mut counts, &title, TITLE_WEIGHT);
+            add_terms(&mut counts, r#abstract.as_deref().unwrap_or_default(), ABSTRACT_WEIGHT);
+            add_terms(&mut counts, content.as_deref().unwrap_or_default(), 1.0);
+            (id, counts)
+        })
+        .collect();
+    if !documents.iter().any(|(id, _)| *id == paper_id) {
+        return Err(AppError::NotFound(format!("Paper {} not found", paper_id)));
+    }
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for (_, counts) in &documents {
+        for term in counts.keys() {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+    let total = documents.len() as f64;
+    let idf = |term: &str| ((1.0 + total) / (1.0 + document_frequency[term] as f64)).ln() + 1.0;
+
+    // Unit length TF-IDF vectors with log-scaled term frequencies
+    let vectors: Vec<(i64, HashMap<&str, f64>)> = documents
+        .iter()
+        .map(|(id, counts)| {
+            let mut vector: HashMap<&str, f64> = counts
+                .iter()
+                .map(|(term, count)| (term.as_str(), (1.0 + count.ln()) * idf(term)))
+                .collect();
+            let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                vector.values_mut().for_each(|w| *w /= norm);
+            }
+            (*id, vector)
+        })
+        .collect();
+
+    let target = &vectors.iter().find(|(id, _)| *id == paper_id).expect("checked above").1;
+    let mut scored: Vec<(i64, f64, Vec<String>)> = vectors
+        .iter()
+        .filter(|(id, _)| *id != paper_id)
+        .filter_map(|(id, vector)| {
+            let mut shared: Vec<(&str, f64)> = target
+                .iter()
+                .filter_map(|(term, weight)| vector.get(term).map(|other| (*term, weight * other)))
+                .collect();
+            let score: f64 = shared.iter().map(|(_, product)| product).sum();
+            if score < MIN_SCORE {
+                return None;
+            }
+            shared.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let terms = shared.iter().take(SHARED_TERMS).map(|(term, _)| term.to_string()).collect();
+            Some((*id, score, terms))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+
+    let ids: Vec<i64> = scored.iter().map(|(id, ..)| *id).collect();
+    let mut papers: HashMap<i64, Paper> = get_papers_by_ids(pool, &ids).await?.into_iter().map(|p| (p.id, p)).collect();
+    Ok(scored
+        .into_iter()
+        .filter_map(|(id, score, terms)| Some(SimilarPaper { paper: papers.remove(&id)?, score, terms }))
+        .collect())
+}
+
+fn add_terms(counts: &mut HashMap<String, f64>, text: &str, weight: f64) {
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.chars().count() < 3 || word.chars().all(|c| c.is_numeric()) {
+            continue;
+        }
+        let word = word.to_lowercase();
+        if STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(stem(&word)).or_default() += weight;
+    }
+}
+
+// Just plurals, so "network" and "networks" count as one term
+fn stem(word: &str) -> String {
+    let keep = word.len() <= 4 || ["ss", "us", "is"].iter().any(|end| word.ends_with(end));
+    match word.strip_suffix('s') {
+        Some(singular) if !keep => singular.to_string(),
+        _ => word.to_string(),
+    }
+}
@@ -0,0 +1,148 @@
+// src-tauri/src/db/smart_collections.rs
+
+use super::{count_papers, PaperFilter, ReadingStatus};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+// The saved part of a smart collection; fields left as None don't narrow it
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SmartQuery {
+    // Papers must carry every one of these tags
+    pub tags: Option<Vec<String>>,
+    pub year_from: Option<i64>,
+    pub year_to: Option<i64>,
+    // Words to find in the title or PDF text, or a substring of the authors
+    pub query: Option<String>,
+    pub status: Option<ReadingStatus>,
+    pub starred: Option<bool>,
+    pub journal: Option<String>,
+    // Only papers that are also in this (ordinary) collection
+    pub collection_id: Option<i64>,
+}
+
+impl SmartQuery {
+    // The saved conditions on top of `filter`, which still decides sorting and paging and can
+    // narrow further wherever the saved query leaves a field open. Tags from both apply.
+    pub fn apply(&self, filter: PaperFilter) -> PaperFilter {
+        let tags = match (self.tags.clone(), filter.tags) {
+            (Some(mut saved), Some(extra)) => {
+                saved.extend(extra);
+                Some(saved)
+            }
+            (saved, extra) => saved.or(extra),
+        };
+        PaperFilter {
+            tags,
+            year_from: self.year_from.or(filter.year_from),
+            year_to: self.year_to.or(filter.year_to),
+            query: self.query.clone().or(filter.query),
+            status: self.status.or(filter.status),
+            starred: self.starred.or(filter.starred),
+            journal: self.journal.clone().or(filter.journal),
+            collection_id: self.collection_id.or(filter.collection_id),
+            ..filter
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SmartCollection {
+    pub id: i64,
+    pub name: String,
+    pub query: SmartQuery,
+    // Papers matching right now, not counting the Trash
+    pub paper_count: i64,
+    pub created_at: Option<String>,
+}
+
+async fn to_smart_collection(
+    pool: &SqlitePool,
+    (id, name, query, created_at): (i64, String, String, Option<String>),
+) -> Result<SmartCollection, AppError> {
+    let query: SmartQuery = serde_json::from_str(&query)
+        .map_err(|e| AppError::Db(format!("Smart collection \"{}\" has an invalid query: {}", name, e)))?;
+    let paper_count = count_papers(pool, &query.apply(PaperFilter::default())).await?;
+    Ok(SmartCollection { id, name, query, paper_count, created_at })
+}
+
+pub async fn get_smart_collection(pool: &SqlitePool, id: i64) -> Result<SmartCollection, AppError> {
+    let row: (i64, String, String, Option<String>) =
+        sqlx::query_as("SELECT id, name, query, created_at FROM smart_collections WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch smart collection: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Smart collection {} not found", id)))?;
+    to_smart_collection(pool, row).await
+}
+
+pub async fn get_smart_collections(pool: &SqlitePool) -> Result<Vec<SmartCollection>, AppError> {
+    let rows: Vec<(i64, String, String, Option<String>)> =
+        sqlx::query_as("SELECT id, name, query, created_at FROM smart_collections ORDER BY name COLLATE NOCASE")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch smart collections: {}", e)))?;
+
+    let mut collections = Vec::with_capacity(rows.len());
+    for row in rows {
+        collections.push(to_smart_collection(pool, row).await?);
+    }
+    Ok(collections)
+}
+
+pub async fn create_smart_collection(
+    pool: &SqlitePool,
+    name: &str,
+    query: &SmartQuery,
+) -> Result<SmartCollection, AppError> {
+    let query = serde_json::to_string(query)
+        .map_err(|e| AppError::Db(format!("Failed to create smart collection: {}", e)))?;
+    let result = sqlx::query("INSERT INTO smart_collections (name, query) VALUES (?, ?)")
+        .bind(name)
+        .bind(query)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to create smart collection: {}", e)))?;
+
+    get_smart_collection(pool, result.last_insert_rowid()).await
+}
+
+// None leaves the name or query as it is
+pub async fn update_smart_collection(
+    pool: &SqlitePool,
+    id: i64,
+    name: Option<&str>,
+    query: Option<&SmartQuery>,
+) -> Result<SmartCollection, AppError> {
+    let query = query
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| AppError::Db(format!("Failed to update smart collection: {}", e)))?;
+    let result = sqlx::query("UPDATE smart_collections SET name = COALESCE(?, name), query = COALESCE(?, query) WHERE id = ?")
+        .bind(name)
+        .bind(query)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update smart collection: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Smart collection {} not found", id)));
+    }
+
+    get_smart_collection(pool, id).await
+}
+
+pub async fn delete_smart_collection(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM smart_collections WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete smart collection: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Smart collection {} not found", id)));
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// src-tauri/src/db/stats.rs
+
+// Counts for the library dashboard. Papers in the Trash are left out everywhere except
+// `trashed`.
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+// Journals beyond this many, counted by papers, are left out of `per_journal`
+const TOP_JOURNALS: i64 = 25;
+
+#[derive(Serialize)]
+pub struct LibraryStats {
+    pub papers: i64,
+    pub with_pdf: i64,
+    pub starred: i64,
+    pub trashed: i64,
+    pub unread: i64,
+    pub reading: i64,
+    pub read: i64,
+    // Size of the PDFs kept in the papers folder
+    pub stored_bytes: i64,
+    // Size of the linked files, which live outside it
+    pub linked_bytes: i64,
+    // Oldest year first; papers without a year are counted under None
+    pub per_year: Vec<YearCount>,
+    // Canonical venue names where matched, most papers first
+    pub per_journal: Vec<NamedCount>,
+    // Every tag, most papers first
+    pub per_tag: Vec<NamedCount>,
+    // "2026-01" and so on, oldest first
+    pub added_per_month: Vec<NamedCount>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct YearCount {
+    pub year: Option<i64>,
+    pub count: i64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct NamedCount {
+    pub name: String,
+    pub count: i64,
+}
+
+pub async fn get_library_stats(pool: &SqlitePool) -> Result<LibraryStats, AppError> {
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to compute library statistics: {}", e));
+
+    let totals: (i64, i64, i64, i64, i64, i64, i64, i64, i64) = sqlx::query_as(
+        "SELECT \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL), \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL AND pdf_path != ''), \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL AND starred), \
+             COUNT(*) FILTER (WHERE deleted_at IS NOT NULL), \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL AND reading_status = 'unread'), \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL AND reading_status = 'reading'), \
+             COUNT(*) FILTER (WHERE deleted_at IS NULL AND reading_status = 'read'), \
+             COALESCE(SUM(file_size) FILTER (WHERE deleted_at IS NULL AND file_mode = 'stored'), 0), \
+             COALESCE(SUM(file_size) FILTER (WHERE deleted_at IS NULL AND file_mode = 'linked'), 0) \
+         FROM papers",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(err)?;
+
+    let per_year = sqlx::query_as::<_, YearCount>(
+        "SELECT year, COUNT(*) AS count FROM papers WHERE deleted_at IS NULL GROUP BY year ORDER BY year",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let per_journal = sqlx::query_as::<_, NamedCount>(
+        "SELECT COALESCE(v.name, TRIM(p.journal)) AS name, COUNT(*) AS count \
+         FROM papers p LEFT JOIN venues v ON v.id = p.venue_id \
+         WHERE p.deleted_at IS NULL AND (v.id IS NOT NULL OR TRIM(COALESCE(p.journal, '')) != '') \
+         GROUP BY 1 ORDER BY count DESC, name LIMIT ?",
+    )
+    .bind(TOP_JOURNALS)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let per_tag = sqlx::query_as::<_, NamedCount>(
+        "SELECT t.name AS name, COUNT(p.id) AS count \
+         FROM tags t \
+         LEFT JOIN paper_tags pt ON pt.tag_id = t.id \
+         LEFT JOIN papers p ON p.id = pt.paper_id AND p.deleted_at IS NULL \
+         GROUP BY t.id ORDER BY count DESC, t.name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let added_per_month = sqlx::query_as::<_, NamedCount>(
+        "SELECT strftime('%Y-%m', created_at) AS name, COUNT(*) AS count \
+         FROM papers WHERE deleted_at IS NULL AND created_at IS NOT NULL \
+         GROUP BY 1 ORDER BY 1",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let (papers, with_pdf, starred, trashed, unread, reading, read, stored_bytes, linked_bytes) = totals;
+    Ok(LibraryStats {
+        papers,
+        with_pdf,
+        starred,
+        trashed,
+        unread,
+        reading,
+        read,
+        stored_bytes,
+        linked_bytes,
+        per_year,
+        per_journal,
+        per_tag,
+        added_per_month,
+    })
+}
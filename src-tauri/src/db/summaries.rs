@@ -0,0 +1,60 @@
+// src-tauri/src/db/summaries.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+#[derive(Serialize)]
+pub struct Summary {
+    pub paper_id: i64,
+    pub summary: String,
+    pub key_points: Vec<String>,
+    // The model that wrote it
+    pub model: String,
+    pub created_at: Option<String>,
+}
+
+pub async fn get_summary(pool: &SqlitePool, paper_id: i64) -> Result<Option<Summary>, AppError> {
+    let row: Option<(String, String, String, Option<String>)> =
+        sqlx::query_as("SELECT summary, key_points, model, created_at FROM summaries WHERE paper_id = ?")
+            .bind(paper_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch summary: {}", e)))?;
+
+    Ok(row.map(|(summary, key_points, model, created_at)| Summary {
+        paper_id,
+        summary,
+        key_points: serde_json::from_str(&key_points).unwrap_or_default(),
+        model,
+        created_at,
+    }))
+}
+
+// Replaces the paper's previous summary
+pub async fn save_summary(
+    pool: &SqlitePool,
+    paper_id: i64,
+    summary: &str,
+    key_points: &[String],
+    model: &str,
+) -> Result<Summary, AppError> {
+    let key_points =
+        serde_json::to_string(key_points).map_err(|e| AppError::Db(format!("Failed to save summary: {}", e)))?;
+    sqlx::query(
+        "INSERT INTO summaries (paper_id, summary, key_points, model) VALUES (?, ?, ?, ?) \
+         ON CONFLICT(paper_id) DO UPDATE SET summary = excluded.summary, key_points = excluded.key_points, \
+         model = excluded.model, created_at = CURRENT_TIMESTAMP",
+    )
+    .bind(paper_id)
+    .bind(summary)
+    .bind(key_points)
+    .bind(model)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save summary: {}", e)))?;
+
+    get_summary(pool, paper_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Summary of paper {} not found", paper_id)))
+}
@@ -0,0 +1,175 @@
+// src-tauri/src/db/tags.rs
+
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub paper_count: i64,
+    pub created_at: Option<String>,
+}
+
+// "nlp, Transformers,, nlp" -> ["nlp", "Transformers"]
+pub fn split_tags(tags: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in tags.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+const TAG_SELECT: &str = r#"
+    SELECT t.id, t.name, t.created_at,
+           (SELECT COUNT(*) FROM paper_tags pt JOIN papers p ON p.id = pt.paper_id
+            WHERE pt.tag_id = t.id AND p.deleted_at IS NULL) AS paper_count
+    FROM tags t
+"#;
+
+pub async fn get_tag(pool: &SqlitePool, id: i64) -> Result<Tag, AppError> {
+    sqlx::query_as::<_, Tag>(&format!("{} WHERE t.id = ?", TAG_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch tag: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Tag {} not found", id)))
+}
+
+pub async fn get_tags(pool: &SqlitePool) -> Result<Vec<Tag>, AppError> {
+    sqlx::query_as::<_, Tag>(&format!("{} ORDER BY t.name COLLATE NOCASE", TAG_SELECT))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch tags: {}", e)))
+}
+
+pub async fn get_paper_tag_names(pool: &SqlitePool, paper_id: i64) -> Result<Vec<String>, AppError> {
+    sqlx::query_scalar(
+        "SELECT t.name FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.paper_id = ? ORDER BY t.name COLLATE NOCASE",
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch tags: {}", e)))
+}
+
+// Returns the existing tag when one with the same name (ignoring case) is already there
+pub async fn create_tag(pool: &SqlitePool, name: &str) -> Result<Tag, AppError> {
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO tags (name) VALUES (?) ON CONFLICT(name) DO UPDATE SET name = tags.name RETURNING id",
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to create tag: {}", e)))?;
+
+    get_tag(pool, id).await
+}
+
+pub async fn delete_tag(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM paper_tags WHERE tag_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete tag: {}", e)))?;
+
+    let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to delete tag: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Tag {} not found", id)));
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn tag_papers(pool: &SqlitePool, tag_id: i64, paper_ids: &[i64]) -> Result<(), AppError> {
+    get_tag(pool, tag_id).await?;
+
+    let mut tx = pool.begin().await?;
+    for paper_id in paper_ids {
+        sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT id, ? FROM papers WHERE id = ?")
+            .bind(tag_id)
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to tag paper: {}", e)))?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn untag_papers(pool: &SqlitePool, tag_id: i64, paper_ids: &[i64]) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    for paper_id in paper_ids {
+        sqlx::query("DELETE FROM paper_tags WHERE tag_id = ? AND paper_id = ?")
+            .bind(tag_id)
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to untag paper: {}", e)))?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+// Replaces the paper's tags with `names`, creating any tags that don't exist yet
+pub async fn set_paper_tags(pool: &SqlitePool, paper_id: i64, names: &[String]) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM paper_tags WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update tags: {}", e)))?;
+
+    for name in names {
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to update tags: {}", e)))?;
+        sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT ?, id FROM tags WHERE name = ?")
+            .bind(paper_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to update tags: {}", e)))?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// Adds `names` to the paper's tags, creating any tags that don't exist yet
+pub async fn add_paper_tags(pool: &SqlitePool, paper_id: i64, names: &[String]) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    for name in names {
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to tag paper: {}", e)))?;
+        sqlx::query("INSERT OR IGNORE INTO paper_tags (paper_id, tag_id) SELECT ?, id FROM tags WHERE name = ?")
+            .bind(paper_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to tag paper: {}", e)))?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
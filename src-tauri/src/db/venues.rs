@@ -0,0 +1,262 @@
+// src-tauri/src/db/venues.rs
+
+use super::journal;
+use crate::error::AppError;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+#[derive(Serialize, FromRow)]
+pub struct Venue {
+    pub id: i64,
+    pub name: String,
+    pub abbreviation: Option<String>,
+    pub paper_count: i64,
+    pub created_at: Option<String>,
+}
+
+const VENUE_SELECT: &str = r#"
+    SELECT v.id, v.name, v.abbreviation, v.created_at,
+           (SELECT COUNT(*) FROM papers p WHERE p.venue_id = v.id AND p.deleted_at IS NULL) AS paper_count
+    FROM venues v
+"#;
+
+// "Proc. of the IEEE" -> "proc of the ieee"
+pub fn venue_key(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_lowercase().next().unwrap_or(c) } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub async fn get_venue(pool: &SqlitePool, id: i64) -> Result<Venue, AppError> {
+    sqlx::query_as::<_, Venue>(&format!("{} WHERE v.id = ?", VENUE_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch venue: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Venue {} not found", id)))
+}
+
+// Venues of papers outside the Trash, by name
+pub async fn get_venues(pool: &SqlitePool) -> Result<Vec<Venue>, AppError> {
+    sqlx::query_as::<_, Venue>(&format!(
+        "SELECT * FROM ({}) WHERE paper_count > 0 ORDER BY name COLLATE NOCASE",
+        VENUE_SELECT
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch venues: {}", e)))
+}
+
+pub async fn set_paper_venue(pool: &SqlitePool, paper_id: i64, journal: Option<&str>) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    set_paper_venue_in(&mut tx, paper_id, journal)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to update venue: {}", e)))?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// Matches the paper's journal string to a venue, creating one for a name not seen before
+pub async fn set_paper_venue_in(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    journal: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let venue_id = match journal.map(str::trim).filter(|j| !venue_key(j).is_empty()) {
+        Some(journal) => Some(find_or_create(&mut *conn, journal).await?),
+        None => None,
+    };
+
+    sqlx::query("UPDATE papers SET venue_id = ? WHERE id = ?")
+        .bind(venue_id)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Venues nobody uses any more, unless a merge or abbreviation points at them
+    sqlx::query(
+        "DELETE FROM venues WHERE id NOT IN (SELECT venue_id FROM papers WHERE venue_id IS NOT NULL) \
+         AND id NOT IN (SELECT venue_id FROM venue_aliases)",
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn find_or_create(conn: &mut SqliteConnection, name: &str) -> Result<i64, sqlx::Error> {
+    let key = venue_key(name);
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT venue_id FROM venue_aliases WHERE key = ?1 UNION ALL SELECT id FROM venues WHERE key = ?1 LIMIT 1",
+    )
+    .bind(&key)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar("INSERT INTO venues (name, key) VALUES (?, ?) RETURNING id")
+        .bind(name)
+        .bind(&key)
+        .fetch_one(&mut *conn)
+        .await
+}
+
+// Which venue other than `venue_id` already answers to `key`, if any
+async fn key_owner(conn: &mut SqliteConnection, key: &str, venue_id: i64) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT id FROM venues WHERE key = ?1 AND id != ?2 \
+         UNION ALL SELECT venue_id FROM venue_aliases WHERE key = ?1 AND venue_id != ?2 LIMIT 1",
+    )
+    .bind(key)
+    .bind(venue_id)
+    .fetch_optional(&mut *conn)
+    .await
+}
+
+// Renames the venue and/or sets its abbreviation; None leaves a field as it is, an empty
+// abbreviation clears it. The old name and the abbreviation keep matching papers.
+pub async fn update_venue(
+    pool: &SqlitePool,
+    id: i64,
+    name: Option<&str>,
+    abbreviation: Option<&str>,
+) -> Result<Venue, AppError> {
+    let current = get_venue(pool, id).await?;
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to update venue: {}", e));
+    let mut tx = pool.begin().await?;
+
+    if let Some(name) = name {
+        let key = venue_key(name);
+        if key.is_empty() {
+            return Err(AppError::Validation("Venue name cannot be empty".to_string()));
+        }
+        if key_owner(&mut tx, &key, id).await.map_err(err)?.is_some() {
+            return Err(AppError::Conflict(format!("Another venue is already called \"{}\"; merge them instead", name)));
+        }
+        sqlx::query("INSERT OR REPLACE INTO venue_aliases (key, venue_id) VALUES (?, ?)")
+            .bind(venue_key(&current.name))
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("DELETE FROM venue_aliases WHERE key = ?")
+            .bind(&key)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("UPDATE venues SET name = ?, key = ? WHERE id = ?")
+            .bind(name)
+            .bind(&key)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    if let Some(abbreviation) = abbreviation {
+        let key = venue_key(abbreviation);
+        if !key.is_empty() {
+            if key_owner(&mut tx, &key, id).await.map_err(err)?.is_some() {
+                return Err(AppError::Conflict(format!("\"{}\" already stands for another venue", abbreviation)));
+            }
+            sqlx::query("INSERT OR IGNORE INTO venue_aliases (key, venue_id) VALUES (?, ?)")
+                .bind(&key)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(err)?;
+        }
+        sqlx::query("UPDATE venues SET abbreviation = ? WHERE id = ?")
+            .bind(Some(abbreviation).filter(|_| !key.is_empty()))
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+
+    tx.commit().await?;
+    get_venue(pool, id).await
+}
+
+// Folds `merge_ids` into `keep_id`: their papers move over and their names and
+// abbreviations become aliases, so later imports spelling the venue that way match too.
+// Journaled, so it can be undone.
+pub async fn merge_venues(pool: &SqlitePool, keep_id: i64, merge_ids: &[i64]) -> Result<Venue, AppError> {
+    let keep = get_venue(pool, keep_id).await?;
+    let err = |e: sqlx::Error| AppError::Db(format!("Failed to merge venues: {}", e));
+
+    let mut tx = pool.begin().await?;
+    let merge = journal::snapshot_venue_merge(&mut tx, keep_id, merge_ids).await.map_err(err)?;
+    for merge_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        let merged: Option<(String, Option<String>)> =
+            sqlx::query_as("SELECT key, abbreviation FROM venues WHERE id = ?")
+                .bind(merge_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(err)?;
+        let Some((key, abbreviation)) = merged else {
+            return Err(AppError::NotFound(format!("Venue {} not found", merge_id)));
+        };
+
+        sqlx::query("UPDATE venue_aliases SET venue_id = ? WHERE venue_id = ?")
+            .bind(keep_id)
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("INSERT OR REPLACE INTO venue_aliases (key, venue_id) VALUES (?, ?)")
+            .bind(&key)
+            .bind(keep_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        sqlx::query("UPDATE papers SET venue_id = ? WHERE venue_id = ?")
+            .bind(keep_id)
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+        // The kept venue inherits an abbreviation when it has none of its own
+        if keep.abbreviation.is_none() {
+            sqlx::query("UPDATE venues SET abbreviation = COALESCE(abbreviation, ?) WHERE id = ?")
+                .bind(&abbreviation)
+                .bind(keep_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(err)?;
+        }
+        sqlx::query("DELETE FROM venues WHERE id = ?")
+            .bind(merge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(err)?;
+    }
+    let merged = match merge.venues.as_slice() {
+        [venue] => format!("\"{}\"", venue.name),
+        venues => format!("{} venues", venues.len()),
+    };
+    let description = format!("Merge {} into \"{}\"", merged, keep.name);
+    journal::record_in(&mut tx, &description, &journal::UndoData::Venues(merge)).await?;
+    tx.commit().await?;
+
+    get_venue(pool, keep_id).await
+}
+
+// Matches papers added before venues existed
+pub async fn backfill_venues(pool: &SqlitePool) -> Result<usize, AppError> {
+    let papers: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, journal FROM papers WHERE venue_id IS NULL AND journal IS NOT NULL AND trim(journal) != ''",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))?;
+
+    for (id, journal) in &papers {
+        set_paper_venue(pool, *id, Some(journal)).await?;
+    }
+    Ok(papers.len())
+}
@@ -0,0 +1,81 @@
+// src-tauri/src/db/view_states.rs
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ViewMode {
+    // One page at a time
+    Single,
+    #[default]
+    Continuous,
+    // Facing pages side by side
+    TwoPage,
+}
+
+// Zoom to keep the page fitting the window as it is resized, instead of a fixed scale
+#[derive(Serialize, Deserialize, sqlx::Type, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum Fit {
+    Width,
+    Page,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Clone, Debug)]
+pub struct ViewState {
+    // 1-based
+    pub page: i64,
+    // 1.0 is 100%; when `fit` is set, the scale it last came to
+    pub zoom: f64,
+    pub fit: Option<Fit>,
+    // Fractions from 0 to 1 of the scrollable height and width
+    pub scroll_top: f64,
+    pub scroll_left: f64,
+    pub view_mode: ViewMode,
+}
+
+pub async fn get_view_state(pool: &SqlitePool, paper_id: i64) -> Result<Option<ViewState>, AppError> {
+    sqlx::query_as::<_, ViewState>(
+        "SELECT page, zoom, fit, scroll_top, scroll_left, view_mode FROM view_states WHERE paper_id = ?",
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch view state: {}", e)))
+}
+
+pub async fn save_view_state(pool: &SqlitePool, paper_id: i64, state: &ViewState) -> Result<(), AppError> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO view_states (paper_id, page, zoom, fit, scroll_top, scroll_left, view_mode)
+        SELECT id, ?, ?, ?, ?, ?, ? FROM papers WHERE id = ?
+        ON CONFLICT(paper_id) DO UPDATE SET
+            page = excluded.page,
+            zoom = excluded.zoom,
+            fit = excluded.fit,
+            scroll_top = excluded.scroll_top,
+            scroll_left = excluded.scroll_left,
+            view_mode = excluded.view_mode,
+            updated_at = CURRENT_TIMESTAMP
+        "#
+    )
+    .bind(state.page)
+    .bind(state.zoom)
+    .bind(state.fit)
+    .bind(state.scroll_top)
+    .bind(state.scroll_left)
+    .bind(state.view_mode)
+    .bind(paper_id)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save view state: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Paper {} not found", paper_id)));
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// src-tauri/src/db/zotero.rs
+
+use crate::error::AppError;
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(FromRow, Clone)]
+pub struct ZoteroLink {
+    pub paper_id: i64,
+    pub item_key: String,
+    pub item_type: String,
+    pub version: i64,
+    pub synced_fields: String,
+    pub note_key: Option<String>,
+    pub note_version: Option<i64>,
+    pub synced_note: Option<String>,
+    pub conflict_version: Option<i64>,
+    pub note_conflict_version: Option<i64>,
+}
+
+impl ZoteroLink {
+    pub fn has_conflict(&self) -> bool {
+        self.conflict_version.is_some() || self.note_conflict_version.is_some()
+    }
+}
+
+const LINK_COLUMNS: &str = "paper_id, item_key, item_type, version, synced_fields, note_key, note_version, \
+    synced_note, conflict_version, note_conflict_version";
+
+pub async fn get_link(pool: &SqlitePool, paper_id: i64) -> Result<Option<ZoteroLink>, AppError> {
+    sqlx::query_as::<_, ZoteroLink>(&format!("SELECT {} FROM zotero_links WHERE paper_id = ?", LINK_COLUMNS))
+        .bind(paper_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch Zotero link: {}", e)))
+}
+
+pub async fn find_link(pool: &SqlitePool, item_key: &str) -> Result<Option<ZoteroLink>, AppError> {
+    sqlx::query_as::<_, ZoteroLink>(&format!("SELECT {} FROM zotero_links WHERE item_key = ?", LINK_COLUMNS))
+        .bind(item_key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch Zotero link: {}", e)))
+}
+
+pub async fn get_links(pool: &SqlitePool) -> Result<Vec<ZoteroLink>, AppError> {
+    sqlx::query_as::<_, ZoteroLink>(&format!("SELECT {} FROM zotero_links ORDER BY paper_id", LINK_COLUMNS))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Db(format!("Failed to fetch Zotero links: {}", e)))
+}
+
+pub async fn get_conflicts(pool: &SqlitePool) -> Result<Vec<ZoteroLink>, AppError> {
+    sqlx::query_as::<_, ZoteroLink>(&format!(
+        "SELECT {} FROM zotero_links WHERE conflict_version IS NOT NULL OR note_conflict_version IS NOT NULL \
+         ORDER BY paper_id",
+        LINK_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch Zotero conflicts: {}", e)))
+}
+
+pub async fn save_link(pool: &SqlitePool, link: &ZoteroLink) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO zotero_links (paper_id, item_key, item_type, version, synced_fields, note_key, note_version,
+                                  synced_note, conflict_version, note_conflict_version)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(paper_id) DO UPDATE SET
+            item_key = excluded.item_key,
+            item_type = excluded.item_type,
+            version = excluded.version,
+            synced_fields = excluded.synced_fields,
+            note_key = excluded.note_key,
+            note_version = excluded.note_version,
+            synced_note = excluded.synced_note,
+            conflict_version = excluded.conflict_version,
+            note_conflict_version = excluded.note_conflict_version,
+            synced_at = CURRENT_TIMESTAMP
+        "#
+    )
+    .bind(link.paper_id)
+    .bind(&link.item_key)
+    .bind(&link.item_type)
+    .bind(link.version)
+    .bind(&link.synced_fields)
+    .bind(&link.note_key)
+    .bind(link.note_version)
+    .bind(&link.synced_note)
+    .bind(link.conflict_version)
+    .bind(link.note_conflict_version)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save Zotero link: {}", e)))?;
+    Ok(())
+}
+
+// Papers that have never been pushed to Zotero, leaving out the Trash
+pub async fn get_unlinked_paper_ids(pool: &SqlitePool) -> Result<Vec<i64>, AppError> {
+    let rows: Vec<(i64,)> = sqlx::query_as(
+        "SELECT id FROM papers WHERE id NOT IN (SELECT paper_id FROM zotero_links) AND deleted_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+// An unlinked paper that is probably the same work as a Zotero item, matched by DOI
+// and then by title
+pub async fn find_unlinked_match(pool: &SqlitePool, doi: Option<&str>, title: &str) -> Result<Option<i64>, AppError> {
+    let found: Option<(i64,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM papers
+        WHERE id NOT IN (SELECT paper_id FROM zotero_links)
+          AND ((? IS NOT NULL AND LOWER(doi) = LOWER(?)) OR LOWER(TRIM(title)) = LOWER(TRIM(?)))
+        ORDER BY (LOWER(doi) = LOWER(?)) DESC, id
+        LIMIT 1
+        "#
+    )
+    .bind(doi)
+    .bind(doi)
+    .bind(title)
+    .bind(doi)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to fetch papers: {}", e)))?;
+    Ok(found.map(|(id,)| id))
+}
+
+// (library version, last sync time) for `user_id`. Switching accounts starts over.
+pub async fn get_sync_state(pool: &SqlitePool, user_id: i64) -> Result<(i64, Option<String>), AppError> {
+    let state: Option<(i64, i64, Option<String>)> =
+        sqlx::query_as("SELECT user_id, library_version, last_synced_at FROM zotero_sync_state WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::Db(format!("Failed to fetch Zotero sync state: {}", e)))?;
+
+    match state {
+        Some((synced_user, version, last_synced_at)) if synced_user == user_id => Ok((version, last_synced_at)),
+        Some(_) => {
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM zotero_links")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Db(format!("Failed to reset Zotero sync: {}", e)))?;
+            sqlx::query("DELETE FROM zotero_sync_state")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| AppError::Db(format!("Failed to reset Zotero sync: {}", e)))?;
+            tx.commit().await?;
+            Ok((0, None))
+        }
+        None => Ok((0, None)),
+    }
+}
+
+pub async fn set_sync_state(pool: &SqlitePool, user_id: i64, library_version: i64) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO zotero_sync_state (id, user_id, library_version, last_synced_at)
+        VALUES (1, ?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(id) DO UPDATE SET
+            user_id = excluded.user_id,
+            library_version = excluded.library_version,
+            last_synced_at = excluded.last_synced_at
+        "#
+    )
+    .bind(user_id)
+    .bind(library_version)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Db(format!("Failed to save Zotero sync state: {}", e)))?;
+    Ok(())
+}
@@ -0,0 +1,178 @@
+// src-tauri/src/deep_link.rs
+
+// papermaster:// links from the browser, other apps or the command line:
+//
+//     papermaster://open/123                          shows paper 123
+//     papermaster://import?doi=10.1000/xyz            imports by DOI; also arxiv=, url=, title=
+//
+// They arrive as launch arguments (Windows, Linux), as an event (macOS), or from a second
+// launch, which the single-instance plugin passes to the running app. Links are queued and
+// announced with "deep-link:received"; the frontend takes them with take_deep_links and
+// resolves each with open_deep_link, so links that came in before the window was ready
+// aren't lost. PDFs opened with the app are shown through the same queue, as open links.
+
+use crate::companion::SaveRequest;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEME: &str = "papermaster";
+
+pub enum DeepLink {
+    Open(i64),
+    Import(SaveRequest),
+}
+
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    let url = reqwest::Url::parse(link.trim()).map_err(|_| format!("Not a valid link: {}", link))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {}:// link: {}", SCHEME, link));
+    }
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    match url.host_str().unwrap_or_default() {
+        "open" => url
+            .path()
+            .trim_matches('/')
+            .parse()
+            .map(DeepLink::Open)
+            .map_err(|_| format!("No paper id in {}", link)),
+        "import" => {
+            let request = SaveRequest {
+                url: query("url"),
+                doi: query("doi"),
+                arxiv_id: query("arxiv").or_else(|| query("arxiv_id")),
+                title: query("title"),
+            };
+            if request.url.is_none() && request.doi.is_none() && request.arxiv_id.is_none() {
+                return Err(format!("Nothing to import in {}; give a doi, arxiv or url", link));
+            }
+            Ok(DeepLink::Import(request))
+        }
+        action => Err(format!("Unknown action \"{}\" in {}", action, link)),
+    }
+}
+
+// The arguments that are links of ours, e.g. from the OS opening one
+pub fn links_in(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let prefix = format!("{}:", SCHEME);
+    args.into_iter()
+        .filter(|arg| arg.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(&prefix)))
+        .collect()
+}
+
+pub fn open_link(paper_id: i64) -> String {
+    format!("{}://open/{}", SCHEME, paper_id)
+}
+
+// Managed as its own state, since links can arrive before the database is open
+#[derive(Default)]
+pub struct DeepLinks {
+    pending: Mutex<Vec<String>>,
+}
+
+impl DeepLinks {
+
+    // Queues the links, announces them and brings the window to the front
+    pub fn receive(&self, handle: &AppHandle, links: Vec<String>) {
+        if links.is_empty() {
+            return;
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.extend(links);
+        }
+        let _ = handle.emit("deep-link:received", ());
+        focus_main_window(handle);
+    }
+
+    pub fn take(&self) -> Vec<String> {
+        self.pending.lock().map(|mut pending| std::mem::take(&mut *pending)).unwrap_or_default()
+    }
+}
+
+pub fn focus_main_window(handle: &AppHandle) {
+    if let Some(window) = handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// macOS learns the scheme from the bundle's Info.plist. Elsewhere the OS is told at every
+// start, so the links keep working after the app has moved.
+pub fn register_scheme() -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    return register_linux();
+    #[cfg(target_os = "windows")]
+    return register_windows();
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    Ok(())
+}
+
+// Running from an AppImage, the executable is only a temporary mount
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn executable() -> Result<String, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(appimage.to_string_lossy().into_owned());
+    }
+    std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().into_owned())
+        .map_err(|e| format!("Failed to find the executable: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn register_linux() -> Result<(), String> {
+    let applications = dirs::data_dir()
+        .ok_or("No data directory to register the link handler in")?
+        .join("applications");
+    std::fs::create_dir_all(&applications).map_err(|e| format!("Failed to register {}:// links: {}", SCHEME, e))?;
+
+    let desktop_file = format!("paper-master-{}.desktop", SCHEME);
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=paper-master\nExec=\"{}\" %u\nTerminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        executable()?,
+        SCHEME
+    );
+    std::fs::write(applications.join(&desktop_file), entry)
+        .map_err(|e| format!("Failed to register {}:// links: {}", SCHEME, e))?;
+
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", &desktop_file, &format!("x-scheme-handler/{}", SCHEME)])
+        .status()
+        .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+    if !status.success() {
+        return Err(format!("xdg-mime failed to register {}:// links", SCHEME));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn register_windows() -> Result<(), String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let key = format!("HKCU\\Software\\Classes\\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", executable()?);
+    let description = format!("URL:{} link", SCHEME);
+    let open_key = format!("{}\\shell\\open\\command", key);
+    let entries: [&[&str]; 3] = [
+        &["add", &key, "/ve", "/d", &description, "/f"],
+        &["add", &key, "/v", "URL Protocol", "/d", "", "/f"],
+        &["add", &open_key, "/ve", "/d", &command, "/f"],
+    ];
+    for args in entries {
+        let status = std::process::Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status()
+            .map_err(|e| format!("Failed to run reg: {}", e))?;
+        if !status.success() {
+            return Err(format!("Failed to register {}:// links in the registry", SCHEME));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+// src-tauri/src/diagnostics.rs
+
+// A health report to paste into support requests. Every check runs even when an earlier
+// one fails; whatever looks wrong is spelled out in `problems`.
+
+use crate::{db, import, relink};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+// Less free space than this on the library's disk is reported as a problem
+const LOW_DISK_SPACE: u64 = 500 * 1024 * 1024;
+
+#[derive(Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub os: String,
+    pub database_path: String,
+    pub database_size: Option<u64>,
+    pub schema: Option<db::SchemaVersion>,
+    pub pool: PoolStatus,
+    pub papers_dir: Option<String>,
+    pub papers_dir_writable: bool,
+    // Bytes free on the disk holding the papers folder
+    pub free_space: Option<u64>,
+    // Papers whose PDF is gone from where the library expects it
+    pub missing_files: Option<usize>,
+    pub index: Option<db::IndexHealth>,
+    pub problems: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PoolStatus {
+    pub connections: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+    pub closed: bool,
+}
+
+pub async fn run(handle: &AppHandle, pool: &SqlitePool, db_path: &Path) -> Diagnostics {
+    let mut problems = Vec::new();
+
+    let database_size = note(
+        &mut problems,
+        fs::metadata(db_path).map(|m| m.len()).map_err(|e| format!("Database file: {}", e)),
+    );
+    let schema = note(&mut problems, db::get_schema_version(pool).await.map_err(String::from));
+    let papers_dir = note(&mut problems, import::papers_dir(handle));
+    let papers_dir_writable = match &papers_dir {
+        Some(dir) => note(&mut problems, check_writable(dir)).is_some(),
+        None => false,
+    };
+    let free_space = papers_dir.as_deref().and_then(free_space);
+    let missing_files = note(&mut problems, relink::find_missing(pool).await).map(|missing| missing.len());
+    let index = note(&mut problems, db::get_index_health(pool).await.map_err(String::from));
+
+    if let Some(schema) = &schema {
+        if schema.version != Some(schema.latest) {
+            let version = schema.version.map_or("no version".to_string(), |v| v.to_string());
+            problems.push(format!("Database schema is at {}, this build expects {}", version, schema.latest));
+        }
+    }
+    if free_space.is_some_and(|free| free < LOW_DISK_SPACE) {
+        problems.push("Less than 500 MB of disk space left for the library".to_string());
+    }
+    if let Some(missing) = missing_files.filter(|&n| n > 0) {
+        problems.push(format!("{} papers point at a missing PDF", missing));
+    }
+    if let Some(index) = &index {
+        if index.unindexed > 0 || index.orphaned > 0 {
+            problems.push(format!(
+                "Search index is out of step: {} papers missing, {} stale rows",
+                index.unindexed, index.orphaned
+            ));
+        }
+        if let Some(e) = &index.integrity_error {
+            problems.push(format!("Search index is damaged: {}", e));
+        }
+    }
+
+    Diagnostics {
+        app_version: handle.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        database_path: db_path.display().to_string(),
+        database_size,
+        schema,
+        pool: PoolStatus {
+            connections: pool.size(),
+            idle: pool.num_idle(),
+            max_connections: pool.options().get_max_connections(),
+            closed: pool.is_closed(),
+        },
+        papers_dir: papers_dir.map(|dir| dir.display().to_string()),
+        papers_dir_writable,
+        free_space,
+        missing_files,
+        index,
+        problems,
+    }
+}
+
+// The value, or None with the error added to `problems`
+fn note<T>(problems: &mut Vec<String>, result: Result<T, String>) -> Option<T> {
+    result.map_err(|e| problems.push(e)).ok()
+}
+
+fn check_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(format!(".write-test-{}", uuid::Uuid::new_v4()));
+    fs::write(&probe, b"").map_err(|e| format!("Papers folder {} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid statvfs to write into
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // The field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(dir: *const u16, free_to_caller: *mut u64, total: *mut u64, total_free: *mut u64) -> i32;
+    }
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut free = 0u64;
+    // SAFETY: `path` is NUL-terminated; the totals we don't need may be null
+    let ok = unsafe { GetDiskFreeSpaceExW(path.as_ptr(), &mut free, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (ok != 0).then_some(free)
+}
@@ -0,0 +1,391 @@
+// src-tauri/src/downloads.rs
+
+// Every PDF the app fetches goes through here: a few at a time, retried with backoff when the
+// connection drops or the server is busy, and resumed from where it stopped instead of started
+// over. Each change of state is announced with "download:progress"; finished downloads stay
+// listed until more than KEEP_FINISHED have piled up.
+
+use crate::db::{self, Paper};
+use crate::file_naming;
+use crate::import;
+use crate::operations;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+const MAX_CONCURRENT: usize = 3;
+const MAX_ATTEMPTS: u32 = 4;
+// Doubled after every failed attempt
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(2);
+// The shared client gives up after 30 seconds, which a large scan on a slow link can exceed
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+// Progress events are throttled to one per this many bytes
+const PROGRESS_STEP: u64 = 256 * 1024;
+const KEEP_FINISHED: usize = 50;
+const PDF_SIGNATURE: &[u8] = b"%PDF-";
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Queued,
+    Active,
+    // Waiting out the backoff after a failed attempt
+    Retrying,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, DownloadStatus::Done | DownloadStatus::Failed | DownloadStatus::Cancelled)
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct DownloadInfo {
+    pub id: u64,
+    pub url: String,
+    // What the download is for, e.g. a paper title or arXiv id
+    pub label: String,
+    pub status: DownloadStatus,
+    pub received: u64,
+    // From Content-Length; servers streaming the file don't always send it
+    pub total: Option<u64>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+pub struct DownloadRequest<'a> {
+    pub url: &'a str,
+    pub dest: &'a Path,
+    pub label: String,
+    // Links pasted by the user must stay on HTTPS, also after redirects
+    pub https_only: bool,
+    // The operation the download belongs to; cancelling it cancels the download
+    pub cancel: Option<&'a CancellationToken>,
+}
+
+struct Job {
+    info: DownloadInfo,
+    cancel: CancellationToken,
+}
+
+enum Failure {
+    // Worth another attempt: the connection dropped, timed out, or the server was busy
+    Transient(String),
+    Fatal(String),
+}
+
+pub struct Downloads {
+    handle: AppHandle,
+    client: reqwest::Client,
+    slots: Semaphore,
+    jobs: Mutex<Vec<Job>>,
+    next_id: AtomicU64,
+}
+
+impl Downloads {
+    pub fn new(handle: AppHandle, client: reqwest::Client) -> Self {
+        Downloads {
+            handle,
+            client,
+            slots: Semaphore::new(MAX_CONCURRENT),
+            jobs: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn list(&self) -> Vec<DownloadInfo> {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.iter().map(|job| job.info.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn cancel(&self, id: u64) -> Result<(), String> {
+        let jobs = self.jobs.lock().map_err(|_| "Download list unavailable")?;
+        let job = jobs.iter().find(|job| job.info.id == id).ok_or("No such download")?;
+        if job.info.status.is_finished() {
+            return Err("The download has already finished".to_string());
+        }
+        job.cancel.cancel();
+        Ok(())
+    }
+
+    // Downloads a PDF to `request.dest` once a slot is free. Returns the file name the server
+    // suggests, for naming the stored copy.
+    pub async fn fetch(&self, request: DownloadRequest<'_>) -> Result<String, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancel = request.cancel.map(CancellationToken::child_token).unwrap_or_default();
+        self.register(Job {
+            info: DownloadInfo {
+                id,
+                url: request.url.to_string(),
+                label: request.label.clone(),
+                status: DownloadStatus::Queued,
+                received: 0,
+                total: None,
+                attempts: 0,
+                error: None,
+            },
+            cancel: cancel.clone(),
+        });
+
+        let partial = partial_path(request.dest);
+        let (result, cancelled) = tokio::select! {
+            result = self.run(id, &request, &partial) => (result, false),
+            _ = cancel.cancelled() => (Err(operations::CANCELLED.to_string()), true),
+        };
+
+        let result = result.and_then(|name| {
+            fs::rename(&partial, request.dest)
+                .map(|_| name)
+                .map_err(|e| format!("Failed to save PDF: {}", e))
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+
+        self.update(id, |info| match &result {
+            Ok(_) => {
+                info.status = DownloadStatus::Done;
+                info.error = None;
+            }
+            Err(e) => {
+                info.status = if cancelled { DownloadStatus::Cancelled } else { DownloadStatus::Failed };
+                info.error = Some(e.clone());
+            }
+        });
+        self.prune();
+        result
+    }
+
+    // Downloads the PDF at `url` into app storage and attaches it to a record without one
+    pub async fn fetch_into_library(
+        &self,
+        pool: &SqlitePool,
+        paper: &Paper,
+        url: &str,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Paper, String> {
+        // Cite keys come verbatim from imports and may hold path separators
+        let name = paper
+            .cite_key
+            .as_deref()
+            .map(|key| file_naming::sanitize(key).trim_matches('.').to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| paper.id.to_string());
+        let dest = import::unique_destination(&import::papers_dir(&self.handle)?, &format!("{}.pdf", name));
+        self.fetch(DownloadRequest {
+            url,
+            dest: &dest,
+            label: paper.title.clone(),
+            https_only: false,
+            cancel,
+        })
+        .await?;
+
+        if let Err(e) = import::attach_pdf(pool, paper.id, &dest).await {
+            let _ = fs::remove_file(&dest);
+            return Err(e);
+        }
+        Ok(db::get_paper(pool, paper.id).await?)
+    }
+
+    async fn run(&self, id: u64, request: &DownloadRequest<'_>, partial: &Path) -> Result<String, String> {
+        let _slot = self.slots.acquire().await.map_err(|e| e.to_string())?;
+
+        let mut delay = FIRST_RETRY_DELAY;
+        for attempt in 1..=MAX_ATTEMPTS {
+            self.update(id, |info| {
+                info.status = DownloadStatus::Active;
+                info.attempts = attempt;
+            });
+            match self.attempt(id, request, partial).await {
+                Ok(name) => return Ok(name),
+                Err(Failure::Fatal(e)) => return Err(e),
+                Err(Failure::Transient(e)) if attempt == MAX_ATTEMPTS => return Err(e),
+                Err(Failure::Transient(e)) => {
+                    self.update(id, |info| {
+                        info.status = DownloadStatus::Retrying;
+                        info.error = Some(e);
+                    });
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+        unreachable!("the last attempt always returns")
+    }
+
+    // One request, continuing whatever earlier attempts left in `partial`
+    async fn attempt(&self, id: u64, request: &DownloadRequest<'_>, partial: &Path) -> Result<String, Failure> {
+        let url = reqwest::Url::parse(request.url).map_err(|_| Failure::Fatal(format!("Not a valid URL: {}", request.url)))?;
+        if request.https_only && url.scheme() != "https" {
+            return Err(Failure::Fatal("Only https:// links can be imported".to_string()));
+        }
+
+        let offset = tokio::fs::metadata(partial).await.map(|m| m.len()).unwrap_or(0);
+        let mut builder = self.client.get(url).timeout(DOWNLOAD_TIMEOUT);
+        if offset > 0 {
+            builder = builder.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let mut response = builder
+            .send()
+            .await
+            .map_err(|e| Failure::Transient(format!("Download failed: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            return Err(Failure::Transient(format!("Download failed: server answered {}", status)));
+        }
+        if !status.is_success() {
+            return Err(Failure::Fatal(format!("Download failed: server answered {}", status)));
+        }
+        if request.https_only && response.url().scheme() != "https" {
+            return Err(Failure::Fatal("The link redirects to an insecure (http://) address".to_string()));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        // Landing pages and login walls, which many publishers answer PDF links with
+        if content_type.starts_with("text/html") || content_type.starts_with("application/xhtml") {
+            return Err(Failure::Fatal(format!("{} leads to a web page, not a PDF", request.url)));
+        }
+
+        // A server that ignores the range sends the whole file again
+        let resumed = offset > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let mut received = if resumed { offset } else { 0 };
+        let total = response.content_length().map(|length| length + received);
+        let fatal = |e: std::io::Error| Failure::Fatal(format!("Failed to save PDF: {}", e));
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(partial)
+            .await
+            .map_err(fatal)?;
+        self.update(id, |info| {
+            info.received = received;
+            info.total = total;
+        });
+
+        let mut reported = received;
+        // The first chunk can be shorter than the signature, so the start is held back until it's complete
+        let mut head = Vec::new();
+        let mut checked = resumed;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Failure::Transient(format!("Download failed: {}", e)))?
+        {
+            // Servers labelling everything application/octet-stream are common; the magic bytes aren't
+            if !checked {
+                head.extend_from_slice(&chunk);
+                if head.len() < PDF_SIGNATURE.len() {
+                    continue;
+                }
+                if !head.starts_with(PDF_SIGNATURE) {
+                    return Err(Failure::Fatal(format!("{} is not a PDF", request.url)));
+                }
+                checked = true;
+            }
+            let data = if head.is_empty() { &chunk[..] } else { &head[..] };
+            file.write_all(data).await.map_err(fatal)?;
+
+            received += data.len() as u64;
+            head.clear();
+            if received - reported >= PROGRESS_STEP {
+                reported = received;
+                self.update(id, |info| info.received = received);
+            }
+        }
+        file.flush().await.map_err(fatal)?;
+        self.update(id, |info| info.received = received);
+
+        if !head.is_empty() {
+            return Err(Failure::Fatal(format!("{} is not a PDF", request.url)));
+        }
+        if received == 0 {
+            return Err(Failure::Fatal("The download was empty".to_string()));
+        }
+        if total.is_some_and(|total| received < total) {
+            return Err(Failure::Transient("The connection closed before the download finished".to_string()));
+        }
+        Ok(suggested_file_name(response.url()))
+    }
+
+    fn register(&self, job: Job) {
+        let info = job.info.clone();
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.push(job);
+        }
+        let _ = self.handle.emit("download:progress", info);
+    }
+
+    fn update(&self, id: u64, change: impl FnOnce(&mut DownloadInfo)) {
+        let info = match self.jobs.lock() {
+            Ok(mut jobs) => match jobs.iter_mut().find(|job| job.info.id == id) {
+                Some(job) => {
+                    change(&mut job.info);
+                    job.info.clone()
+                }
+                None => return,
+            },
+            Err(_) => return,
+        };
+        let _ = self.handle.emit("download:progress", info);
+    }
+
+    fn prune(&self) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            let finished = jobs.iter().filter(|job| job.info.status.is_finished()).count();
+            let mut excess = finished.saturating_sub(KEEP_FINISHED);
+            jobs.retain(|job| {
+                if excess > 0 && job.info.status.is_finished() {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        }
+    }
+}
+
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+// The last path segment of the (final, post-redirect) URL, as a .pdf name
+fn suggested_file_name(url: &reqwest::Url) -> String {
+    let stem = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(|segment| segment.trim_end_matches(".pdf"))
+        .unwrap_or("")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect::<String>();
+    let stem = stem.trim_matches(|c| c == '.' || c == '_');
+
+    if stem.is_empty() {
+        "download.pdf".to_string()
+    } else {
+        format!("{}.pdf", stem)
+    }
+}
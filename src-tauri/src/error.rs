@@ -0,0 +1,106 @@
+// src-tauri/src/error.rs
+
+// What commands return on failure. It reaches the frontend as { kind, message }, so the UI
+// can tell a missing paper from a network outage without parsing the message.
+//
+// The database layer (db and db/*) returns AppError itself: a missing row is NotFound, a
+// failed query Db, a bad value Validation. Commands that call remote services map their
+// failures to Network. Other is left for errors nothing classifies, mostly from the
+// string-typed pipelines such as import and sync, which mix several kinds. The other way
+// round, an AppError turns into its message, so db helpers can be used with `?` from
+// string-returning code.
+
+use crate::operations;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    // Reading or writing a file failed
+    #[error("{0}")]
+    Io(String),
+    #[error("{0}")]
+    Db(String),
+    #[error("{0}")]
+    NotFound(String),
+    // The arguments don't make sense, e.g. an empty title or an unknown style
+    #[error("{0}")]
+    Validation(String),
+    // A remote service couldn't be reached or answered with an error
+    #[error("{0}")]
+    Network(String),
+    // The thing exists already, e.g. a paper with the same DOI
+    #[error("{0}")]
+    Conflict(String),
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Io(_) => "io",
+            AppError::Db(_) => "db",
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation(_) => "validation",
+            AppError::Network(_) => "network",
+            AppError::Conflict(_) => "conflict",
+            AppError::Cancelled => "cancelled",
+            AppError::Other(_) => "other",
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 2)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        // Cancellation passes through string-typed pipelines as this sentinel
+        if message == operations::CANCELLED {
+            return AppError::Cancelled;
+        }
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::RowNotFound => AppError::NotFound("Not found".to_string()),
+            e => AppError::Db(format!("Database error: {}", e)),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        AppError::Network(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::Io(error.to_string())
+    }
+}
@@ -0,0 +1,58 @@
+// src-tauri/src/events.rs
+
+// Library change events, so the frontend can patch its list instead of polling get_papers.
+// "paper:added" and "paper:updated" carry the full Paper, "paper:deleted" only the id.
+// Trashing and restoring are updates: the paper comes with deleted_at set or cleared.
+
+use crate::config::HookEvent;
+use crate::db::saved_searches::SavedSearchCount;
+use crate::db::{self, Paper};
+use crate::hooks;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter};
+
+// Set by every paper event, so saved search counts are refreshed; see saved_searches.rs
+static LIBRARY_CHANGED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Serialize, Clone)]
+pub struct PaperDeleted {
+    pub id: i64,
+}
+
+pub fn paper_added(handle: &AppHandle, paper: &Paper) {
+    LIBRARY_CHANGED.store(true, Ordering::Relaxed);
+    let _ = handle.emit("paper:added", paper);
+    hooks::paper_event(handle, HookEvent::PaperImported, paper.id, Some(paper));
+}
+
+pub fn paper_updated(handle: &AppHandle, paper: &Paper) {
+    LIBRARY_CHANGED.store(true, Ordering::Relaxed);
+    let _ = handle.emit("paper:updated", paper);
+    hooks::paper_event(handle, HookEvent::PaperUpdated, paper.id, Some(paper));
+}
+
+pub fn paper_deleted(handle: &AppHandle, id: i64) {
+    LIBRARY_CHANGED.store(true, Ordering::Relaxed);
+    let _ = handle.emit("paper:deleted", PaperDeleted { id });
+    hooks::paper_event(handle, HookEvent::PaperDeleted, id, None);
+}
+
+// For commands that change many papers without loading them, e.g. tagging a selection
+pub async fn papers_updated(handle: &AppHandle, pool: &SqlitePool, paper_ids: &[i64]) {
+    match db::get_papers_by_ids(pool, paper_ids).await {
+        Ok(papers) => papers.iter().for_each(|paper| paper_updated(handle, paper)),
+        Err(e) => tracing::warn!("Failed to announce updated papers: {}", e),
+    }
+}
+
+// Whether papers changed since the last call
+pub fn take_library_changed() -> bool {
+    LIBRARY_CHANGED.swap(false, Ordering::Relaxed)
+}
+
+// Current result counts of every saved search, for the sidebar badges
+pub fn saved_search_counts(handle: &AppHandle, counts: &[SavedSearchCount]) {
+    let _ = handle.emit("saved_searches:counts", counts);
+}
@@ -0,0 +1,270 @@
+// src-tauri/src/feeds.rs
+
+// RSS and Atom subscriptions, e.g. a journal's table of contents or an arXiv category. Feeds
+// are polled in the background and new entries land in an inbox (feed_entries) from which
+// they can be imported with one click. RSS 2.0, RSS 1.0 (RDF, which arXiv and many
+// publishers use) and Atom are read by the same parser, going by element names alone.
+
+use crate::arxiv;
+use crate::crossref::{normalize_doi, strip_tags};
+use crate::db::{self, feeds::Feed};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const POLL_MINUTES: i64 = 60;
+const KEEP_DISMISSED_DAYS: i64 = 90;
+
+#[derive(Default)]
+pub struct ParsedFeed {
+    pub title: Option<String>,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Default)]
+pub struct FeedItem {
+    // Identifies the item within its feed: the guid or id, else the link
+    pub guid: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub link: Option<String>,
+    pub summary: Option<String>,
+    // RFC 3339 when the feed's date could be read, as given otherwise
+    pub published: Option<String>,
+    pub doi: Option<String>,
+    pub arxiv_id: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct NewEntries {
+    pub feed_id: i64,
+    pub count: usize,
+}
+
+pub fn start(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    tauri::async_runtime::spawn(poll_loop(handle, pool, http));
+}
+
+async fn poll_loop(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    let mut tick = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+        let feeds = match db::feeds::get_feeds_due(&pool, POLL_MINUTES).await {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                tracing::warn!("{}", e);
+                continue;
+            }
+        };
+        for feed in feeds {
+            poll_feed(&handle, &pool, &http, &feed).await;
+        }
+        if let Err(e) = db::feeds::prune_entries(&pool, KEEP_DISMISSED_DAYS).await {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+// Fetches the feed and stores its new entries, announcing them with "feeds:new_entries".
+// A failure is kept on the feed rather than returned, so one dead feed doesn't stop the rest.
+pub async fn poll_feed(handle: &AppHandle, pool: &SqlitePool, http: &reqwest::Client, feed: &Feed) -> usize {
+    let result = match fetch_feed(http, &feed.url).await {
+        Ok(parsed) => db::feeds::add_entries(pool, feed.id, &parsed.items).await.map_err(String::from),
+        Err(e) => Err(e),
+    };
+
+    let error = result.as_ref().err().map(String::as_str);
+    if let Err(e) = db::feeds::set_polled(pool, feed.id, error).await {
+        tracing::warn!("{}", e);
+    }
+    match result {
+        Ok(count) => {
+            if count > 0 {
+                let _ = handle.emit("feeds:new_entries", NewEntries { feed_id: feed.id, count });
+            }
+            count
+        }
+        Err(e) => {
+            tracing::warn!("Failed to poll {}: {}", feed.url, e);
+            0
+        }
+    }
+}
+
+pub async fn fetch_feed(http: &reqwest::Client, url: &str) -> Result<ParsedFeed, String> {
+    let body = http
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Feed request failed: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Feed request failed: {}", e))?;
+
+    parse_feed(&body)
+}
+
+pub fn parse_feed(xml: &str) -> Result<ParsedFeed, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut feed = ParsedFeed::default();
+
+    // Local names, without namespace prefixes: dc:creator is "creator"
+    let mut path: Vec<String> = Vec::new();
+    let mut item: Option<FeedItem> = None;
+    let mut text = String::new();
+    let mut root = false;
+
+    loop {
+        let event = reader.read_event().map_err(|e| format!("Not a valid feed: {}", e))?;
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                if path.is_empty() {
+                    if !["rss", "RDF", "feed"].contains(&name.as_str()) {
+                        return Err("Not an RSS or Atom feed".to_string());
+                    }
+                    root = true;
+                }
+                if item.is_none() && (name == "item" || name == "entry") {
+                    // RSS 1.0 items carry their URI as rdf:about
+                    item = Some(FeedItem { guid: attribute(&e, "rdf:about").unwrap_or_default(), ..Default::default() });
+                }
+                if name == "link" {
+                    atom_link(&e, item.as_mut());
+                }
+                text.clear();
+                path.push(name);
+            }
+            // Atom links are empty elements
+            Event::Empty(e) if local_name(&e) == "link" => atom_link(&e, item.as_mut()),
+            Event::Text(e) => {
+                text.push_str(&e.unescape().map_err(|e| format!("Not a valid feed: {}", e))?);
+            }
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+            Event::End(_) => {
+                let Some(name) = path.pop() else {
+                    continue;
+                };
+                let value = std::mem::take(&mut text).trim().to_string();
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let grandparent = path.iter().rev().nth(1).map(String::as_str).unwrap_or_default();
+
+                if name == "item" || name == "entry" {
+                    feed.items.extend(item.take().map(finish_item));
+                    continue;
+                }
+                match item.as_mut() {
+                    Some(item) if parent == "item" || parent == "entry" => read_item_field(item, &name, value),
+                    // Atom's <author><name>
+                    Some(item) if name == "name" && parent == "author" && grandparent == "entry" && !value.is_empty() => {
+                        item.authors.push(value);
+                    }
+                    None if name == "title" && (parent == "channel" || parent == "feed") && !value.is_empty() => {
+                        feed.title = Some(collapse_whitespace(&value));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !root {
+        return Err("Not an RSS or Atom feed".to_string());
+    }
+    Ok(feed)
+}
+
+fn read_item_field(item: &mut FeedItem, name: &str, value: String) {
+    if value.is_empty() {
+        return;
+    }
+    match name {
+        "title" => item.title = collapse_whitespace(&strip_tags(&value)),
+        "link" => item.link = Some(value),
+        "guid" | "id" => item.guid = value,
+        "description" | "summary" => item.summary = Some(value),
+        // Full text; only used when there's nothing shorter
+        "encoded" | "content" => item.summary = item.summary.take().or(Some(value)),
+        // arXiv lists every author in one dc:creator
+        "creator" => item.authors.extend(
+            strip_tags(&value).split([',', ';']).map(str::trim).filter(|a| !a.is_empty()).map(str::to_string),
+        ),
+        // RSS 2.0 wants "jane@example.com (Jane Doe)"
+        "author" => {
+            let name = match value.split_once('(') {
+                Some((_, rest)) => rest.trim_end_matches(')').trim().to_string(),
+                None if !value.contains('@') => value,
+                None => return,
+            };
+            item.authors.push(name);
+        }
+        "published" => item.published = Some(value),
+        "pubDate" | "date" | "updated" => item.published = item.published.take().or(Some(value)),
+        "doi" | "identifier" => item.doi = item.doi.take().or_else(|| normalize_doi(&value)),
+        _ => {}
+    }
+}
+
+// <link rel="alternate" href="..."/> is the entry's page; other rels are left alone
+fn atom_link(e: &BytesStart, item: Option<&mut FeedItem>) {
+    let Some(item) = item else {
+        return;
+    };
+    let rel = attribute(e, "rel");
+    if let Some(href) = attribute(e, "href").filter(|_| rel.is_none_or(|r| r == "alternate")) {
+        item.link = Some(href);
+    }
+}
+
+fn finish_item(mut item: FeedItem) -> FeedItem {
+    let link = item.link.as_deref().unwrap_or_default();
+    item.doi = item.doi.take().or_else(|| normalize_doi(link).filter(|_| link.contains("doi.org/")));
+    // arXiv guids look like oai:arXiv.org:2401.01234v1
+    item.arxiv_id = [item.guid.strip_prefix("oai:arXiv.org:").unwrap_or_default(), link]
+        .into_iter()
+        .filter(|id| !id.contains("://") || id.contains("arxiv.org/"))
+        .find_map(arxiv::parse_id);
+
+    item.summary = item
+        .summary
+        .map(|s| strip_tags(&s))
+        .map(|s| match s.split_once("Abstract:") {
+            // arXiv puts "arXiv:2401.01234v1 Announce Type: new" in front of the abstract
+            Some((head, rest)) if head.starts_with("arXiv:") => rest.trim().to_string(),
+            _ => s,
+        })
+        .filter(|s| !s.is_empty());
+    item.published = item.published.map(|date| {
+        chrono::DateTime::parse_from_rfc2822(&date)
+            .or_else(|_| chrono::DateTime::parse_from_rfc3339(&date))
+            .map_or(date, |d| d.to_rfc3339())
+    });
+    if item.guid.is_empty() {
+        item.guid = item.link.clone().unwrap_or_else(|| item.title.clone());
+    }
+    if item.title.is_empty() {
+        item.title = "Untitled".to_string();
+    }
+    item
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+fn attribute(e: &BytesStart, key: &str) -> Option<String> {
+    let value = e.try_get_attribute(key).ok().flatten()?;
+    value.unescape_value().ok().map(|v| v.into_owned())
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
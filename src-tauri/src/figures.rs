@@ -0,0 +1,346 @@
+// src-tauri/src/figures.rs
+
+// Figures for the figure browser: the images embedded in a paper's PDF, saved as
+// <app data>/figures/<paper id>/<page>-<n>.jpg or .png. A background pass works through
+// papers that haven't been scanned; get_figures scans a paper on the spot if the pass hasn't
+// reached it yet.
+//
+// This is a heuristic. Figures drawn as vector graphics, and tables, aren't images and are
+// not found; icons, logos and other small or repeated images are left out.
+
+use crate::db::{self, figures::NewFigure};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+// Images smaller than this on either side, in pixels, are icons and decorations
+const MIN_SIDE: i64 = 150;
+// An image on more pages than this is a logo or a page ornament
+const MAX_REPEATS: usize = 2;
+// How deep to follow form XObjects nested in each other
+const MAX_FORM_DEPTH: u8 = 4;
+
+#[derive(Serialize, Clone)]
+pub struct FigureImage {
+    pub id: i64,
+    pub paper_id: i64,
+    pub page: i64,
+    pub path: String,
+    pub width: i64,
+    pub height: i64,
+}
+
+pub struct Figures {
+    handle: AppHandle,
+    pool: SqlitePool,
+    // One scan at a time, so the background pass and a request can't both write a paper's folder
+    scanning: tokio::sync::Mutex<()>,
+}
+
+impl Figures {
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Arc<Self> {
+        let figures = Arc::new(Figures { handle, pool, scanning: tokio::sync::Mutex::new(()) });
+        tauri::async_runtime::spawn(scan_loop(figures.clone()));
+        figures
+    }
+
+    // Scans the paper first if that hasn't happened yet
+    pub async fn get(&self, paper_id: i64) -> Result<Vec<FigureImage>, String> {
+        if !db::figures::is_scanned(&self.pool, paper_id).await? {
+            let paper = db::get_paper(&self.pool, paper_id).await?;
+            if paper.pdf_path.is_empty() {
+                return Ok(Vec::new());
+            }
+            self.scan(paper_id, &paper.pdf_path).await?;
+        }
+        self.list(paper_id).await
+    }
+
+    // Throws away what was found before and scans the PDF again
+    pub async fn rescan(&self, paper_id: i64) -> Result<Vec<FigureImage>, String> {
+        let paper = db::get_paper(&self.pool, paper_id).await?;
+        if paper.pdf_path.is_empty() {
+            return Err(format!("\"{}\" has no PDF", paper.title));
+        }
+        self.scan(paper_id, &paper.pdf_path).await?;
+        self.list(paper_id).await
+    }
+
+    // After the paper's PDF is replaced or the paper deleted
+    pub async fn remove(&self, paper_id: i64) {
+        let _scanning = self.scanning.lock().await;
+        if let Err(e) = db::figures::clear_figures(&self.pool, paper_id).await {
+            tracing::warn!("{}", e);
+        }
+        remove_files(&self.handle, paper_id);
+    }
+
+    async fn list(&self, paper_id: i64) -> Result<Vec<FigureImage>, String> {
+        let dir = paper_dir(&self.handle, paper_id)?;
+        let figures = db::figures::get_figures(&self.pool, paper_id).await?;
+        Ok(figures
+            .into_iter()
+            .map(|f| FigureImage {
+                id: f.id,
+                paper_id: f.paper_id,
+                page: f.page,
+                path: dir.join(&f.file_name).display().to_string(),
+                width: f.width,
+                height: f.height,
+            })
+            .collect())
+    }
+
+    async fn scan(&self, paper_id: i64, pdf_path: &str) -> Result<(), String> {
+        let _scanning = self.scanning.lock().await;
+        let dir = paper_dir(&self.handle, paper_id)?;
+        let pdf_path = PathBuf::from(pdf_path);
+
+        let found = tokio::task::spawn_blocking(move || extract_figures(&pdf_path, &dir))
+            .await
+            .map_err(|e| format!("Figure extraction aborted: {}", e))??;
+        db::figures::save_figures(&self.pool, paper_id, &found).await?;
+        let _ = self.handle.emit("figures:ready", paper_id);
+        Ok(())
+    }
+}
+
+async fn scan_loop(figures: Arc<Figures>) {
+    let start = tokio::time::Instant::now() + STARTUP_DELAY;
+    let mut tick = tokio::time::interval_at(start, CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+
+        let papers = match db::figures::get_unscanned(&figures.pool).await {
+            Ok(papers) => papers,
+            Err(e) => {
+                tracing::warn!("{}", e);
+                continue;
+            }
+        };
+        for (paper_id, pdf_path) in papers {
+            if let Err(e) = figures.scan(paper_id, &pdf_path).await {
+                // Marked scanned anyway so a broken file isn't retried every few minutes;
+                // rescan_figures tries again
+                tracing::warn!("{}: {}", pdf_path, e);
+                if let Err(e) = db::figures::save_figures(&figures.pool, paper_id, &[]).await {
+                    tracing::warn!("{}", e);
+                }
+            }
+        }
+    }
+}
+
+// A deleted paper's rows go with it, but the images stay on disk until removed here
+pub fn remove_files(handle: &AppHandle, paper_id: i64) {
+    if let Ok(dir) = paper_dir(handle, paper_id) {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+fn paper_dir(handle: &AppHandle, paper_id: i64) -> Result<PathBuf, String> {
+    let dir = handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))?;
+    Ok(dir.join("figures").join(paper_id.to_string()))
+}
+
+// Writes the figures into `dir`, replacing whatever was there
+fn extract_figures(pdf_path: &Path, dir: &Path) -> Result<Vec<NewFigure>, String> {
+    let doc = lopdf::Document::load(pdf_path).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+
+    let mut found: Vec<(u32, lopdf::ObjectId)> = Vec::new();
+    let mut pages_per_image: HashMap<lopdf::ObjectId, usize> = HashMap::new();
+    for (page, page_id) in doc.get_pages() {
+        let mut on_page = Vec::new();
+        if let Some(resources) = page_resources(&doc, page_id) {
+            collect_images(&doc, resources, 0, &mut on_page);
+        }
+        for id in on_page {
+            *pages_per_image.entry(id).or_default() += 1;
+            found.push((page, id));
+        }
+    }
+
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create figures folder: {}", e))?;
+
+    let mut saved = HashSet::new();
+    let mut figures = Vec::new();
+    for (page, id) in found {
+        if pages_per_image[&id] > MAX_REPEATS || !saved.insert(id) {
+            continue;
+        }
+        let Some(image) = doc.get_object(id).ok().and_then(|o| o.as_stream().ok()).and_then(|s| decode(&doc, s)) else {
+            continue;
+        };
+
+        let on_page = figures.iter().filter(|f: &&NewFigure| f.page == page as i64).count();
+        let file_name = format!("{}-{}.{}", page, on_page + 1, image.extension);
+        fs::write(dir.join(&file_name), &image.data).map_err(|e| format!("Failed to save figure: {}", e))?;
+        figures.push(NewFigure { page: page as i64, file_name, width: image.width, height: image.height });
+    }
+
+    Ok(figures)
+}
+
+// Resources may be inherited from any ancestor in the page tree
+fn page_resources(doc: &lopdf::Document, page_id: lopdf::ObjectId) -> Option<&lopdf::Dictionary> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    for _ in 0..32 {
+        if let Ok(resources) = node.get_deref(b"Resources", doc).and_then(|r| r.as_dict()) {
+            return Some(resources);
+        }
+        node = node.get_deref(b"Parent", doc).and_then(|p| p.as_dict()).ok()?;
+    }
+    None
+}
+
+// Image XObjects in the resources, including those inside form XObjects
+fn collect_images(doc: &lopdf::Document, resources: &lopdf::Dictionary, depth: u8, out: &mut Vec<lopdf::ObjectId>) {
+    let Ok(xobjects) = resources.get_deref(b"XObject", doc).and_then(|x| x.as_dict()) else {
+        return;
+    };
+    for (_, xobject) in xobjects.iter() {
+        let Ok(id) = xobject.as_reference() else {
+            continue;
+        };
+        let Ok(stream) = doc.get_object(id).and_then(|o| o.as_stream()) else {
+            continue;
+        };
+        match stream.dict.get(b"Subtype").and_then(|s| s.as_name()) {
+            Ok(b"Image") if !out.contains(&id) => out.push(id),
+            Ok(b"Form") if depth < MAX_FORM_DEPTH => {
+                if let Ok(inner) = stream.dict.get_deref(b"Resources", doc).and_then(|r| r.as_dict()) {
+                    collect_images(doc, inner, depth + 1, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct DecodedImage {
+    extension: &'static str,
+    data: Vec<u8>,
+    width: i64,
+    height: i64,
+}
+
+// JPEGs are kept as they are; uncompressed or Flate-compressed 8-bit images are turned into
+// PNGs. Anything else (JPEG 2000, fax and JBIG2 scans, odd bit depths) is skipped.
+fn decode(doc: &lopdf::Document, stream: &lopdf::Stream) -> Option<DecodedImage> {
+    let dict = &stream.dict;
+    let number = |key: &[u8]| dict.get_deref(key, doc).ok()?.as_i64().ok();
+    let (width, height) = (number(b"Width")?, number(b"Height")?);
+    if width < MIN_SIDE || height < MIN_SIDE {
+        return None;
+    }
+    if dict.get(b"ImageMask").and_then(|m| m.as_bool()).unwrap_or(false) {
+        return None;
+    }
+
+    let filters: Vec<&[u8]> = match dict.get_deref(b"Filter", doc) {
+        Ok(lopdf::Object::Name(name)) => vec![name.as_slice()],
+        Ok(lopdf::Object::Array(names)) => names.iter().filter_map(|n| n.as_name().ok()).collect(),
+        _ => Vec::new(),
+    };
+    if filters == [b"DCTDecode".as_slice()] {
+        return Some(DecodedImage { extension: "jpg", data: stream.content.clone(), width, height });
+    }
+    if !filters.iter().all(|f| *f == b"FlateDecode") || number(b"BitsPerComponent") != Some(8) {
+        return None;
+    }
+
+    let samples = if filters.is_empty() { stream.content.clone() } else { stream.decompressed_content().ok()? };
+    let color_space = dict.get_deref(b"ColorSpace", doc).ok()?;
+    let (color, pixels) = to_pixels(doc, color_space, &samples, (width * height) as usize)?;
+    let data = encode_png(width as u32, height as u32, color, &pixels)?;
+    Some(DecodedImage { extension: "png", data, width, height })
+}
+
+// The samples as gray or RGB pixels a PNG can hold
+fn to_pixels(
+    doc: &lopdf::Document,
+    color_space: &lopdf::Object,
+    samples: &[u8],
+    pixel_count: usize,
+) -> Option<(png::ColorType, Vec<u8>)> {
+    if let Some(components) = components(doc, color_space) {
+        let samples = samples.get(..pixel_count * components)?;
+        return match components {
+            1 => Some((png::ColorType::Grayscale, samples.to_vec())),
+            3 => Some((png::ColorType::Rgb, samples.to_vec())),
+            4 => Some((png::ColorType::Rgb, samples.chunks(4).flat_map(cmyk_to_rgb).collect())),
+            _ => None,
+        };
+    }
+
+    // [/Indexed base hival lookup], each sample an index into the lookup table
+    let parts = color_space.as_array().ok()?;
+    if parts.first()?.as_name().ok()? != b"Indexed" {
+        return None;
+    }
+    let base = doc.dereference(parts.get(1)?).ok()?.1;
+    let base_components = components(doc, base)?;
+    let lookup = match doc.dereference(parts.get(3)?).ok()?.1 {
+        lopdf::Object::String(bytes, _) => bytes.clone(),
+        lopdf::Object::Stream(stream) => stream.decompressed_content().unwrap_or_else(|_| stream.content.clone()),
+        _ => return None,
+    };
+    let mut pixels = Vec::with_capacity(pixel_count * 3);
+    for &index in samples.get(..pixel_count)? {
+        let start = index as usize * base_components;
+        let entry = lookup.get(start..start + base_components)?;
+        match base_components {
+            1 => pixels.extend([entry[0]; 3]),
+            3 => pixels.extend_from_slice(entry),
+            4 => pixels.extend(cmyk_to_rgb(entry)),
+            _ => return None,
+        }
+    }
+    Some((png::ColorType::Rgb, pixels))
+}
+
+// Components per pixel of a device, calibrated or ICC-based colour space
+fn components(doc: &lopdf::Document, color_space: &lopdf::Object) -> Option<usize> {
+    let name = match color_space {
+        lopdf::Object::Name(name) => name.as_slice(),
+        lopdf::Object::Array(parts) => parts.first()?.as_name().ok()?,
+        _ => return None,
+    };
+    match name {
+        b"DeviceGray" | b"CalGray" | b"G" => Some(1),
+        b"DeviceRGB" | b"CalRGB" | b"RGB" => Some(3),
+        b"DeviceCMYK" | b"CMYK" => Some(4),
+        b"ICCBased" => {
+            let profile = doc.dereference(color_space.as_array().ok()?.get(1)?).ok()?.1.as_stream().ok()?;
+            profile.dict.get(b"N").ok()?.as_i64().ok().map(|n| n as usize)
+        }
+        _ => None,
+    }
+}
+
+fn cmyk_to_rgb(cmyk: &[u8]) -> [u8; 3] {
+    let k = 255 - cmyk[3] as u16;
+    let channel = |c: u8| ((255 - c as u16) * k / 255) as u8;
+    [channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2])]
+}
+
+fn encode_png(width: u32, height: u32, color: png::ColorType, pixels: &[u8]) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(color);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().and_then(|mut writer| writer.write_image_data(pixels)).ok()?;
+    Some(data)
+}
@@ -0,0 +1,188 @@
+// src-tauri/src/file_naming.rs
+
+// Names stored PDFs after their metadata, e.g. "2017 - Vaswani - Attention Is All You Need.pdf",
+// using the pattern from the config.
+
+use crate::db::{self, FileMode, Paper};
+use crate::import;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+const PLACEHOLDERS: &[&str] = &["title", "year", "first_author", "journal", "cite_key"];
+// Keeps the full path well inside Windows' 260 character limit
+const MAX_STEM_CHARS: usize = 150;
+
+enum Token<'a> {
+    Text(&'a str),
+    Field(&'a str),
+}
+
+fn tokenize(pattern: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        let end = start
+            + rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("Unclosed placeholder in \"{}\"", pattern))?;
+        let name = &rest[start + 1..end];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Unknown placeholder {{{}}}; available are {}",
+                name,
+                PLACEHOLDERS.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        tokens.push(Token::Field(name));
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+    Ok(tokens)
+}
+
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    let tokens = tokenize(pattern)?;
+    if !tokens.iter().any(|t| matches!(t, Token::Field(_))) {
+        return Err("The file name pattern needs at least one placeholder".to_string());
+    }
+    // Stored PDFs all live directly in the papers folder
+    if tokens.iter().any(|t| matches!(t, Token::Text(text) if text.contains(['/', '\\']))) {
+        return Err("The file name pattern can't contain / or \\".to_string());
+    }
+    Ok(())
+}
+
+// A field the paper doesn't have is dropped with the separator before it (after it, at the
+// start), so a missing year doesn't leave " - Vaswani - ..." and a missing journal doesn't
+// leave "Title ()"
+pub fn file_name(pattern: &str, paper: &Paper) -> Result<String, String> {
+    let tokens = tokenize(pattern)?;
+    // (is separator text, value)
+    let mut parts: Vec<(bool, String)> = Vec::new();
+    let mut skip_separator = false;
+
+    for token in &tokens {
+        match token {
+            Token::Text(text) => {
+                let text = match std::mem::take(&mut skip_separator) {
+                    true if parts.is_empty() => continue,
+                    true => text.trim_start_matches([')', ']', '}']),
+                    false => text,
+                };
+                // A pattern saved before validation could still hold a path separator
+                let text: String = text.chars().filter(|&c| !is_invalid(c)).collect();
+                if !text.is_empty() {
+                    parts.push((true, text));
+                }
+            }
+            Token::Field(name) => {
+                let value = sanitize(&field_value(paper, name));
+                if !value.is_empty() {
+                    parts.push((false, value));
+                } else {
+                    if parts.last().is_some_and(|(is_text, _)| *is_text) {
+                        parts.pop();
+                    }
+                    skip_separator = true;
+                }
+            }
+        }
+    }
+
+    let stem: String = parts.into_iter().map(|(_, value)| value).collect();
+    // The extension is added here, so a pattern ending in ".pdf" doesn't double it
+    let stem = stem.strip_suffix(".pdf").unwrap_or(&stem);
+    let stem: String = trim_name(stem).chars().take(MAX_STEM_CHARS).collect();
+    let stem = trim_name(&stem);
+    Ok(format!("{}.pdf", if stem.is_empty() { "Untitled" } else { stem }))
+}
+
+fn field_value(paper: &Paper, name: &str) -> String {
+    match name {
+        "title" => paper.title.clone(),
+        "year" => paper.year.map(|y| y.to_string()).unwrap_or_default(),
+        // Authors are stored "First Last, First Last"
+        "first_author" => paper
+            .authors
+            .as_deref()
+            .and_then(|a| a.split(", ").next())
+            .and_then(|first| first.split_whitespace().last())
+            .unwrap_or_default()
+            .to_string(),
+        "journal" => paper.journal.clone().unwrap_or_default(),
+        "cite_key" => paper.cite_key.clone().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// Drops characters that aren't allowed in file names on Windows or macOS
+pub fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if is_invalid(c) { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_invalid(c: char) -> bool {
+    c.is_control() || "<>:\"/\\|?*".contains(c)
+}
+
+// Windows doesn't allow names ending in a dot or space
+fn trim_name(name: &str) -> &str {
+    name.trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '.' | ','))
+}
+
+// unique_destination may have added "_2" when another paper had the same name
+fn has_name(path: &Path, name: &str) -> bool {
+    let Some(current) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    let wanted = name.trim_end_matches(".pdf");
+    current == wanted
+        || current
+            .strip_prefix(wanted)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+// Renames the paper's PDF to match `pattern` and points pdf_path at the new name. Linked
+// files and anything else outside app storage belong to the user and keep their names.
+// Returns the new path if the file was renamed.
+pub async fn rename_stored_file(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    paper: &Paper,
+    pattern: &str,
+) -> Result<Option<String>, String> {
+    let papers_dir = import::papers_dir(handle)?;
+    let current = Path::new(&paper.pdf_path);
+    if paper.file_mode == FileMode::Linked || !current.starts_with(&papers_dir) || !current.is_file() {
+        return Ok(None);
+    }
+
+    let name = file_name(pattern, paper)?;
+    if has_name(current, &name) {
+        return Ok(None);
+    }
+
+    let dest = import::unique_destination(&papers_dir, &name);
+    let dest_path = dest.to_str().ok_or("Invalid file path")?.to_string();
+    fs::rename(current, &dest).map_err(|e| format!("Failed to rename PDF: {}", e))?;
+
+    // Put the file back if the database can't follow, so pdf_path never points at nothing
+    if let Err(e) = db::set_pdf_path(pool, paper.id, &dest_path).await {
+        let _ = fs::rename(&dest, current);
+        return Err(e.into());
+    }
+    Ok(Some(dest_path))
+}
@@ -0,0 +1,127 @@
+// src-tauri/src/formats/annotations.rs
+
+// A paper's highlights and comments as a document to read or paste into a literature
+// summary: grouped by page, each quote followed by the comment made on it.
+
+use crate::db::annotations::Annotation;
+use crate::db::Paper;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+// Annotations are expected in reading order, as get_annotations returns them. Ones with
+// neither text nor a comment are left out.
+pub fn export(paper: &Paper, annotations: &[Annotation], format: AnnotationFormat) -> String {
+    let annotations: Vec<&Annotation> = annotations
+        .iter()
+        .filter(|a| quote(a).is_some() || comment(a).is_some())
+        .collect();
+    let mut pages: Vec<(i64, Vec<&Annotation>)> = Vec::new();
+    for annotation in annotations {
+        match pages.last_mut() {
+            Some((page, on_page)) if *page == annotation.page => on_page.push(annotation),
+            _ => pages.push((annotation.page, vec![annotation])),
+        }
+    }
+
+    match format {
+        AnnotationFormat::Markdown => markdown(paper, &pages),
+        AnnotationFormat::Html => html(paper, &pages),
+    }
+}
+
+fn quote(annotation: &Annotation) -> Option<&str> {
+    annotation.selected_text.as_deref().map(str::trim).filter(|t| !t.is_empty())
+}
+
+fn comment(annotation: &Annotation) -> Option<&str> {
+    annotation.comment.as_deref().map(str::trim).filter(|c| !c.is_empty())
+}
+
+// "Vaswani et al., 2017, NeurIPS" or whatever of it is known
+fn byline(paper: &Paper) -> Option<String> {
+    let parts: Vec<String> = [
+        paper.authors.clone().filter(|a| !a.trim().is_empty()),
+        paper.year.map(|y| y.to_string()),
+        paper.journal.clone().filter(|j| !j.trim().is_empty()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn markdown(paper: &Paper, pages: &[(i64, Vec<&Annotation>)]) -> String {
+    let mut out = format!("# {}\n\n", paper.title);
+    if let Some(byline) = byline(paper) {
+        out.push_str(&format!("{}\n\n", byline));
+    }
+    if pages.is_empty() {
+        out.push_str("No highlights or comments yet.\n");
+    }
+
+    for (page, annotations) in pages {
+        out.push_str(&format!("## Page {}\n\n", page));
+        for annotation in annotations {
+            if let Some(text) = quote(annotation) {
+                for line in text.lines() {
+                    out.push_str(&format!("> {}\n", line));
+                }
+                out.push('\n');
+            }
+            if let Some(comment) = comment(annotation) {
+                out.push_str(&format!("{}\n\n", comment));
+            }
+        }
+    }
+
+    format!("{}\n", out.trim_end())
+}
+
+fn html(paper: &Paper, pages: &[(i64, Vec<&Annotation>)]) -> String {
+    let title = escape(&paper.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, title
+    );
+    if let Some(byline) = byline(paper) {
+        out.push_str(&format!("<p><em>{}</em></p>\n", escape(&byline)));
+    }
+    if pages.is_empty() {
+        out.push_str("<p>No highlights or comments yet.</p>\n");
+    }
+
+    for (page, annotations) in pages {
+        out.push_str(&format!("<h2>Page {}</h2>\n", page));
+        for annotation in annotations {
+            if let Some(text) = quote(annotation) {
+                out.push_str(&format!(
+                    "<blockquote style=\"border-left: 4px solid {}; padding-left: 0.75em\">{}</blockquote>\n",
+                    escape(&annotation.color),
+                    escape(text).replace('\n', "<br>")
+                ));
+            }
+            if let Some(comment) = comment(annotation) {
+                for paragraph in comment.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+                    out.push_str(&format!("<p>{}</p>\n", escape(paragraph).replace('\n', "<br>")));
+                }
+            }
+        }
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,672 @@
+// src-tauri/src/formats/bibtex.rs
+
+use crate::crossref::normalize_doi;
+use crate::db::{ItemType, NewPaper, Paper};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub struct BibEntry {
+    pub entry_type: String,
+    pub key: String,
+    // Field names are lowercased, values have braces and LaTeX escapes resolved
+    // (except file/url, which are kept verbatim).
+    // author/editor are already converted to the library's "First Last, First Last" form.
+    pub fields: Vec<(String, String)>,
+}
+
+impl BibEntry {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    pub fn to_new_paper(&self) -> NewPaper {
+        let is_arxiv = self
+            .get("archiveprefix")
+            .or(self.get("eprinttype"))
+            .is_some_and(|p| p.eq_ignore_ascii_case("arxiv"));
+
+        let keywords: Vec<&str> = self
+            .get("keywords")
+            .map(|k| k.split([',', ';']).map(str::trim).filter(|k| !k.is_empty()).collect())
+            .unwrap_or_default();
+
+        let item_type = match self.entry_type.as_str() {
+            "book" | "inbook" | "incollection" | "collection" | "mvbook" => ItemType::Book,
+            "phdthesis" | "mastersthesis" | "thesis" => ItemType::Thesis,
+            "techreport" | "report" => ItemType::Report,
+            "unpublished" | "online" | "misc" if is_arxiv => ItemType::Preprint,
+            _ => ItemType::Article,
+        };
+
+        NewPaper {
+            title: self.get("title").unwrap_or_default().to_string(),
+            authors: self.get("author").or(self.get("editor")).map(str::to_string),
+            // A chapter's book; whole books, theses and reports have no venue of their own
+            journal: self
+                .get("journal")
+                .or(self.get("journaltitle"))
+                .or(self.get("booktitle"))
+                .map(str::to_string),
+            item_type,
+            isbn: self.get("isbn").map(str::to_string),
+            publisher: self.get("publisher").map(str::to_string),
+            institution: self.get("school").or(self.get("institution")).map(str::to_string),
+            year: self
+                .get("year")
+                .or(self.get("date"))
+                .and_then(|y| y.get(..4))
+                .and_then(|y| y.parse().ok()),
+            doi: self.get("doi").and_then(normalize_doi),
+            arxiv_id: self.get("eprint").filter(|_| is_arxiv).map(str::to_string),
+            pmid: self.get("pmid").map(str::to_string),
+            cite_key: Some(self.key.clone()).filter(|k| !k.trim().is_empty()),
+            r#abstract: self.get("abstract").map(str::to_string),
+            tags: (!keywords.is_empty()).then(|| keywords.join(", ")),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Bibliography {
+    pub entries: Vec<BibEntry>,
+    // Malformed entries are skipped and reported here rather than failing the whole file
+    pub errors: Vec<String>,
+}
+
+pub fn parse(input: &str) -> Bibliography {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+        strings: default_strings(),
+    };
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    while parser.skip_to_at() {
+        let start_line = parser.line();
+        match parser.entry() {
+            Ok(Some(entry)) => entries.push(entry),
+            Ok(None) => {}
+            Err(e) => errors.push(format!("line {}: {}", start_line, e)),
+        }
+    }
+
+    Bibliography { entries, errors }
+}
+
+// Turns "Vaswani, Ashish and Shazeer, Noam" into "Ashish Vaswani, Noam Shazeer"
+pub fn authors_to_display(authors: &str) -> String {
+    split_authors(authors)
+        .iter()
+        .map(|name| match top_level_comma(name) {
+            Some(idx) if !name[idx + 1..].trim().is_empty() => {
+                format!("{} {}", name[idx + 1..].trim(), name[..idx].trim())
+            }
+            Some(idx) => name[..idx].trim().to_string(),
+            None => name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn top_level_comma(name: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, c) in name.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn split_authors(authors: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    let words: Vec<&str> = authors.split_whitespace().collect();
+    for word in words {
+        if depth == 0 && word.eq_ignore_ascii_case("and") {
+            if !current.is_empty() {
+                names.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        depth += word.matches('{').count() as i32 - word.matches('}').count() as i32;
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        names.push(current);
+    }
+    names
+}
+
+// JabRef writes ":path:PDF", Mendeley "path:pdf", Zotero plain paths separated by ';'
+pub fn linked_files(file_field: &str, bib_dir: &Path) -> Vec<PathBuf> {
+    file_field
+        .split(';')
+        .filter_map(|part| {
+            let part = part.trim();
+            let path = if part.contains(':') && !looks_like_windows_path(part) {
+                let pieces: Vec<&str> = part.split(':').collect();
+                match pieces.len() {
+                    // description:path:type
+                    3 => pieces[1].to_string(),
+                    // path:type
+                    2 => pieces[0].to_string(),
+                    // description:C:\path:type
+                    4 => format!("{}:{}", pieces[1], pieces[2]),
+                    _ => part.to_string(),
+                }
+            } else {
+                part.to_string()
+            };
+            let path = path.replace("\\:", ":");
+            (!path.is_empty()).then(|| PathBuf::from(path))
+        })
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")))
+        .map(|p| if p.is_absolute() { p } else { bib_dir.join(p) })
+        .collect()
+}
+
+// Papers are exported in the order given under their stored citation keys; papers without
+// one get a key from their metadata
+pub fn export(papers: &[Paper]) -> String {
+    let mut used_keys = HashMap::new();
+    let mut out = String::new();
+
+    for paper in papers {
+        let key = unique_key(&mut used_keys, paper.cite_key.clone().unwrap_or_else(|| cite_key(paper)));
+        let entry_type = match paper.item_type {
+            ItemType::Book if paper.journal.is_some() => "incollection",
+            ItemType::Book => "book",
+            ItemType::Thesis => "phdthesis",
+            ItemType::Report => "techreport",
+            ItemType::Article if paper.journal.is_some() => "article",
+            ItemType::Article | ItemType::Preprint => "misc",
+        };
+
+        let mut fields: Vec<(&str, String)> = vec![("title", paper.title.clone())];
+        if let Some(authors) = &paper.authors {
+            fields.push(("author", authors.split(", ").collect::<Vec<_>>().join(" and ")));
+        }
+        if let Some(journal) = &paper.journal {
+            let name = if entry_type == "incollection" { "booktitle" } else { "journal" };
+            fields.push((name, journal.clone()));
+        }
+        if let Some(publisher) = &paper.publisher {
+            fields.push(("publisher", publisher.clone()));
+        }
+        if let Some(institution) = &paper.institution {
+            let name = if entry_type == "phdthesis" { "school" } else { "institution" };
+            fields.push((name, institution.clone()));
+        }
+        if let Some(isbn) = &paper.isbn {
+            fields.push(("isbn", isbn.clone()));
+        }
+        if let Some(year) = paper.year {
+            fields.push(("year", year.to_string()));
+        }
+        if let Some(doi) = &paper.doi {
+            fields.push(("doi", doi.clone()));
+        }
+        if let Some(arxiv_id) = &paper.arxiv_id {
+            fields.push(("eprint", arxiv_id.clone()));
+            fields.push(("archiveprefix", "arXiv".to_string()));
+        }
+        if let Some(pmid) = &paper.pmid {
+            fields.push(("pmid", pmid.clone()));
+        }
+        if let Some(tags) = &paper.tags {
+            fields.push(("keywords", tags.clone()));
+        }
+        if let Some(r#abstract) = &paper.r#abstract {
+            fields.push(("abstract", r#abstract.clone()));
+        }
+        if !paper.pdf_path.is_empty() {
+            fields.push(("file", format!(":{}:PDF", paper.pdf_path.replace(':', "\\:"))));
+        }
+
+        out.push_str(&format!("@{}{{{},\n", entry_type, key));
+        for (name, value) in fields {
+            let value = if name == "file" { value } else { escape_latex(&value) };
+            out.push_str(&format!("  {} = {{{}}},\n", name, value));
+        }
+        out.push_str("}\n\n");
+    }
+
+    out
+}
+
+// smith2021attention: first author's surname, year, first significant title word
+pub fn cite_key(paper: &Paper) -> String {
+    let surname = paper
+        .authors
+        .as_deref()
+        .and_then(|a| a.split(", ").next())
+        .and_then(|first| first.split_whitespace().last())
+        .map(ascii_word)
+        .unwrap_or_default();
+
+    let title_word = paper
+        .title
+        .split_whitespace()
+        .map(ascii_word)
+        .find(|w| w.len() > 3 && !STOP_WORDS.contains(&w.as_str()))
+        .unwrap_or_default();
+
+    let key = format!(
+        "{}{}{}",
+        surname,
+        paper.year.map(|y| y.to_string()).unwrap_or_default(),
+        title_word
+    );
+    if key.is_empty() {
+        format!("paper{}", paper.id)
+    } else {
+        key
+    }
+}
+
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "from", "into", "over", "than", "that", "their", "there", "these", "this",
+    "towards", "under", "using", "when", "where", "which", "with", "without",
+];
+
+fn unique_key(used: &mut HashMap<String, usize>, key: String) -> String {
+    let count = used.entry(key.clone()).or_insert(0);
+    *count += 1;
+    suffixed_key(&key, *count)
+}
+
+// The first of key, keya, keyb, ... that isn't taken
+pub fn free_key(key: &str, taken: &HashSet<String>) -> String {
+    let mut n = 1;
+    loop {
+        let candidate = suffixed_key(key, n);
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+// smith2021attention, smith2021attentiona, smith2021attentionb, ...
+fn suffixed_key(key: &str, n: usize) -> String {
+    match n {
+        1 => key.to_string(),
+        n if n <= 27 => format!("{}{}", key, (b'a' + (n - 2) as u8) as char),
+        n => format!("{}{}", key, n - 1),
+    }
+}
+
+fn ascii_word(word: &str) -> String {
+    word.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            'ä' | 'á' | 'à' | 'â' | 'ã' | 'å' | 'ā' => Some('a'),
+            'é' | 'è' | 'ê' | 'ë' | 'ē' => Some('e'),
+            'í' | 'ì' | 'î' | 'ï' => Some('i'),
+            'ö' | 'ó' | 'ò' | 'ô' | 'õ' | 'ø' | 'ő' => Some('o'),
+            'ü' | 'ú' | 'ù' | 'û' | 'ű' => Some('u'),
+            'ç' | 'č' | 'ć' => Some('c'),
+            'ñ' | 'ń' | 'ň' => Some('n'),
+            'š' | 'ś' | 'ş' => Some('s'),
+            'ž' | 'ź' | 'ż' => Some('z'),
+            'ß' => Some('s'),
+            'ł' => Some('l'),
+            c if c.is_ascii_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn escape_latex(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            // Unbalanced braces would break the entry
+            '{' | '}' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn looks_like_windows_path(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && (bytes[2] == b'\\' || bytes[2] == b'/')
+}
+
+fn default_strings() -> HashMap<String, String> {
+    [
+        "january", "february", "march", "april", "may", "june", "july", "august", "september",
+        "october", "november", "december",
+    ]
+    .iter()
+    .map(|month| (month[..3].to_string(), capitalize(month)))
+    .collect()
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    strings: HashMap<String, String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn line(&self) -> usize {
+        self.chars[..self.pos].iter().filter(|c| **c == '\n').count() + 1
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // Anything outside an @entry is a comment in BibTeX
+    fn skip_to_at(&mut self) -> bool {
+        while let Some(c) = self.peek() {
+            if c == '@' {
+                return true;
+            }
+            self.pos += 1;
+        }
+        false
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(format!("expected '{}' but found '{}'", expected, c)),
+            None => Err(format!("expected '{}' but reached end of file", expected)),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"{}(),=#\"@".contains(c))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn entry(&mut self) -> Result<Option<BibEntry>, String> {
+        // Consume '@'
+        self.pos += 1;
+        let entry_type = self.identifier().to_lowercase();
+        if entry_type.is_empty() {
+            return Err("missing entry type after '@'".to_string());
+        }
+
+        self.skip_whitespace();
+        let close = match self.peek() {
+            Some('{') => '}',
+            Some('(') => ')',
+            _ => return Err(format!("expected '{{' after @{}", entry_type)),
+        };
+        self.pos += 1;
+
+        match entry_type.as_str() {
+            "comment" | "preamble" => {
+                self.pos -= 1;
+                self.braced_raw()?;
+                return Ok(None);
+            }
+            "string" => {
+                let name = self.identifier().to_lowercase();
+                self.expect('=')?;
+                let value = self.value()?;
+                self.strings.insert(name, value);
+                self.expect(close)?;
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        let key = self.identifier();
+        let mut fields = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.pos += 1;
+                }
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    break;
+                }
+                None => return Err(format!("unterminated entry '{}'", key)),
+                _ => {}
+            }
+
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                continue;
+            }
+
+            let name = self.identifier().to_lowercase();
+            if name.is_empty() {
+                return Err(format!("malformed field in entry '{}'", key));
+            }
+            self.expect('=')?;
+            let value = self.value()?;
+            let value = match name.as_str() {
+                "author" | "editor" => clean_latex(&authors_to_display(&value)),
+                // Paths and links are not LaTeX
+                "file" | "url" => value.trim().to_string(),
+                _ => clean_latex(&value),
+            };
+            fields.push((name, value));
+        }
+
+        Ok(Some(BibEntry {
+            entry_type,
+            key,
+            fields,
+        }))
+    }
+
+    // value = part ('#' part)*
+    fn value(&mut self) -> Result<String, String> {
+        let mut out = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('{') => {
+                    let raw = self.braced_raw()?;
+                    out.push_str(&raw);
+                }
+                Some('"') => {
+                    self.pos += 1;
+                    let start = self.pos;
+                    let mut depth = 0;
+                    loop {
+                        match self.peek() {
+                            Some('{') => depth += 1,
+                            Some('}') => depth -= 1,
+                            Some('"') if depth == 0 => break,
+                            None => return Err("unterminated quoted value".to_string()),
+                            _ => {}
+                        }
+                        self.pos += 1;
+                    }
+                    out.extend(&self.chars[start..self.pos]);
+                    self.pos += 1;
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.pos += 1;
+                    }
+                    out.extend(&self.chars[start..self.pos]);
+                }
+                Some(_) => {
+                    let name = self.identifier().to_lowercase();
+                    if name.is_empty() {
+                        return Err("expected a field value".to_string());
+                    }
+                    out.push_str(self.strings.get(&name).map_or(name.as_str(), |v| v.as_str()));
+                }
+                None => return Err("unexpected end of file".to_string()),
+            }
+
+            self.skip_whitespace();
+            if self.peek() == Some('#') {
+                self.pos += 1;
+            } else {
+                return Ok(out);
+            }
+        }
+    }
+
+    // Returns the contents of a balanced {...} group without the outer braces
+    fn braced_raw(&mut self) -> Result<String, String> {
+        self.expect('{')?;
+        let start = self.pos;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.peek() {
+                Some('{') => depth += 1,
+                Some('}') => depth -= 1,
+                Some('\\') => self.pos += 1,
+                None => return Err("unbalanced braces".to_string()),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Ok(self.chars[start..self.pos - 1].iter().collect())
+    }
+}
+
+// Resolves the common LaTeX accents/escapes and drops case-protecting braces
+pub fn clean_latex(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let chars: Vec<char> = value.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' | '}' => i += 1,
+            '\\' if i + 1 < chars.len() => {
+                let cmd = chars[i + 1];
+                if "&%$#_{}".contains(cmd) {
+                    out.push(cmd);
+                    i += 2;
+                    continue;
+                }
+                if "'`^\"~=.cvuH".contains(cmd) {
+                    // \'e, \'{e}, {\'e}
+                    let mut j = i + 2;
+                    while j < chars.len() && (chars[j] == '{' || chars[j] == ' ') {
+                        j += 1;
+                    }
+                    if let Some(&base) = chars.get(j) {
+                        if let Some(accented) = accent(cmd, base) {
+                            out.push(accented);
+                            i = j + 1;
+                            continue;
+                        }
+                    }
+                }
+                // Named commands such as \ss, \o, \textit
+                let mut j = i + 1;
+                while j < chars.len() && chars[j].is_ascii_alphabetic() {
+                    j += 1;
+                }
+                let name: String = chars[i + 1..j].iter().collect();
+                match name.as_str() {
+                    "ss" => out.push('ß'),
+                    "o" => out.push('ø'),
+                    "O" => out.push('Ø'),
+                    "ae" => out.push('æ'),
+                    "AE" => out.push('Æ'),
+                    "aa" => out.push('å'),
+                    "AA" => out.push('Å'),
+                    "l" => out.push('ł'),
+                    "L" => out.push('Ł'),
+                    "i" => out.push('ı'),
+                    // Formatting commands only wrap their argument
+                    "textit" | "textbf" | "textsc" | "texttt" | "textrm" | "textsl" | "emph"
+                    | "mathrm" | "mbox" | "url" | "it" | "bf" | "em" | "sc" | "tt" | "rm"
+                    | "sf" | "protect" | "relax" | "ensuremath" => {}
+                    // \TeX, \LaTeX and friends read fine as their own name
+                    _ => out.push_str(&name),
+                }
+                if j > i + 1 {
+                    i = j;
+                    // Swallow the space that terminates a command word
+                    if chars.get(i) == Some(&' ') {
+                        i += 1;
+                    }
+                } else {
+                    i += 2;
+                }
+            }
+            '~' => {
+                out.push(' ');
+                i += 1;
+            }
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn accent(cmd: char, base: char) -> Option<char> {
+    let table: &[(char, &str, &str)] = &[
+        ('\'', "aeiouyAEIOUYcnszCNSZ", "áéíóúýÁÉÍÓÚÝćńśźĆŃŚŹ"),
+        ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        ('"', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+        ('~', "anoANO", "ãñõÃÑÕ"),
+        ('c', "csCS", "çşÇŞ"),
+        ('v', "csznrCSZNR", "čšžňřČŠŽŇŘ"),
+        ('u', "agAG", "ăğĂĞ"),
+        ('H', "ouOU", "őűŐŰ"),
+        ('=', "aeiouAEIOU", "āēīōūĀĒĪŌŪ"),
+        ('.', "zZ", "żŻ"),
+    ];
+    let (_, from, to) = table.iter().find(|(c, _, _)| *c == cmd)?;
+    let idx = from.chars().position(|c| c == base)?;
+    to.chars().nth(idx)
+}
@@ -0,0 +1,172 @@
+// src-tauri/src/formats/csl.rs
+
+// Citations and reference lists in any CSL style, rendered by hayagriva. Papers go through
+// the BibTeX export so they are described the same way as in an exported .bib file.
+
+use super::bibtex;
+use crate::db::Paper;
+use hayagriva::archive::{self, ArchivedStyle};
+use hayagriva::citationberg::{IndependentStyle, Locale, Style};
+use hayagriva::{
+    BibliographyDriver, BibliographyRequest, BufWriteFormat, CitationItem, CitationRequest, ElemChildren, Entry,
+    Library,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Plain,
+    Html,
+}
+
+#[derive(Serialize)]
+pub struct FormattedCitation {
+    // In-text form, e.g. "(Smith, 2021)" or "[1]"
+    pub citation: String,
+    // The paper's reference list entry; None for styles without a bibliography
+    pub reference: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UserStyles {
+    pub directory: String,
+    // File names without the .csl extension
+    pub styles: Vec<String>,
+}
+
+pub struct CslStyle {
+    style: IndependentStyle,
+    locales: Vec<Locale>,
+}
+
+// `name` is a style bundled with hayagriva ("apa", "ieee", "chicago-author-date", ...) or the
+// file name of a .csl in `styles_dir`, with or without the extension. User styles win.
+pub fn load_style(name: &str, styles_dir: &Path) -> Result<CslStyle, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("No citation style given".to_string());
+    }
+
+    let file_name = if name.to_lowercase().ends_with(".csl") { name.to_string() } else { format!("{}.csl", name) };
+    let user_path = styles_dir.join(&file_name);
+    let style = if !file_name.contains(['/', '\\']) && user_path.is_file() {
+        let xml = std::fs::read_to_string(&user_path).map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+        Style::from_xml(&xml).map_err(|e| format!("Failed to parse {}: {}", file_name, e))?
+    } else {
+        ArchivedStyle::by_name(name)
+            .ok_or_else(|| format!("Unknown citation style \"{}\"", name))?
+            .get()
+    };
+
+    match style {
+        Style::Independent(style) => Ok(CslStyle { style, locales: archive::locales() }),
+        // These only point at a parent style by URL, which we can't fetch
+        Style::Dependent(_) => Err(format!(
+            "\"{}\" is a dependent style; use the style it is based on instead",
+            name
+        )),
+    }
+}
+
+pub fn user_styles(styles_dir: &Path) -> UserStyles {
+    let mut styles: Vec<String> = std::fs::read_dir(styles_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("csl")))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    styles.sort_by_key(|name| name.to_lowercase());
+    UserStyles { directory: styles_dir.to_string_lossy().to_string(), styles }
+}
+
+fn to_library(papers: &[Paper]) -> Result<Library, String> {
+    hayagriva::io::from_biblatex_str(&bibtex::export(papers)).map_err(|errors| {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        format!("Failed to convert papers for citing: {}", messages.join("; "))
+    })
+}
+
+fn buf_format(format: OutputFormat) -> BufWriteFormat {
+    match format {
+        OutputFormat::Plain => BufWriteFormat::Plain,
+        OutputFormat::Html => BufWriteFormat::Html,
+    }
+}
+
+fn render(children: &ElemChildren, format: OutputFormat) -> String {
+    let mut out = String::new();
+    let _ = children.write_buf(&mut out, buf_format(format));
+    out
+}
+
+fn render_references(
+    entries: &[&Entry],
+    style: &CslStyle,
+    format: OutputFormat,
+) -> (Vec<String>, Option<Vec<String>>) {
+    let mut driver = BibliographyDriver::new();
+    for entry in entries {
+        driver.citation(CitationRequest::from_items(
+            vec![CitationItem::with_entry(*entry)],
+            &style.style,
+            &style.locales,
+        ));
+    }
+    let rendered = driver.finish(BibliographyRequest {
+        style: &style.style,
+        locale: None,
+        locale_files: &style.locales,
+    });
+
+    let citations = rendered.citations.iter().map(|c| render(&c.citation, format)).collect();
+    let references = rendered.bibliography.map(|bibliography| {
+        bibliography
+            .items
+            .iter()
+            .map(|item| {
+                let mut out = String::new();
+                // Numbered styles put the label, e.g. "[1]", in a field of its own
+                if let Some(first_field) = &item.first_field {
+                    let _ = first_field.write_buf(&mut out, buf_format(format));
+                    out.push(' ');
+                }
+                out.push_str(&render(&item.content, format));
+                out
+            })
+            .collect()
+    });
+    (citations, references)
+}
+
+pub fn format_citation(paper: &Paper, style: &CslStyle, format: OutputFormat) -> Result<FormattedCitation, String> {
+    let library = to_library(std::slice::from_ref(paper))?;
+    let entries: Vec<&Entry> = library.iter().collect();
+    let (citations, references) = render_references(&entries, style, format);
+
+    Ok(FormattedCitation {
+        citation: citations.into_iter().next().unwrap_or_default(),
+        reference: references.and_then(|r| r.into_iter().next()),
+    })
+}
+
+// The reference list for `papers`, ordered the way the style orders it (numbered styles keep
+// the order given). Entries are separated by newlines, or wrapped in divs for HTML.
+pub fn format_bibliography(papers: &[Paper], style: &CslStyle, format: OutputFormat) -> Result<String, String> {
+    let library = to_library(papers)?;
+    let entries: Vec<&Entry> = library.iter().collect();
+    let (_, references) = render_references(&entries, style, format);
+    let references = references.ok_or("This citation style has no bibliography")?;
+
+    Ok(match format {
+        OutputFormat::Plain => references.join("\n"),
+        OutputFormat::Html => references
+            .iter()
+            .map(|r| format!("<div class=\"csl-entry\">{}</div>", r))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    })
+}
@@ -0,0 +1,343 @@
+// src-tauri/src/formats/csv.rs
+
+// Comma separated values with a header row, as spreadsheets write them (RFC 4180): cells
+// containing commas, quotes or line breaks are quoted and quotes inside them doubled
+
+use crate::arxiv;
+use crate::crossref::normalize_doi;
+use crate::db::{ItemType, NewPaper, Paper};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Tags are one cell; within it they are separated by semicolons so the cell survives
+// spreadsheets that split on commas
+const TAG_SEPARATOR: &str = "; ";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvField {
+    Id,
+    Title,
+    Authors,
+    Journal,
+    Year,
+    ItemType,
+    Isbn,
+    Publisher,
+    Institution,
+    Doi,
+    ArxivId,
+    Pmid,
+    Abstract,
+    Tags,
+    CiteKey,
+    Notes,
+    ReadingStatus,
+    Starred,
+    Rating,
+    Priority,
+    PdfPath,
+    PageCount,
+    CreatedAt,
+}
+
+impl CsvField {
+    pub const ALL: [CsvField; 23] = [
+        CsvField::Id,
+        CsvField::Title,
+        CsvField::Authors,
+        CsvField::Journal,
+        CsvField::Year,
+        CsvField::ItemType,
+        CsvField::Isbn,
+        CsvField::Publisher,
+        CsvField::Institution,
+        CsvField::Doi,
+        CsvField::ArxivId,
+        CsvField::Pmid,
+        CsvField::Abstract,
+        CsvField::Tags,
+        CsvField::CiteKey,
+        CsvField::Notes,
+        CsvField::ReadingStatus,
+        CsvField::Starred,
+        CsvField::Rating,
+        CsvField::Priority,
+        CsvField::PdfPath,
+        CsvField::PageCount,
+        CsvField::CreatedAt,
+    ];
+
+    // Header used on export, and matched case-insensitively on import when no mapping is given
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvField::Id => "id",
+            CsvField::Title => "title",
+            CsvField::Authors => "authors",
+            CsvField::Journal => "journal",
+            CsvField::Year => "year",
+            CsvField::ItemType => "item_type",
+            CsvField::Isbn => "isbn",
+            CsvField::Publisher => "publisher",
+            CsvField::Institution => "institution",
+            CsvField::Doi => "doi",
+            CsvField::ArxivId => "arxiv_id",
+            CsvField::Pmid => "pmid",
+            CsvField::Abstract => "abstract",
+            CsvField::Tags => "tags",
+            CsvField::CiteKey => "cite_key",
+            CsvField::Notes => "notes",
+            CsvField::ReadingStatus => "reading_status",
+            CsvField::Starred => "starred",
+            CsvField::Rating => "rating",
+            CsvField::Priority => "priority",
+            CsvField::PdfPath => "pdf_path",
+            CsvField::PageCount => "page_count",
+            CsvField::CreatedAt => "created_at",
+        }
+    }
+
+    // Only metadata a new paper starts with is read back; the rest is export-only
+    pub fn importable(self) -> bool {
+        matches!(
+            self,
+            CsvField::Title
+                | CsvField::Authors
+                | CsvField::Journal
+                | CsvField::Year
+                | CsvField::ItemType
+                | CsvField::Isbn
+                | CsvField::Publisher
+                | CsvField::Institution
+                | CsvField::Doi
+                | CsvField::ArxivId
+                | CsvField::Pmid
+                | CsvField::Abstract
+                | CsvField::Tags
+                | CsvField::CiteKey
+                | CsvField::PdfPath
+        )
+    }
+
+    fn value(self, paper: &Paper) -> String {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let number = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+        match self {
+            CsvField::Id => paper.id.to_string(),
+            CsvField::Title => paper.title.clone(),
+            CsvField::Authors => text(&paper.authors),
+            CsvField::Journal => text(&paper.journal),
+            CsvField::Year => number(paper.year),
+            CsvField::ItemType => enum_name(&paper.item_type),
+            CsvField::Isbn => text(&paper.isbn),
+            CsvField::Publisher => text(&paper.publisher),
+            CsvField::Institution => text(&paper.institution),
+            CsvField::Doi => text(&paper.doi),
+            CsvField::ArxivId => text(&paper.arxiv_id),
+            CsvField::Pmid => text(&paper.pmid),
+            CsvField::Abstract => text(&paper.r#abstract),
+            CsvField::Tags => split_list(paper.tags.as_deref().unwrap_or_default(), ',').join(TAG_SEPARATOR),
+            CsvField::CiteKey => text(&paper.cite_key),
+            CsvField::Notes => text(&paper.notes),
+            CsvField::ReadingStatus => enum_name(&paper.reading_status),
+            CsvField::Starred => paper.starred.to_string(),
+            CsvField::Rating => number(paper.rating),
+            CsvField::Priority => enum_name(&paper.priority),
+            CsvField::PdfPath => paper.pdf_path.clone(),
+            CsvField::PageCount => number(paper.page_count),
+            CsvField::CreatedAt => text(&paper.created_at),
+        }
+    }
+}
+
+// The serde name, e.g. "reading"
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn split_list(value: &str, separator: char) -> Vec<&str> {
+    value.split(separator).map(str::trim).filter(|v| !v.is_empty()).collect()
+}
+
+// A user-defined field exported after the built-in columns
+pub struct CustomColumn {
+    pub name: String,
+    // Paper id -> value
+    pub values: HashMap<i64, String>,
+}
+
+pub fn export(papers: &[Paper], fields: &[CsvField], custom: &[CustomColumn]) -> String {
+    let mut out = String::new();
+    let header = fields.iter().map(|f| f.name().to_string());
+    push_row(&mut out, header.chain(custom.iter().map(|c| c.name.clone())));
+    for paper in papers {
+        let custom_cells = custom.iter().map(|c| c.values.get(&paper.id).cloned().unwrap_or_default());
+        push_row(&mut out, fields.iter().map(|f| f.value(paper)).chain(custom_cells));
+    }
+    out
+}
+
+fn push_row(out: &mut String, cells: impl Iterator<Item = String>) {
+    let cells: Vec<String> = cells.map(|cell| quote(&cell)).collect();
+    out.push_str(&cells.join(","));
+    out.push_str("\r\n");
+}
+
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+// All rows including the header; blank lines are dropped
+pub fn parse(input: &str) -> Vec<Vec<String>> {
+    let input = input.trim_start_matches('\u{feff}');
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut cell)),
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                row.push(std::mem::take(&mut cell));
+                if row.iter().any(|cell| !cell.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => cell.push(c),
+        }
+    }
+
+    row.push(cell);
+    if row.iter().any(|cell| !cell.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+pub struct CsvRecord {
+    // Row number in the file, counting the header as 1, for error messages
+    pub line: usize,
+    pub values: HashMap<CsvField, String>,
+}
+
+// Resolves each column to a field through `mapping` (header -> field), or by header name when
+// there is no mapping. Unmapped columns are ignored; mapping to an export-only field is an error.
+pub fn read_records(
+    rows: Vec<Vec<String>>,
+    mapping: Option<&HashMap<String, CsvField>>,
+) -> Result<Vec<CsvRecord>, String> {
+    let mut rows = rows.into_iter();
+    let header = rows.next().ok_or("The CSV file is empty")?;
+
+    let mut columns: Vec<(usize, CsvField)> = Vec::new();
+    for (index, name) in header.iter().enumerate() {
+        let name = name.trim();
+        let field = match mapping {
+            Some(mapping) => mapping.get(name).copied(),
+            None => CsvField::ALL.into_iter().find(|f| f.name().eq_ignore_ascii_case(name)),
+        };
+        let Some(field) = field else {
+            continue;
+        };
+        if !field.importable() {
+            if mapping.is_some() {
+                return Err(format!("Column \"{}\" can't be imported as {}", name, field.name()));
+            }
+            continue;
+        }
+        columns.push((index, field));
+    }
+    if !columns.iter().any(|(_, field)| *field == CsvField::Title) {
+        return Err("No column is mapped to the title".to_string());
+    }
+
+    Ok(rows
+        .enumerate()
+        .map(|(index, row)| CsvRecord {
+            line: index + 2,
+            values: columns
+                .iter()
+                .filter_map(|(column, field)| {
+                    let value = row.get(*column)?.trim();
+                    (!value.is_empty()).then(|| (*field, value.to_string()))
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+impl CsvRecord {
+    fn get(&self, field: CsvField) -> Option<&str> {
+        self.values.get(&field).map(String::as_str)
+    }
+
+    // The title if there is one, otherwise the line, for error messages
+    pub fn label(&self) -> String {
+        match self.get(CsvField::Title) {
+            Some(title) => title.to_string(),
+            None => format!("line {}", self.line),
+        }
+    }
+
+    pub fn to_new_paper(&self) -> NewPaper {
+        let owned = |field| self.get(field).map(str::to_string);
+        // Tags written by other tools may be comma separated instead
+        let tags = self.get(CsvField::Tags).map(|tags| {
+            let separator = if tags.contains(';') { ';' } else { ',' };
+            split_list(tags, separator).join(", ")
+        });
+
+        NewPaper {
+            title: owned(CsvField::Title).unwrap_or_default(),
+            authors: owned(CsvField::Authors),
+            journal: owned(CsvField::Journal),
+            year: self.get(CsvField::Year).and_then(|y| y.get(..4)).and_then(|y| y.parse().ok()),
+            item_type: self.get(CsvField::ItemType).and_then(ItemType::parse).unwrap_or_default(),
+            isbn: owned(CsvField::Isbn),
+            publisher: owned(CsvField::Publisher),
+            institution: owned(CsvField::Institution),
+            doi: self.get(CsvField::Doi).and_then(normalize_doi),
+            arxiv_id: self.get(CsvField::ArxivId).and_then(arxiv::parse_id),
+            pmid: owned(CsvField::Pmid),
+            r#abstract: owned(CsvField::Abstract),
+            tags: tags.filter(|t| !t.is_empty()),
+            cite_key: owned(CsvField::CiteKey),
+            ..Default::default()
+        }
+    }
+
+    // Relative paths are taken from the CSV file's folder
+    pub fn linked_file(&self, csv_dir: &Path) -> Option<PathBuf> {
+        let path = PathBuf::from(self.get(CsvField::PdfPath)?);
+        let path = if path.is_absolute() { path } else { csv_dir.join(path) };
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+            .then_some(path)
+    }
+}
@@ -0,0 +1,73 @@
+// src-tauri/src/formats/html.rs
+
+// Zotero and Mendeley store notes as HTML
+
+// Keeps paragraphs, headings, list items and emphasis and drops the rest
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_lowercase();
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        match name {
+            "br" | "br/" => out.push('\n'),
+            "/p" | "/div" | "/h1" | "/h2" | "/h3" | "/h4" | "/h5" | "/h6" | "/blockquote" | "/ul" | "/ol" => {
+                out.push_str("\n\n")
+            }
+            "/li" => out.push('\n'),
+            "li" => out.push_str("- "),
+            "h1" => out.push_str("# "),
+            "h2" => out.push_str("## "),
+            "h3" | "h4" | "h5" | "h6" => out.push_str("### "),
+            "strong" | "/strong" | "b" | "/b" => out.push_str("**"),
+            "em" | "/em" | "i" | "/i" => out.push('*'),
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+
+    let text = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    // Collapse the blank lines left behind by nested blocks
+    let mut markdown = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank = !markdown.is_empty();
+            continue;
+        }
+        if blank {
+            markdown.push('\n');
+            blank = false;
+        }
+        markdown.push_str(line);
+        markdown.push('\n');
+    }
+    markdown.trim_end().to_string()
+}
+
+// The reverse for notes sent back to Zotero: paragraphs and line breaks, everything
+// else as plain text
+pub fn from_markdown(markdown: &str) -> String {
+    markdown
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let escaped = p.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            format!("<p>{}</p>", escaped.replace('\n', "<br/>"))
+        })
+        .collect()
+}
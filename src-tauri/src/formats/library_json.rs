@@ -0,0 +1,242 @@
+// src-tauri/src/formats/library_json.rs
+
+// The whole library as one JSON document, for scripts and for moving to another machine
+// without the SQLite file. PDFs are not included: papers keep their pdf_path and file_hash,
+// so files copied over separately can be found again with relink.
+
+use crate::db::custom_fields::FieldType;
+use crate::db::{self, FileMode, ItemType, Paper, Priority, ReadingStatus};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+
+pub const FORMAT: &str = "paper-master-library";
+// Bump when a change would make older readers misread a document
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct LibraryDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    // Every tag, including ones no paper carries
+    pub tags: Vec<String>,
+    // Parents come before their children
+    pub collections: Vec<CollectionRecord>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomFieldRecord>,
+    pub papers: Vec<PaperRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CustomFieldRecord {
+    pub name: String,
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionRecord {
+    // Only meaningful within the document, for parent_id and PaperRecord.collection_ids
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PaperRecord {
+    pub title: String,
+    #[serde(default)]
+    pub authors: Option<String>,
+    #[serde(default)]
+    pub journal: Option<String>,
+    #[serde(default)]
+    pub year: Option<i64>,
+    #[serde(default)]
+    pub item_type: ItemType,
+    #[serde(default)]
+    pub isbn: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub institution: Option<String>,
+    #[serde(default)]
+    pub doi: Option<String>,
+    #[serde(default)]
+    pub arxiv_id: Option<String>,
+    #[serde(default)]
+    pub pmid: Option<String>,
+    #[serde(default)]
+    pub r#abstract: Option<String>,
+    #[serde(default)]
+    pub keywords: Option<String>,
+    #[serde(default)]
+    pub cite_key: Option<String>,
+    // Markdown
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub collection_ids: Vec<i64>,
+    #[serde(default)]
+    pub reading_status: ReadingStatus,
+    #[serde(default)]
+    pub last_read_page: Option<i64>,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub rating: Option<i64>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub pdf_path: String,
+    #[serde(default)]
+    pub file_hash: Option<String>,
+    #[serde(default)]
+    pub file_mode: FileMode,
+    #[serde(default)]
+    pub annotations: Vec<AnnotationRecord>,
+    // Custom field name -> value
+    #[serde(default)]
+    pub custom_values: BTreeMap<String, String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AnnotationRecord {
+    pub page: i64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub selected_text: Option<String>,
+    pub color: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+// Papers in the Trash are left out
+pub async fn export(pool: &SqlitePool) -> Result<LibraryDocument, String> {
+    let tags = db::tags::get_tags(pool).await?.into_iter().map(|t| t.name).collect();
+
+    let rows = db::collections::get_collections(pool)
+        .await?
+        .into_iter()
+        .map(|c| (c.id, c.name, c.parent_id))
+        .collect();
+    let collections = crate::import::parents_first(rows)
+        .into_iter()
+        .map(|c| CollectionRecord { id: c.id, name: c.name, parent_id: c.parent_id })
+        .collect();
+
+    let mut memberships: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (paper_id, collection_id) in db::collections::get_memberships(pool).await? {
+        memberships.entry(paper_id).or_default().push(collection_id);
+    }
+
+    let fields = db::custom_fields::get_fields(pool).await?;
+    let field_names: HashMap<i64, String> = fields.iter().map(|f| (f.id, f.name.clone())).collect();
+    let mut custom_values: HashMap<i64, BTreeMap<String, String>> = HashMap::new();
+    for (paper_id, field_id, value) in db::custom_fields::get_all_values(pool).await? {
+        if let Some(name) = field_names.get(&field_id) {
+            custom_values.entry(paper_id).or_default().insert(name.clone(), value);
+        }
+    }
+    let custom_fields = fields
+        .into_iter()
+        .map(|f| CustomFieldRecord { name: f.name, field_type: f.field_type, options: f.options })
+        .collect();
+
+    let mut papers = db::get_all_papers(pool).await?;
+    // Oldest first, so importing recreates them in the order they were added
+    papers.sort_by_key(|p| p.id);
+    let mut records = Vec::with_capacity(papers.len());
+    for paper in papers {
+        let annotations = db::annotations::get_annotations(pool, paper.id)
+            .await?
+            .into_iter()
+            .map(|a| AnnotationRecord {
+                page: a.page,
+                x: a.x,
+                y: a.y,
+                width: a.width,
+                height: a.height,
+                selected_text: a.selected_text,
+                color: a.color,
+                comment: a.comment,
+            })
+            .collect();
+        let collection_ids = memberships.remove(&paper.id).unwrap_or_default();
+        let values = custom_values.remove(&paper.id).unwrap_or_default();
+        records.push(to_record(paper, collection_ids, annotations, values));
+    }
+
+    Ok(LibraryDocument {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tags,
+        collections,
+        custom_fields,
+        papers: records,
+    })
+}
+
+fn to_record(
+    paper: Paper,
+    collection_ids: Vec<i64>,
+    annotations: Vec<AnnotationRecord>,
+    custom_values: BTreeMap<String, String>,
+) -> PaperRecord {
+    PaperRecord {
+        tags: db::tags::split_tags(paper.tags.as_deref().unwrap_or_default()),
+        title: paper.title,
+        authors: paper.authors,
+        journal: paper.journal,
+        year: paper.year,
+        item_type: paper.item_type,
+        isbn: paper.isbn,
+        publisher: paper.publisher,
+        institution: paper.institution,
+        doi: paper.doi,
+        arxiv_id: paper.arxiv_id,
+        pmid: paper.pmid,
+        r#abstract: paper.r#abstract,
+        keywords: paper.keywords,
+        cite_key: paper.cite_key,
+        notes: paper.notes.filter(|n| !n.is_empty()),
+        collection_ids,
+        reading_status: paper.reading_status,
+        last_read_page: paper.last_read_page,
+        starred: paper.starred,
+        rating: paper.rating,
+        priority: paper.priority,
+        pdf_path: paper.pdf_path,
+        file_hash: paper.file_hash,
+        file_mode: paper.file_mode,
+        annotations,
+        custom_values,
+        created_at: paper.created_at,
+    }
+}
+
+pub fn parse(json: &str) -> Result<LibraryDocument, String> {
+    // Checked before the full parse so a wrong file gets a clear message
+    let header: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Not a JSON file: {}", e))?;
+    if header.get("format").and_then(|f| f.as_str()) != Some(FORMAT) {
+        return Err("Not a library export".to_string());
+    }
+    let version = header.get("version").and_then(|v| v.as_u64()).unwrap_or_default();
+    if version > VERSION as u64 {
+        return Err(format!(
+            "The library export is version {}; this version of the app reads up to version {}",
+            version, VERSION
+        ));
+    }
+
+    serde_json::from_value(header).map_err(|e| format!("Invalid library export: {}", e))
+}
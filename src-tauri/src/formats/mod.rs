@@ -0,0 +1,10 @@
+// src-tauri/src/formats/mod.rs
+
+pub mod annotations;
+pub mod bibtex;
+pub mod csl;
+pub mod csv;
+pub mod html;
+pub mod library_json;
+pub mod ris;
+pub mod zotero;
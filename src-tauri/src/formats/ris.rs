@@ -0,0 +1,228 @@
+// src-tauri/src/formats/ris.rs
+
+use crate::arxiv;
+use crate::crossref::normalize_doi;
+use crate::db::{ItemType, NewPaper, Paper};
+use crate::formats::bibtex::authors_to_display;
+use std::path::{Path, PathBuf};
+
+pub struct RisRecord {
+    // Tags in file order; repeatable tags such as AU and KW appear once per line
+    pub fields: Vec<(String, String)>,
+}
+
+impl RisRecord {
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        self.get_all(tag).into_iter().next()
+    }
+
+    pub fn get_all(&self, tag: &str) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(t, v)| t == tag && !v.is_empty())
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
+    fn first_of(&self, tags: &[&str]) -> Option<&str> {
+        tags.iter().find_map(|tag| self.get(tag))
+    }
+
+    // ID if present, otherwise the title, for error messages
+    pub fn label(&self) -> String {
+        self.first_of(&["ID", "TI", "T1"])
+            .unwrap_or("untitled record")
+            .to_string()
+    }
+
+    pub fn to_new_paper(&self) -> NewPaper {
+        let authors: Vec<String> = ["AU", "A1", "A2"]
+            .iter()
+            .flat_map(|tag| self.get_all(tag))
+            .map(authors_to_display)
+            .collect();
+        let keywords = self.get_all("KW");
+        let arxiv_id = self
+            .get_all("UR")
+            .into_iter()
+            .filter(|url| url.contains("arxiv.org/"))
+            .find_map(arxiv::parse_id);
+
+        let item_type = match self.get("TY").unwrap_or_default() {
+            "BOOK" | "CHAP" | "EBOOK" | "ECHAP" | "EDBOOK" => ItemType::Book,
+            "THES" => ItemType::Thesis,
+            "RPRT" => ItemType::Report,
+            "UNPB" | "MANSCPT" => ItemType::Preprint,
+            "GEN" | "ELEC" if arxiv_id.is_some() => ItemType::Preprint,
+            _ => ItemType::Article,
+        };
+        // PB is the publisher of a book but the university of a thesis or the issuer of a report
+        let (publisher, institution) = match item_type {
+            ItemType::Thesis | ItemType::Report => (None, self.get("PB")),
+            _ => (self.get("PB"), None),
+        };
+
+        NewPaper {
+            title: self.first_of(&["TI", "T1", "CT"]).unwrap_or_default().to_string(),
+            authors: (!authors.is_empty()).then(|| authors.join(", ")),
+            journal: self
+                .first_of(&["JO", "JF", "T2", "JA", "J2", "BT"])
+                .map(str::to_string),
+            // PY is "2017" or "2017/06/12/"
+            year: self
+                .first_of(&["PY", "Y1", "DA"])
+                .and_then(|y| y.get(..4))
+                .and_then(|y| y.parse().ok()),
+            item_type,
+            // SN is the ISSN of journal articles
+            isbn: self.get("SN").filter(|_| item_type == ItemType::Book).map(str::to_string),
+            publisher: publisher.map(str::to_string),
+            institution: institution.map(str::to_string),
+            doi: self.get("DO").and_then(normalize_doi),
+            arxiv_id,
+            r#abstract: self.first_of(&["AB", "N2"]).map(str::to_string),
+            tags: (!keywords.is_empty()).then(|| keywords.join(", ")),
+            ..Default::default()
+        }
+    }
+
+    // L1 holds attached files, either as paths or file:// URLs
+    pub fn linked_files(&self, ris_dir: &Path) -> Vec<PathBuf> {
+        self.get_all("L1")
+            .into_iter()
+            .flat_map(|v| v.split(';'))
+            .map(|v| v.trim())
+            .map(|v| v.strip_prefix("file://").unwrap_or(v))
+            .filter(|v| v.to_lowercase().ends_with(".pdf"))
+            .map(|v| {
+                let path = PathBuf::from(v);
+                if path.is_absolute() {
+                    path
+                } else {
+                    ris_dir.join(path)
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn parse(input: &str) -> Vec<RisRecord> {
+    let mut records = Vec::new();
+    let mut current: Option<RisRecord> = None;
+
+    for line in input.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim_end();
+        let Some((tag, value)) = split_tag(line) else {
+            // Continuation of a wrapped value
+            if let Some((_, last)) = current.as_mut().and_then(|r| r.fields.last_mut()) {
+                if !line.trim().is_empty() {
+                    last.push(' ');
+                    last.push_str(line.trim());
+                }
+            }
+            continue;
+        };
+
+        match tag {
+            "TY" => {
+                current = Some(RisRecord {
+                    fields: vec![("TY".to_string(), value.to_string())],
+                });
+            }
+            "ER" => {
+                if let Some(record) = current.take() {
+                    records.push(record);
+                }
+            }
+            _ => {
+                if let Some(record) = current.as_mut() {
+                    record.fields.push((tag.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+
+    // Tolerate a missing trailing ER
+    if let Some(record) = current {
+        records.push(record);
+    }
+
+    records
+}
+
+// "AU  - Smith, J." -> ("AU", "Smith, J.")
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let tag = line.get(..2)?;
+    if !tag.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+        return None;
+    }
+    let rest = line[2..].trim_start_matches(' ');
+    let value = rest.strip_prefix('-')?;
+    Some((tag, value.trim()))
+}
+
+pub fn export(papers: &[Paper]) -> String {
+    let mut out = String::new();
+
+    for paper in papers {
+        let ty = match paper.item_type {
+            ItemType::Book if paper.journal.is_some() => "CHAP",
+            ItemType::Book => "BOOK",
+            ItemType::Thesis => "THES",
+            ItemType::Report => "RPRT",
+            ItemType::Preprint => "UNPB",
+            ItemType::Article if paper.journal.is_some() => "JOUR",
+            ItemType::Article => "GEN",
+        };
+        push_tag(&mut out, "TY", ty);
+        push_tag(&mut out, "TI", &paper.title);
+        for author in paper.authors.iter().flat_map(|a| a.split(", ")) {
+            push_tag(&mut out, "AU", &surname_first(author));
+        }
+        if let Some(journal) = &paper.journal {
+            push_tag(&mut out, if ty == "CHAP" { "T2" } else { "JO" }, journal);
+        }
+        if let Some(publisher) = paper.publisher.as_ref().or(paper.institution.as_ref()) {
+            push_tag(&mut out, "PB", publisher);
+        }
+        if let Some(isbn) = &paper.isbn {
+            push_tag(&mut out, "SN", isbn);
+        }
+        if let Some(year) = paper.year {
+            push_tag(&mut out, "PY", &year.to_string());
+        }
+        if let Some(doi) = &paper.doi {
+            push_tag(&mut out, "DO", doi);
+        }
+        if let Some(r#abstract) = &paper.r#abstract {
+            push_tag(&mut out, "AB", r#abstract);
+        }
+        for keyword in paper.tags.iter().flat_map(|t| t.split(',')) {
+            push_tag(&mut out, "KW", keyword.trim());
+        }
+        if let Some(arxiv_id) = &paper.arxiv_id {
+            push_tag(&mut out, "UR", &format!("https://arxiv.org/abs/{}", arxiv_id));
+        }
+        if !paper.pdf_path.is_empty() {
+            push_tag(&mut out, "L1", &paper.pdf_path);
+        }
+        push_tag(&mut out, "ER", "");
+        out.push_str("\r\n");
+    }
+
+    out
+}
+
+fn push_tag(out: &mut String, tag: &str, value: &str) {
+    // Values must stay on one line
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    out.push_str(&format!("{}  - {}\r\n", tag, value).replace(" \r\n", "\r\n"));
+}
+
+// "Ashish Vaswani" -> "Vaswani, Ashish"
+fn surname_first(name: &str) -> String {
+    match name.trim().rsplit_once(' ') {
+        Some((given, surname)) => format!("{}, {}", surname, given),
+        None => name.trim().to_string(),
+    }
+}
@@ -0,0 +1,236 @@
+// src-tauri/src/formats/zotero.rs
+
+// Reads a Zotero 5+ data directory's zotero.sqlite. Zotero keeps the database locked
+// while it runs, so we work on a copy.
+
+use crate::arxiv;
+use crate::crossref::normalize_doi;
+use crate::db::NewPaper;
+use crate::formats::html;
+use crate::import::{self, ExternalItem, ExternalLibrary};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub async fn read_library(db_path: &Path) -> Result<ExternalLibrary, String> {
+    let data_dir = db_path.parent().unwrap_or(Path::new("."));
+
+    let copy = std::env::temp_dir().join(format!("paper-master-zotero-{}.sqlite", std::process::id()));
+    tokio::fs::copy(db_path, &copy)
+        .await
+        .map_err(|e| format!("Failed to read Zotero database: {}", e))?;
+
+    let options = SqliteConnectOptions::new().filename(&copy).read_only(true);
+    let result = match SqlitePoolOptions::new().max_connections(1).connect_with(options).await {
+        Ok(pool) => {
+            let library = read_tables(&pool, data_dir).await;
+            pool.close().await;
+            library
+        }
+        Err(e) => Err(format!("Not a Zotero database: {}", e)),
+    };
+
+    let _ = tokio::fs::remove_file(&copy).await;
+    result
+}
+
+async fn read_tables(pool: &SqlitePool, data_dir: &Path) -> Result<ExternalLibrary, String> {
+    let err = |e: sqlx::Error| format!("Failed to read Zotero database: {}", e);
+
+    let item_rows: Vec<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT i.itemID, i.key FROM items i
+        JOIN itemTypes t ON t.itemTypeID = i.itemTypeID
+        WHERE t.typeName NOT IN ('attachment', 'note', 'annotation')
+          AND i.itemID NOT IN (SELECT itemID FROM deletedItems)
+        ORDER BY i.itemID
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let mut fields: HashMap<i64, HashMap<String, String>> = HashMap::new();
+    let field_rows: Vec<(i64, String, String)> = sqlx::query_as(
+        r#"
+        SELECT d.itemID, f.fieldName, CAST(v.value AS TEXT) FROM itemData d
+        JOIN fields f ON f.fieldID = d.fieldID
+        JOIN itemDataValues v ON v.valueID = d.valueID
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (item_id, field, value) in field_rows {
+        fields.entry(item_id).or_default().insert(field, value);
+    }
+
+    // Authors if the item has any, otherwise whoever else is credited (editors, ...)
+    let mut creators: HashMap<i64, Vec<(bool, String)>> = HashMap::new();
+    let creator_rows: Vec<(i64, String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT ic.itemID, ct.creatorType, c.firstName, c.lastName FROM itemCreators ic
+        JOIN creators c ON c.creatorID = ic.creatorID
+        JOIN creatorTypes ct ON ct.creatorTypeID = ic.creatorTypeID
+        ORDER BY ic.itemID, ic.orderIndex
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (item_id, creator_type, first, last) in creator_rows {
+        let name = format!("{} {}", first.trim(), last.trim()).trim().to_string();
+        creators.entry(item_id).or_default().push((creator_type == "author", name));
+    }
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    let tag_rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT it.itemID, t.name FROM itemTags it JOIN tags t ON t.tagID = it.tagID",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (item_id, name) in tag_rows {
+        // Our tag lists are comma separated
+        tags.entry(item_id).or_default().push(name.replace(',', " "));
+    }
+
+    let mut notes: HashMap<i64, Vec<String>> = HashMap::new();
+    let note_rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT parentItemID, note FROM itemNotes WHERE parentItemID IS NOT NULL ORDER BY itemID",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (item_id, note) in note_rows {
+        let markdown = html::to_markdown(&note);
+        if !markdown.is_empty() {
+            notes.entry(item_id).or_default().push(markdown);
+        }
+    }
+
+    let mut pdfs: HashMap<i64, Vec<PathBuf>> = HashMap::new();
+    let attachment_rows: Vec<(i64, String, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT a.parentItemID, i.key, a.contentType, a.path FROM itemAttachments a
+        JOIN items i ON i.itemID = a.itemID
+        WHERE a.parentItemID IS NOT NULL
+          AND a.itemID NOT IN (SELECT itemID FROM deletedItems)
+        ORDER BY a.itemID
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (item_id, key, content_type, path) in attachment_rows {
+        let Some(path) = path.and_then(|p| attachment_path(data_dir, &key, &p)) else {
+            continue;
+        };
+        let is_pdf = content_type.as_deref() == Some("application/pdf")
+            || path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+        if is_pdf && path.is_file() {
+            pdfs.entry(item_id).or_default().push(path);
+        }
+    }
+
+    let mut item_collections: HashMap<i64, Vec<i64>> = HashMap::new();
+    let membership_rows: Vec<(i64, i64)> = sqlx::query_as("SELECT collectionID, itemID FROM collectionItems")
+        .fetch_all(pool)
+        .await
+        .map_err(err)?;
+    for (collection_id, item_id) in membership_rows {
+        item_collections.entry(item_id).or_default().push(collection_id);
+    }
+
+    let collection_rows: Vec<(i64, String, Option<i64>)> = sqlx::query_as(
+        "SELECT collectionID, collectionName, parentCollectionID FROM collections ORDER BY collectionID",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let items = item_rows
+        .into_iter()
+        .map(|(item_id, key)| {
+            let fields = fields.remove(&item_id).unwrap_or_default();
+            let creators = creators.remove(&item_id).unwrap_or_default();
+            let tags = tags.remove(&item_id).unwrap_or_default();
+            ExternalItem {
+                // Zotero's 8 character item key
+                key,
+                paper: to_new_paper(&fields, creators, tags),
+                pdfs: pdfs.remove(&item_id).unwrap_or_default(),
+                notes: notes.remove(&item_id).unwrap_or_default(),
+                collection_ids: item_collections.remove(&item_id).unwrap_or_default(),
+                annotations: Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok(ExternalLibrary {
+        items,
+        collections: import::parents_first(collection_rows),
+    })
+}
+
+pub fn to_new_paper(fields: &HashMap<String, String>, creators: Vec<(bool, String)>, tags: Vec<String>) -> NewPaper {
+    let get = |name: &str| fields.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+
+    let has_authors = creators.iter().any(|(is_author, _)| *is_author);
+    let authors: Vec<String> = creators
+        .into_iter()
+        .filter(|(is_author, name)| (*is_author || !has_authors) && !name.is_empty())
+        .map(|(_, name)| name)
+        .collect();
+
+    // arXiv preprints keep their id in the URL or in Extra as "arXiv: 1706.03762"
+    let arxiv_id = get("url")
+        .filter(|url| url.contains("arxiv.org/"))
+        .and_then(arxiv::parse_id)
+        .or_else(|| {
+            get("extra")?
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("arXiv:"))
+                .and_then(arxiv::parse_id)
+        });
+    // Zotero's PubMed translator writes "PMID: 31452104" into Extra
+    let pmid = get("extra").and_then(|extra| {
+        extra
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("PMID:"))
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    });
+
+    NewPaper {
+        title: get("title").unwrap_or_default().to_string(),
+        authors: (!authors.is_empty()).then(|| authors.join(", ")),
+        journal: ["publicationTitle", "proceedingsTitle", "bookTitle", "websiteTitle"]
+            .iter()
+            .find_map(|name| get(name))
+            .map(str::to_string),
+        // Zotero stores dates as "2017-06-12 June 12, 2017"
+        year: get("date")
+            .and_then(|d| d.get(..4))
+            .and_then(|y| y.parse().ok())
+            .filter(|y| *y > 0),
+        doi: get("DOI").and_then(normalize_doi),
+        arxiv_id,
+        pmid,
+        r#abstract: get("abstractNote").map(str::to_string),
+        tags: (!tags.is_empty()).then(|| tags.join(", ")),
+        ..Default::default()
+    }
+}
+
+// "storage:paper.pdf" lives in storage/<attachment key>/; linked files are stored as
+// absolute paths. Paths relative to Zotero's linked attachment base directory can't be
+// resolved without Zotero's preferences and are skipped.
+fn attachment_path(data_dir: &Path, key: &str, path: &str) -> Option<PathBuf> {
+    if let Some(name) = path.strip_prefix("storage:") {
+        return Some(data_dir.join("storage").join(key).join(name));
+    }
+    let path = PathBuf::from(path);
+    path.is_absolute().then_some(path)
+}
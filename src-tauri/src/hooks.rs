@@ -0,0 +1,188 @@
+// src-tauri/src/hooks.rs
+
+// User commands run on library events, for automations such as posting new papers to a
+// chat channel. The command gets the event as JSON on stdin,
+//
+//     {"event": "paper_imported", "paper_id": 12, "paper": {...}, "notes": null}
+//
+// with "paper" null once the paper is deleted and "notes" set for notes_saved, and the same
+// in the PAPER_MASTER_EVENT and PAPER_MASTER_PAPER_ID environment variables. Hooks run in
+// the background, a few at a time; failures are only logged, so a broken hook never gets in
+// the way of the change that set it off.
+
+use crate::config::{self, Hook, HookEvent};
+use crate::db::{self, Paper};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::process::Stdio;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+const MAX_RUNNING: usize = 4;
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: HookEvent,
+    paper_id: i64,
+    paper: Option<&'a Paper>,
+    notes: Option<&'a str>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HookOutput {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+// Managed as its own state so events.rs can reach it; the configured hooks are kept here
+// rather than read from the config file on every event
+pub struct Hooks {
+    hooks: RwLock<Vec<Hook>>,
+    running: Arc<Semaphore>,
+}
+
+impl Hooks {
+    pub fn new(handle: &AppHandle) -> Self {
+        Hooks {
+            hooks: RwLock::new(config::load(handle).hooks),
+            running: Arc::new(Semaphore::new(MAX_RUNNING)),
+        }
+    }
+
+    pub fn set(&self, hooks: Vec<Hook>) {
+        if let Ok(mut current) = self.hooks.write() {
+            *current = hooks;
+        }
+    }
+
+    fn commands_for(&self, event: HookEvent) -> Vec<String> {
+        self.hooks
+            .read()
+            .map(|hooks| {
+                hooks
+                    .iter()
+                    .filter(|hook| hook.enabled && hook.event == event && !hook.command.trim().is_empty())
+                    .map(|hook| hook.command.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn paper_event(handle: &AppHandle, event: HookEvent, paper_id: i64, paper: Option<&Paper>) {
+    fire(handle, event, HookPayload { event, paper_id, paper, notes: None });
+}
+
+// Looks the paper up only when a hook wants it
+pub async fn notes_saved(handle: &AppHandle, pool: &SqlitePool, paper_id: i64, markdown: &str) {
+    let has_hooks = handle
+        .try_state::<Hooks>()
+        .is_some_and(|hooks| !hooks.commands_for(HookEvent::NotesSaved).is_empty());
+    if !has_hooks {
+        return;
+    }
+    let paper = db::get_paper(pool, paper_id).await.ok();
+    let payload = HookPayload { event: HookEvent::NotesSaved, paper_id, paper: paper.as_ref(), notes: Some(markdown) };
+    fire(handle, HookEvent::NotesSaved, payload);
+}
+
+fn fire(handle: &AppHandle, event: HookEvent, payload: HookPayload) {
+    // None until setup has managed the hooks
+    let Some(hooks) = handle.try_state::<Hooks>() else {
+        return;
+    };
+    let commands = hooks.commands_for(event);
+    if commands.is_empty() {
+        return;
+    }
+    let paper_id = payload.paper_id;
+    let input = match serde_json::to_string(&payload) {
+        Ok(input) => input,
+        Err(e) => return tracing::warn!("Failed to serialize {:?} for hooks: {}", event, e),
+    };
+
+    for command in commands {
+        let running = hooks.running.clone();
+        let input = input.clone();
+        tauri::async_runtime::spawn(async move {
+            let Ok(_permit) = running.acquire().await else {
+                return;
+            };
+            match run(&command, event, paper_id, &input).await {
+                Ok(output) if output.exit_code == Some(0) => {}
+                Ok(output) => tracing::warn!(
+                    "Hook \"{}\" exited with {:?}: {}",
+                    command,
+                    output.exit_code,
+                    output.stderr.trim()
+                ),
+                Err(e) => tracing::warn!("Hook \"{}\" failed: {}", command, e),
+            }
+        });
+    }
+}
+
+// Runs one hook right away with the given paper, for trying it out from the settings
+pub async fn test(hook: &Hook, paper: &Paper) -> Result<HookOutput, String> {
+    let notes = (hook.event == HookEvent::NotesSaved).then_some("");
+    let paper = (hook.event != HookEvent::PaperDeleted).then_some(paper);
+    let payload = HookPayload { event: hook.event, paper_id: paper.map_or(0, |p| p.id), paper, notes };
+    let input = serde_json::to_string(&payload).map_err(|e| e.to_string())?;
+    run(&hook.command, hook.event, payload.paper_id, &input).await
+}
+
+async fn run(command: &str, event: HookEvent, paper_id: i64, input: &str) -> Result<HookOutput, String> {
+    let event_name = serde_json::to_value(event)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut child = shell(command)
+        .env("PAPER_MASTER_EVENT", event_name)
+        .env("PAPER_MASTER_PAPER_ID", paper_id.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start: {}", e))?;
+    // Written alongside the wait, so a hook that never reads its input still times out;
+    // hooks that ignore it close it early
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        tauri::async_runtime::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+
+    let output = tokio::time::timeout(TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("Still running after {} seconds; stopped", TIMEOUT.as_secs()))?
+        .map_err(|e| e.to_string())?;
+    Ok(HookOutput {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+fn shell(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    }
+    #[cfg(not(windows))]
+    {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
@@ -0,0 +1,833 @@
+// src-tauri/src/import.rs
+
+use crate::config;
+use crate::db::annotations::NewAnnotation;
+use crate::db::{self, FileMode, NewPaper};
+use crate::events;
+use crate::formats::bibtex;
+use crate::formats::library_json::{LibraryDocument, PaperRecord};
+use crate::keywords;
+use crate::pdf;
+use crate::references;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Serialize, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+    // Stopped by cancel_operation before every file was done
+    pub cancelled: bool,
+}
+
+// What to do when an imported file's SHA-256 matches a paper already in the library
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    #[default]
+    Reject,
+    Allow,
+}
+
+// How import_library_json treats a paper that is already in the library, matched by DOI,
+// arXiv id, PMID or file hash
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    // Leave the library's paper alone
+    #[default]
+    Skip,
+    // Take metadata, notes and reading state from the document; tags, collections and
+    // annotations are added to the paper's own
+    Update,
+    // Import it again as a separate paper
+    Duplicate,
+}
+
+pub struct ImportedPaper {
+    pub paper: db::Paper,
+    // Set when the file was imported anyway under DuplicatePolicy::Allow
+    pub duplicate_of: Option<db::Paper>,
+}
+
+// A record parsed from a reference file (BibTeX, RIS, ...) waiting to be imported
+pub struct ImportRecord {
+    // Shown in error messages, e.g. the citation key
+    pub label: String,
+    pub paper: NewPaper,
+    pub file: Option<PathBuf>,
+}
+
+// An entry read from another reference manager's library (Zotero, Mendeley)
+pub struct ExternalItem {
+    // The other application's id for the entry, for error messages
+    pub key: String,
+    pub paper: NewPaper,
+    // Attached PDFs that exist on disk, in the other application's order
+    pub pdfs: Vec<PathBuf>,
+    // Notes converted to Markdown
+    pub notes: Vec<String>,
+    pub collection_ids: Vec<i64>,
+    // Highlights on the first PDF; paper_id is filled in once the paper is inserted
+    pub annotations: Vec<NewAnnotation>,
+}
+
+pub struct ExternalCollection {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+pub struct ExternalLibrary {
+    pub items: Vec<ExternalItem>,
+    // Parents always come before their children
+    pub collections: Vec<ExternalCollection>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ImportProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+pub fn papers_dir(handle: &AppHandle) -> Result<PathBuf, String> {
+    let papers_dir = match config::load(handle).papers_dir {
+        Some(dir) => dir,
+        None => handle
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| format!("Path resolve error: {}", e))?
+            .join("papers"),
+    };
+    fs::create_dir_all(&papers_dir).map_err(|e| e.to_string())?;
+    Ok(papers_dir)
+}
+
+// Avoid overwriting existing files by appending _1, _2, ...
+pub fn unique_destination(papers_dir: &Path, file_name: &str) -> PathBuf {
+    let mut counter = 1;
+    let mut final_dest = papers_dir.join(file_name);
+    while final_dest.exists() {
+        let new_name = format!("{}_{}.pdf", file_name.trim_end_matches(".pdf"), counter);
+        final_dest = papers_dir.join(new_name);
+        counter += 1;
+    }
+    final_dest
+}
+
+// Copies a PDF into app storage and registers it in the library. With FileMode::Linked
+// on `known`, the file is registered where it is instead.
+pub async fn import_file(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    source: &Path,
+    known: NewPaper,
+    on_duplicate: DuplicatePolicy,
+) -> Result<ImportedPaper, String> {
+    if known.file_mode == FileMode::Linked {
+        return link_file(pool, source, known, on_duplicate).await;
+    }
+    let papers_dir = papers_dir(handle)?;
+
+    // Extract filename
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid file name")?;
+
+    let final_dest = unique_destination(&papers_dir, file_name);
+
+    // Copy file into app storage
+    fs::copy(source, &final_dest).map_err(|e| format!("Copy failed: {}", e))?;
+
+    match register_file(pool, &final_dest, known, on_duplicate).await {
+        Ok(paper) => Ok(paper),
+        Err(e) => {
+            let _ = fs::remove_file(&final_dest);
+            Err(e)
+        }
+    }
+}
+
+async fn link_file(
+    pool: &SqlitePool,
+    source: &Path,
+    known: NewPaper,
+    on_duplicate: DuplicatePolicy,
+) -> Result<ImportedPaper, String> {
+    let source = std::path::absolute(source).map_err(|e| format!("Invalid file path: {}", e))?;
+    // A synced file that changes on disk must not turn into a second paper
+    if let Some(existing) = db::find_paper_by_path(pool, source.to_str().ok_or("Invalid file path")?).await? {
+        return Err(format!("Already linked: {}", existing.title));
+    }
+
+    register_file(pool, &source, known, on_duplicate).await
+}
+
+// Inserts a PDF that already lives in app storage, or a linked file where it lies. Fields
+// set on `known` win over whatever can be read out of the file itself.
+pub async fn register_file(
+    pool: &SqlitePool,
+    stored_path: &Path,
+    known: NewPaper,
+    on_duplicate: DuplicatePolicy,
+) -> Result<ImportedPaper, String> {
+    tracing::debug!("Registering {}", stored_path.display());
+    let file_hash = match known.file_hash.clone() {
+        Some(hash) => hash,
+        None => file_sha256_async(stored_path).await?,
+    };
+
+    let duplicate_of = db::find_paper_by_hash(pool, &file_hash).await?;
+    if let (Some(existing), DuplicatePolicy::Reject) = (&duplicate_of, on_duplicate) {
+        return Err(format!("Already in library: {}", existing.title));
+    }
+
+    // Pull title/authors/year out of the PDF, falling back to the file name
+    let metadata = pdf::extract_metadata_async(stored_path).await.unwrap_or_else(|e| {
+        tracing::warn!("{}: {}", stored_path.display(), e);
+        pdf::PdfMetadata::default()
+    });
+
+    let stats = pdf::file_stats_async(stored_path).await?;
+
+    let title = Some(known.title)
+        .filter(|t| !t.trim().is_empty())
+        .or(metadata.title)
+        .unwrap_or_else(|| {
+            stored_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string()
+        });
+
+    let new_paper = NewPaper {
+        title,
+        authors: known.authors.or(metadata.authors),
+        year: known.year.or(metadata.year),
+        r#abstract: known.r#abstract.or(metadata.r#abstract),
+        keywords: known.keywords.or(metadata.keywords),
+        pdf_path: stored_path.to_str().ok_or("Invalid file path")?.to_string(),
+        file_hash: Some(file_hash),
+        page_count: stats.page_count,
+        file_size: Some(stats.file_size),
+        pdf_version: stats.pdf_version,
+        ..known
+    };
+
+    // Insert metadata into database
+    let paper_id = db::insert_paper(pool, &new_paper)
+        .await
+        .map_err(|e| format!("Database insert failed: {}", e))?;
+
+    // Index full text; a PDF without extractable text is still searchable by title
+    let pages = pdf::extract_pages_async(stored_path).await.unwrap_or_else(|e| {
+        tracing::warn!("{}: {}", stored_path.display(), e);
+        Vec::new()
+    });
+    db::index_paper_text(pool, paper_id, &new_paper.title, &pages).await?;
+
+    let content = pages.join("\n\n");
+    let extracted = references::extract_references(&content);
+    if !extracted.is_empty() {
+        db::references::save_extracted_references(pool, paper_id, &extracted).await?;
+    }
+
+    let text = db::PaperText {
+        title: new_paper.title.clone(),
+        r#abstract: new_paper.r#abstract.clone(),
+        content: Some(content),
+    };
+    keywords::update_keywords(pool, paper_id, &text).await?;
+
+    assign_cite_key(pool, paper_id, new_paper.cite_key.as_deref()).await?;
+    if let Err(e) = import_pdf_annotations(pool, paper_id, stored_path).await {
+        tracing::warn!("{}: {}", stored_path.display(), e);
+    }
+    match pdf::extract_outline_async(stored_path).await {
+        Ok(outline) if !outline.is_empty() => db::outline::save_outline(pool, paper_id, &outline).await?,
+        Ok(_) => {}
+        Err(e) => tracing::warn!("{}: {}", stored_path.display(), e),
+    }
+    tracing::info!("Imported {} as paper {}", stored_path.display(), paper_id);
+
+    Ok(ImportedPaper {
+        paper: db::get_paper(pool, paper_id).await?,
+        duplicate_of,
+    })
+}
+
+// Copies highlights and notes made in other viewers out of the PDF, skipping any the paper
+// already has in the same place. Returns the ones added.
+pub async fn import_pdf_annotations(
+    pool: &SqlitePool,
+    paper_id: i64,
+    path: &Path,
+) -> Result<Vec<db::annotations::Annotation>, String> {
+    let found = pdf::extract_annotations_async(path).await?;
+    if found.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let current = db::annotations::get_annotations(pool, paper_id).await?;
+    let mut added = Vec::new();
+    for annotation in found {
+        // Coordinates come back from the database as stored, but allow for rounding
+        let present = current.iter().any(|a| {
+            a.page == annotation.page
+                && (a.x - annotation.x).abs() < 0.5
+                && (a.y - annotation.y).abs() < 0.5
+                && (a.width - annotation.width).abs() < 0.5
+                && (a.height - annotation.height).abs() < 0.5
+        });
+        if present {
+            continue;
+        }
+        let annotation = NewAnnotation {
+            paper_id,
+            page: annotation.page,
+            x: annotation.x,
+            y: annotation.y,
+            width: annotation.width,
+            height: annotation.height,
+            selected_text: None,
+            color: annotation.color,
+            comment: annotation.comment,
+        };
+        added.push(db::annotations::add_annotation(pool, &annotation).await?);
+    }
+
+    if !added.is_empty() {
+        tracing::info!("Imported {} annotations from {}", added.len(), path.display());
+    }
+    Ok(added)
+}
+
+// Collects PDFs under `dir`, sorted so progress is reported in a predictable order
+pub fn find_pdfs(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let mut walker = walkdir::WalkDir::new(dir).follow_links(true);
+    if !recursive {
+        walker = walker.max_depth(1);
+    }
+
+    let mut pdfs: Vec<PathBuf> = walker
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")))
+        .collect();
+    pdfs.sort();
+    pdfs
+}
+
+pub fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to hash {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub async fn file_sha256_async(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || file_sha256(&path))
+        .await
+        .map_err(|e| format!("Hashing aborted: {}", e))?
+}
+
+// Returns the paper's citation key, assigning one first if it has none. `preferred` is
+// used when no other paper has it; otherwise the key is built from the metadata.
+pub async fn assign_cite_key(pool: &SqlitePool, paper_id: i64, preferred: Option<&str>) -> Result<String, String> {
+    let paper = db::get_paper(pool, paper_id).await?;
+    if let Some(key) = paper.cite_key {
+        return Ok(key);
+    }
+
+    let generated = bibtex::cite_key(&paper);
+    // A concurrent import can take the key between the lookup and the write
+    for _ in 0..3 {
+        let key = match preferred {
+            Some(key) if !db::get_cite_keys_like(pool, key).await?.iter().any(|k| k == key) => key.to_string(),
+            _ => {
+                let taken: HashSet<String> = db::get_cite_keys_like(pool, &generated).await?.into_iter().collect();
+                bibtex::free_key(&generated, &taken)
+            }
+        };
+        if db::set_cite_key(pool, paper_id, &key).await? {
+            return Ok(key);
+        }
+    }
+    Err(format!("Could not find a free citation key for \"{}\"", paper.title))
+}
+
+// Gives papers imported before citation keys existed one each
+pub async fn backfill_cite_keys(pool: &SqlitePool) -> Result<usize, String> {
+    let papers = db::get_papers_missing_cite_key(pool).await?;
+    for paper in &papers {
+        assign_cite_key(pool, paper.id, None).await?;
+    }
+    Ok(papers.len())
+}
+
+// Fills in hashes for papers imported before duplicate detection existed
+pub async fn backfill_file_hashes(pool: &SqlitePool) -> Result<usize, String> {
+    let mut updated = 0;
+    for (id, pdf_path) in db::get_papers_missing_hash(pool).await? {
+        match file_sha256_async(Path::new(&pdf_path)).await {
+            Ok(hash) => {
+                db::set_file_hash(pool, id, &hash).await?;
+                updated += 1;
+            }
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+    Ok(updated)
+}
+
+// Re-indexes papers added before text was stored per page, so their hits get page numbers
+pub async fn backfill_page_text(pool: &SqlitePool) -> Result<usize, String> {
+    let mut updated = 0;
+    for paper in db::get_papers_missing_pages(pool).await? {
+        let pages = match db::ocr::get_ocr_text(pool, paper.id).await? {
+            Some(ocr) => ocr.content.split(db::ocr::PAGE_BREAK).map(str::to_string).collect(),
+            None => match pdf::extract_pages_async(Path::new(&paper.pdf_path)).await {
+                Ok(pages) => pages,
+                Err(e) => {
+                    tracing::warn!("{}: {}", paper.pdf_path, e);
+                    continue;
+                }
+            },
+        };
+        db::index_paper_text(pool, paper.id, &paper.title, &pages).await?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+// Makes `path`, a PDF already in app storage, the paper's file and indexes its text. Whatever
+// file the paper had before is left where it is.
+pub async fn attach_pdf(pool: &SqlitePool, paper_id: i64, path: &Path) -> Result<(), String> {
+    let paper = db::get_paper(pool, paper_id).await?;
+    let file_hash = file_sha256_async(path).await?;
+    let stats = pdf::file_stats_async(path).await?;
+
+    db::set_pdf_path(pool, paper_id, path.to_str().ok_or("Invalid file path")?).await?;
+    db::set_file_mode(pool, paper_id, FileMode::Stored).await?;
+    db::set_file_hash(pool, paper_id, &file_hash).await?;
+    db::set_file_stats(pool, paper_id, stats.page_count, stats.file_size, stats.pdf_version.as_deref()).await?;
+
+    let pages = pdf::extract_pages_async(path).await.unwrap_or_else(|e| {
+        tracing::warn!("{}: {}", path.display(), e);
+        Vec::new()
+    });
+    db::index_paper_text(pool, paper_id, &paper.title, &pages).await?;
+
+    let content = pages.join("\n\n");
+    if db::references::get_extracted_references(pool, paper_id).await?.is_empty() {
+        let extracted = references::extract_references(&content);
+        if !extracted.is_empty() {
+            db::references::save_extracted_references(pool, paper_id, &extracted).await?;
+        }
+    }
+    keywords::update_keywords(pool, paper_id, &db::get_paper_text(pool, paper_id).await?).await?;
+    Ok(())
+}
+
+// Adds a metadata-only record that has no PDF attached (yet)
+pub async fn add_record(pool: &SqlitePool, known: NewPaper) -> Result<db::Paper, String> {
+    if known.title.trim().is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+
+    let new_paper = NewPaper {
+        pdf_path: String::new(),
+        ..known
+    };
+    let paper_id = db::insert_paper(pool, &new_paper).await?;
+    db::index_paper_text(pool, paper_id, &new_paper.title, &[]).await?;
+    assign_cite_key(pool, paper_id, new_paper.cite_key.as_deref()).await?;
+
+    Ok(db::get_paper(pool, paper_id).await?)
+}
+
+// Imports each record, copying its PDF when one is given, and tallies the outcome
+pub async fn import_records(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    records: Vec<ImportRecord>,
+    on_duplicate: DuplicatePolicy,
+    summary: &mut ImportSummary,
+) {
+    let total = records.len();
+    for (index, record) in records.into_iter().enumerate() {
+        let result = if record.paper.title.trim().is_empty() {
+            Err("entry has no title".to_string())
+        } else {
+            match &record.file {
+                Some(file) => import_file(handle, pool, file, record.paper, on_duplicate)
+                    .await
+                    .map(|imported| imported.paper),
+                None => add_record(pool, record.paper).await,
+            }
+        };
+
+        match result {
+            Ok(paper) => {
+                summary.imported += 1;
+                events::paper_added(handle, &paper);
+            }
+            Err(e) => {
+                summary.skipped += 1;
+                summary.errors.push(format!("{}: {}", record.label, e));
+            }
+        }
+
+        let _ = handle.emit(
+            "import:progress",
+            ImportProgress {
+                current: index + 1,
+                total,
+                path: record.label,
+                imported: summary.imported,
+                skipped: summary.skipped,
+            },
+        );
+    }
+}
+
+// Orders (id, name, parent id) rows so every collection can be created after its parent
+pub fn parents_first(rows: Vec<(i64, String, Option<i64>)>) -> Vec<ExternalCollection> {
+    let mut pending: Vec<ExternalCollection> = rows
+        .into_iter()
+        .map(|(id, name, parent_id)| ExternalCollection { id, name, parent_id })
+        .collect();
+    let mut ordered: Vec<ExternalCollection> = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        let (ready, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|c| {
+            c.parent_id.is_none_or(|parent| ordered.iter().any(|o| o.id == parent))
+        });
+        if ready.is_empty() {
+            // Parent missing from the database; keep the collection at the top level
+            ordered.extend(rest.into_iter().map(|c| ExternalCollection { parent_id: None, ..c }));
+            break;
+        }
+        ordered.extend(ready);
+        pending = rest;
+    }
+
+    ordered
+}
+
+// Brings over items with their first PDF, notes, tags, annotations and the collection tree
+pub async fn import_library(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    library: ExternalLibrary,
+    summary: &mut ImportSummary,
+) -> Result<(), String> {
+    // Their collection id -> ours. Re-running an import reuses the collections it made.
+    let mut collections: HashMap<i64, i64> = HashMap::new();
+    for collection in &library.collections {
+        let parent_id = collection.parent_id.and_then(|p| collections.get(&p).copied());
+        let id = match db::collections::find_collection(pool, &collection.name, parent_id).await? {
+            Some(id) => id,
+            None => db::collections::create_collection(pool, &collection.name, parent_id).await?.id,
+        };
+        collections.insert(collection.id, id);
+    }
+
+    let total = library.items.len();
+    for (index, item) in library.items.into_iter().enumerate() {
+        let label = if item.paper.title.trim().is_empty() {
+            item.key.clone()
+        } else {
+            item.paper.title.clone()
+        };
+
+        let result = if item.paper.title.trim().is_empty() {
+            Err("item has no title".to_string())
+        } else {
+            match item.pdfs.first() {
+                Some(pdf) => import_file(handle, pool, pdf, item.paper, DuplicatePolicy::Reject)
+                    .await
+                    .map(|imported| imported.paper),
+                None => add_record(pool, item.paper).await,
+            }
+        };
+
+        match result {
+            Ok(paper) => {
+                summary.imported += 1;
+                if !item.notes.is_empty() {
+                    db::notes::save_notes(pool, paper.id, &item.notes.join("\n\n---\n\n")).await?;
+                }
+                for external_id in &item.collection_ids {
+                    if let Some(collection_id) = collections.get(external_id) {
+                        db::collections::add_papers(pool, *collection_id, &[paper.id]).await?;
+                    }
+                }
+                for annotation in item.annotations {
+                    let annotation = NewAnnotation {
+                        paper_id: paper.id,
+                        ..annotation
+                    };
+                    db::annotations::add_annotation(pool, &annotation).await?;
+                }
+                events::paper_added(handle, &paper);
+            }
+            Err(e) => {
+                summary.skipped += 1;
+                summary.errors.push(format!("{}: {}", label, e));
+            }
+        }
+
+        let _ = handle.emit(
+            "import:progress",
+            ImportProgress {
+                current: index + 1,
+                total,
+                path: label,
+                imported: summary.imported,
+                skipped: summary.skipped,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+// Brings in a document written by library_json::export: tags and the collection tree first,
+// then each paper with its notes, reading state and annotations
+pub async fn import_library_json(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    document: LibraryDocument,
+    strategy: MergeStrategy,
+    summary: &mut ImportSummary,
+) -> Result<(), String> {
+    for name in &document.tags {
+        db::tags::create_tag(pool, name).await?;
+    }
+
+    // Document collection id -> ours. Collections with the same name and parent are reused.
+    let mut collections: HashMap<i64, i64> = HashMap::new();
+    for collection in &document.collections {
+        let parent_id = collection.parent_id.and_then(|p| collections.get(&p).copied());
+        let id = match db::collections::find_collection(pool, &collection.name, parent_id).await? {
+            Some(id) => id,
+            None => db::collections::create_collection(pool, &collection.name, parent_id).await?.id,
+        };
+        collections.insert(collection.id, id);
+    }
+
+    // Lowercased name -> field. A field that already exists under the name is used as it is.
+    let mut custom_fields: HashMap<String, db::custom_fields::CustomField> = HashMap::new();
+    for field in &document.custom_fields {
+        let existing = db::custom_fields::find_field(pool, &field.name).await?;
+        let created = match existing {
+            Some(existing) => Ok(existing),
+            None => match db::custom_fields::validate_name(&field.name) {
+                Ok(()) => {
+                    let options = db::custom_fields::clean_options(&field.options);
+                    db::custom_fields::create_field(pool, &field.name, field.field_type, &options).await
+                }
+                Err(e) => Err(e),
+            },
+        };
+        match created {
+            Ok(created) => {
+                custom_fields.insert(created.name.to_lowercase(), created);
+            }
+            Err(e) => summary.errors.push(format!("Custom field {}: {}", field.name, e)),
+        }
+    }
+
+    let total = document.papers.len();
+    for (index, record) in document.papers.into_iter().enumerate() {
+        let label = record.title.clone();
+        match import_paper_record(pool, record, strategy, &collections, &custom_fields).await {
+            Ok((paper, true)) => {
+                summary.imported += 1;
+                events::paper_added(handle, &paper);
+            }
+            Ok((paper, false)) => {
+                summary.imported += 1;
+                events::paper_updated(handle, &paper);
+            }
+            Err(e) => {
+                summary.skipped += 1;
+                summary.errors.push(format!("{}: {}", label, e));
+            }
+        }
+
+        let _ = handle.emit(
+            "import:progress",
+            ImportProgress {
+                current: index + 1,
+                total,
+                path: label,
+                imported: summary.imported,
+                skipped: summary.skipped,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+// The paper as it is now, and whether it was newly added rather than merged into one
+async fn import_paper_record(
+    pool: &SqlitePool,
+    record: PaperRecord,
+    strategy: MergeStrategy,
+    collections: &HashMap<i64, i64>,
+    custom_fields: &HashMap<String, db::custom_fields::CustomField>,
+) -> Result<(db::Paper, bool), String> {
+    if record.title.trim().is_empty() {
+        return Err("entry has no title".to_string());
+    }
+
+    let existing = match strategy {
+        MergeStrategy::Duplicate => None,
+        _ => {
+            db::find_matching_paper(
+                pool,
+                record.doi.as_deref(),
+                record.arxiv_id.as_deref(),
+                record.pmid.as_deref(),
+                record.file_hash.as_deref(),
+            )
+            .await?
+        }
+    };
+
+    let (paper_id, added) = match existing {
+        Some(paper) if strategy == MergeStrategy::Skip => {
+            return Err(format!("already in the library as \"{}\"", paper.title));
+        }
+        Some(paper) => {
+            let changes = db::PaperUpdate {
+                title: Some(record.title.clone()),
+                authors: record.authors.clone(),
+                journal: record.journal.clone(),
+                year: record.year,
+                item_type: Some(record.item_type),
+                isbn: record.isbn.clone(),
+                publisher: record.publisher.clone(),
+                institution: record.institution.clone(),
+                doi: record.doi.clone(),
+                pmid: record.pmid.clone(),
+                r#abstract: record.r#abstract.clone(),
+                keywords: record.keywords.clone(),
+                ..Default::default()
+            };
+            db::update_paper(pool, paper.id, &changes).await?;
+            (paper.id, false)
+        }
+        None => {
+            // The file reference is kept for relinking, unless another paper already owns that file
+            let keep_file =
+                !record.pdf_path.is_empty() && db::find_paper_by_path(pool, &record.pdf_path).await?.is_none();
+            let new_paper = NewPaper {
+                title: record.title.clone(),
+                authors: record.authors.clone(),
+                journal: record.journal.clone(),
+                year: record.year,
+                item_type: record.item_type,
+                isbn: record.isbn.clone(),
+                publisher: record.publisher.clone(),
+                institution: record.institution.clone(),
+                doi: record.doi.clone(),
+                arxiv_id: record.arxiv_id.clone(),
+                pmid: record.pmid.clone(),
+                r#abstract: record.r#abstract.clone(),
+                keywords: record.keywords.clone(),
+                pdf_path: if keep_file { record.pdf_path.clone() } else { String::new() },
+                file_hash: record.file_hash.clone().filter(|_| keep_file),
+                file_mode: if keep_file { record.file_mode } else { FileMode::default() },
+                ..Default::default()
+            };
+            let paper_id = db::insert_paper(pool, &new_paper).await?;
+            db::index_paper_text(pool, paper_id, &new_paper.title, &[]).await?;
+            assign_cite_key(pool, paper_id, record.cite_key.as_deref()).await?;
+            (paper_id, true)
+        }
+    };
+
+    if let Some(notes) = record.notes.as_deref().filter(|n| !n.is_empty()) {
+        db::notes::save_notes(pool, paper_id, notes).await?;
+    }
+    if let Some(page) = record.last_read_page {
+        db::set_reading_position(pool, paper_id, page).await?;
+    }
+    let state = db::BulkUpdate {
+        reading_status: Some(record.reading_status),
+        priority: Some(record.priority),
+        starred: Some(record.starred),
+        rating: record.rating,
+        add_tags: (!record.tags.is_empty()).then(|| record.tags.join(", ")),
+        ..Default::default()
+    };
+    if let Some(error) = db::update_papers_bulk(pool, &[paper_id], &state).await?.into_iter().find_map(|r| r.error) {
+        return Err(error);
+    }
+
+    for external_id in &record.collection_ids {
+        if let Some(collection_id) = collections.get(external_id) {
+            db::collections::add_papers(pool, *collection_id, &[paper_id]).await?;
+        }
+    }
+
+    // Merging into an existing paper skips highlights it already has
+    let current = db::annotations::get_annotations(pool, paper_id).await?;
+    for annotation in record.annotations {
+        let present = current.iter().any(|a| {
+            a.page == annotation.page
+                && a.x == annotation.x
+                && a.y == annotation.y
+                && a.width == annotation.width
+                && a.height == annotation.height
+                && a.selected_text == annotation.selected_text
+        });
+        if present {
+            continue;
+        }
+        let annotation = NewAnnotation {
+            paper_id,
+            page: annotation.page,
+            x: annotation.x,
+            y: annotation.y,
+            width: annotation.width,
+            height: annotation.height,
+            selected_text: annotation.selected_text,
+            color: Some(annotation.color),
+            comment: annotation.comment,
+        };
+        db::annotations::add_annotation(pool, &annotation).await?;
+    }
+
+    // Values that don't fit the field as defined here are left out
+    for (name, value) in &record.custom_values {
+        let Some(field) = custom_fields.get(&name.to_lowercase()) else {
+            continue;
+        };
+        match db::custom_fields::normalize_value(field, value) {
+            Ok(value) => db::custom_fields::set_value(pool, paper_id, field.id, value.as_deref()).await?,
+            Err(e) => tracing::warn!("{}: {}", record.title, e),
+        }
+    }
+
+    Ok((db::get_paper(pool, paper_id).await?, added))
+}
@@ -0,0 +1,653 @@
+// src-tauri/src/importers/mendeley.rs
+
+// Reads a Mendeley Desktop library, either straight from its SQLite database
+// (<account>@www.mendeley.com.sqlite) or from a BibTeX export with its PDFs alongside.
+// Only the database carries highlights and sticky notes.
+
+use crate::crossref::normalize_doi;
+use crate::db::annotations::NewAnnotation;
+use crate::db::NewPaper;
+use crate::formats::{bibtex, html};
+use crate::import::{self, ExternalCollection, ExternalItem, ExternalLibrary};
+use crate::pdf;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Size of the marker created for a sticky note, in points
+const NOTE_SIZE: f64 = 16.0;
+
+#[derive(FromRow)]
+struct Document {
+    id: i64,
+    title: Option<String>,
+    year: Option<i64>,
+    publication: Option<String>,
+    doi: Option<String>,
+    arxiv_id: Option<String>,
+    pmid: Option<String>,
+    r#abstract: Option<String>,
+}
+
+#[derive(FromRow)]
+struct HighlightRect {
+    highlight_id: i64,
+    color: Option<String>,
+    page: i64,
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+}
+
+#[derive(FromRow)]
+struct StickyNote {
+    page: i64,
+    x: f64,
+    y: f64,
+    note: Option<String>,
+    color: Option<String>,
+}
+
+pub async fn read_library(db_path: &Path) -> Result<ExternalLibrary, String> {
+    // Mendeley may hold a lock on its database while it runs
+    let copy = std::env::temp_dir().join(format!("paper-master-mendeley-{}.sqlite", std::process::id()));
+    tokio::fs::copy(db_path, &copy)
+        .await
+        .map_err(|e| format!("Failed to read Mendeley database: {}", e))?;
+
+    let options = SqliteConnectOptions::new().filename(&copy).read_only(true);
+    let result = match SqlitePoolOptions::new().max_connections(1).connect_with(options).await {
+        Ok(pool) => {
+            let library = read_tables(&pool).await;
+            pool.close().await;
+            library
+        }
+        Err(e) => Err(format!("Not a Mendeley database: {}", e)),
+    };
+
+    let _ = tokio::fs::remove_file(&copy).await;
+    result
+}
+
+async fn read_tables(pool: &SqlitePool) -> Result<ExternalLibrary, String> {
+    let err = |e: sqlx::Error| format!("Failed to read Mendeley database: {}", e);
+
+    // Booleans are stored as 'true'/'false' strings
+    let documents: Vec<Document> = sqlx::query_as(
+        r#"
+        SELECT id, title, CAST(year AS INTEGER) AS year, publication, doi, arxivId AS arxiv_id,
+            CAST(pmid AS TEXT) AS pmid, abstract
+        FROM Documents
+        WHERE COALESCE(deletionPending, 'false') NOT IN ('true', '1')
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    // Authors if the document has any, otherwise whoever else is credited (editors, ...)
+    let mut contributors: HashMap<i64, Vec<(bool, String)>> = HashMap::new();
+    let contributor_rows: Vec<(i64, String, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT documentId, contribution, firstNames, lastName FROM DocumentContributors ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (document_id, contribution, first, last) in contributor_rows {
+        let name = format!("{} {}", first.unwrap_or_default().trim(), last.unwrap_or_default().trim())
+            .trim()
+            .to_string();
+        contributors
+            .entry(document_id)
+            .or_default()
+            .push((contribution == "DocumentAuthor", name));
+    }
+
+    // User tags plus the paper's own keywords, as the BibTeX export does
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    let tag_rows: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT documentId, tag FROM DocumentTags UNION ALL SELECT documentId, keyword FROM DocumentKeywords",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (document_id, tag) in tag_rows {
+        // Our tag lists are comma separated
+        tags.entry(document_id).or_default().push(tag.replace(',', " "));
+    }
+
+    let mut notes: HashMap<i64, Vec<String>> = HashMap::new();
+    let note_rows: Vec<(i64, String)> =
+        sqlx::query_as("SELECT documentId, text FROM DocumentNotes WHERE text IS NOT NULL ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(err)?;
+    for (document_id, text) in note_rows {
+        let markdown = html::to_markdown(&text);
+        if !markdown.is_empty() {
+            notes.entry(document_id).or_default().push(markdown);
+        }
+    }
+
+    // (file hash, path) so annotations can be matched to the PDF that gets imported
+    let mut files: HashMap<i64, Vec<(String, PathBuf)>> = HashMap::new();
+    let file_rows: Vec<(i64, String, String)> = sqlx::query_as(
+        r#"
+        SELECT df.documentId, f.hash, f.localUrl FROM DocumentFiles df
+        JOIN Files f ON f.hash = df.hash
+        WHERE COALESCE(df.unlinked, 'false') NOT IN ('true', '1')
+        ORDER BY df.rowid
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+    for (document_id, hash, url) in file_rows {
+        let Some(path) = local_url_path(&url) else {
+            continue;
+        };
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) && path.is_file() {
+            files.entry(document_id).or_default().push((hash, path));
+        }
+    }
+
+    let mut document_folders: HashMap<i64, Vec<i64>> = HashMap::new();
+    let membership_rows: Vec<(i64, i64)> = sqlx::query_as("SELECT documentId, folderId FROM DocumentFolders")
+        .fetch_all(pool)
+        .await
+        .map_err(err)?;
+    for (document_id, folder_id) in membership_rows {
+        document_folders.entry(document_id).or_default().push(folder_id);
+    }
+
+    // Top-level folders have a parentId of -1
+    let folder_rows: Vec<(i64, String, Option<i64>)> = sqlx::query_as(
+        "SELECT id, name, CASE WHEN parentId < 0 THEN NULL ELSE parentId END FROM Folders ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let mut items = Vec::with_capacity(documents.len());
+    for document in documents {
+        let document_id = document.id;
+        let files = files.remove(&document_id).unwrap_or_default();
+        let annotations = match files.first() {
+            Some((hash, path)) => read_annotations(pool, document_id, hash, path).await?,
+            None => Vec::new(),
+        };
+
+        let contributors = contributors.remove(&document_id).unwrap_or_default();
+        let has_authors = contributors.iter().any(|(is_author, _)| *is_author);
+        let authors: Vec<String> = contributors
+            .into_iter()
+            .filter(|(is_author, name)| (*is_author || !has_authors) && !name.is_empty())
+            .map(|(_, name)| name)
+            .collect();
+        let tags = tags.remove(&document_id).unwrap_or_default();
+
+        let text = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        items.push(ExternalItem {
+            key: document_id.to_string(),
+            paper: NewPaper {
+                title: text(document.title).unwrap_or_default(),
+                authors: (!authors.is_empty()).then(|| authors.join(", ")),
+                journal: text(document.publication),
+                year: document.year.filter(|y| *y > 0),
+                doi: text(document.doi).as_deref().and_then(normalize_doi),
+                arxiv_id: text(document.arxiv_id),
+                pmid: text(document.pmid),
+                r#abstract: text(document.r#abstract),
+                tags: (!tags.is_empty()).then(|| tags.join(", ")),
+                ..Default::default()
+            },
+            pdfs: files.into_iter().map(|(_, path)| path).collect(),
+            notes: notes.remove(&document_id).unwrap_or_default(),
+            collection_ids: document_folders.remove(&document_id).unwrap_or_default(),
+            annotations,
+        });
+    }
+
+    Ok(ExternalLibrary {
+        items,
+        collections: import::parents_first(folder_rows),
+    })
+}
+
+// Mendeley stores rectangles in PDF space (origin bottom-left), one row per line of a
+// highlight. Each highlight becomes one annotation per page covering all its lines.
+async fn read_annotations(
+    pool: &SqlitePool,
+    document_id: i64,
+    file_hash: &str,
+    pdf_path: &Path,
+) -> Result<Vec<NewAnnotation>, String> {
+    let err = |e: sqlx::Error| format!("Failed to read Mendeley annotations: {}", e);
+
+    let rects: Vec<HighlightRect> = sqlx::query_as(
+        r#"
+        SELECT h.id AS highlight_id, h.color, r.page,
+            CAST(r.x1 AS REAL) AS x1, CAST(r.y1 AS REAL) AS y1,
+            CAST(r.x2 AS REAL) AS x2, CAST(r.y2 AS REAL) AS y2
+        FROM FileHighlights h
+        JOIN FileHighlightRects r ON r.highlightId = h.id
+        WHERE h.documentId = ? AND h.fileHash = ?
+          AND COALESCE(h.unlinked, 'false') NOT IN ('true', '1')
+        ORDER BY h.id, r.page, r.id
+        "#
+    )
+    .bind(document_id)
+    .bind(file_hash)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    let sticky_notes: Vec<StickyNote> = sqlx::query_as(
+        r#"
+        SELECT page, CAST(x AS REAL) AS x, CAST(y AS REAL) AS y, note, color FROM FileNotes
+        WHERE documentId = ? AND fileHash = ?
+          AND COALESCE(unlinked, 'false') NOT IN ('true', '1')
+        ORDER BY id
+        "#
+    )
+    .bind(document_id)
+    .bind(file_hash)
+    .fetch_all(pool)
+    .await
+    .map_err(err)?;
+
+    if rects.is_empty() && sticky_notes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let heights = pdf::page_heights_async(pdf_path).await?;
+    let flip = |page: i64, y: f64| {
+        let height = usize::try_from(page - 1).ok().and_then(|i| heights.get(i)).copied();
+        height.map(|h| h - y)
+    };
+
+    // Bounding box of each highlight on each page, in PDF space with (x1, y1) the
+    // bottom-left corner
+    let mut boxes: Vec<HighlightRect> = Vec::new();
+    for r in rects {
+        let (x1, y1, x2, y2) = (r.x1.min(r.x2), r.y1.min(r.y2), r.x1.max(r.x2), r.y1.max(r.y2));
+        match boxes.last_mut() {
+            Some(b) if b.highlight_id == r.highlight_id && b.page == r.page => {
+                b.x1 = b.x1.min(x1);
+                b.y1 = b.y1.min(y1);
+                b.x2 = b.x2.max(x2);
+                b.y2 = b.y2.max(y2);
+            }
+            _ => boxes.push(HighlightRect { x1, y1, x2, y2, ..r }),
+        }
+    }
+
+    let mut annotations = Vec::new();
+    for b in boxes {
+        let Some(top) = flip(b.page, b.y2) else {
+            continue;
+        };
+        annotations.push(NewAnnotation {
+            paper_id: 0,
+            page: b.page,
+            x: b.x1,
+            y: top,
+            width: b.x2 - b.x1,
+            height: b.y2 - b.y1,
+            selected_text: None,
+            color: b.color.as_deref().and_then(hex_color),
+            comment: None,
+        });
+    }
+
+    for note in sticky_notes {
+        let Some(top) = flip(note.page, note.y) else {
+            continue;
+        };
+        annotations.push(NewAnnotation {
+            paper_id: 0,
+            page: note.page,
+            x: note.x,
+            y: top,
+            width: NOTE_SIZE,
+            height: NOTE_SIZE,
+            selected_text: None,
+            color: note.color.as_deref().and_then(hex_color),
+            comment: note.note.map(|n| html::to_markdown(&n)).filter(|n| !n.is_empty()),
+        });
+    }
+
+    Ok(annotations)
+}
+
+// Reads a "File > Export" BibTeX file. Mendeley folders arrive as mendeley-groups and
+// become flat collections; returns the library and any entries that failed to parse.
+pub fn read_bibtex(input: &str, bib_dir: &Path) -> (ExternalLibrary, Vec<String>) {
+    let bibliography = bibtex::parse(input);
+    let mut collections: Vec<ExternalCollection> = Vec::new();
+
+    let items = bibliography
+        .entries
+        .iter()
+        .map(|entry| {
+            let mut paper = entry.to_new_paper();
+            let user_tags = list_field(entry.get("mendeley-tags"));
+            if !user_tags.is_empty() {
+                paper.tags = Some(match paper.tags {
+                    Some(keywords) => format!("{}, {}", keywords, user_tags.join(", ")),
+                    None => user_tags.join(", "),
+                });
+            }
+
+            let collection_ids = list_field(entry.get("mendeley-groups"))
+                .into_iter()
+                .map(|name| match collections.iter().find(|c| c.name == name) {
+                    Some(collection) => collection.id,
+                    None => {
+                        let id = collections.len() as i64 + 1;
+                        collections.push(ExternalCollection { id, name, parent_id: None });
+                        id
+                    }
+                })
+                .collect();
+
+            ExternalItem {
+                key: entry.key.clone(),
+                paper,
+                pdfs: entry.get("file").map(|f| linked_pdfs(f, bib_dir)).unwrap_or_default(),
+                notes: entry.get("annote").map(|n| vec![n.to_string()]).unwrap_or_default(),
+                collection_ids,
+                annotations: Vec::new(),
+            }
+        })
+        .collect();
+
+    (ExternalLibrary { items, collections }, bibliography.errors)
+}
+
+fn list_field(value: Option<&str>) -> Vec<String> {
+    value
+        .map(|v| v.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+// Mendeley drops the leading slash from absolute paths ("{:home/me/paper.pdf:pdf}") and
+// writes Windows drive colons as "C$\backslash$:". Falls back to a file of the same name
+// next to the .bib, for exports copied to another machine together with their PDFs.
+fn linked_pdfs(file_field: &str, bib_dir: &Path) -> Vec<PathBuf> {
+    let file_field = file_field.replace("$\\backslash$", "\\");
+    bibtex::linked_files(&file_field, bib_dir)
+        .into_iter()
+        .filter_map(|path| {
+            let rooted = path.strip_prefix(bib_dir).ok().map(|p| Path::new("/").join(p));
+            let beside_bib = path.file_name().map(|name| bib_dir.join(name));
+            [Some(path), rooted, beside_bib].into_iter().flatten().find(|p| p.is_file())
+        })
+        .collect()
+}
+
+// file:///home/me/My%20Paper.pdf or file:///C:/Users/me/paper.pdf
+fn local_url_path(url: &str) -> Option<PathBuf> {
+    let encoded = url.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    let path = String::from_utf8_lossy(&decoded).into_owned();
+    let is_drive_path = path.get(1..).is_some_and(|p| p.as_bytes().get(1) == Some(&b':'));
+    Some(PathBuf::from(if is_drive_path { &path[1..] } else { &path[..] }))
+}
+
+fn hex_color(color: &str) -> Option<String> {
+    let hex = color.strip_prefix('#')?;
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| color.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Object};
+
+    // The tables and columns read_library uses, as Mendeley Desktop 1.19 creates them
+    const SCHEMA: &str = r#"
+        CREATE TABLE Documents (id INTEGER PRIMARY KEY, title TEXT, year INTEGER, publication TEXT,
+            doi TEXT, arxivId TEXT, pmid INTEGER, abstract TEXT, deletionPending TEXT);
+        CREATE TABLE DocumentContributors (id INTEGER PRIMARY KEY, documentId INTEGER, contribution TEXT,
+            firstNames TEXT, lastName TEXT);
+        CREATE TABLE DocumentTags (documentId INTEGER, tag TEXT);
+        CREATE TABLE DocumentKeywords (documentId INTEGER, keyword TEXT);
+        CREATE TABLE DocumentNotes (id INTEGER PRIMARY KEY, documentId INTEGER, text TEXT);
+        CREATE TABLE Files (hash TEXT PRIMARY KEY, localUrl TEXT);
+        CREATE TABLE DocumentFiles (documentId INTEGER, hash TEXT, unlinked TEXT);
+        CREATE TABLE Folders (id INTEGER PRIMARY KEY, name TEXT, parentId INTEGER);
+        CREATE TABLE DocumentFolders (documentId INTEGER, folderId INTEGER);
+        CREATE TABLE FileHighlights (id INTEGER PRIMARY KEY, documentId INTEGER, fileHash TEXT, color TEXT,
+            unlinked TEXT);
+        CREATE TABLE FileHighlightRects (id INTEGER PRIMARY KEY, highlightId INTEGER, page INTEGER,
+            x1 REAL, y1 REAL, x2 REAL, y2 REAL);
+        CREATE TABLE FileNotes (id INTEGER PRIMARY KEY, documentId INTEGER, fileHash TEXT, page INTEGER,
+            x REAL, y REAL, note TEXT, color TEXT, unlinked TEXT);
+    "#;
+
+    // One paper with a PDF, highlights and a sticky note in two nested folders, one edited
+    // volume, and one document waiting to be deleted
+    const DATA: &str = r#"
+        INSERT INTO Documents VALUES
+            (1, 'Attention Is All You Need', 2017, 'NeurIPS', '10.5555/3295222.3295349', NULL, NULL, NULL, 'false'),
+            (2, 'Deleted', 2020, NULL, NULL, NULL, NULL, NULL, 'true'),
+            (3, 'Edited Volume', NULL, NULL, NULL, NULL, 31452104, NULL, NULL);
+        INSERT INTO DocumentContributors VALUES
+            (1, 1, 'DocumentAuthor', 'Ashish', 'Vaswani'),
+            (2, 1, 'DocumentEditor', 'Someone', 'Else'),
+            (3, 1, 'DocumentAuthor', 'Noam', 'Shazeer'),
+            (4, 3, 'DocumentEditor', 'Jane', 'Doe');
+        INSERT INTO DocumentTags VALUES (1, 'transformers');
+        INSERT INTO DocumentKeywords VALUES (1, 'attention, neural');
+        INSERT INTO DocumentNotes VALUES (1, 1, '<p>Read <b>again</b></p>');
+        INSERT INTO DocumentFiles VALUES (1, 'abc', 'false');
+        INSERT INTO Folders VALUES (1, 'Reading', -1), (2, 'Transformers', 3), (3, 'ML', -1);
+        INSERT INTO DocumentFolders VALUES (1, 2), (1, 1);
+        INSERT INTO FileHighlights VALUES (1, 1, 'abc', '#FFFF00', 'false'), (2, 1, 'abc', NULL, 'false');
+        INSERT INTO FileHighlightRects VALUES
+            (1, 1, 1, 100, 700, 300, 712),
+            (2, 1, 1, 90, 688, 250, 700),
+            (3, 2, 2, 50, 400, 150, 410);
+        INSERT INTO FileNotes VALUES (1, 1, 'abc', 1, 20, 780, '<p>Check this</p>', '#ff0000', 'false');
+    "#;
+
+    // A scratch directory, removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("paper-master-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // A PDF of blank pages with the given heights
+    fn write_pdf(path: &Path, heights: &[i64]) {
+        let mut doc = lopdf::Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let kids: Vec<Object> = heights
+            .iter()
+            .map(|&height| {
+                let page = dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![0.into(), 0.into(), 612.into(), height.into()],
+                };
+                doc.add_object(page).into()
+            })
+            .collect();
+        let pages = dictionary! { "Type" => "Pages", "Count" => kids.len() as i64, "Kids" => kids };
+        doc.objects.insert(pages_id, Object::Dictionary(pages));
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path).unwrap();
+    }
+
+    async fn write_fixture(db_path: &Path, pdf_path: &Path) {
+        let options = SqliteConnectOptions::new().filename(db_path).create_if_missing(true);
+        let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await.unwrap();
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.unwrap();
+        sqlx::raw_sql(DATA).execute(&pool).await.unwrap();
+        let url = format!("file://{}", pdf_path.to_string_lossy().replace(' ', "%20"));
+        sqlx::query("INSERT INTO Files VALUES ('abc', ?)").bind(url).execute(&pool).await.unwrap();
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn reads_a_mendeley_database() {
+        let dir = TempDir::new("mendeley-db");
+        let pdf_path = dir.0.join("My Paper.pdf");
+        write_pdf(&pdf_path, &[792, 500]);
+        let db_path = dir.0.join("me@www.mendeley.com.sqlite");
+        write_fixture(&db_path, &pdf_path).await;
+
+        let library = read_library(&db_path).await.unwrap();
+
+        let keys: Vec<&str> = library.items.iter().map(|item| item.key.as_str()).collect();
+        assert_eq!(keys, ["1", "3"]);
+        let paper = &library.items[0].paper;
+        assert_eq!(paper.title, "Attention Is All You Need");
+        assert_eq!(paper.authors.as_deref(), Some("Ashish Vaswani, Noam Shazeer"));
+        assert_eq!(paper.doi.as_deref(), Some("10.5555/3295222.3295349"));
+        assert_eq!(paper.tags.as_deref(), Some("transformers, attention  neural"));
+        assert_eq!(library.items[0].pdfs, [pdf_path]);
+        assert!(library.items[0].notes[0].contains("again"));
+        // Without authors, the editors are credited
+        assert_eq!(library.items[1].paper.authors.as_deref(), Some("Jane Doe"));
+        assert_eq!(library.items[1].paper.pmid.as_deref(), Some("31452104"));
+    }
+
+    #[tokio::test]
+    async fn keeps_folders_as_collections() {
+        let dir = TempDir::new("mendeley-folders");
+        let pdf_path = dir.0.join("paper.pdf");
+        write_pdf(&pdf_path, &[792]);
+        let db_path = dir.0.join("library.sqlite");
+        write_fixture(&db_path, &pdf_path).await;
+
+        let library = read_library(&db_path).await.unwrap();
+
+        let collections: Vec<(i64, &str, Option<i64>)> =
+            library.collections.iter().map(|c| (c.id, c.name.as_str(), c.parent_id)).collect();
+        assert_eq!(collections, [(1, "Reading", None), (3, "ML", None), (2, "Transformers", Some(3))]);
+        let mut collection_ids = library.items[0].collection_ids.clone();
+        collection_ids.sort();
+        assert_eq!(collection_ids, [1, 2]);
+        assert!(library.items[1].collection_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn converts_highlights_and_sticky_notes() {
+        let dir = TempDir::new("mendeley-annotations");
+        let pdf_path = dir.0.join("paper.pdf");
+        write_pdf(&pdf_path, &[792, 500]);
+        let db_path = dir.0.join("library.sqlite");
+        write_fixture(&db_path, &pdf_path).await;
+
+        let library = read_library(&db_path).await.unwrap();
+        let annotations = &library.items[0].annotations;
+        assert_eq!(annotations.len(), 3);
+
+        // Both lines of the first highlight make one box, flipped to a top-left origin
+        let first = &annotations[0];
+        assert_eq!((first.page, first.x, first.y, first.width, first.height), (1, 90.0, 80.0, 210.0, 24.0));
+        assert_eq!(first.color.as_deref(), Some("#ffff00"));
+        // Flipped with the height of its own page
+        let second = &annotations[1];
+        assert_eq!((second.page, second.x, second.y), (2, 50.0, 90.0));
+        assert_eq!(second.color, None);
+
+        let note = &annotations[2];
+        assert_eq!((note.page, note.x, note.y, note.width), (1, 20.0, 12.0, NOTE_SIZE));
+        assert!(note.comment.as_deref().is_some_and(|c| c.contains("Check this")));
+        assert!(library.items[1].annotations.is_empty());
+    }
+
+    #[test]
+    fn reads_a_bibtex_export_with_its_folder() {
+        let dir = TempDir::new("mendeley-bibtex");
+        // Exported on another machine: found next to the .bib instead
+        std::fs::write(dir.0.join("vaswani.pdf"), b"%PDF-1.5").unwrap();
+        // Mendeley's absolute path without its leading slash
+        let absolute = dir.0.join("elsewhere").join("second.pdf");
+        std::fs::create_dir_all(absolute.parent().unwrap()).unwrap();
+        std::fs::write(&absolute, b"%PDF-1.5").unwrap();
+        let unrooted = absolute.to_string_lossy().trim_start_matches('/').to_string();
+
+        let input = format!(
+            r#"
+@article{{vaswani2017,
+  title = {{Attention Is All You Need}},
+  author = {{Vaswani, Ashish and Shazeer, Noam}},
+  year = {{2017}},
+  mendeley-tags = {{to-read,transformers}},
+  mendeley-groups = {{Reading,ML}},
+  file = {{:Users/someone/Documents/Mendeley Desktop/vaswani.pdf:pdf}},
+  annote = {{Read again}}
+}}
+@article{{second,
+  title = {{Second}},
+  mendeley-groups = {{ML}},
+  file = {{:{}:pdf}}
+}}
+"#,
+            unrooted
+        );
+        let (library, errors) = read_bibtex(&input, &dir.0);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        let collections: Vec<(i64, &str)> = library.collections.iter().map(|c| (c.id, c.name.as_str())).collect();
+        assert_eq!(collections, [(1, "Reading"), (2, "ML")]);
+        assert!(library.collections.iter().all(|c| c.parent_id.is_none()));
+
+        let first = &library.items[0];
+        assert_eq!(first.collection_ids, [1, 2]);
+        assert_eq!(first.paper.tags.as_deref(), Some("to-read, transformers"));
+        assert_eq!(first.pdfs, [dir.0.join("vaswani.pdf")]);
+        assert_eq!(first.notes, ["Read again"]);
+
+        let second = &library.items[1];
+        assert_eq!(second.collection_ids, [2]);
+        if cfg!(unix) {
+            assert_eq!(second.pdfs, [absolute]);
+        }
+    }
+
+    #[test]
+    fn decodes_file_urls() {
+        assert_eq!(local_url_path("file:///home/me/My%20Paper.pdf"), Some(PathBuf::from("/home/me/My Paper.pdf")));
+        assert_eq!(local_url_path("file:///C:/Users/me/paper.pdf"), Some(PathBuf::from("C:/Users/me/paper.pdf")));
+        assert_eq!(local_url_path("https://example.org/paper.pdf"), None);
+    }
+}
@@ -0,0 +1,3 @@
+// src-tauri/src/importers/mod.rs
+
+pub mod mendeley;
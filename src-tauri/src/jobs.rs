@@ -0,0 +1,142 @@
+// src-tauri/src/jobs.rs
+
+// Long-running background work such as OCR over the whole library. Every job gets a row in
+// `jobs`, waits for one of MAX_CONCURRENT slots and reports through its JobContext, which
+// updates the row and emits "job:progress" with it. Cancelling trips the job's
+// CancellationToken; the work checks it between items and stops early.
+
+use crate::db::{self, jobs::{Job, JobStatus}};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+const MAX_CONCURRENT: usize = 2;
+
+#[derive(Clone)]
+pub struct JobContext {
+    pub id: i64,
+    pub handle: AppHandle,
+    pub pool: SqlitePool,
+    pub token: CancellationToken,
+}
+
+impl JobContext {
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    // Progress only shows in the UI, so a failure to record it doesn't stop the job
+    pub async fn progress(&self, current: usize, total: Option<usize>, message: Option<&str>) {
+        match db::jobs::set_progress(&self.pool, self.id, current as i64, total.map(|t| t as i64), message).await {
+            Ok(job) => emit(&self.handle, &job),
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+}
+
+pub struct Jobs {
+    handle: AppHandle,
+    pool: SqlitePool,
+    slots: Arc<Semaphore>,
+    tokens: Arc<Mutex<HashMap<i64, CancellationToken>>>,
+    // Held while checking for a running job of the same kind and adding the new one
+    starting: tokio::sync::Mutex<()>,
+}
+
+impl Jobs {
+    pub fn new(handle: AppHandle, pool: SqlitePool) -> Self {
+        Jobs {
+            handle,
+            pool,
+            slots: Arc::new(Semaphore::new(MAX_CONCURRENT)),
+            tokens: Arc::default(),
+            starting: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    // Queues `work` and returns its job right away. Only one job of a `kind` is queued or
+    // running at a time. The work returns a line saying how it went, e.g. "Indexed 120 papers".
+    pub async fn spawn<F, Fut>(&self, kind: &str, label: &str, work: F) -> Result<Job, String>
+    where
+        F: FnOnce(JobContext) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        let _starting = self.starting.lock().await;
+        if db::jobs::is_kind_active(&self.pool, kind).await? {
+            return Err(format!("{} is already running", label));
+        }
+        let job = db::jobs::create_job(&self.pool, kind, label).await?;
+        emit(&self.handle, &job);
+
+        let token = CancellationToken::new();
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(job.id, token.clone());
+        }
+        let context = JobContext {
+            id: job.id,
+            handle: self.handle.clone(),
+            pool: self.pool.clone(),
+            token,
+        };
+        tauri::async_runtime::spawn(run(context, self.slots.clone(), self.tokens.clone(), work));
+
+        Ok(job)
+    }
+
+    pub fn cancel(&self, id: i64) -> Result<(), String> {
+        let tokens = self.tokens.lock().map_err(|_| "Job list unavailable")?;
+        let token = tokens.get(&id).ok_or("The job has already finished")?;
+        token.cancel();
+        Ok(())
+    }
+
+    pub async fn list(&self, limit: i64) -> Result<Vec<Job>, String> {
+        Ok(db::jobs::get_jobs(&self.pool, limit).await?)
+    }
+}
+
+async fn run<F, Fut>(
+    context: JobContext,
+    slots: Arc<Semaphore>,
+    tokens: Arc<Mutex<HashMap<i64, CancellationToken>>>,
+    work: F,
+) where
+    F: FnOnce(JobContext) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let slot = tokio::select! {
+        slot = slots.acquire_owned() => slot.ok(),
+        _ = context.token.cancelled() => None,
+    };
+
+    let (status, message, error) = match slot {
+        None => (JobStatus::Cancelled, None, None),
+        Some(_slot) => {
+            match db::jobs::set_running(&context.pool, context.id).await {
+                Ok(job) => emit(&context.handle, &job),
+                Err(e) => tracing::warn!("{}", e),
+            }
+            match work(context.clone()).await {
+                Ok(message) if context.is_cancelled() => (JobStatus::Cancelled, Some(message), None),
+                Ok(message) => (JobStatus::Done, Some(message), None),
+                Err(e) => (JobStatus::Failed, None, Some(e)),
+            }
+        }
+    };
+
+    match db::jobs::finish_job(&context.pool, context.id, status, message.as_deref(), error.as_deref()).await {
+        Ok(job) => emit(&context.handle, &job),
+        Err(e) => tracing::warn!("{}", e),
+    }
+    if let Ok(mut tokens) = tokens.lock() {
+        tokens.remove(&context.id);
+    }
+}
+
+fn emit(handle: &AppHandle, job: &Job) {
+    let _ = handle.emit("job:progress", job);
+}
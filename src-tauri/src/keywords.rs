@@ -0,0 +1,203 @@
+// src-tauri/src/keywords.rs
+
+// Keyword extraction in the style of RAKE: the text is cut into candidate phrases at stop
+// words and punctuation, each word scores by how many other words it co-occurs with in those
+// phrases relative to how often it appears, and a phrase scores the sum of its words. Phrases
+// from the title and abstract count more than ones from the body. The top phrases are stored
+// at import and offered as tags, together with library tags the paper mentions.
+
+use crate::db::{self, PaperText};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+// The opening pages (abstract, introduction) say the most about what a paper is about
+const TEXT_CHARS: usize = 20_000;
+const TITLE_WEIGHT: f64 = 3.0;
+const ABSTRACT_WEIGHT: f64 = 2.0;
+// Longer phrases are rarely useful as tags
+const MAX_PHRASE_WORDS: usize = 3;
+// A phrase only in the body has to come up this often to be more than a passing mention
+const MIN_BODY_MENTIONS: f64 = 3.0;
+const MAX_KEYWORDS: usize = 10;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "across", "after", "again", "against", "all", "almost", "also", "although", "always",
+    "among", "an", "and", "another", "any", "approach", "are", "as", "at", "based", "be", "because", "been",
+    "before", "being", "below", "between", "both", "but", "by", "can", "cannot", "could", "did", "do", "does",
+    "done", "due", "during", "each", "either", "et", "al", "etc", "even", "every", "fig", "figure", "first",
+    "for", "from", "further", "given", "had", "has", "have", "having", "here", "how", "however", "i", "ie", "eg",
+    "if", "in", "into", "is", "it", "its", "itself", "just", "less", "let", "like", "made", "make", "many", "may",
+    "method", "methods", "more", "most", "much", "must", "new", "no", "nor", "not", "now", "of", "often", "on",
+    "one", "only", "or", "other", "our", "ours", "out", "over", "paper", "per", "present", "propose", "proposed",
+    "rather", "result", "results", "same", "second", "section", "see", "several", "should", "show", "shown",
+    "shows", "since", "so", "some", "such", "table", "than", "that", "the", "their", "them", "then", "there",
+    "therefore", "these", "they", "third", "this", "those", "three", "through", "thus", "to", "too", "two",
+    "under", "up", "upon", "us", "use", "used", "uses", "using", "various", "very", "via", "was", "we", "well",
+    "were", "what", "when", "where", "whether", "which", "while", "who", "whose", "why", "will", "with", "within",
+    "without", "work", "would", "yet", "you",
+];
+
+pub struct Keyword {
+    pub phrase: String,
+    pub score: f64,
+}
+
+#[derive(Serialize)]
+pub struct TagSuggestion {
+    pub name: String,
+    // Relative to the paper's best keyword, from 0 to 1
+    pub score: f64,
+    // Whether a tag with this name already exists in the library
+    pub existing: bool,
+}
+
+// The best MAX_KEYWORDS phrases, highest score first
+pub fn extract_keywords(text: &PaperText) -> Vec<Keyword> {
+    let content: String = text.content.as_deref().unwrap_or_default().chars().take(TEXT_CHARS).collect();
+    // Words broken across lines by the PDF's hyphenation
+    let content = content.replace("-\n", "");
+
+    let sources = [
+        (text.title.as_str(), TITLE_WEIGHT),
+        (text.r#abstract.as_deref().unwrap_or_default(), ABSTRACT_WEIGHT),
+        (content.as_str(), 1.0),
+    ];
+
+    // Weighted mentions of each phrase, and whether one was in the title or abstract
+    let mut phrases: HashMap<Vec<String>, (f64, bool)> = HashMap::new();
+    for (source, weight) in sources {
+        for phrase in candidate_phrases(source) {
+            let entry = phrases.entry(phrase).or_default();
+            entry.0 += weight;
+            entry.1 |= weight > 1.0;
+        }
+    }
+
+    let mut frequency: HashMap<&str, f64> = HashMap::new();
+    let mut degree: HashMap<&str, f64> = HashMap::new();
+    for (words, (mentions, _)) in &phrases {
+        for word in words {
+            *frequency.entry(word).or_default() += mentions;
+            *degree.entry(word).or_default() += mentions * words.len() as f64;
+        }
+    }
+
+    let mut keywords: Vec<Keyword> = phrases
+        .iter()
+        .filter(|(_, (mentions, summary))| *summary || *mentions >= MIN_BODY_MENTIONS)
+        .map(|(words, (mentions, _))| {
+            let score: f64 = words.iter().map(|w| degree[w.as_str()] / frequency[w.as_str()]).sum();
+            // Plain RAKE only favours long phrases; how often the phrase itself comes up counts too
+            Keyword { phrase: words.join(" "), score: score * (1.0 + mentions).ln() }
+        })
+        .collect();
+    keywords.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.phrase.cmp(&b.phrase)));
+
+    // "neural network" and "neural networks" are the same tag
+    let mut seen: Vec<String> = Vec::new();
+    keywords.retain(|k| {
+        let key = singular(&k.phrase);
+        let new = !seen.contains(&key);
+        seen.push(key);
+        new
+    });
+    keywords.truncate(MAX_KEYWORDS);
+    keywords
+}
+
+// Runs of content words, lowercased, cut at stop words, punctuation and numbers
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    for fragment in text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\'' || c.is_whitespace())) {
+        let mut phrase: Vec<String> = Vec::new();
+        for word in fragment.split_whitespace() {
+            let word = word.trim_matches(|c: char| c == '-' || c == '\'').to_lowercase();
+            if is_content_word(&word) {
+                phrase.push(word);
+            } else {
+                push_phrase(&mut phrases, std::mem::take(&mut phrase));
+            }
+        }
+        push_phrase(&mut phrases, phrase);
+    }
+    phrases
+}
+
+fn push_phrase(phrases: &mut Vec<Vec<String>>, phrase: Vec<String>) {
+    if !phrase.is_empty() && phrase.len() <= MAX_PHRASE_WORDS {
+        phrases.push(phrase);
+    }
+}
+
+fn is_content_word(word: &str) -> bool {
+    word.chars().count() >= 3
+        && word.chars().any(|c| c.is_alphabetic())
+        && !word.chars().all(|c| c.is_ascii_digit() || c == '-')
+        && !STOP_WORDS.contains(&word)
+}
+
+fn singular(phrase: &str) -> String {
+    match phrase.strip_suffix('s') {
+        Some(stem) if !phrase.ends_with("ss") && stem.len() > 2 => stem.to_string(),
+        _ => phrase.to_string(),
+    }
+}
+
+// Extracts and stores the paper's keywords, e.g. after its text was indexed
+pub async fn update_keywords(pool: &SqlitePool, paper_id: i64, text: &PaperText) -> Result<Vec<Keyword>, String> {
+    let keywords = extract_keywords(text);
+    db::keywords::save_keywords(pool, paper_id, &keywords).await?;
+    Ok(keywords)
+}
+
+// Tags the paper could be given: its stored keywords, extracted now for papers imported before
+// keywords were, and library tags its title, abstract or keywords mention. Tags the paper
+// already has are left out.
+pub async fn get_tag_suggestions(pool: &SqlitePool, paper_id: i64) -> Result<Vec<TagSuggestion>, String> {
+    let text = db::get_paper_text(pool, paper_id).await?;
+    let mut keywords = db::keywords::get_keywords(pool, paper_id).await?;
+    if keywords.is_empty() {
+        keywords = update_keywords(pool, paper_id, &text).await?;
+    }
+
+    let current: Vec<String> = db::tags::get_paper_tag_names(pool, paper_id)
+        .await?
+        .iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let tags = db::tags::get_tags(pool).await?;
+    let summary = format!("{} {}", text.title, text.r#abstract.as_deref().unwrap_or_default()).to_lowercase();
+    let top_score = keywords.first().map_or(1.0, |k| k.score);
+
+    let mut suggestions: Vec<TagSuggestion> = Vec::new();
+    for keyword in &keywords {
+        let existing = tags.iter().find(|t| singular(&t.name.to_lowercase()) == singular(&keyword.phrase));
+        suggestions.push(TagSuggestion {
+            name: existing.map_or_else(|| keyword.phrase.clone(), |t| t.name.clone()),
+            score: keyword.score / top_score,
+            existing: existing.is_some(),
+        });
+    }
+    for tag in &tags {
+        let name = tag.name.to_lowercase();
+        if contains_phrase(&summary, &name) && !suggestions.iter().any(|s| s.name.eq_ignore_ascii_case(&tag.name)) {
+            suggestions.push(TagSuggestion { name: tag.name.clone(), score: 1.0, existing: true });
+        }
+    }
+
+    suggestions.retain(|s| !current.contains(&s.name.to_lowercase()));
+    // Tags already in use first, so a library's vocabulary stays consistent
+    suggestions.sort_by(|a, b| b.existing.cmp(&a.existing).then_with(|| b.score.total_cmp(&a.score)));
+    Ok(suggestions)
+}
+
+// Whole words only, so the tag "ai" doesn't match "detail"
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    !phrase.is_empty()
+        && text.match_indices(phrase).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + phrase.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
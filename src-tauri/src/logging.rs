@@ -0,0 +1,214 @@
+// src-tauri/src/logging.rs
+
+// Everything logged through `tracing` goes to paper-master.log in the app's log directory
+// and to stderr. Once the file passes MAX_FILE_SIZE it is renamed to paper-master.log.1,
+// shifting older ones up, and only KEEP_FILES old files are kept. The level can be changed
+// while the app runs; it is saved in the config so it sticks across restarts.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+const LOG_FILE: &str = "paper-master.log";
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const KEEP_FILES: usize = 3;
+// Module path prefix of everything logged from this crate
+const APP_TARGET: &str = "paper_master";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn from_level(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+pub fn set_level(level: LogLevel) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+struct LogFile {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(dir: &Path) -> Self {
+        let path = dir.join(LOG_FILE);
+        let file = fs::create_dir_all(dir)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+            .map_err(|e| eprintln!("Logging to {} failed: {}", path.display(), e))
+            .ok();
+        let size = file.as_ref().and_then(|f| f.metadata().ok()).map_or(0, |m| m.len());
+        LogFile { dir: dir.to_path_buf(), file, size }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.size + line.len() as u64 > MAX_FILE_SIZE {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                self.size += line.len() as u64;
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        for n in (1..KEEP_FILES).rev() {
+            let _ = fs::rename(rotated_path(&self.dir, n), rotated_path(&self.dir, n + 1));
+        }
+        let _ = fs::rename(self.dir.join(LOG_FILE), rotated_path(&self.dir, 1));
+        *self = LogFile::open(&self.dir);
+    }
+}
+
+fn rotated_path(dir: &Path, n: usize) -> PathBuf {
+    dir.join(format!("{}.{}", LOG_FILE, n))
+}
+
+struct FileLogger {
+    log_file: Mutex<LogFile>,
+    next_span: AtomicU64,
+}
+
+impl Subscriber for FileLogger {
+    // The level can change at any time, so callsites are asked every time rather than cached
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    // Below warnings only the app's own messages are kept; the HTTP and database crates'
+    // debug output would drown them out
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let level = LogLevel::from_level(metadata.level());
+        (level <= LogLevel::Warn || metadata.target().starts_with(APP_TARGET))
+            && level as u8 <= LEVEL.load(Ordering::Relaxed)
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+
+        let line = format!(
+            "{} {:>5} {}: {}{}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            metadata.level(),
+            metadata.target(),
+            fields.message,
+            fields.rest
+        );
+        eprint!("{}", line);
+        if let Ok(mut log_file) = self.log_file.lock() {
+            log_file.write_line(&line);
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    // Any other fields, as " key=value"
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.rest, " {}={}", field.name(), value);
+        }
+    }
+}
+
+pub fn log_dir(handle: &AppHandle) -> Result<PathBuf, String> {
+    handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))
+}
+
+// Starts logging to `dir`; called once at startup before anything is logged
+pub fn init(dir: &Path, level: LogLevel) {
+    set_level(level);
+    let logger = FileLogger {
+        log_file: Mutex::new(LogFile::open(dir)),
+        next_span: AtomicU64::new(1),
+    };
+    if let Err(e) = tracing::subscriber::set_global_default(logger) {
+        eprintln!("Logging already set up: {}", e);
+    }
+}
+
+// The last `count` lines logged, oldest first, reaching into the rotated files if the
+// current one is shorter
+pub fn recent_lines(dir: &Path, count: usize) -> Result<Vec<String>, String> {
+    let mut lines: Vec<String> = Vec::new();
+    let files = std::iter::once(dir.join(LOG_FILE)).chain((1..=KEEP_FILES).map(|n| rotated_path(dir, n)));
+    for path in files {
+        if lines.len() >= count {
+            break;
+        }
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        let wanted = count - lines.len();
+        let mut older: Vec<String> = contents.lines().rev().take(wanted).map(str::to_string).collect();
+        older.reverse();
+        older.append(&mut lines);
+        lines = older;
+    }
+    Ok(lines)
+}
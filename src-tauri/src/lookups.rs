@@ -0,0 +1,168 @@
+// src-tauri/src/lookups.rs
+
+// Metadata lookups that failed because Crossref or arXiv couldn't be reached are queued and
+// retried in the background, less often the longer they keep failing and right away once
+// the connection is back. When one goes through, the fields the paper is still missing are
+// filled in, announced with "paper:updated" and "lookup:completed".
+
+use crate::arxiv;
+use crate::config;
+use crate::crossref::{self, WorkMetadata};
+use crate::db::{self, lookups::LookupKind, PaperUpdate};
+use crate::events;
+use crate::network;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const FIRST_RETRY_MINUTES: i64 = 2;
+const MAX_RETRY_MINUTES: i64 = 60;
+// Probed to tell when the connection is back
+const CONNECTIVITY_CHECK_URL: &str = "https://api.crossref.org";
+
+#[derive(Serialize, Clone)]
+pub struct LookupCompleted {
+    pub paper_id: i64,
+    pub kind: LookupKind,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LookupFailed {
+    pub paper_id: i64,
+    pub kind: LookupKind,
+    pub error: String,
+}
+
+pub enum LookupError {
+    // The service couldn't be reached; worth queueing
+    Offline(String),
+    // The service answered, e.g. that it doesn't know the identifier
+    Failed(String),
+}
+
+impl LookupError {
+    pub fn message(self) -> String {
+        match self {
+            LookupError::Offline(e) | LookupError::Failed(e) => e,
+        }
+    }
+}
+
+pub fn start(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    tauri::async_runtime::spawn(retry_loop(handle, pool, http));
+}
+
+async fn retry_loop(handle: AppHandle, pool: SqlitePool, http: reqwest::Client) {
+    let mut tick = tokio::time::interval(CHECK_INTERVAL);
+    let mut offline = false;
+
+    loop {
+        tick.tick().await;
+        // Waiting out the backoff makes no sense once the connection is back
+        if offline && network::is_reachable(&http, CONNECTIVITY_CHECK_URL).await {
+            if let Err(e) = db::lookups::make_lookups_due(&pool).await {
+                tracing::warn!("{}", e);
+            }
+        }
+        match retry_due(&handle, &pool, &http).await {
+            Ok(still_offline) => offline = still_offline,
+            Err(e) => tracing::warn!("Retrying metadata lookups failed: {}", e),
+        }
+    }
+}
+
+// Retries every lookup that is due. Returns whether any of them still couldn't get through.
+pub async fn retry_due(handle: &AppHandle, pool: &SqlitePool, http: &reqwest::Client) -> Result<bool, String> {
+    let due = db::lookups::get_due_lookups(pool).await?;
+    if due.is_empty() {
+        return Ok(false);
+    }
+    let mailto = config::load(handle).network.contact_email;
+
+    let mut offline = false;
+    for lookup in due {
+        match fetch(http, lookup.kind, &lookup.identifier, mailto.as_deref()).await {
+            Ok(metadata) => {
+                db::lookups::delete_lookup(pool, lookup.id).await?;
+                let paper = fill_in(pool, lookup.paper_id, metadata).await?;
+                events::paper_updated(handle, &paper);
+                let _ = handle.emit(
+                    "lookup:completed",
+                    LookupCompleted {
+                        paper_id: lookup.paper_id,
+                        kind: lookup.kind,
+                    },
+                );
+            }
+            Err(LookupError::Offline(e)) => {
+                offline = true;
+                let minutes = (FIRST_RETRY_MINUTES << lookup.attempts.min(5)).min(MAX_RETRY_MINUTES);
+                db::lookups::reschedule_lookup(pool, lookup.id, &e, minutes).await?;
+            }
+            // Asking again won't change the answer
+            Err(LookupError::Failed(error)) => {
+                db::lookups::delete_lookup(pool, lookup.id).await?;
+                let _ = handle.emit(
+                    "lookup:failed",
+                    LookupFailed {
+                        paper_id: lookup.paper_id,
+                        kind: lookup.kind,
+                        error,
+                    },
+                );
+            }
+        }
+    }
+    Ok(offline)
+}
+
+pub async fn fetch(
+    http: &reqwest::Client,
+    kind: LookupKind,
+    identifier: &str,
+    mailto: Option<&str>,
+) -> Result<WorkMetadata, LookupError> {
+    let (result, service) = match kind {
+        LookupKind::Doi => (crossref::fetch_by_doi(http, identifier, mailto).await, CONNECTIVITY_CHECK_URL),
+        LookupKind::Arxiv => (
+            arxiv::fetch_entry(http, identifier).await.map(WorkMetadata::from),
+            arxiv::API_HOST,
+        ),
+    };
+
+    match result {
+        Ok(metadata) => Ok(metadata),
+        Err(e) if network::is_reachable(http, service).await => Err(LookupError::Failed(e)),
+        Err(e) => Err(LookupError::Offline(e)),
+    }
+}
+
+// Sets the fields the paper is still missing; anything the user entered in the meantime stays
+async fn fill_in(pool: &SqlitePool, paper_id: i64, metadata: WorkMetadata) -> Result<db::Paper, String> {
+    let paper = db::get_paper(pool, paper_id).await?;
+    let missing = |current: &Option<String>, found: Option<String>| match current.as_deref() {
+        Some(value) if !value.trim().is_empty() => None,
+        _ => found,
+    };
+
+    let changes = PaperUpdate {
+        authors: missing(&paper.authors, metadata.authors),
+        journal: missing(&paper.journal, metadata.journal),
+        year: paper.year.is_none().then_some(metadata.year).flatten(),
+        doi: missing(&paper.doi, metadata.doi),
+        r#abstract: missing(&paper.r#abstract, metadata.r#abstract),
+        keywords: missing(&paper.keywords, metadata.keywords),
+        ..Default::default()
+    };
+
+    let before = db::journal::snapshot_papers(pool, &[paper_id]).await?;
+    db::update_paper(pool, paper_id, &changes).await?;
+    let description = format!("Fill in metadata for \"{}\"", paper.title);
+    if let Err(e) = db::journal::record_papers(pool, &description, before).await {
+        tracing::warn!("Failed to journal \"{}\": {}", description, e);
+    }
+
+    Ok(db::get_paper(pool, paper_id).await?)
+}
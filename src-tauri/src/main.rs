@@ -2,37 +2,1858 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod arxiv;
+mod arxiv_updates;
+mod autobackup;
+mod backup;
+mod cli;
+mod clipboard;
+mod companion;
+mod config;
+mod crossref;
 mod db;
-use db::{init_db, insert_paper, get_all_papers};
-use tauri::{State, Manager};
+mod deep_link;
+mod diagnostics;
+mod downloads;
+mod error;
+mod events;
+mod feeds;
+mod figures;
+mod file_naming;
+mod formats;
+mod hooks;
+mod import;
+mod importers;
+mod jobs;
+mod keywords;
+mod logging;
+mod lookups;
+mod maintenance;
+mod network;
+mod notes;
+mod ocr;
+mod operations;
+mod pdf;
+mod pdf_protocol;
+mod providers;
+mod pubmed;
+mod query;
+mod references;
+mod relink;
+mod resolve;
+mod saved_searches;
+mod secrets;
+mod semantic;
+mod semantic_scholar;
+mod settings;
+mod summarize;
+mod thumbnails;
+mod unpaywall;
+mod vault;
+mod watcher;
+mod zotero_api;
+mod zotero_sync;
+use error::AppError;
+use db::{init_db, get_all_papers, get_paper, index_paper_text, lookups::LookupKind, NewPaper, PaperUpdate};
+use formats::{bibtex, csl, csv, library_json, ris, zotero};
+use importers::mendeley;
+use import::DuplicatePolicy;
+use tauri::{Emitter, State, Manager};
 use sqlx::SqlitePool;
 use std::fs;
 
 
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_dialog::{DialogExt, FilePath};
+use tauri_plugin_opener::OpenerExt;
+use tokio_util::sync::CancellationToken;
 
 struct AppState {
     db: SqlitePool,
+    // Where the open database lives; a new location only takes effect after a restart
+    db_path: std::path::PathBuf,
+    http: reqwest::Client,
+    watcher: watcher::FolderWatcher,
+    notes: notes::NotesAutosave,
+    autobackup: autobackup::AutoBackup,
+    maintenance: std::sync::Arc<maintenance::Maintenance>,
+    zotero_sync: zotero_sync::ZoteroSync,
+    thumbnails: thumbnails::Thumbnails,
+    figures: std::sync::Arc<figures::Figures>,
+    clipboard: std::sync::Arc<clipboard::ClipboardWatcher>,
+    companion: std::sync::Arc<companion::Companion>,
+    downloads: downloads::Downloads,
+    jobs: jobs::Jobs,
+    operations: operations::Operations,
+    semantic: semantic::Semantic,
 }
 
+// Everything when no filter is given. With a smart collection its saved query applies on
+// top of `filter`.
+#[tauri::command]
+async fn get_papers(
+    state: State<'_, AppState>,
+    filter: Option<db::PaperFilter>,
+    smart_collection_id: Option<i64>,
+) -> Result<Vec<db::Paper>, AppError> {
+    let filter = resolve_filter(&state.db, filter, smart_collection_id).await?;
+    db::get_papers_filtered(&state.db, &filter).await
+}
+
+// Total for the same filter as get_papers, so the list can be sized before pages load
+#[tauri::command]
+async fn count_papers(
+    state: State<'_, AppState>,
+    filter: Option<db::PaperFilter>,
+    smart_collection_id: Option<i64>,
+) -> Result<i64, AppError> {
+    let filter = resolve_filter(&state.db, filter, smart_collection_id).await?;
+    db::count_papers(&state.db, &filter).await
+}
+
+async fn resolve_filter(
+    pool: &SqlitePool,
+    filter: Option<db::PaperFilter>,
+    smart_collection_id: Option<i64>,
+) -> Result<db::PaperFilter, String> {
+    let filter = filter.unwrap_or_default();
+    match smart_collection_id {
+        Some(id) => Ok(db::smart_collections::get_smart_collection(pool, id).await?.query.apply(filter)),
+        None => Ok(filter),
+    }
+}
+
+#[tauri::command]
+async fn update_paper(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+    changes: PaperUpdate,
+) -> Result<db::Paper, AppError> {
+    if let Some(title) = &changes.title {
+        if title.trim().is_empty() {
+            return Err(AppError::Validation("Title cannot be empty".to_string()));
+        }
+    }
+
+    let before = db::journal::snapshot_papers(&state.db, &[id]).await?;
+    db::update_paper(&state.db, id, &changes).await?;
+    record_paper_changes(&state.db, "Edit", "", before).await;
+    rename_after_edit(&handle, &state.db, id).await;
+    Ok(updated_paper(&handle, &state.db, id).await?)
+}
+
+// The same partial update for many papers at once, e.g. adding a tag or setting the journal.
+// Returns one result per id; papers that fail don't stop the others.
+#[tauri::command]
+async fn update_papers_bulk(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+    changes: db::BulkUpdate,
+) -> Result<Vec<db::BulkUpdateResult>, AppError> {
+    if changes.rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(AppError::Validation("Rating must be between 1 and 5".to_string()));
+    }
+    for names in [&changes.add_tags, &changes.remove_tags].into_iter().flatten() {
+        if db::tags::split_tags(names).is_empty() {
+            return Err(AppError::Validation("No tag names given".to_string()));
+        }
+    }
+
+    let before = db::journal::snapshot_papers(&state.db, &ids).await?;
+    let mut results = db::update_papers_bulk(&state.db, &ids, &changes).await?;
+
+    let before = before
+        .into_iter()
+        .filter(|p| results.iter().any(|r| r.paper_id == p.id && r.error.is_none()))
+        .collect();
+    record_paper_changes(&state.db, "Edit", "", before).await;
+    for result in results.iter_mut().filter(|r| r.paper.is_some()) {
+        // Renaming changes pdf_path, so the paper is read again afterwards
+        rename_after_edit(&handle, &state.db, result.paper_id).await;
+        result.paper = Some(updated_paper(&handle, &state.db, result.paper_id).await?);
+    }
+    Ok(results)
+}
+
+// Re-reads a paper after a command changed it and announces the change
+async fn updated_paper(handle: &tauri::AppHandle, pool: &SqlitePool, id: i64) -> Result<db::Paper, String> {
+    let paper = get_paper(pool, id).await?;
+    events::paper_updated(handle, &paper);
+    Ok(paper)
+}
+
+// Journals an operation on papers from their state before it ran, so undo_last_operation can
+// put them back, e.g. "Edit \"Attention Is All You Need\"" or "Move 3 papers to Trash".
+// The operation itself has already succeeded, so a failure here is only logged.
+async fn record_paper_changes(pool: &SqlitePool, verb: &str, rest: &str, before: Vec<db::journal::PaperSnapshot>) {
+    if before.is_empty() {
+        return;
+    }
+    let papers = match before.as_slice() {
+        [paper] => format!("\"{}\"", paper.title),
+        papers => format!("{} papers", papers.len()),
+    };
+    let description = format!("{} {}{}", verb, papers, rest);
+    if let Err(e) = db::journal::record_papers(pool, &description, before).await {
+        tracing::warn!("Failed to journal \"{}\": {}", description, e);
+    }
+}
+
+// Renames the stored PDF after its new metadata when the file naming setting is on.
+// The edit itself has been saved either way, so a failed rename is only logged.
+async fn rename_after_edit(handle: &tauri::AppHandle, pool: &SqlitePool, id: i64) {
+    let naming = config::load(handle).file_naming;
+    if !naming.rename_on_edit {
+        return;
+    }
+
+    let result = match get_paper(pool, id).await {
+        Ok(paper) => file_naming::rename_stored_file(handle, pool, &paper, &naming.pattern).await,
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to rename PDF for paper {}: {}", id, e);
+    }
+}
+
+#[tauri::command]
+async fn toggle_star(handle: tauri::AppHandle, state: State<'_, AppState>, paper_id: i64) -> Result<db::Paper, AppError> {
+    db::toggle_star(&state.db, paper_id).await?;
+    Ok(updated_paper(&handle, &state.db, paper_id).await?)
+}
+
+#[tauri::command]
+async fn set_rating(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    rating: Option<i64>,
+) -> Result<db::Paper, AppError> {
+    if rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(AppError::Validation("Rating must be between 1 and 5".to_string()));
+    }
+    db::set_rating(&state.db, paper_id, rating).await?;
+    Ok(updated_paper(&handle, &state.db, paper_id).await?)
+}
+
+#[tauri::command]
+async fn set_priority(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    priority: db::Priority,
+) -> Result<db::Paper, AppError> {
+    db::set_priority(&state.db, paper_id, priority).await?;
+    Ok(updated_paper(&handle, &state.db, paper_id).await?)
+}
+
+#[tauri::command]
+async fn set_reading_status(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    status: db::ReadingStatus,
+) -> Result<db::Paper, AppError> {
+    db::set_reading_status(&state.db, paper_id, status).await?;
+    Ok(updated_paper(&handle, &state.db, paper_id).await?)
+}
+
+#[tauri::command]
+async fn set_reading_position(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    page: i64,
+) -> Result<(), AppError> {
+    if page < 1 {
+        return Err(AppError::Validation("Page numbers start at 1".to_string()));
+    }
+    db::set_reading_position(&state.db, paper_id, page).await?;
+    updated_paper(&handle, &state.db, paper_id).await?;
+    Ok(())
+}
+
+// How the paper was last shown in the viewer. Papers never viewed since view states were
+// kept open at the last page read, or the first.
+#[tauri::command]
+async fn get_view_state(state: State<'_, AppState>, paper_id: i64) -> Result<db::view_states::ViewState, AppError> {
+    if let Some(view) = db::view_states::get_view_state(&state.db, paper_id).await? {
+        return Ok(view);
+    }
+    let paper = get_paper(&state.db, paper_id).await?;
+    Ok(db::view_states::ViewState {
+        page: paper.last_read_page.unwrap_or(1),
+        zoom: 1.0,
+        fit: Some(db::view_states::Fit::Width),
+        scroll_top: 0.0,
+        scroll_left: 0.0,
+        view_mode: db::view_states::ViewMode::default(),
+    })
+}
+
+// Saved often while reading, so unlike set_reading_position this doesn't announce a paper update
+#[tauri::command]
+async fn save_view_state(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    view: db::view_states::ViewState,
+) -> Result<(), AppError> {
+    if view.page < 1 {
+        return Err(AppError::Validation("Page numbers start at 1".to_string()));
+    }
+    if !(0.1..=10.0).contains(&view.zoom) {
+        return Err(AppError::Validation("Zoom must be between 10% and 1000%".to_string()));
+    }
+    let view = db::view_states::ViewState {
+        scroll_top: view.scroll_top.clamp(0.0, 1.0),
+        scroll_left: view.scroll_left.clamp(0.0, 1.0),
+        ..view
+    };
+    db::view_states::save_view_state(&state.db, paper_id, &view).await
+}
+
+// Called when the viewer shows a paper; any other open session ends here
+#[tauri::command]
+async fn start_reading_session(state: State<'_, AppState>, paper_id: i64) -> Result<db::reading::ReadingSession, AppError> {
+    get_paper(&state.db, paper_id).await?;
+    db::reading::start_session(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn end_reading_session(state: State<'_, AppState>, session_id: i64) -> Result<db::reading::ReadingSession, AppError> {
+    db::reading::end_session(&state.db, session_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Reading session {} not found", session_id)))
+}
+
+// Time per paper, and per week for the last `weeks` weeks (12 by default)
+#[tauri::command]
+async fn get_reading_stats(state: State<'_, AppState>, weeks: Option<i64>) -> Result<db::reading::ReadingStats, AppError> {
+    db::reading::get_reading_stats(&state.db, weeks.unwrap_or(12).max(1)).await
+}
+
+// Opens the PDF in the system's default viewer
+#[tauri::command]
+async fn open_external(handle: tauri::AppHandle, state: State<'_, AppState>, paper_id: i64) -> Result<(), AppError> {
+    let pdf_path = existing_pdf(&state.db, paper_id).await?;
+    handle
+        .opener()
+        .open_path(pdf_path, None::<&str>)
+        .map_err(|e| format!("Failed to open PDF: {}", e))?;
+    db::record_open(&state.db, paper_id).await
+}
+
+// Shows the PDF selected in Explorer, Finder or the Linux file manager
+#[tauri::command]
+async fn reveal_in_folder(handle: tauri::AppHandle, state: State<'_, AppState>, paper_id: i64) -> Result<(), AppError> {
+    let pdf_path = existing_pdf(&state.db, paper_id).await?;
+    handle
+        .opener()
+        .reveal_item_in_dir(&pdf_path)
+        .map_err(|e| AppError::Other(format!("Failed to show PDF in folder: {}", e)))
+}
+
+async fn existing_pdf(pool: &SqlitePool, paper_id: i64) -> Result<String, AppError> {
+    let paper = get_paper(pool, paper_id).await?;
+    if paper.pdf_path.is_empty() {
+        return Err(AppError::Validation(format!("\"{}\" has no PDF", paper.title)));
+    }
+    if !std::path::Path::new(&paper.pdf_path).is_file() {
+        return Err(AppError::NotFound(format!("PDF not found: {}", paper.pdf_path)));
+    }
+    Ok(paper.pdf_path)
+}
+
+// Totals and breakdowns for the library dashboard
+#[tauri::command]
+async fn get_library_stats(state: State<'_, AppState>) -> Result<db::stats::LibraryStats, AppError> {
+    db::stats::get_library_stats(&state.db).await
+}
+
+// Papers whose PDF was opened most recently, for "Continue reading"
+#[tauri::command]
+async fn get_recent_papers(state: State<'_, AppState>, limit: Option<i64>) -> Result<Vec<db::Paper>, AppError> {
+    db::get_recent_papers(&state.db, limit.unwrap_or(10).max(1)).await
+}
+
+// Deletes permanently, skipping the Trash
+#[tauri::command]
+async fn delete_paper(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+    delete_file: bool,
+) -> Result<String, AppError> {
+    let paper = get_paper(&state.db, id).await?;
+    Ok(remove_paper(&handle, &state.db, &paper, delete_file).await?)
+}
+
+async fn remove_paper(
+    handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    paper: &db::Paper,
+    delete_file: bool,
+) -> Result<String, String> {
+    let revisions = db::revisions::get_revisions(pool, paper.id).await?;
+    db::delete_paper(pool, paper.id).await?;
+    thumbnails::remove(handle, paper.id);
+    figures::remove_files(handle, paper.id);
+    events::paper_deleted(handle, paper.id);
+
+    if !delete_file {
+        return Ok(format!("Paper removed from library: {}", paper.title));
+    }
+    if paper.file_mode == db::FileMode::Linked {
+        return Ok(format!("Paper removed from library: {} (linked file was kept)", paper.title));
+    }
+
+    // Only ever remove files that live in our own storage
+    let papers_dir = import::papers_dir(handle)?;
+    // Earlier versions of the PDF go with it
+    for revision in &revisions {
+        let path = std::path::Path::new(&revision.pdf_path);
+        if path.starts_with(&papers_dir) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    let pdf_path = std::path::Path::new(&paper.pdf_path);
+    if !pdf_path.starts_with(&papers_dir) {
+        return Ok(format!(
+            "Paper removed from library: {} (file outside app storage was kept)",
+            paper.title
+        ));
+    }
+
+    match fs::remove_file(pdf_path) {
+        Ok(()) => Ok(format!("Paper and file deleted: {}", paper.title)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(format!(
+            "Paper removed from library: {} (file was already missing)",
+            paper.title
+        )),
+        Err(e) => Err(format!("Paper removed but file could not be deleted: {}", e)),
+    }
+}
+
+// Hides the paper from the library; its file stays until the Trash is emptied
+#[tauri::command]
+async fn trash_paper(handle: tauri::AppHandle, state: State<'_, AppState>, id: i64) -> Result<db::Paper, AppError> {
+    let before = db::journal::snapshot_papers(&state.db, &[id]).await?;
+    db::set_trashed(&state.db, id, true).await?;
+    record_paper_changes(&state.db, "Move", " to Trash", before).await;
+    Ok(updated_paper(&handle, &state.db, id).await?)
+}
+
+#[tauri::command]
+async fn restore_paper(handle: tauri::AppHandle, state: State<'_, AppState>, id: i64) -> Result<db::Paper, AppError> {
+    db::set_trashed(&state.db, id, false).await?;
+    Ok(updated_paper(&handle, &state.db, id).await?)
+}
+
+// Permanently deletes trashed papers and their stored files; with `older_than_days`,
+// only those that have been in the Trash at least that long
+#[tauri::command]
+async fn empty_trash(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    older_than_days: Option<i64>,
+) -> Result<String, AppError> {
+    if older_than_days.is_some_and(|days| days < 0) {
+        return Err(AppError::Validation("Days must not be negative".to_string()));
+    }
+
+    let papers = db::get_trashed_papers(&state.db, older_than_days).await?;
+    let mut errors = Vec::new();
+    for paper in &papers {
+        if let Err(e) = remove_paper(&handle, &state.db, paper, true).await {
+            errors.push(format!("{}: {}", paper.title, e));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n").into());
+    }
+    Ok(format!("Deleted {} papers from the Trash", papers.len()))
+}
+
+// Papers whose PDF is no longer where the library expects it
+#[tauri::command]
+async fn verify_files(state: State<'_, AppState>) -> Result<Vec<relink::MissingFile>, AppError> {
+    Ok(relink::find_missing(&state.db).await?)
+}
+
+#[tauri::command]
+async fn relink_paper(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+    new_path: String,
+) -> Result<db::Paper, AppError> {
+    let paper = get_paper(&state.db, id).await?;
+    let relinked = relink::relink(&handle, &state.db, &paper, std::path::Path::new(&new_path)).await?;
+    events::paper_updated(&handle, &relinked);
+    state.thumbnails.wake();
+    Ok(relinked)
+}
+
+// Searches `folder` for all missing files at once, matching by content, then by file name
+#[tauri::command]
+async fn relink_missing_files(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    folder: String,
+    recursive: Option<bool>,
+) -> Result<relink::RelinkSummary, AppError> {
+    let dir = std::path::Path::new(&folder);
+    if !dir.is_dir() {
+        return Err(AppError::Validation(format!("Not a folder: {}", folder)));
+    }
+
+    let summary = relink::relink_from_folder(&handle, &state.db, dir, recursive.unwrap_or(true)).await?;
+    state.thumbnails.wake();
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn search_papers(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<db::SearchResult>, AppError> {
+    db::search_papers(&state.db, &query, limit.unwrap_or(50)).await
+}
+
+// Every hit in one paper's text, in page order, for stepping through them in the viewer
+#[tauri::command]
+async fn search_paper_pages(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    query: String,
+) -> Result<Vec<db::PageMatch>, AppError> {
+    db::search_paper_pages(&state.db, paper_id, &query).await
+}
+
+// Exact hits of `query` in the paper with their boxes on the page, for highlighting in the
+// viewer. The first search in a paper lays its pages out once and caches them.
+#[tauri::command]
+async fn search_in_paper(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    query: String,
+) -> Result<Vec<db::pages::InPaperMatch>, AppError> {
+    if !db::pages::has_pages(&state.db, paper_id).await? {
+        let pdf_path = existing_pdf(&state.db, paper_id).await?;
+        let pages = state.thumbnails.page_layout(std::path::Path::new(&pdf_path)).await?;
+        db::pages::save_pages(&state.db, paper_id, &pages).await?;
+    }
+    db::pages::search_in_paper(&state.db, paper_id, &query).await
+}
+
+// Papers in the library with the most vocabulary in common with this one
+#[tauri::command]
+async fn get_similar_papers(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    limit: Option<usize>,
+) -> Result<Vec<db::similar::SimilarPaper>, AppError> {
+    db::similar::get_similar_papers(&state.db, paper_id, limit.unwrap_or(10)).await
+}
+
+#[tauri::command]
+async fn get_semantic_status(state: State<'_, AppState>) -> Result<semantic::SemanticStatus, AppError> {
+    Ok(state.semantic.status(&state.db).await?)
+}
+
+// Starts embedding papers for semantic search in the background; returns how many were queued
+#[tauri::command]
+async fn build_semantic_index(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    rebuild: Option<bool>,
+) -> Result<usize, AppError> {
+    Ok(state.semantic.start_indexing(handle, state.db.clone(), rebuild.unwrap_or(false)).await?)
+}
+
+// Papers whose text is closest in meaning to `query`; needs a build with semantic search
+#[tauri::command]
+async fn semantic_search(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<semantic::SemanticMatch>, AppError> {
+    Ok(state.semantic.search(&state.db, &query, limit.unwrap_or(20)).await?)
+}
+
+// Starts a job re-reading every paper's text into the full-text index
+#[tauri::command]
+async fn rebuild_search_index(state: State<'_, AppState>) -> Result<db::jobs::Job, AppError> {
+    Ok(state.jobs.spawn("search_index", "Rebuild search index", reindex_library).await?)
+}
+
+async fn reindex_library(context: jobs::JobContext) -> Result<String, String> {
+    let papers = get_all_papers(&context.pool).await?;
+    let total = papers.len();
+
+    for (index, paper) in papers.into_iter().enumerate() {
+        if context.is_cancelled() {
+            return Ok(format!("Indexed {} of {} papers", index, total));
+        }
+        context.progress(index, Some(total), Some(&paper.title)).await;
+
+        let pages = match db::ocr::get_ocr_text(&context.pool, paper.id).await? {
+            Some(ocr) => ocr.content.split(db::ocr::PAGE_BREAK).map(str::to_string).collect(),
+            None if paper.pdf_path.is_empty() => Vec::new(),
+            None => pdf::extract_pages_async(std::path::Path::new(&paper.pdf_path))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("{}: {}", paper.pdf_path, e);
+                    Vec::new()
+                }),
+        };
+        index_paper_text(&context.pool, paper.id, &paper.title, &pages).await?;
+    }
+
+    Ok(format!("Indexed {} papers", total))
+}
+
+// Recent jobs, newest first
+#[tauri::command]
+async fn list_jobs(state: State<'_, AppState>, limit: Option<i64>) -> Result<Vec<db::jobs::Job>, AppError> {
+    Ok(state.jobs.list(limit.unwrap_or(50)).await?)
+}
+
+#[tauri::command]
+async fn cancel_job(state: State<'_, AppState>, job_id: i64) -> Result<(), AppError> {
+    Ok(state.jobs.cancel(job_id)?)
+}
+
+// Re-reads page count, size and PDF version for the given papers, or every paper with a PDF
+#[tauri::command]
+async fn refresh_file_stats(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_ids: Option<Vec<i64>>,
+) -> Result<String, AppError> {
+    let papers = match paper_ids {
+        Some(ids) => db::get_papers_by_ids(&state.db, &ids).await?,
+        None => get_all_papers(&state.db).await?,
+    };
+
+    let mut updated = Vec::new();
+    for paper in papers.iter().filter(|p| !p.pdf_path.is_empty()) {
+        match pdf::file_stats_async(std::path::Path::new(&paper.pdf_path)).await {
+            Ok(stats) => {
+                db::set_file_stats(&state.db, paper.id, stats.page_count, stats.file_size, stats.pdf_version.as_deref())
+                    .await?;
+                updated.push(paper.id);
+            }
+            Err(e) => tracing::warn!("{}: {}", paper.pdf_path, e),
+        }
+    }
+    events::papers_updated(&handle, &state.db, &updated).await;
+
+    Ok(format!("Updated file stats for {} papers", updated.len()))
+}
+
+#[tauri::command]
+async fn fetch_metadata_by_doi(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    doi: String,
+    paper_id: Option<i64>,
+) -> Result<crossref::WorkMetadata, AppError> {
+    let mailto = config::load(&handle).network.contact_email;
+    match (lookups::fetch(&state.http, LookupKind::Doi, &doi, mailto.as_deref()).await, paper_id) {
+        (Ok(metadata), _) => Ok(metadata),
+        // Looked up for a paper, the lookup is kept and the paper filled in once back online
+        (Err(lookups::LookupError::Offline(_)), Some(paper_id)) => {
+            db::lookups::queue_lookup(&state.db, paper_id, LookupKind::Doi, &doi).await?;
+            Err(AppError::Network(
+                "Crossref can't be reached; the paper will be filled in once you're back online".to_string(),
+            ))
+        }
+        (Err(e), _) => Err(AppError::Network(e.message())),
+    }
+}
+
+// Lookups waiting for the connection to come back
+#[tauri::command]
+async fn list_pending_lookups(state: State<'_, AppState>) -> Result<Vec<db::lookups::PendingLookup>, AppError> {
+    db::lookups::get_pending_lookups(&state.db).await
+}
+
+// Retries every queued lookup now instead of waiting out the backoff. Returns how many
+// are still waiting.
+#[tauri::command]
+async fn retry_pending_lookups(handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<usize, AppError> {
+    db::lookups::make_lookups_due(&state.db).await?;
+    lookups::retry_due(&handle, &state.db, &state.http).await?;
+    Ok(db::lookups::get_pending_lookups(&state.db).await?.len())
+}
+
+// Works on Crossref matching a (half-remembered) title; the chosen one can be passed to
+// import_crossref_work or apply_metadata
+#[tauri::command]
+async fn search_crossref(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<crossref::WorkMetadata>, AppError> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mailto = config::load(&handle).network.contact_email;
+    crossref::search(&state.http, query.trim(), limit.unwrap_or(20), mailto.as_deref())
+        .await
+        .map_err(AppError::Network)
+}
+
+// Built-in and script providers, with manifests that failed to load
+#[tauri::command]
+async fn list_metadata_providers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<providers::ProviderList, AppError> {
+    Ok(providers::Providers::load(&handle, &state.http).list())
+}
+
+// A record for any kind of identifier a provider knows, e.g. ("pmid", "31452104"); the result
+// can go to apply_metadata or import_crossref_work like a Crossref one
+#[tauri::command]
+async fn lookup_metadata(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    kind: String,
+    identifier: String,
+    provider: Option<String>,
+) -> Result<crossref::WorkMetadata, AppError> {
+    let providers = providers::Providers::load(&handle, &state.http);
+    providers
+        .lookup(&kind, &identifier, provider.as_deref())
+        .await
+        .map_err(AppError::Network)
+}
+
+#[tauri::command]
+async fn search_metadata(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    query: String,
+    provider: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<crossref::WorkMetadata>, AppError> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let providers = providers::Providers::load(&handle, &state.http);
+    providers
+        .search(&query, limit.unwrap_or(20), provider.as_deref())
+        .await
+        .map_err(AppError::Network)
+}
+
+#[derive(serde::Serialize)]
+struct ImportedWork {
+    paper: db::Paper,
+    // Why the PDF couldn't be fetched when one was asked for; the record is created regardless
+    pdf_error: Option<String>,
+}
+
+// Creates a record from a Crossref search result, with the PDF Crossref lists for it when
+// `fetch_pdf` is set
+#[tauri::command]
+async fn import_crossref_work(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    metadata: crossref::WorkMetadata,
+    fetch_pdf: Option<bool>,
+) -> Result<ImportedWork, AppError> {
+    if let Some(existing) = db::find_matching_paper(&state.db, metadata.doi.as_deref(), None, None, None).await? {
+        return Err(AppError::Conflict(format!("Already in library: {}", existing.title)));
+    }
+
+    let pdf_url = metadata.pdf_url.clone();
+    let known = NewPaper {
+        title: metadata.title,
+        authors: metadata.authors,
+        journal: metadata.journal,
+        year: metadata.year,
+        doi: metadata.doi,
+        r#abstract: metadata.r#abstract,
+        keywords: metadata.keywords,
+        ..Default::default()
+    };
+    let mut paper = import::add_record(&state.db, known).await?;
+
+    let pdf_error = match (fetch_pdf.unwrap_or(false), pdf_url) {
+        (false, _) => None,
+        (true, None) => Some("Crossref lists no PDF for this work".to_string()),
+        (true, Some(url)) => match state.downloads.fetch_into_library(&state.db, &paper, &url, None).await {
+            Ok(updated) => {
+                paper = updated;
+                state.thumbnails.wake();
+                None
+            }
+            Err(e) => Some(e),
+        },
+    };
+
+    events::paper_added(&handle, &paper);
+    Ok(ImportedWork { paper, pdf_error })
+}
+
+// Creates a record from the DOI's Crossref metadata, e.g. one offered by the clipboard watcher
+#[tauri::command]
+async fn import_from_doi(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    doi: String,
+    fetch_pdf: Option<bool>,
+) -> Result<ImportedWork, AppError> {
+    let doi = crossref::normalize_doi(&doi).ok_or_else(|| AppError::Validation(format!("Not a valid DOI: {}", doi)))?;
+    if let Some(existing) = db::find_matching_paper(&state.db, Some(&doi), None, None, None).await? {
+        return Err(AppError::Conflict(format!("Already in library: {}", existing.title)));
+    }
+    let mailto = config::load(&handle).network.contact_email;
+    let metadata = lookups::fetch(&state.http, LookupKind::Doi, &doi, mailto.as_deref())
+        .await
+        .map_err(|e| AppError::Network(e.message()))?;
+    import_crossref_work(handle, state, metadata, fetch_pdf).await
+}
+
+// Looks for a legal open-access copy of a record's PDF on Unpaywall and attaches it
+#[tauri::command]
+async fn fetch_open_access_pdf(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::Paper, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    if !paper.pdf_path.is_empty() {
+        return Err(AppError::Conflict("Paper already has a PDF".to_string()));
+    }
+    let doi = paper.doi.as_deref().ok_or("Paper needs a DOI to find an open-access PDF")?;
+    let email = config::load(&handle)
+        .network
+        .contact_email
+        .ok_or("Unpaywall needs a contact email; set one in the network settings")?;
+
+    let url = unpaywall::find_pdf_url(&state.http, doi, &email)
+        .await
+        .map_err(AppError::Network)?
+        .ok_or_else(|| AppError::NotFound("No open-access PDF found".to_string()))?;
+    let paper = state.downloads.fetch_into_library(&state.db, &paper, &url, None).await?;
+
+    state.thumbnails.wake();
+    events::paper_updated(&handle, &paper);
+    Ok(paper)
+}
+
+// Looks the paper up on Semantic Scholar by DOI or arXiv id and stores what it finds
+#[tauri::command]
+async fn fetch_citations(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::citations::CitationData, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    let lookup_id = semantic_scholar::lookup_id(paper.doi.as_deref(), paper.arxiv_id.as_deref())
+        .ok_or("Paper needs a DOI or arXiv id to look up citations")?;
+
+    let lookup = semantic_scholar::fetch_citations(&state.http, &lookup_id).await.map_err(AppError::Network)?;
+    let metrics = db::citations::CitationMetrics {
+        paper_id,
+        s2_paper_id: lookup.s2_paper_id,
+        citation_count: lookup.citation_count,
+        influential_citation_count: lookup.influential_citation_count,
+        reference_count: lookup.reference_count,
+        fetched_at: None,
+    };
+    db::citations::save_citation_data(&state.db, &metrics, &lookup.references, &lookup.citations).await?;
+    db::links::refresh_citation_links(&state.db).await?;
+
+    db::citations::get_citation_data(&state.db, paper_id).await
+}
+
+// What was stored by the last fetch_citations, without going online
+#[tauri::command]
+async fn get_citations(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::citations::CitationData, AppError> {
+    db::citations::get_citation_data(&state.db, paper_id).await
+}
+
+// Library papers this paper cites, for the in-library citation graph
+#[tauri::command]
+async fn get_references(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<db::Paper>, AppError> {
+    db::links::get_references(&state.db, paper_id).await
+}
+
+// Library papers that cite this paper
+#[tauri::command]
+async fn get_citing_papers(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<db::Paper>, AppError> {
+    db::links::get_citing_papers(&state.db, paper_id).await
+}
+
+// Bibliography parsed from the paper's PDF when it was imported
+#[tauri::command]
+async fn get_extracted_references(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::references::ExtractedReference>, AppError> {
+    db::references::get_extracted_references(&state.db, paper_id).await
+}
+
+// Parses the bibliography out of the PDF again, replacing what was stored
+#[tauri::command]
+async fn extract_references(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::references::ExtractedReference>, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    if paper.pdf_path.is_empty() {
+        return Err(AppError::Validation("Paper has no PDF to extract references from".to_string()));
+    }
+
+    let text = pdf::extract_text_async(std::path::Path::new(&paper.pdf_path)).await?;
+    let extracted = references::extract_references(&text);
+    db::references::save_extracted_references(&state.db, paper_id, &extracted).await?;
+
+    db::references::get_extracted_references(&state.db, paper_id).await
+}
+
+// Matches the paper's extracted references against the library; matches show up in the
+// citation graph
+#[tauri::command]
+async fn match_references(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::references::ExtractedReference>, AppError> {
+    let extracted = db::references::get_extracted_references(&state.db, paper_id).await?;
+    let papers = get_all_papers(&state.db).await?;
+    let matches = references::match_references(&extracted, &papers, paper_id);
+    db::references::set_reference_matches(&state.db, paper_id, &matches).await?;
+
+    db::references::get_extracted_references(&state.db, paper_id).await
+}
+
+// Runs OCR on one paper whether or not it looks like a scan, replacing earlier OCR text.
+// `language` is a tesseract code such as "deu+eng"; English by default.
+#[tauri::command]
+async fn ocr_paper(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    language: Option<String>,
+    op_id: Option<String>,
+) -> Result<db::ocr::OcrText, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    if paper.pdf_path.is_empty() {
+        return Err(AppError::Validation("Paper has no PDF to recognise".to_string()));
+    }
+
+    let operation = state.operations.begin(op_id)?;
+    let language = language.unwrap_or_else(|| ocr::DEFAULT_LANGUAGE.to_string());
+    let pdf_path = std::path::Path::new(&paper.pdf_path);
+    Ok(ocr::ocr_paper(&state.db, paper.id, &paper.title, pdf_path, &language, &operation.token).await?)
+}
+
+// Starts a job running OCR on every paper without a text layer
+#[tauri::command]
+async fn ocr_library(state: State<'_, AppState>, language: Option<String>) -> Result<db::jobs::Job, AppError> {
+    let language = language.unwrap_or_else(|| ocr::DEFAULT_LANGUAGE.to_string());
+    Ok(state
+        .jobs
+        .spawn("ocr", "OCR for scanned papers", move |context| ocr::ocr_library(context, language))
+        .await?)
+}
+
+#[tauri::command]
+async fn get_ocr_text(state: State<'_, AppState>, paper_id: i64) -> Result<Option<db::ocr::OcrText>, AppError> {
+    db::ocr::get_ocr_text(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn get_network_settings(handle: tauri::AppHandle) -> Result<network::NetworkStatus, AppError> {
+    Ok(network::NetworkStatus {
+        settings: config::load(&handle).network,
+        proxy_password_set: secrets::get(network::PROXY_PASSWORD_SECRET)?.is_some(),
+    })
+}
+
+// `proxy_password` replaces the saved password when given; an empty one removes it. Proxy and
+// certificate changes take effect after a restart, like a new database location.
+#[tauri::command]
+async fn set_network_settings(
+    handle: tauri::AppHandle,
+    settings: config::NetworkSettings,
+    proxy_password: Option<String>,
+) -> Result<network::NetworkStatus, AppError> {
+    let contact_email = settings::normalize_email(settings.contact_email.as_deref())?;
+    match proxy_password.as_deref() {
+        Some("") => secrets::delete(network::PROXY_PASSWORD_SECRET)?,
+        Some(password) => secrets::set(network::PROXY_PASSWORD_SECRET, password)?,
+        None => {}
+    }
+
+    let settings = config::NetworkSettings { contact_email, ..settings };
+    // Refused here rather than found out at the next start
+    network::build_client(&settings)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.network = settings;
+    config::save(&handle, &app_config)?;
+    get_network_settings(handle).await
+}
+
+// The end of the log file, for attaching to bug reports
+#[tauri::command]
+async fn get_recent_logs(handle: tauri::AppHandle, lines: Option<usize>) -> Result<Vec<String>, AppError> {
+    let dir = logging::log_dir(&handle)?;
+    let lines = lines.unwrap_or(500);
+    Ok(tauri::async_runtime::spawn_blocking(move || logging::recent_lines(&dir, lines))
+        .await
+        .map_err(|e| e.to_string())??)
+}
+
+#[tauri::command]
+async fn get_log_level(handle: tauri::AppHandle) -> Result<logging::LogLevel, AppError> {
+    Ok(config::load(&handle).log_level)
+}
+
+// Takes effect right away and is kept for the next start
+#[tauri::command]
+async fn set_log_level(handle: tauri::AppHandle, level: logging::LogLevel) -> Result<logging::LogLevel, AppError> {
+    let mut app_config = config::load(&handle);
+    app_config.log_level = level;
+    config::save(&handle, &app_config)?;
+    logging::set_level(level);
+    tracing::info!("Log level set to {:?}", level);
+    Ok(level)
+}
+
+// Applies looked-up metadata to an existing paper, or creates a new record without a file
+#[tauri::command]
+async fn apply_metadata(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    metadata: crossref::WorkMetadata,
+    paper_id: Option<i64>,
+) -> Result<db::Paper, AppError> {
+    if metadata.title.trim().is_empty() {
+        return Err(AppError::Validation("Title cannot be empty".to_string()));
+    }
+
+    match paper_id {
+        Some(id) => {
+            let changes = PaperUpdate {
+                title: Some(metadata.title),
+                authors: metadata.authors,
+                journal: metadata.journal,
+                year: metadata.year,
+                doi: metadata.doi,
+                r#abstract: metadata.r#abstract,
+                keywords: metadata.keywords,
+                ..Default::default()
+            };
+            let before = db::journal::snapshot_papers(&state.db, &[id]).await?;
+            db::update_paper(&state.db, id, &changes).await?;
+            record_paper_changes(&state.db, "Apply metadata to", "", before).await;
+            rename_after_edit(&handle, &state.db, id).await;
+            Ok(updated_paper(&handle, &state.db, id).await?)
+        }
+        None => {
+            let known = NewPaper {
+                title: metadata.title,
+                authors: metadata.authors,
+                journal: metadata.journal,
+                year: metadata.year,
+                doi: metadata.doi,
+                r#abstract: metadata.r#abstract,
+                keywords: metadata.keywords,
+                ..Default::default()
+            };
+            let paper = import::add_record(&state.db, known).await?;
+            events::paper_added(&handle, &paper);
+            Ok(paper)
+        }
+    }
+}
+
+// Starts a job looking up DOIs on Crossref for papers whose title is only their file name
+#[tauri::command]
+async fn resolve_missing_metadata(state: State<'_, AppState>) -> Result<db::jobs::Job, AppError> {
+    let http = state.http.clone();
+    Ok(state
+        .jobs
+        .spawn("resolve_metadata", "Find missing metadata", move |context| {
+            resolve::resolve_missing_metadata(context, http)
+        })
+        .await?)
+}
+
+// Matches resolve_missing_metadata wasn't sure enough about to apply
+#[tauri::command]
+async fn get_metadata_matches(
+    state: State<'_, AppState>,
+) -> Result<Vec<db::metadata_matches::MetadataMatch>, AppError> {
+    db::metadata_matches::get_pending_matches(&state.db).await
+}
+
+#[tauri::command]
+async fn accept_metadata_match(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::Paper, AppError> {
+    let metadata = db::metadata_matches::get_match(&state.db, paper_id)
+        .await?
+        .filter(|m| m.status == db::metadata_matches::MatchStatus::Pending)
+        .and_then(|m| m.metadata)
+        .ok_or_else(|| AppError::NotFound(format!("No metadata match to review for paper {}", paper_id)))?;
+    let paper = resolve::apply_match(&handle, &state.db, paper_id, &metadata).await?;
+    db::metadata_matches::set_status(&state.db, paper_id, db::metadata_matches::MatchStatus::Applied).await?;
+    Ok(paper)
+}
+
+// The paper keeps its title and isn't looked up again
+#[tauri::command]
+async fn reject_metadata_match(state: State<'_, AppState>, paper_id: i64) -> Result<(), AppError> {
+    db::metadata_matches::set_status(&state.db, paper_id, db::metadata_matches::MatchStatus::Rejected).await
+}
+
+// Copies the paper's citation key to the clipboard and returns it
+#[tauri::command]
+async fn copy_cite_key(handle: tauri::AppHandle, state: State<'_, AppState>, paper_id: i64) -> Result<String, AppError> {
+    let key = import::assign_cite_key(&state.db, paper_id, None).await?;
+    updated_paper(&handle, &state.db, paper_id).await?;
+    handle
+        .clipboard()
+        .write_text(key.clone())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok(key)
+}
+
+// User CSL styles live in <config dir>/styles
+fn citation_styles_dir(handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let config_dir = handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))?;
+    Ok(config_dir.join("styles"))
+}
+
+// `style` is a bundled CSL style name such as "apa", "ieee" or "chicago-author-date", or the
+// name of a .csl file in the user styles directory
+#[tauri::command]
+async fn format_citation(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    style: String,
+    format: Option<csl::OutputFormat>,
+) -> Result<csl::FormattedCitation, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    let style = csl::load_style(&style, &citation_styles_dir(&handle)?)?;
+    Ok(csl::format_citation(&paper, &style, format.unwrap_or_default())?)
+}
+
+#[tauri::command]
+async fn format_bibliography(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_ids: Vec<i64>,
+    style: String,
+    format: Option<csl::OutputFormat>,
+) -> Result<String, AppError> {
+    let papers = db::get_papers_by_ids(&state.db, &paper_ids).await?;
+    if papers.is_empty() {
+        return Err(AppError::Validation("No papers to cite".to_string()));
+    }
+    let style = csl::load_style(&style, &citation_styles_dir(&handle)?)?;
+    Ok(csl::format_bibliography(&papers, &style, format.unwrap_or_default())?)
+}
+
+// Names of the .csl files in the user styles directory, which is created if missing so it can
+// be opened for the user to drop styles into
+#[tauri::command]
+async fn list_citation_styles(handle: tauri::AppHandle) -> Result<csl::UserStyles, AppError> {
+    let dir = citation_styles_dir(&handle)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create styles directory: {}", e))?;
+    Ok(csl::user_styles(&dir))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CopyFormat {
+    // Reference list entry in a CSL style
+    Reference,
+    // In-text citation in a CSL style, e.g. "(Smith, 2021)"
+    InText,
+    Bibtex,
+    // \cite{key}
+    Cite,
+}
+
+// Puts the paper on the clipboard in the chosen form and returns the copied text. `style`
+// is used by the CSL forms and defaults to APA.
+#[tauri::command]
+async fn copy_citation(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    format: CopyFormat,
+    style: Option<String>,
+) -> Result<String, AppError> {
+    let text = match format {
+        CopyFormat::Reference | CopyFormat::InText => {
+            let paper = get_paper(&state.db, paper_id).await?;
+            let style = csl::load_style(style.as_deref().unwrap_or("apa"), &citation_styles_dir(&handle)?)?;
+            let formatted = csl::format_citation(&paper, &style, csl::OutputFormat::Plain)?;
+            match format {
+                // Styles without a bibliography only have the citation itself
+                CopyFormat::Reference => formatted.reference.unwrap_or(formatted.citation),
+                _ => formatted.citation,
+            }
+        }
+        CopyFormat::Bibtex | CopyFormat::Cite => {
+            // Pasted keys have to keep working, so the paper gets a stored one first
+            let key = import::assign_cite_key(&state.db, paper_id, None).await?;
+            let paper = updated_paper(&handle, &state.db, paper_id).await?;
+            match format {
+                CopyFormat::Bibtex => bibtex::export(std::slice::from_ref(&paper)).trim_end().to_string(),
+                _ => format!("\\cite{{{}}}", key),
+            }
+        }
+    };
+
+    handle
+        .clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    Ok(text)
+}
+
+// Path of the cached first-page PNG, or None until the background job has rendered it
+// ("thumbnail:ready" fires when it has)
+#[tauri::command]
+async fn get_thumbnail(handle: tauri::AppHandle, state: State<'_, AppState>, paper_id: i64) -> Result<Option<String>, AppError> {
+    let thumbnail = thumbnails::find(&handle, paper_id)?;
+    if thumbnail.is_none() {
+        if let Some(e) = state.thumbnails.last_error() {
+            return Err(e.into());
+        }
+        state.thumbnails.wake();
+    }
+    Ok(thumbnail.map(|path| path.display().to_string()))
+}
+
+// Images found in the paper's PDF, in page order. Scans the PDF now if the background pass
+// hasn't got to it yet.
+#[tauri::command]
+async fn get_figures(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<figures::FigureImage>, AppError> {
+    Ok(state.figures.get(paper_id).await?)
+}
+
+#[tauri::command]
+async fn rescan_figures(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<figures::FigureImage>, AppError> {
+    Ok(state.figures.rescan(paper_id).await?)
+}
+
+#[tauri::command]
+async fn import_from_arxiv(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id_or_url: String,
+    op_id: Option<String>,
+) -> Result<db::Paper, AppError> {
+    let id = arxiv::parse_id(&id_or_url)
+        .ok_or_else(|| format!("Not a valid arXiv id or URL: {}", id_or_url))?;
+    let operation = state.operations.begin(op_id)?;
+    Ok(add_arxiv_paper(&handle, &state, &id, Some(&operation.token)).await?)
+}
+
+// Downloads the PDF and imports it with the metadata from arXiv
+async fn add_arxiv_paper(
+    handle: &tauri::AppHandle,
+    state: &AppState,
+    id: &str,
+    cancel: Option<&CancellationToken>,
+) -> Result<db::Paper, String> {
+    let entry = arxiv::fetch_entry(&state.http, id).await?;
+
+    let papers_dir = import::papers_dir(handle)?;
+    let dest = import::unique_destination(&papers_dir, &format!("{}.pdf", entry.id.replace('/', "_")));
+    state
+        .downloads
+        .fetch(downloads::DownloadRequest {
+            url: &arxiv::pdf_url(&entry.id),
+            dest: &dest,
+            label: format!("arXiv {}", entry.id),
+            https_only: false,
+            cancel,
+        })
+        .await?;
+
+    let known = NewPaper {
+        title: entry.title,
+        authors: (!entry.authors.is_empty()).then(|| entry.authors.join(", ")),
+        journal: entry.journal_ref,
+        year: entry.year,
+        doi: entry.doi,
+        arxiv_id: Some(entry.id),
+        r#abstract: Some(entry.r#abstract).filter(|a| !a.is_empty()),
+        tags: (!entry.categories.is_empty()).then(|| entry.categories.join(", ")),
+        ..Default::default()
+    };
+
+    match import::register_file(&state.db, &dest, known, DuplicatePolicy::Reject).await {
+        Ok(imported) => {
+            events::paper_added(handle, &imported.paper);
+            state.thumbnails.wake();
+            Ok(imported.paper)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&dest);
+            Err(e)
+        }
+    }
+}
+
+// Papers whose PDF is older than the newest arXiv version seen in the background checks
+#[tauri::command]
+async fn get_arxiv_updates(state: State<'_, AppState>) -> Result<Vec<db::revisions::ArxivUpdate>, AppError> {
+    db::revisions::get_arxiv_updates(&state.db).await
+}
+
+// Checks papers that are due now instead of waiting for the next background check
+#[tauri::command]
+async fn check_arxiv_updates(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<db::revisions::ArxivUpdate>, AppError> {
+    arxiv_updates::check_due(&handle, &state.db, &state.http).await?;
+    db::revisions::get_arxiv_updates(&state.db).await
+}
+
+// Downloads the newest arXiv version of the paper; the old PDF is kept as a revision
+#[tauri::command]
+async fn update_arxiv_version(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::Paper, AppError> {
+    let paper = arxiv_updates::update_version(&handle, &state.db, &state.http, &state.downloads, paper_id).await?;
+    thumbnails::remove(&handle, paper_id);
+    state.thumbnails.wake();
+    state.figures.remove(paper_id).await;
+    db::pages::clear_pages(&state.db, paper_id).await?;
+    events::paper_updated(&handle, &paper);
+    Ok(paper)
+}
+
+#[tauri::command]
+async fn get_paper_revisions(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<db::revisions::PaperRevision>, AppError> {
+    db::revisions::get_revisions(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn list_feeds(state: State<'_, AppState>) -> Result<Vec<db::feeds::Feed>, AppError> {
+    db::feeds::get_feeds(&state.db).await
+}
+
+// Fetches the feed once to check it's one, then fills the inbox with its current entries
+#[tauri::command]
+async fn subscribe_feed(handle: tauri::AppHandle, state: State<'_, AppState>, url: String) -> Result<db::feeds::Feed, AppError> {
+    let url = url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(AppError::Validation(format!("Not a feed URL: {}", url)));
+    }
+
+    let parsed = feeds::fetch_feed(&state.http, url).await.map_err(AppError::Network)?;
+    let title = parsed.title.unwrap_or_else(|| url.to_string());
+    let feed = db::feeds::create_feed(&state.db, url, &title).await?;
+    feeds::poll_feed(&handle, &state.db, &state.http, &feed).await;
+    db::feeds::get_feed(&state.db, feed.id).await
+}
+
+#[tauri::command]
+async fn unsubscribe_feed(state: State<'_, AppState>, feed_id: i64) -> Result<(), AppError> {
+    db::feeds::delete_feed(&state.db, feed_id).await
+}
+
+// Polls every feed now, or just `feed_id`; returns how many new entries turned up
+#[tauri::command]
+async fn refresh_feeds(handle: tauri::AppHandle, state: State<'_, AppState>, feed_id: Option<i64>) -> Result<usize, AppError> {
+    let to_poll = match feed_id {
+        Some(id) => vec![db::feeds::get_feed(&state.db, id).await?],
+        None => db::feeds::get_feeds(&state.db).await?,
+    };
+
+    let mut added = 0;
+    for feed in &to_poll {
+        added += feeds::poll_feed(&handle, &state.db, &state.http, feed).await;
+    }
+    Ok(added)
+}
+
+// The inbox, newest first; imported and dismissed entries only with `include_handled`
+#[tauri::command]
+async fn get_feed_entries(
+    state: State<'_, AppState>,
+    feed_id: Option<i64>,
+    include_handled: Option<bool>,
+    limit: Option<i64>,
+) -> Result<Vec<db::feeds::FeedEntry>, AppError> {
+    db::feeds::get_entries(&state.db, feed_id, include_handled.unwrap_or(false), limit.unwrap_or(200)).await
+}
+
+#[tauri::command]
+async fn dismiss_feed_entries(state: State<'_, AppState>, entry_ids: Vec<i64>) -> Result<(), AppError> {
+    db::feeds::dismiss_entries(&state.db, &entry_ids).await
+}
 
+// arXiv entries come with their PDF; other entries become a record from Crossref when they
+// have a DOI, or from what the feed said otherwise
 #[tauri::command]
-async fn read_pdf_file(path: String) -> Result<Vec<u8>, String> {
-    use tokio::fs;
-    let bytes = fs::read(&path)
+async fn import_feed_entry(handle: tauri::AppHandle, state: State<'_, AppState>, entry_id: i64) -> Result<db::Paper, AppError> {
+    let entry = db::feeds::get_entry(&state.db, entry_id).await?;
+    if let Some(existing) =
+        db::find_matching_paper(&state.db, entry.doi.as_deref(), entry.arxiv_id.as_deref(), None, None).await?
+    {
+        db::feeds::set_entry_paper(&state.db, entry_id, existing.id).await?;
+        return Err(AppError::Conflict(format!("Already in library: {}", existing.title)));
+    }
+
+    let mailto = config::load(&handle).network.contact_email;
+    // Offline, the record is made from the feed entry and filled in once the lookup goes through
+    let (known, pending) = match (&entry.arxiv_id, &entry.doi) {
+        (Some(arxiv_id), _) => match add_arxiv_paper(&handle, &state, arxiv_id, None).await {
+            Ok(paper) => {
+                db::feeds::set_entry_paper(&state.db, entry_id, paper.id).await?;
+                return Ok(paper);
+            }
+            Err(_) if !network::is_reachable(&state.http, arxiv::API_HOST).await => {
+                let known = NewPaper {
+                    arxiv_id: Some(arxiv_id.clone()),
+                    ..feed_entry_record(&entry)
+                };
+                (known, Some((LookupKind::Arxiv, arxiv_id.clone())))
+            }
+            Err(e) => return Err(AppError::Network(e)),
+        },
+        (None, Some(doi)) => match lookups::fetch(&state.http, LookupKind::Doi, doi, mailto.as_deref()).await {
+            Ok(metadata) => {
+                let known = NewPaper {
+                    title: metadata.title,
+                    authors: metadata.authors.or(entry.authors.clone()),
+                    journal: metadata.journal,
+                    year: metadata.year,
+                    doi: metadata.doi,
+                    r#abstract: metadata.r#abstract.or(entry.summary.clone()),
+                    keywords: metadata.keywords,
+                    ..Default::default()
+                };
+                (known, None)
+            }
+            Err(lookups::LookupError::Offline(_)) => (feed_entry_record(&entry), Some((LookupKind::Doi, doi.clone()))),
+            Err(lookups::LookupError::Failed(_)) => (feed_entry_record(&entry), None),
+        },
+        (None, None) => (feed_entry_record(&entry), None),
+    };
+
+    let paper = import::add_record(&state.db, known).await?;
+    if let Some((kind, identifier)) = pending {
+        db::lookups::queue_lookup(&state.db, paper.id, kind, &identifier).await?;
+    }
+    events::paper_added(&handle, &paper);
+
+    db::feeds::set_entry_paper(&state.db, entry_id, paper.id).await?;
+    Ok(paper)
+}
+
+// What the feed itself says about the entry
+fn feed_entry_record(entry: &db::feeds::FeedEntry) -> NewPaper {
+    NewPaper {
+        title: entry.title.clone(),
+        authors: entry.authors.clone(),
+        year: entry.published.as_deref().and_then(pdf::find_year),
+        doi: entry.doi.clone(),
+        r#abstract: entry.summary.clone(),
+        ..Default::default()
+    }
+}
+
+// PubMed has no PDFs, so this creates a metadata-only record; MeSH headings become tags
+#[tauri::command]
+async fn import_from_pubmed(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    pmid: String,
+) -> Result<db::Paper, AppError> {
+    let pmid = pubmed::parse_pmid(&pmid).ok_or_else(|| format!("Not a valid PMID or PubMed URL: {}", pmid))?;
+    if let Some(existing) = db::find_paper_by_pmid(&state.db, &pmid).await? {
+        return Err(AppError::Conflict(format!("PMID {} is already in the library as \"{}\"", pmid, existing.title)));
+    }
+
+    let article = pubmed::fetch_article(&state.http, &pmid).await.map_err(AppError::Network)?;
+    // Tags are stored comma-separated, and some headings contain commas ("Neoplasms, Experimental")
+    let mesh_terms: Vec<String> = article.mesh_terms.iter().map(|t| t.replace(',', "")).collect();
+
+    let known = NewPaper {
+        title: article.title,
+        authors: (!article.authors.is_empty()).then(|| article.authors.join(", ")),
+        journal: article.journal,
+        year: article.year,
+        doi: article.doi,
+        pmid: Some(article.pmid),
+        r#abstract: article.r#abstract,
+        tags: (!mesh_terms.is_empty()).then(|| mesh_terms.join(", ")),
+        ..Default::default()
+    };
+    let paper = import::add_record(&state.db, known).await?;
+    events::paper_added(&handle, &paper);
+    Ok(paper)
+}
+
+#[tauri::command]
+async fn import_bibtex(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    import_files: Option<bool>,
+) -> Result<import::ImportSummary, AppError> {
+    let bib_path = std::path::Path::new(&path);
+    let bytes = tokio::fs::read(bib_path)
+        .await
+        .map_err(|e| format!("Failed to read BibTeX file: {}", e))?;
+    let bibliography = bibtex::parse(&String::from_utf8_lossy(&bytes));
+    let bib_dir = bib_path.parent().unwrap_or(std::path::Path::new("."));
+
+    // Use the first linked PDF that actually exists on disk
+    let import_files = import_files.unwrap_or(false);
+    let records = bibliography
+        .entries
+        .iter()
+        .map(|entry| import::ImportRecord {
+            label: entry.key.clone(),
+            paper: entry.to_new_paper(),
+            file: import_files
+                .then(|| entry.get("file"))
+                .flatten()
+                .and_then(|f| bibtex::linked_files(f, bib_dir).into_iter().find(|p| p.is_file())),
+        })
+        .collect();
+
+    let mut summary = import::ImportSummary {
+        errors: bibliography.errors,
+        ..Default::default()
+    };
+    import::import_records(&handle, &state.db, records, DuplicatePolicy::Reject, &mut summary).await;
+    state.thumbnails.wake();
+
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn export_bibtex(
+    state: State<'_, AppState>,
+    paper_ids: Option<Vec<i64>>,
+    dest: String,
+) -> Result<String, AppError> {
+    let mut papers = match paper_ids {
+        Some(ids) => db::get_papers_by_ids(&state.db, &ids).await?,
+        None => get_all_papers(&state.db).await?,
+    };
+    if papers.is_empty() {
+        return Err(AppError::Validation("No papers to export".to_string()));
+    }
+    // Oldest first so citation key suffixes stay stable as the library grows
+    papers.sort_by_key(|p| p.id);
+
+    tokio::fs::write(&dest, bibtex::export(&papers))
+        .await
+        .map_err(|e| format!("Failed to write BibTeX file: {}", e))?;
+
+    Ok(format!("Exported {} papers to {}", papers.len(), dest))
+}
+
+#[tauri::command]
+async fn import_ris(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    import_files: Option<bool>,
+) -> Result<import::ImportSummary, AppError> {
+    let ris_path = std::path::Path::new(&path);
+    let bytes = tokio::fs::read(ris_path)
+        .await
+        .map_err(|e| format!("Failed to read RIS file: {}", e))?;
+    let ris_dir = ris_path.parent().unwrap_or(std::path::Path::new("."));
+
+    let import_files = import_files.unwrap_or(false);
+    let records = ris::parse(&String::from_utf8_lossy(&bytes))
+        .iter()
+        .map(|record| import::ImportRecord {
+            label: record.label(),
+            paper: record.to_new_paper(),
+            file: import_files
+                .then(|| record.linked_files(ris_dir).into_iter().find(|p| p.is_file()))
+                .flatten(),
+        })
+        .collect();
+
+    let mut summary = import::ImportSummary::default();
+    import::import_records(&handle, &state.db, records, DuplicatePolicy::Reject, &mut summary).await;
+    state.thumbnails.wake();
+
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn export_ris(
+    state: State<'_, AppState>,
+    paper_ids: Option<Vec<i64>>,
+    dest: String,
+) -> Result<String, AppError> {
+    let papers = match paper_ids {
+        Some(ids) => db::get_papers_by_ids(&state.db, &ids).await?,
+        None => get_all_papers(&state.db).await?,
+    };
+    if papers.is_empty() {
+        return Err(AppError::Validation("No papers to export".to_string()));
+    }
+
+    tokio::fs::write(&dest, ris::export(&papers))
+        .await
+        .map_err(|e| format!("Failed to write RIS file: {}", e))?;
+
+    Ok(format!("Exported {} papers to {}", papers.len(), dest))
+}
+
+// `fields` picks the columns and their order; all of them by default
+#[tauri::command]
+async fn export_csv(
+    state: State<'_, AppState>,
+    paper_ids: Option<Vec<i64>>,
+    dest: String,
+    fields: Option<Vec<csv::CsvField>>,
+    custom_fields: Option<Vec<i64>>,
+) -> Result<String, AppError> {
+    let papers = match paper_ids {
+        Some(ids) => db::get_papers_by_ids(&state.db, &ids).await?,
+        None => get_all_papers(&state.db).await?,
+    };
+    if papers.is_empty() {
+        return Err(AppError::Validation("No papers to export".to_string()));
+    }
+    let fields = fields.unwrap_or_else(|| csv::CsvField::ALL.to_vec());
+
+    // Every custom field unless some are picked, in the order given
+    let defined = db::custom_fields::get_fields(&state.db).await?;
+    let picked: Vec<&db::custom_fields::CustomField> = match &custom_fields {
+        Some(ids) => ids.iter().filter_map(|id| defined.iter().find(|f| f.id == *id)).collect(),
+        None => defined.iter().collect(),
+    };
+    let mut custom: Vec<csv::CustomColumn> = picked
+        .iter()
+        .map(|f| csv::CustomColumn { name: f.name.clone(), values: Default::default() })
+        .collect();
+    for (paper_id, field_id, value) in db::custom_fields::get_all_values(&state.db).await? {
+        if let Some(index) = picked.iter().position(|f| f.id == field_id) {
+            custom[index].values.insert(paper_id, value);
+        }
+    }
+    if fields.is_empty() && custom.is_empty() {
+        return Err(AppError::Validation("No columns to export".to_string()));
+    }
+
+    tokio::fs::write(&dest, csv::export(&papers, &fields, &custom))
+        .await
+        .map_err(|e| format!("Failed to write CSV file: {}", e))?;
+
+    Ok(format!("Exported {} papers to {}", papers.len(), dest))
+}
+
+// `mapping` maps column headers to fields; without one, columns named like the export's are
+// used. With `import_files`, a pdf_path column brings in the PDF it points at.
+#[tauri::command]
+async fn import_csv(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    mapping: Option<std::collections::HashMap<String, csv::CsvField>>,
+    import_files: Option<bool>,
+) -> Result<import::ImportSummary, AppError> {
+    let csv_path = std::path::Path::new(&path);
+    let bytes = tokio::fs::read(csv_path)
         .await
-        .map_err(|e| format!("Failed to read PDF: {}", e))?;
-    Ok(bytes)
+        .map_err(|e| format!("Failed to read CSV file: {}", e))?;
+    let csv_dir = csv_path.parent().unwrap_or(std::path::Path::new("."));
+
+    let import_files = import_files.unwrap_or(false);
+    let records = csv::read_records(csv::parse(&String::from_utf8_lossy(&bytes)), mapping.as_ref())?
+        .iter()
+        .map(|record| import::ImportRecord {
+            label: record.label(),
+            paper: record.to_new_paper(),
+            file: import_files
+                .then(|| record.linked_file(csv_dir).filter(|p| p.is_file()))
+                .flatten(),
+        })
+        .collect();
+
+    let mut summary = import::ImportSummary::default();
+    import::import_records(&handle, &state.db, records, DuplicatePolicy::Reject, &mut summary).await;
+    state.thumbnails.wake();
+
+    Ok(summary)
 }
 
+// Papers outside the Trash with their tags, collections, notes and annotations; no PDFs
+#[tauri::command]
+async fn export_library_json(state: State<'_, AppState>, dest: String) -> Result<String, AppError> {
+    let document = library_json::export(&state.db).await?;
+    let json = serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to export library: {}", e))?;
+
+    tokio::fs::write(&dest, json)
+        .await
+        .map_err(|e| format!("Failed to write library export: {}", e))?;
 
+    Ok(format!("Exported {} papers to {}", document.papers.len(), dest))
+}
 
 #[tauri::command]
-async fn  get_papers(state: State<'_, AppState>)->Result<Vec<db::Paper>, String>
-{
-    get_all_papers(&state.db)
+async fn import_library_json(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    merge_strategy: Option<import::MergeStrategy>,
+) -> Result<import::ImportSummary, AppError> {
+    let json = tokio::fs::read_to_string(&path)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| format!("Failed to read library export: {}", e))?;
+    let document = library_json::parse(&json)?;
+
+    let mut summary = import::ImportSummary::default();
+    import::import_library_json(&handle, &state.db, document, merge_strategy.unwrap_or_default(), &mut summary).await?;
+
+    Ok(summary)
+}
+
+// One Markdown file per paper with its notes and highlights, e.g. into an Obsidian vault.
+// Incremental exports (the default) skip papers whose file hasn't changed.
+#[tauri::command]
+async fn export_vault(
+    state: State<'_, AppState>,
+    folder: String,
+    incremental: Option<bool>,
+) -> Result<vault::VaultExportSummary, AppError> {
+    Ok(vault::export_vault(&state.db, std::path::Path::new(&folder), incremental.unwrap_or(true)).await?)
+}
+
+// `path` is zotero.sqlite or the Zotero data directory that contains it
+#[tauri::command]
+async fn import_zotero(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<import::ImportSummary, AppError> {
+    let mut db_path = std::path::PathBuf::from(&path);
+    if db_path.is_dir() {
+        db_path = db_path.join("zotero.sqlite");
+    }
+    if db_path.extension().is_some_and(|e| e.eq_ignore_ascii_case("rdf")) {
+        return Err(AppError::Validation(
+            "Zotero RDF exports aren't supported; choose zotero.sqlite in your Zotero data directory instead"
+                .to_string(),
+        ));
+    }
+    if !db_path.is_file() {
+        return Err(AppError::NotFound(format!("Zotero database not found: {}", db_path.display())));
+    }
+
+    let library = zotero::read_library(&db_path).await?;
+    let mut summary = import::ImportSummary::default();
+    import::import_library(&handle, &state.db, library, &mut summary).await?;
+    state.thumbnails.wake();
+
+    Ok(summary)
+}
+
+// `path` is Mendeley Desktop's <account>@www.mendeley.com.sqlite or a BibTeX export
+#[tauri::command]
+async fn import_mendeley(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<import::ImportSummary, AppError> {
+    let source = std::path::Path::new(&path);
+    let is_bibtex = source.extension().is_some_and(|e| e.eq_ignore_ascii_case("bib"));
+
+    let mut summary = import::ImportSummary::default();
+    let library = if is_bibtex {
+        let bytes = tokio::fs::read(source)
+            .await
+            .map_err(|e| format!("Failed to read BibTeX file: {}", e))?;
+        let bib_dir = source.parent().unwrap_or(std::path::Path::new("."));
+        let (library, errors) = mendeley::read_bibtex(&String::from_utf8_lossy(&bytes), bib_dir);
+        summary.errors = errors;
+        library
+    } else {
+        if !source.is_file() {
+            return Err(AppError::NotFound(format!("Mendeley database not found: {}", source.display())));
+        }
+        mendeley::read_library(source).await?
+    };
+    import::import_library(&handle, &state.db, library, &mut summary).await?;
+    state.thumbnails.wake();
+
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn add_watched_folder(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    recursive: Option<bool>,
+    file_mode: Option<db::FileMode>,
+) -> Result<db::WatchedFolder, AppError> {
+    let folder = std::path::Path::new(&path);
+    if !folder.is_dir() {
+        return Err(AppError::Validation(format!("Not a folder: {}", path)));
+    }
+    let papers_dir = import::papers_dir(&handle)?;
+    if papers_dir.starts_with(folder) || folder.starts_with(&papers_dir) {
+        return Err(AppError::Validation("Cannot watch the library's own storage folder".to_string()));
+    }
+
+    let recursive = recursive.unwrap_or(false);
+    let file_mode = file_mode.unwrap_or_else(|| config::load(&handle).import.file_mode);
+    let watched = db::add_watched_folder(&state.db, &path, recursive, file_mode).await?;
+    if let Err(e) = state.watcher.watch(folder, recursive) {
+        let _ = db::remove_watched_folder(&state.db, watched.id).await;
+        return Err(e.into());
+    }
+
+    Ok(watched)
+}
+
+#[tauri::command]
+async fn remove_watched_folder(state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    let removed = db::remove_watched_folder(&state.db, id).await?;
+    // The folder may already be gone from disk, in which case there is nothing to unwatch
+    if let Err(e) = state.watcher.unwatch(std::path::Path::new(&removed.path)) {
+        tracing::warn!("{}", e);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_watched_folders(state: State<'_, AppState>) -> Result<Vec<db::WatchedFolder>, AppError> {
+    db::get_watched_folders(&state.db).await
 }
 
 #[tauri::command]
@@ -41,16 +1862,508 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn db_test(state: State<'_, AppState>) -> Result<String, String> {
-    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM papers")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(format!("Database connected. Papers stored: {}", row.0))
+async fn db_test(state: State<'_, AppState>) -> Result<String, AppError> {
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM papers")
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(format!("Database connected. Papers stored: {}", row.0))
+}
+
+// A health report of the database, storage and search index for support requests
+#[tauri::command]
+async fn run_diagnostics(handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<diagnostics::Diagnostics, AppError> {
+    Ok(diagnostics::run(&handle, &state.db, &state.db_path).await)
+}
+
+#[tauri::command]
+async fn get_database_path(state: State<'_, AppState>) -> Result<String, AppError> {
+    Ok(state.db_path.display().to_string())
+}
+
+// None goes back to the default location. An empty target gets a copy of the current
+// library; an existing database there is used as is.
+#[tauri::command]
+async fn set_database_path(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, AppError> {
+    let mut app_config = config::load(&handle);
+
+    match path {
+        Some(path) => {
+            let path = std::path::PathBuf::from(path);
+            if !path.is_absolute() {
+                return Err(AppError::Validation("Database path must be absolute".to_string()));
+            }
+            if !path.exists() {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+                }
+                db::copy_database(&state.db, &path).await?;
+            }
+            app_config.database_path = Some(path);
+        }
+        None => app_config.database_path = None,
+    }
+
+    config::save(&handle, &app_config)?;
+    let new_path = db::database_path(&handle)?;
+    Ok(format!("Database location set to {}. Restart to use it.", new_path.display()))
+}
+
+// Moves every stored PDF to the new folder, or back to the default one for None
+#[tauri::command]
+async fn set_papers_dir(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, AppError> {
+    let old_dir = import::papers_dir(&handle)?;
+    let old_config = config::load(&handle);
+    let mut app_config = old_config.clone();
+    app_config.papers_dir = path.map(std::path::PathBuf::from);
+    let new_dir = match &app_config.papers_dir {
+        Some(dir) if !dir.is_absolute() => {
+            return Err(AppError::Validation("Papers folder must be an absolute path".to_string()))
+        }
+        Some(dir) => dir.clone(),
+        None => handle
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| format!("Path resolve error: {}", e))?
+            .join("papers"),
+    };
+    if new_dir == old_dir {
+        config::save(&handle, &app_config)?;
+        return Ok(format!("Papers are stored in {}", new_dir.display()));
+    }
+    if new_dir.starts_with(&old_dir) || old_dir.starts_with(&new_dir) {
+        return Err(AppError::Validation("Choose a folder outside the current papers folder".to_string()));
+    }
+    if fs::read_dir(&new_dir).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(AppError::Validation("Choose an empty folder".to_string()));
+    }
+
+    move_papers(&old_dir, &new_dir).await?;
+
+    // The paths are only committed once the config points at the new folder too; if either
+    // fails, the files go back and the library is as it was
+    let prefix = |dir: &std::path::Path| format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR);
+    let rebased = async {
+        let mut tx = state.db.begin().await?;
+        let moved = db::rebase_pdf_paths_in(&mut tx, &prefix(&old_dir), &prefix(&new_dir)).await?;
+        config::save(&handle, &app_config)?;
+        tx.commit().await?;
+        Ok::<_, AppError>(moved)
+    }
+    .await;
+    match rebased {
+        Ok(moved) => Ok(format!("Moved {} papers to {}", moved, new_dir.display())),
+        Err(e) => {
+            // An unsaved config is left as it was; a saved one must not outlive the rollback
+            let _ = config::save(&handle, &old_config);
+            match move_papers(&new_dir, &old_dir).await {
+                Ok(()) => Err(e),
+                Err(back) => {
+                    tracing::error!("Failed to move papers back to {}: {}", old_dir.display(), back);
+                    Err(AppError::Io(format!(
+                        "{}; the papers are still in {} and couldn't be moved back: {}",
+                        e,
+                        new_dir.display(),
+                        back
+                    )))
+                }
+            }
+        }
+    }
+}
+
+async fn move_papers(from: &std::path::Path, to: &std::path::Path) -> Result<(), AppError> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        backup::move_path(&from, &to)
+    })
+    .await
+    .map_err(|e| format!("Failed to move papers: {}", e))?
+    .map_err(|e| AppError::Io(format!("Failed to move papers: {}", e)))
+}
+
+// Every setting; see settings.rs
+#[tauri::command]
+async fn get_settings(handle: tauri::AppHandle) -> Result<config::AppConfig, AppError> {
+    Ok(config::load(&handle))
+}
+
+#[tauri::command]
+async fn update_settings(handle: tauri::AppHandle, settings: serde_json::Value) -> Result<config::AppConfig, AppError> {
+    settings::update(&handle, settings).await
+}
+
+#[tauri::command]
+async fn get_database_pool_size(handle: tauri::AppHandle) -> Result<u32, AppError> {
+    Ok(config::load(&handle).database_pool_size.unwrap_or(db::DEFAULT_POOL_SIZE))
+}
+
+// None goes back to the default. Like a new database location, this applies after a restart.
+#[tauri::command]
+async fn set_database_pool_size(handle: tauri::AppHandle, size: Option<u32>) -> Result<u32, AppError> {
+    settings::validate_pool_size(size)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.database_pool_size = size;
+    config::save(&handle, &app_config)?;
+    Ok(size.unwrap_or(db::DEFAULT_POOL_SIZE))
+}
+
+#[tauri::command]
+async fn create_backup(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    dest_path: String,
+) -> Result<backup::BackupInfo, AppError> {
+    state.notes.flush_all().await?;
+    let papers_dir = import::papers_dir(&handle)?;
+    Ok(backup::create_backup(&state.db, &papers_dir, std::path::Path::new(&dest_path)).await?)
+}
+
+// Replaces the whole library with the backup's contents and restarts the app. If the files
+// can't be swapped, the old ones stay and the error is returned instead.
+#[tauri::command]
+async fn restore_backup(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), AppError> {
+    let papers_dir = import::papers_dir(&handle)?;
+    let staged = backup::stage_restore(std::path::Path::new(&path), &papers_dir).await?;
+
+    state.notes.flush_all().await?;
+    state.db.close().await;
+    if let Err(e) = staged.swap(&state.db_path, &papers_dir) {
+        // swap puts the old files back, but the pool stays closed until the next start
+        tracing::error!("Restore failed, library left unchanged: {}", e);
+        return Err(AppError::Io(format!(
+            "{}. The library was left as it was; restart the app to keep using it.",
+            e
+        )));
+    }
+
+    // Start over on the restored library
+    handle.restart()
+}
+
+#[tauri::command]
+async fn get_file_naming(handle: tauri::AppHandle) -> Result<config::FileNaming, AppError> {
+    Ok(config::load(&handle).file_naming)
+}
+
+#[tauri::command]
+async fn set_file_naming(handle: tauri::AppHandle, naming: config::FileNaming) -> Result<config::FileNaming, AppError> {
+    file_naming::validate_pattern(&naming.pattern)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.file_naming = naming.clone();
+    config::save(&handle, &app_config)?;
+
+    Ok(naming)
+}
+
+#[tauri::command]
+async fn get_summarizer_settings(handle: tauri::AppHandle) -> Result<summarize::SummarizerStatus, AppError> {
+    Ok(summarize::SummarizerStatus {
+        settings: config::load(&handle).summarizer,
+        api_key_set: secrets::get(summarize::API_KEY_SECRET)?.is_some(),
+    })
+}
+
+// `api_key` replaces the saved key when given; an empty one removes it. Local servers such
+// as Ollama don't need one.
+#[tauri::command]
+async fn set_summarizer_settings(
+    handle: tauri::AppHandle,
+    settings: config::SummarizerSettings,
+    api_key: Option<String>,
+) -> Result<summarize::SummarizerStatus, AppError> {
+    settings::validate_summarizer(&settings)?;
+    match api_key.as_deref().map(str::trim) {
+        Some("") => secrets::delete(summarize::API_KEY_SECRET)?,
+        Some(key) => secrets::set(summarize::API_KEY_SECRET, key)?,
+        None => {}
+    }
+
+    let mut app_config = config::load(&handle);
+    app_config.summarizer = settings;
+    config::save(&handle, &app_config)?;
+
+    get_summarizer_settings(handle).await
+}
+
+// Sends the paper's text to the configured model and stores what comes back, replacing any
+// earlier summary
+#[tauri::command]
+async fn summarize_paper(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<db::summaries::Summary, AppError> {
+    let settings = config::load(&handle).summarizer;
+    if !settings.enabled {
+        return Err(AppError::Validation("Paper summaries are turned off; turn them on in settings first".to_string()));
+    }
+    let api_key = secrets::get(summarize::API_KEY_SECRET)?;
+    let text = db::get_paper_text(&state.db, paper_id).await?;
+
+    let generated = summarize::summarize(&state.http, &settings, api_key.as_deref(), &text)
+        .await
+        .map_err(AppError::Network)?;
+    db::summaries::save_summary(&state.db, paper_id, &generated.summary, &generated.key_points, &generated.model).await
+}
+
+#[tauri::command]
+async fn get_summary(state: State<'_, AppState>, paper_id: i64) -> Result<Option<db::summaries::Summary>, AppError> {
+    db::summaries::get_summary(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn get_backup_status(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<autobackup::BackupStatus, AppError> {
+    Ok(state.autobackup.status(&handle))
+}
+
+#[tauri::command]
+async fn set_backup_schedule(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    schedule: config::BackupSchedule,
+) -> Result<autobackup::BackupStatus, AppError> {
+    settings::validate_backup(&schedule)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.backup = schedule;
+    config::save(&handle, &app_config)?;
+
+    Ok(state.autobackup.status(&handle))
+}
+
+// Checks integrity, then VACUUMs and ANALYZEs the database; a damaged one is only checked
+#[tauri::command]
+async fn maintain_database(state: State<'_, AppState>) -> Result<maintenance::MaintenanceReport, AppError> {
+    Ok(state.maintenance.run().await?)
+}
+
+#[tauri::command]
+async fn get_clipboard_watch(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.clipboard.is_enabled())
+}
+
+#[tauri::command]
+async fn set_clipboard_watch(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<bool, AppError> {
+    let mut app_config = config::load(&handle);
+    app_config.watch_clipboard = enabled;
+    config::save(&handle, &app_config)?;
+    state.clipboard.set_enabled(enabled);
+    Ok(enabled)
+}
+
+#[tauri::command]
+async fn get_hooks(handle: tauri::AppHandle) -> Result<Vec<config::Hook>, AppError> {
+    Ok(config::load(&handle).hooks)
+}
+
+#[tauri::command]
+async fn set_hooks(
+    handle: tauri::AppHandle,
+    running: State<'_, hooks::Hooks>,
+    hooks: Vec<config::Hook>,
+) -> Result<Vec<config::Hook>, AppError> {
+    let mut app_config = config::load(&handle);
+    app_config.hooks = hooks;
+    config::save(&handle, &app_config)?;
+    running.set(app_config.hooks.clone());
+    Ok(app_config.hooks)
+}
+
+// Runs a hook once with one of the library's papers and returns what it printed
+#[tauri::command]
+async fn test_hook(state: State<'_, AppState>, hook: config::Hook, paper_id: i64) -> Result<hooks::HookOutput, AppError> {
+    if hook.command.trim().is_empty() {
+        return Err(AppError::Validation("The hook has no command".to_string()));
+    }
+    let paper = get_paper(&state.db, paper_id).await?;
+    Ok(hooks::test(&hook, &paper).await?)
+}
+
+#[tauri::command]
+async fn get_companion_status(state: State<'_, AppState>) -> Result<companion::CompanionStatus, AppError> {
+    Ok(state.companion.status().await?)
+}
+
+// Restarts the endpoint with the new settings
+#[tauri::command]
+async fn set_companion_settings(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: config::CompanionSettings,
+) -> Result<companion::CompanionStatus, AppError> {
+    settings::validate_companion(&settings)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.companion = settings;
+    config::save(&handle, &app_config)?;
+    state.companion.restart().await;
+
+    Ok(state.companion.status().await?)
+}
+
+// The extension has to be given the new token
+#[tauri::command]
+async fn regenerate_companion_token(state: State<'_, AppState>) -> Result<companion::CompanionStatus, AppError> {
+    state.companion.regenerate_token().await?;
+    Ok(state.companion.status().await?)
+}
+
+// Links that arrived since the last call; see deep_link.rs
+#[tauri::command]
+fn take_deep_links(deep_links: State<'_, deep_link::DeepLinks>) -> Vec<String> {
+    deep_links.take()
+}
+
+// The paper a papermaster:// link points to, importing it first if the library doesn't have it
+#[tauri::command]
+async fn open_deep_link(handle: tauri::AppHandle, state: State<'_, AppState>, link: String) -> Result<db::Paper, AppError> {
+    match deep_link::parse(&link).map_err(AppError::Validation)? {
+        deep_link::DeepLink::Open(paper_id) => Ok(get_paper(&state.db, paper_id).await?),
+        deep_link::DeepLink::Import(request) => {
+            let doi = request.doi.as_deref().and_then(crossref::normalize_doi);
+            let arxiv_id = request.arxiv_id.as_deref().and_then(arxiv::parse_id);
+            if doi.is_some() || arxiv_id.is_some() {
+                if let Some(existing) = db::find_matching_paper(&state.db, doi.as_deref(), arxiv_id.as_deref(), None, None).await? {
+                    return Ok(existing);
+                }
+            }
+            save_from_companion(&handle, companion::Save::Link(request)).await
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_maintenance_status(state: State<'_, AppState>) -> Result<maintenance::MaintenanceStatus, AppError> {
+    Ok(state.maintenance.status())
+}
+
+#[tauri::command]
+async fn set_maintenance_schedule(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    schedule: config::MaintenanceSchedule,
+) -> Result<maintenance::MaintenanceStatus, AppError> {
+    settings::validate_maintenance(&schedule)?;
+
+    let mut app_config = config::load(&handle);
+    app_config.maintenance = schedule;
+    config::save(&handle, &app_config)?;
+
+    Ok(state.maintenance.status())
+}
+
+#[tauri::command]
+async fn get_zotero_sync_status(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<zotero_sync::ZoteroSyncStatus, AppError> {
+    Ok(state.zotero_sync.status(&handle, &state.db).await?)
+}
+
+// `api_key` replaces the saved key when given; an empty one disconnects the account
+#[tauri::command]
+async fn set_zotero_sync(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+    api_key: Option<String>,
+    interval_minutes: Option<u64>,
+) -> Result<zotero_sync::ZoteroSyncStatus, AppError> {
+    let mut app_config = config::load(&handle);
+    let settings = &mut app_config.zotero;
+
+    match api_key.as_deref().map(str::trim) {
+        Some("") => {
+            secrets::delete(zotero_sync::API_KEY_SECRET)?;
+            settings.has_api_key = false;
+            settings.user_id = None;
+            settings.username = None;
+        }
+        Some(key) => {
+            let info = zotero_api::key_info(&state.http, key).await?;
+            secrets::set(zotero_sync::API_KEY_SECRET, key)?;
+            settings.has_api_key = true;
+            settings.user_id = Some(info.user_id);
+            settings.username = Some(info.username);
+        }
+        None => {}
+    }
+    if let Some(minutes) = interval_minutes {
+        settings::validate_zotero_interval(minutes)?;
+        settings.interval_minutes = minutes;
+    }
+    if enabled && !settings.has_api_key {
+        return Err(AppError::Validation("Add a Zotero API key before turning on sync".to_string()));
+    }
+    settings.enabled = enabled;
+    config::save(&handle, &app_config)?;
+
+    Ok(state.zotero_sync.status(&handle, &state.db).await?)
+}
+
+#[tauri::command]
+async fn sync_zotero(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<zotero_sync::SyncSummary, AppError> {
+    // Pending autosaves count as local edits
+    state.notes.flush_all().await?;
+    Ok(state.zotero_sync.sync_now(&handle, &state.db, &state.http).await?)
+}
+
+#[tauri::command]
+async fn resolve_zotero_conflict(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    keep: zotero_sync::ConflictSide,
+) -> Result<(), AppError> {
+    state.notes.flush(paper_id).await?;
+    state
+        .zotero_sync
+        .resolve_conflict(&handle, &state.db, &state.http, paper_id, keep)
+        .await?;
+    updated_paper(&handle, &state.db, paper_id).await?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_schema_version(state: State<'_, AppState>) -> Result<db::SchemaVersion, AppError> {
+    db::get_schema_version(&state.db).await
 }
 
 #[tauri::command]
-async fn add_paper(handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+async fn add_paper(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    on_duplicate: Option<DuplicatePolicy>,
+    file_mode: Option<db::FileMode>,
+) -> Result<String, AppError> {
     //bridge callback-based dialog API into async
     let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -68,72 +2381,1100 @@ async fn add_paper(handle: tauri::AppHandle, state: State<'_, AppState>) -> Resu
     // Convert FilePath enum into PathBuf
     let selected_path = match selected_path {
         Some(FilePath::Path(path)) => path,
-        Some(FilePath::Url(_)) => return Err("URL selection not supported".to_string()),
+        Some(FilePath::Url(_)) => return Err(AppError::Validation("URL selection not supported".to_string())),
         None => return Ok("No file selected".to_string()),
     };
 
-    // Resolve app-local data directory
-    let app_data_dir = handle
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| format!("Path resolve error: {}", e))?;
+    let imported = import::import_file(
+        &handle,
+        &state.db,
+        &selected_path,
+        NewPaper {
+            file_mode: file_mode.unwrap_or_else(|| config::load(&handle).import.file_mode),
+            ..Default::default()
+        },
+        on_duplicate.unwrap_or_else(|| config::load(&handle).import.on_duplicate),
+    )
+    .await?;
+    events::paper_added(&handle, &imported.paper);
+    state.thumbnails.wake();
+
+    match imported.duplicate_of {
+        Some(existing) => Ok(format!(
+            "Paper added successfully: {} (duplicate of \"{}\")",
+            imported.paper.title, existing.title
+        )),
+        None => Ok(format!("Paper added successfully: {}", imported.paper.title)),
+    }
+}
+
+
+#[tauri::command]
+async fn add_paper_from_path(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    on_duplicate: Option<DuplicatePolicy>,
+    file_mode: Option<db::FileMode>,
+) -> Result<db::Paper, AppError> {
+    let source = std::path::Path::new(&path);
+    if !source.is_file() {
+        return Err(AppError::NotFound(format!("File not found: {}", path)));
+    }
+    if !source.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+        return Err(AppError::Validation(format!("Not a PDF: {}", path)));
+    }
+
+    let imported = import::import_file(
+        &handle,
+        &state.db,
+        source,
+        NewPaper {
+            file_mode: file_mode.unwrap_or_else(|| config::load(&handle).import.file_mode),
+            ..Default::default()
+        },
+        on_duplicate.unwrap_or_else(|| config::load(&handle).import.on_duplicate),
+    )
+    .await?;
+    events::paper_added(&handle, &imported.paper);
+    state.thumbnails.wake();
+
+    Ok(imported.paper)
+}
+
+// Downloads queued, running, and recently finished, oldest first
+#[tauri::command]
+async fn list_downloads(state: State<'_, AppState>) -> Result<Vec<downloads::DownloadInfo>, AppError> {
+    Ok(state.downloads.list())
+}
+
+// Stops a command started with this `op_id`; it returns early with "Cancelled", or with what
+// it got done before that
+#[tauri::command]
+async fn cancel_operation(state: State<'_, AppState>, op_id: String) -> Result<(), AppError> {
+    Ok(state.operations.cancel(&op_id)?)
+}
+
+#[tauri::command]
+async fn cancel_download(state: State<'_, AppState>, id: u64) -> Result<(), AppError> {
+    Ok(state.downloads.cancel(id)?)
+}
+
+// Downloads the PDF behind a direct link and imports it like a local file
+#[tauri::command]
+async fn import_from_url(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    on_duplicate: Option<DuplicatePolicy>,
+    op_id: Option<String>,
+) -> Result<db::Paper, AppError> {
+    let operation = state.operations.begin(op_id)?;
+    let on_duplicate = on_duplicate.unwrap_or_else(|| config::load(&handle).import.on_duplicate);
+    download_paper(&handle, &state, &url, NewPaper::default(), on_duplicate, Some(&operation.token))
+        .await
+}
 
-    let papers_dir = app_data_dir.join("papers");
-    fs::create_dir_all(&papers_dir).map_err(|e| e.to_string())?;
+// Downloads a PDF from a direct link and imports it with whatever is already `known` about it
+async fn download_paper(
+    handle: &tauri::AppHandle,
+    state: &AppState,
+    url: &str,
+    known: NewPaper,
+    on_duplicate: DuplicatePolicy,
+    cancel: Option<&CancellationToken>,
+) -> Result<db::Paper, AppError> {
+    let papers_dir = import::papers_dir(handle)?;
+    // The name is only known once the server has answered, so the file arrives under a temporary one
+    let temporary = papers_dir.join(format!(".download-{}.pdf", uuid::Uuid::new_v4()));
+    let file_name = state
+        .downloads
+        .fetch(downloads::DownloadRequest {
+            url: url.trim(),
+            dest: &temporary,
+            label: url.trim().to_string(),
+            https_only: true,
+            cancel,
+        })
+        .await?;
+
+    let dest = import::unique_destination(&papers_dir, &file_name);
+    if let Err(e) = fs::rename(&temporary, &dest) {
+        let _ = fs::remove_file(&temporary);
+        return Err(AppError::Io(format!("Failed to save PDF: {}", e)));
+    }
+
+    match import::register_file(&state.db, &dest, known, on_duplicate).await {
+        Ok(imported) => {
+            events::paper_added(handle, &imported.paper);
+            state.thumbnails.wake();
+            Ok(imported.paper)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&dest);
+            Err(e.into())
+        }
+    }
+}
+
+// The arguments the app was launched with, or those of a later launch passed on by the
+// single-instance plugin: papermaster:// links go to the frontend, and PDFs are opened.
+// Either way the window comes to the front.
+pub(crate) async fn open_launch_args(handle: &tauri::AppHandle, args: Vec<String>, cwd: &std::path::Path) {
+    deep_link::focus_main_window(handle);
+    let links = deep_link::links_in(args.iter().cloned());
+    let paths: Vec<_> = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-') && !links.contains(arg))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")))
+        .collect();
+    handle.state::<deep_link::DeepLinks>().receive(handle, links);
+
+    for path in paths {
+        match open_pdf(handle, &path).await {
+            Ok(paper) => handle
+                .state::<deep_link::DeepLinks>()
+                .receive(handle, vec![deep_link::open_link(paper.id)]),
+            Err(e) => tracing::warn!("Failed to open {}: {}", path.display(), e),
+        }
+    }
+}
+
+// The library's paper for a PDF opened with the app, imported first unless the same file
+// is already in it
+async fn open_pdf(handle: &tauri::AppHandle, path: &std::path::Path) -> Result<db::Paper, AppError> {
+    let state = handle.state::<AppState>();
+    let hash = import::file_sha256_async(path).await?;
+    if let Some(existing) = db::find_paper_by_hash(&state.db, &hash).await? {
+        return Ok(existing);
+    }
+    add_paper_from_path(handle.clone(), state, path.to_string_lossy().into_owned(), None, None).await
+}
+
+// Saves a paper sent by the browser extension (see companion.rs): the uploaded PDF, else the
+// arXiv paper, else the PDF behind the link, else the DOI's Crossref record
+pub(crate) async fn save_from_companion(handle: &tauri::AppHandle, save: companion::Save) -> Result<db::Paper, AppError> {
+    let state = handle.state::<AppState>();
+    let known = |request: &companion::SaveRequest| NewPaper {
+        title: request.title.clone().unwrap_or_default(),
+        doi: request.doi.as_deref().and_then(crossref::normalize_doi),
+        arxiv_id: request.arxiv_id.as_deref().and_then(arxiv::parse_id),
+        ..Default::default()
+    };
+
+    let request = match save {
+        companion::Save::Pdf { file, file_name, request } => {
+            let dest = import::unique_destination(&import::papers_dir(handle)?, &file_name);
+            if let Err(e) = fs::rename(&file, &dest) {
+                let _ = fs::remove_file(&file);
+                return Err(AppError::Io(format!("Failed to save PDF: {}", e)));
+            }
+            return match import::register_file(&state.db, &dest, known(&request), DuplicatePolicy::Reject).await {
+                Ok(imported) => {
+                    events::paper_added(handle, &imported.paper);
+                    state.thumbnails.wake();
+                    Ok(imported.paper)
+                }
+                Err(e) => {
+                    let _ = fs::remove_file(&dest);
+                    Err(e.into())
+                }
+            };
+        }
+        companion::Save::Link(request) => request,
+    };
 
-    // Extract filename
-    let file_name = selected_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or("Invalid file name")?
-        .to_string();
+    let arxiv_id = request.arxiv_id.as_deref().or(request.url.as_deref()).and_then(arxiv::parse_id);
+    if let Some(id) = arxiv_id {
+        if let Some(existing) = db::find_matching_paper(&state.db, None, Some(&id), None, None).await? {
+            return Err(AppError::Conflict(format!("Already in library: {}", existing.title)));
+        }
+        return Ok(add_arxiv_paper(handle, &state, &id, None).await?);
+    }
 
-    let dest_path = papers_dir.join(&file_name);
+    if let Some(url) = request.url.as_deref().filter(|u| !u.trim().is_empty()) {
+        match download_paper(handle, &state, url, known(&request), DuplicatePolicy::Reject, None).await {
+            Ok(paper) => return Ok(paper),
+            // The link may be the article's page rather than its PDF; the DOI still finds it
+            Err(e) if request.doi.is_some() && !matches!(e, AppError::Conflict(_) | AppError::Cancelled) => {
+                tracing::info!("{}: {}; saving by DOI instead", url, e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
 
-    // Avoid overwriting existing files
-    let mut counter = 1;
-    let mut final_dest = dest_path.clone();
-    while final_dest.exists() {
-        let new_name = format!("{}_{}.pdf", file_name.trim_end_matches(".pdf"), counter);
-        final_dest = papers_dir.join(new_name);
-        counter += 1;
+    match request.doi {
+        Some(doi) => Ok(import_from_doi(handle.clone(), state, doi, Some(true)).await?.paper),
+        None => Err(AppError::Validation("Send a url, doi or arxiv_id".to_string())),
     }
+}
 
-    // Copy file into app storage
-    fs::copy(&selected_path, &final_dest).map_err(|e| format!("Copy failed: {}", e))?;
+#[tauri::command]
+async fn add_papers_from_paths(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    on_duplicate: Option<DuplicatePolicy>,
+    file_mode: Option<db::FileMode>,
+    op_id: Option<String>,
+) -> Result<import::ImportSummary, AppError> {
+    let operation = state.operations.begin(op_id)?;
+    let mut summary = import::ImportSummary::default();
+
+    let total = paths.len();
+    for (index, path) in paths.into_iter().enumerate() {
+        if operation.token.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+        match add_paper_from_path(handle.clone(), state.clone(), path.clone(), on_duplicate, file_mode).await {
+            Ok(_) => summary.imported += 1,
+            Err(e) => {
+                tracing::warn!("Import of {} failed: {}", path, e);
+                summary.skipped += 1;
+                summary.errors.push(format!("{}: {}", path, e));
+            }
+        }
 
-    
-    let title = final_dest
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("Untitled")
-        .to_string();
+        let _ = handle.emit(
+            "import:progress",
+            import::ImportProgress {
+                current: index + 1,
+                total,
+                path,
+                imported: summary.imported,
+                skipped: summary.skipped,
+            },
+        );
+    }
 
-    let internal_path = final_dest.to_str().unwrap().to_string();
+    Ok(summary)
+}
+#[tauri::command]
+async fn import_directory(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    recursive: bool,
+    file_mode: Option<db::FileMode>,
+    op_id: Option<String>,
+) -> Result<import::ImportSummary, AppError> {
+    let operation = state.operations.begin(op_id)?;
+    let dir = std::path::PathBuf::from(&path);
+    if !dir.is_dir() {
+        return Err(AppError::Validation(format!("Not a folder: {}", path)));
+    }
+    let papers_dir = import::papers_dir(&handle)?;
+    let file_mode = file_mode.unwrap_or_else(|| config::load(&handle).import.file_mode);
 
-    // Insert metadata into database
-    insert_paper(&state.db, &title, &internal_path)
+    let pdfs = tokio::task::spawn_blocking(move || import::find_pdfs(&dir, recursive))
         .await
-        .map_err(|e| format!("Database insert failed: {}", e))?;
+        .map_err(|e| format!("Failed to scan folder: {}", e))?;
+    let total = pdfs.len();
+    let mut summary = import::ImportSummary::default();
+
+    for (index, pdf) in pdfs.iter().enumerate() {
+        if operation.token.is_cancelled() {
+            summary.cancelled = true;
+            break;
+        }
+        let result = if pdf.starts_with(&papers_dir) {
+            Err("Already in library storage".to_string())
+        } else {
+            let known = NewPaper {
+                file_mode,
+                ..Default::default()
+            };
+            import::import_file(&handle, &state.db, pdf, known, DuplicatePolicy::Reject)
+                .await
+                .map(|imported| imported.paper)
+        };
+
+        match result {
+            Ok(paper) => {
+                summary.imported += 1;
+                events::paper_added(&handle, &paper);
+            }
+            Err(e) => {
+                tracing::warn!("Import of {} failed: {}", pdf.display(), e);
+                summary.skipped += 1;
+                summary.errors.push(format!("{}: {}", pdf.display(), e));
+            }
+        }
+
+        let _ = handle.emit(
+            "import:progress",
+            import::ImportProgress {
+                current: index + 1,
+                total,
+                path: pdf.display().to_string(),
+                imported: summary.imported,
+                skipped: summary.skipped,
+            },
+        );
+    }
+    state.thumbnails.wake();
+
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn list_collections(state: State<'_, AppState>) -> Result<Vec<db::collections::Collection>, AppError> {
+    db::collections::get_collections(&state.db).await
+}
+
+#[tauri::command]
+async fn create_collection(
+    state: State<'_, AppState>,
+    name: String,
+    parent_id: Option<i64>,
+) -> Result<db::collections::Collection, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Collection name cannot be empty".to_string()));
+    }
+    db::collections::create_collection(&state.db, name, parent_id).await
+}
+
+#[tauri::command]
+async fn rename_collection(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+) -> Result<db::collections::Collection, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Collection name cannot be empty".to_string()));
+    }
+    db::collections::rename_collection(&state.db, id, name).await
+}
+
+#[tauri::command]
+async fn delete_collection(handle: tauri::AppHandle, state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    let collection = db::collections::get_collection(&state.db, id).await?;
+    let undo = db::journal::snapshot_collection(&state.db, id).await?;
+    db::collections::delete_collection(&state.db, id).await?;
+    db::journal::record(&state.db, &format!("Delete collection \"{}\"", collection.name), &undo).await?;
+    events::papers_updated(&handle, &state.db, &undo.paper_ids()).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn add_to_collection(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    collection_id: i64,
+    paper_ids: Vec<i64>,
+) -> Result<(), AppError> {
+    let collection = db::collections::get_collection(&state.db, collection_id).await?;
+    let before = db::journal::snapshot_papers(&state.db, &paper_ids).await?;
+    db::collections::add_papers(&state.db, collection_id, &paper_ids).await?;
+    record_paper_changes(&state.db, "Add", &format!(" to \"{}\"", collection.name), before).await;
+    events::papers_updated(&handle, &state.db, &paper_ids).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_from_collection(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    collection_id: i64,
+    paper_ids: Vec<i64>,
+) -> Result<(), AppError> {
+    let collection = db::collections::get_collection(&state.db, collection_id).await?;
+    let before = db::journal::snapshot_papers(&state.db, &paper_ids).await?;
+    db::collections::remove_papers(&state.db, collection_id, &paper_ids).await?;
+    record_paper_changes(&state.db, "Remove", &format!(" from \"{}\"", collection.name), before).await;
+    events::papers_updated(&handle, &state.db, &paper_ids).await;
+    Ok(())
+}
+#[tauri::command]
+async fn get_authors(state: State<'_, AppState>) -> Result<Vec<db::authors::Author>, AppError> {
+    db::authors::get_authors(&state.db).await
+}
+
+#[tauri::command]
+async fn get_papers_by_author(state: State<'_, AppState>, author_id: i64) -> Result<Vec<db::Paper>, AppError> {
+    db::authors::get_author(&state.db, author_id).await?;
+    db::authors::get_papers_by_author(&state.db, author_id).await
+}
+
+// Unifies spellings of one person, e.g. "J. Smith" into "John Smith". Bylines on the papers
+// are left as printed.
+#[tauri::command]
+async fn merge_authors(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    keep_id: i64,
+    merge_ids: Vec<i64>,
+) -> Result<db::authors::Author, AppError> {
+    if merge_ids.iter().all(|id| *id == keep_id) {
+        return Err(AppError::Validation("Choose at least one other author to merge".to_string()));
+    }
+    let mut moved = Vec::new();
+    for author_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        moved.extend(db::authors::get_papers_by_author(&state.db, *author_id).await?.into_iter().map(|p| p.id));
+    }
+
+    let author = db::authors::merge_authors(&state.db, keep_id, &merge_ids).await?;
+    events::papers_updated(&handle, &state.db, &moved).await;
+    Ok(author)
+}
+
+#[tauri::command]
+async fn get_venues(state: State<'_, AppState>) -> Result<Vec<db::venues::Venue>, AppError> {
+    db::venues::get_venues(&state.db).await
+}
+
+// Newest first, whichever way each paper spells the venue
+#[tauri::command]
+async fn get_papers_by_venue(state: State<'_, AppState>, venue_id: i64) -> Result<Vec<db::Paper>, AppError> {
+    db::venues::get_venue(&state.db, venue_id).await?;
+    let filter = db::PaperFilter {
+        venue_id: Some(venue_id),
+        sort: Some(vec![db::SortKey { field: db::SortBy::Year, descending: None }]),
+        ..Default::default()
+    };
+    db::get_papers_filtered(&state.db, &filter).await
+}
+
+#[tauri::command]
+async fn update_venue(
+    state: State<'_, AppState>,
+    id: i64,
+    name: Option<String>,
+    abbreviation: Option<String>,
+) -> Result<db::venues::Venue, AppError> {
+    let name = name.as_deref().map(str::trim);
+    let abbreviation = abbreviation.as_deref().map(str::trim);
+    db::venues::update_venue(&state.db, id, name, abbreviation).await
+}
+
+// Unifies spellings of one venue, e.g. "Proc. NeurIPS" into "Advances in Neural Information
+// Processing Systems". papers.journal is left as printed.
+#[tauri::command]
+async fn merge_venues(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    keep_id: i64,
+    merge_ids: Vec<i64>,
+) -> Result<db::venues::Venue, AppError> {
+    if merge_ids.iter().all(|id| *id == keep_id) {
+        return Err(AppError::Validation("Choose at least one other venue to merge".to_string()));
+    }
+    let mut moved = Vec::new();
+    for venue_id in merge_ids.iter().filter(|id| **id != keep_id) {
+        let filter = db::PaperFilter { venue_id: Some(*venue_id), ..Default::default() };
+        moved.extend(db::get_papers_filtered(&state.db, &filter).await?.into_iter().map(|p| p.id));
+    }
+
+    let venue = db::venues::merge_venues(&state.db, keep_id, &merge_ids).await?;
+    events::papers_updated(&handle, &state.db, &moved).await;
+    Ok(venue)
+}
+
+#[tauri::command]
+async fn list_smart_collections(
+    state: State<'_, AppState>,
+) -> Result<Vec<db::smart_collections::SmartCollection>, AppError> {
+    db::smart_collections::get_smart_collections(&state.db).await
+}
+
+#[tauri::command]
+async fn create_smart_collection(
+    state: State<'_, AppState>,
+    name: String,
+    query: db::smart_collections::SmartQuery,
+) -> Result<db::smart_collections::SmartCollection, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Collection name cannot be empty".to_string()));
+    }
+    validate_smart_query(&state.db, &query).await?;
+    db::smart_collections::create_smart_collection(&state.db, name, &query).await
+}
+
+#[tauri::command]
+async fn update_smart_collection(
+    state: State<'_, AppState>,
+    id: i64,
+    name: Option<String>,
+    query: Option<db::smart_collections::SmartQuery>,
+) -> Result<db::smart_collections::SmartCollection, AppError> {
+    let name = name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err(AppError::Validation("Collection name cannot be empty".to_string()));
+    }
+    if let Some(query) = &query {
+        validate_smart_query(&state.db, query).await?;
+    }
+    db::smart_collections::update_smart_collection(&state.db, id, name, query.as_ref()).await
+}
+
+#[tauri::command]
+async fn delete_smart_collection(state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    db::smart_collections::delete_smart_collection(&state.db, id).await
+}
+
+async fn validate_smart_query(pool: &SqlitePool, query: &db::smart_collections::SmartQuery) -> Result<(), AppError> {
+    if let (Some(from), Some(to)) = (query.year_from, query.year_to) {
+        if from > to {
+            return Err(AppError::Validation(format!("The year range {}-{} is empty", from, to)));
+        }
+    }
+    if let Some(collection_id) = query.collection_id {
+        db::collections::get_collection(pool, collection_id).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_saved_searches(state: State<'_, AppState>) -> Result<Vec<db::saved_searches::SavedSearch>, AppError> {
+    db::saved_searches::get_saved_searches(&state.db).await
+}
+
+#[tauri::command]
+async fn save_search(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    query: String,
+) -> Result<db::saved_searches::SavedSearch, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Search name cannot be empty".to_string()));
+    }
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::Validation("Search query cannot be empty".to_string()));
+    }
+    let saved = db::saved_searches::save_search(&state.db, name, query).await?;
+    saved_searches::emit_counts(&handle, &state.db).await;
+    Ok(saved)
+}
+
+// The papers the saved search finds, sorted and paged by `filter`, which can narrow further
+#[tauri::command]
+async fn run_saved_search(
+    state: State<'_, AppState>,
+    id: i64,
+    filter: Option<db::PaperFilter>,
+) -> Result<Vec<db::Paper>, AppError> {
+    let saved = db::saved_searches::get_saved_search(&state.db, id).await?;
+    let filter = db::saved_searches::apply(&saved.query, filter.unwrap_or_default());
+    db::get_papers_filtered(&state.db, &filter).await
+}
+
+#[tauri::command]
+async fn delete_saved_search(state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    db::saved_searches::delete_saved_search(&state.db, id).await
+}
+
+#[tauri::command]
+async fn list_custom_fields(state: State<'_, AppState>) -> Result<Vec<db::custom_fields::CustomField>, AppError> {
+    db::custom_fields::get_fields(&state.db).await
+}
+
+// `options` lists the allowed values of an enum field and is ignored for the other types
+#[tauri::command]
+async fn create_custom_field(
+    state: State<'_, AppState>,
+    name: String,
+    field_type: db::custom_fields::FieldType,
+    options: Option<Vec<String>>,
+) -> Result<db::custom_fields::CustomField, AppError> {
+    let name = name.trim();
+    db::custom_fields::validate_name(name)?;
+    let options = match field_type {
+        db::custom_fields::FieldType::Enum => db::custom_fields::clean_options(&options.unwrap_or_default()),
+        _ => Vec::new(),
+    };
+    if field_type == db::custom_fields::FieldType::Enum && options.is_empty() {
+        return Err(AppError::Validation("An enum field needs at least one option".to_string()));
+    }
+    db::custom_fields::create_field(&state.db, name, field_type, &options).await
+}
+
+// Renames the field or changes an enum field's options. Options still in use can't be removed.
+#[tauri::command]
+async fn update_custom_field(
+    state: State<'_, AppState>,
+    id: i64,
+    name: Option<String>,
+    options: Option<Vec<String>>,
+) -> Result<db::custom_fields::CustomField, AppError> {
+    let field = db::custom_fields::get_field(&state.db, id).await?;
+    let name = name.as_deref().map(str::trim);
+    if let Some(name) = name {
+        db::custom_fields::validate_name(name)?;
+    }
+
+    let options = match options {
+        Some(_) if field.field_type != db::custom_fields::FieldType::Enum => {
+            return Err(AppError::Validation(format!("{} has no options to change", field.name)));
+        }
+        Some(options) => Some(db::custom_fields::clean_options(&options)),
+        None => None,
+    };
+    if let Some(options) = &options {
+        if options.is_empty() {
+            return Err(AppError::Validation("An enum field needs at least one option".to_string()));
+        }
+        for removed in field.options.iter().filter(|o| !options.contains(o)) {
+            let used = db::custom_fields::count_values(&state.db, id, removed).await?;
+            if used > 0 {
+                return Err(AppError::Conflict(format!(
+                    "{} papers have {} set to \"{}\"; change them before removing the option",
+                    used, field.name, removed
+                )));
+            }
+        }
+    }
+    db::custom_fields::update_field(&state.db, id, name, options.as_deref()).await
+}
+
+#[tauri::command]
+async fn delete_custom_field(state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    db::custom_fields::delete_field(&state.db, id).await
+}
+
+#[tauri::command]
+async fn get_custom_values(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::custom_fields::CustomValue>, AppError> {
+    db::custom_fields::get_values(&state.db, paper_id).await
+}
+
+// A blank or missing value clears the field. Numbers and dates are stored normalised.
+#[tauri::command]
+async fn set_custom_value(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    field_id: i64,
+    value: Option<String>,
+) -> Result<Vec<db::custom_fields::CustomValue>, AppError> {
+    let field = db::custom_fields::get_field(&state.db, field_id).await?;
+    let value = db::custom_fields::normalize_value(&field, value.as_deref().unwrap_or_default())?;
+    db::custom_fields::set_value(&state.db, paper_id, field_id, value.as_deref()).await?;
+    updated_paper(&handle, &state.db, paper_id).await?;
+    db::custom_fields::get_values(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn list_tags(state: State<'_, AppState>) -> Result<Vec<db::tags::Tag>, AppError> {
+    db::tags::get_tags(&state.db).await
+}
+
+#[tauri::command]
+async fn create_tag(state: State<'_, AppState>, name: String) -> Result<db::tags::Tag, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("Tag name cannot be empty".to_string()));
+    }
+    // Paper.tags is a comma separated list, so a comma would split the tag in two
+    if name.contains(',') {
+        return Err(AppError::Validation("Tag names cannot contain commas".to_string()));
+    }
+    db::tags::create_tag(&state.db, name).await
+}
+
+#[tauri::command]
+async fn delete_tag(handle: tauri::AppHandle, state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    let undo = db::journal::snapshot_tag(&state.db, id).await?;
+    db::tags::delete_tag(&state.db, id).await?;
+    if let db::journal::UndoData::Tag { name, .. } = &undo {
+        db::journal::record(&state.db, &format!("Delete tag \"{}\"", name), &undo).await?;
+    }
+    events::papers_updated(&handle, &state.db, &undo.paper_ids()).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn tag_papers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    tag_id: i64,
+    paper_ids: Vec<i64>,
+) -> Result<(), AppError> {
+    let tag = db::tags::get_tag(&state.db, tag_id).await?;
+    let before = db::journal::snapshot_papers(&state.db, &paper_ids).await?;
+    db::tags::tag_papers(&state.db, tag_id, &paper_ids).await?;
+    record_paper_changes(&state.db, "Tag", &format!(" with \"{}\"", tag.name), before).await;
+    events::papers_updated(&handle, &state.db, &paper_ids).await;
+    Ok(())
+}
+
+// Keywords from the paper's text and library tags it mentions, for the user to pick from
+#[tauri::command]
+async fn get_tag_suggestions(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<keywords::TagSuggestion>, AppError> {
+    Ok(keywords::get_tag_suggestions(&state.db, paper_id).await?)
+}
+
+// Gives the paper every one of `names`, creating the tags that don't exist yet
+#[tauri::command]
+async fn accept_tag_suggestions(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    paper_id: i64,
+    names: Vec<String>,
+) -> Result<db::Paper, AppError> {
+    let names = db::tags::split_tags(&names.join(","));
+    if names.is_empty() {
+        return Err(AppError::Validation("No tag names given".to_string()));
+    }
+
+    let before = db::journal::snapshot_papers(&state.db, &[paper_id]).await?;
+    db::tags::add_paper_tags(&state.db, paper_id, &names).await?;
+    let rest = match names.as_slice() {
+        [name] => format!(" with \"{}\"", name),
+        names => format!(" with {} tags", names.len()),
+    };
+    record_paper_changes(&state.db, "Tag", &rest, before).await;
+    Ok(updated_paper(&handle, &state.db, paper_id).await?)
+}
 
-    Ok(format!("Paper added successfully: {}", title))
+#[tauri::command]
+async fn untag_papers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    tag_id: i64,
+    paper_ids: Vec<i64>,
+) -> Result<(), AppError> {
+    let tag = db::tags::get_tag(&state.db, tag_id).await?;
+    let before = db::journal::snapshot_papers(&state.db, &paper_ids).await?;
+    db::tags::untag_papers(&state.db, tag_id, &paper_ids).await?;
+    record_paper_changes(&state.db, &format!("Remove tag \"{}\" from", tag.name), "", before).await;
+    events::papers_updated(&handle, &state.db, &paper_ids).await;
+    Ok(())
+}
+
+// Recently journaled operations, newest first
+#[tauri::command]
+async fn list_operations(state: State<'_, AppState>) -> Result<Vec<db::journal::Operation>, AppError> {
+    db::journal::get_operations(&state.db).await
+}
+
+// Reverts the most recent journaled operation (an edit, tagging, filing, moving to Trash,
+// deleting a tag or collection or merging authors or venues) and returns it, or None when there is nothing left to undo.
+// Permanent deletes and emptying the Trash can't be undone.
+#[tauri::command]
+async fn undo_last_operation(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<db::journal::Operation>, AppError> {
+    let Some((operation, undo)) = db::journal::undo_last(&state.db).await? else {
+        return Ok(None);
+    };
+    // Undoing an edit brings back the old metadata, so the stored file follows it
+    if let db::journal::UndoData::Papers { papers, .. } = &undo {
+        for paper in papers {
+            rename_after_edit(&handle, &state.db, paper.id).await;
+        }
+    }
+    events::papers_updated(&handle, &state.db, &undo.paper_ids()).await;
+    Ok(Some(operation))
+}
+#[tauri::command]
+async fn get_annotations(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::annotations::Annotation>, AppError> {
+    db::annotations::get_annotations(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn add_annotation(
+    state: State<'_, AppState>,
+    annotation: db::annotations::NewAnnotation,
+) -> Result<db::annotations::Annotation, AppError> {
+    if annotation.page < 1 {
+        return Err(AppError::Validation("Page numbers start at 1".to_string()));
+    }
+    db::annotations::add_annotation(&state.db, &annotation).await
+}
+
+#[tauri::command]
+async fn update_annotation(
+    state: State<'_, AppState>,
+    id: i64,
+    changes: db::annotations::AnnotationUpdate,
+) -> Result<db::annotations::Annotation, AppError> {
+    if changes.page.is_some_and(|page| page < 1) {
+        return Err(AppError::Validation("Page numbers start at 1".to_string()));
+    }
+    db::annotations::update_annotation(&state.db, id, &changes).await
+}
+
+#[tauri::command]
+async fn delete_annotation(state: State<'_, AppState>, id: i64) -> Result<(), AppError> {
+    db::annotations::delete_annotation(&state.db, id).await
+}
+
+// The paper's highlights and comments as one document, grouped by page
+#[tauri::command]
+async fn export_annotations(
+    state: State<'_, AppState>,
+    paper_id: i64,
+    format: Option<formats::annotations::AnnotationFormat>,
+) -> Result<String, AppError> {
+    let paper = get_paper(&state.db, paper_id).await?;
+    let annotations = db::annotations::get_annotations(&state.db, paper_id).await?;
+    Ok(formats::annotations::export(&paper, &annotations, format.unwrap_or_default()))
+}
+
+// The PDF's table of contents for section navigation. Papers imported before outlines were
+// read get theirs from the file on first request.
+#[tauri::command]
+async fn get_outline(state: State<'_, AppState>, paper_id: i64) -> Result<Vec<db::outline::OutlineItem>, AppError> {
+    let outline = db::outline::get_outline(&state.db, paper_id).await?;
+    if !outline.is_empty() {
+        return Ok(outline);
+    }
+    let paper = get_paper(&state.db, paper_id).await?;
+    let path = std::path::Path::new(&paper.pdf_path);
+    if paper.pdf_path.is_empty() || !path.is_file() {
+        return Ok(outline);
+    }
+
+    let entries = pdf::extract_outline_async(path).await?;
+    if entries.is_empty() {
+        return Ok(outline);
+    }
+    db::outline::save_outline(&state.db, paper_id, &entries).await?;
+    db::outline::get_outline(&state.db, paper_id).await
 }
 
+// A copy of the paper's PDF with its highlights and comments embedded as regular PDF
+// annotations, for sharing with people who don't use the app. The library's own file is
+// left untouched.
+#[tauri::command]
+async fn export_annotated_pdf(state: State<'_, AppState>, paper_id: i64, dest: String) -> Result<String, AppError> {
+    let pdf_path = existing_pdf(&state.db, paper_id).await?;
+    let dest_path = std::path::Path::new(&dest);
+    if std::fs::canonicalize(dest_path).ok() == std::fs::canonicalize(&pdf_path).ok() {
+        return Err(AppError::Validation("Choose a different file than the paper's own PDF".to_string()));
+    }
+
+    let annotations: Vec<pdf::PdfAnnotation> = db::annotations::get_annotations(&state.db, paper_id)
+        .await?
+        .into_iter()
+        .map(|a| pdf::PdfAnnotation {
+            // An icon-sized mark with only a comment is a note, the rest are highlights
+            note: a.selected_text.is_none()
+                && a.comment.is_some()
+                && a.width <= pdf::NOTE_SIZE
+                && a.height <= pdf::NOTE_SIZE,
+            page: a.page,
+            x: a.x,
+            y: a.y,
+            width: a.width,
+            height: a.height,
+            color: Some(a.color),
+            comment: a.comment,
+        })
+        .collect();
+
+    let written = pdf::write_annotations_async(std::path::Path::new(&pdf_path), dest_path, annotations).await?;
+    Ok(format!("Exported {} annotations to {}", written, dest))
+}
+
+// Highlights and notes made in another viewer, for PDFs imported before they were read
+// automatically or annotated since. Ones already in the library are skipped.
+#[tauri::command]
+async fn import_pdf_annotations(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::annotations::Annotation>, AppError> {
+    let pdf_path = existing_pdf(&state.db, paper_id).await?;
+    Ok(import::import_pdf_annotations(&state.db, paper_id, std::path::Path::new(&pdf_path)).await?)
+}
+
+#[tauri::command]
+async fn get_notes(state: State<'_, AppState>, paper_id: i64) -> Result<db::notes::Notes, AppError> {
+    state.notes.flush(paper_id).await?;
+    db::notes::get_notes(&state.db, paper_id).await
+}
+
+#[tauri::command]
+async fn save_notes(state: State<'_, AppState>, paper_id: i64, markdown: String) -> Result<(), AppError> {
+    Ok(state.notes.save(paper_id, markdown)?)
+}
+
+// Called when the editor closes or switches papers so nothing waits on the autosave delay
+#[tauri::command]
+async fn flush_notes(state: State<'_, AppState>, paper_id: i64) -> Result<(), AppError> {
+    Ok(state.notes.flush(paper_id).await?)
+}
+
+#[tauri::command]
+async fn get_note_revisions(
+    state: State<'_, AppState>,
+    paper_id: i64,
+) -> Result<Vec<db::notes::NoteRevision>, AppError> {
+    state.notes.flush(paper_id).await?;
+    db::notes::get_revisions(&state.db, paper_id).await
+}
 
+// The restore is saved like any other edit, so it can itself be undone from the history
+#[tauri::command]
+async fn restore_note_revision(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    revision_id: i64,
+) -> Result<db::notes::Notes, AppError> {
+    let revision = db::notes::get_revision(&state.db, revision_id).await?;
+    state.notes.flush(revision.paper_id).await?;
+    db::notes::save_notes(&state.db, revision.paper_id, &revision.markdown).await?;
+    hooks::notes_saved(&handle, &state.db, revision.paper_id, &revision.markdown).await;
+    updated_paper(&handle, &state.db, revision.paper_id).await?;
+    db::notes::get_notes(&state.db, revision.paper_id).await
+}
 
 fn main() {
+    let context = tauri::generate_context!();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = cli::parse(&args) {
+        std::process::exit(cli::run(context, command));
+    }
+
     tauri::Builder::default()
+        // Must come first: a second launch hands its arguments to this one and exits before
+        // the other plugins start
+        .plugin(tauri_plugin_single_instance::init(|handle, argv, cwd| {
+            let handle = handle.clone();
+            deep_link::focus_main_window(&handle);
+            tauri::async_runtime::spawn(async move {
+                let args = argv.into_iter().skip(1).collect();
+                open_launch_args(&handle, args, std::path::Path::new(&cwd)).await
+            });
+        }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_opener::init())
+        .register_asynchronous_uri_scheme_protocol(pdf_protocol::SCHEME, |ctx, request, responder| {
+            let handle = ctx.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let pool = handle.state::<AppState>().db.clone();
+                responder.respond(pdf_protocol::respond(&pool, &request).await);
+            });
+        })
+        .manage(deep_link::DeepLinks::default())
+        .setup(move |app| {
             let handle = app.handle().clone();
-            let pool = tauri::async_runtime::block_on(init_db(&handle))
+            match logging::log_dir(&handle) {
+                Ok(dir) => logging::init(&dir, config::load(&handle).log_level),
+                Err(e) => eprintln!("Logging to a file disabled: {}", e),
+            }
+            tauri::async_runtime::spawn_blocking(|| {
+                if let Err(e) = deep_link::register_scheme() {
+                    tracing::warn!("{}", e);
+                }
+            });
+            let db_path = db::database_path(&handle).expect("Failed to resolve database path");
+            let pool_size = config::load(&handle).database_pool_size.unwrap_or(db::DEFAULT_POOL_SIZE);
+            let pool = tauri::async_runtime::block_on(init_db(&db_path, pool_size))
                 .expect("Failed to connect to database");
-            app.manage(AppState { db: pool });
+            let http = network::build_client(&config::load(&handle).network).unwrap_or_else(|e| {
+                tracing::warn!("{}; falling back to the default network settings", e);
+                network::build_client(&config::NetworkSettings::default()).expect("Failed to create HTTP client")
+            });
+
+            let backfill_pool = pool.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = import::backfill_file_hashes(&backfill_pool).await {
+                    tracing::warn!("File hash backfill failed: {}", e);
+                }
+                if let Err(e) = import::backfill_cite_keys(&backfill_pool).await {
+                    tracing::warn!("Citation key backfill failed: {}", e);
+                }
+                if let Err(e) = db::authors::backfill_authors(&backfill_pool).await {
+                    tracing::warn!("Author backfill failed: {}", e);
+                }
+                if let Err(e) = db::venues::backfill_venues(&backfill_pool).await {
+                    tracing::warn!("Venue backfill failed: {}", e);
+                }
+                if let Err(e) = import::backfill_page_text(&backfill_pool).await {
+                    tracing::warn!("Page text backfill failed: {}", e);
+                }
+            });
+
+            let watcher = watcher::FolderWatcher::start(handle.clone(), pool.clone())
+                .expect("Failed to start folder watcher");
+            let folders = tauri::async_runtime::block_on(db::get_watched_folders(&pool))
+                .unwrap_or_default();
+            for folder in folders {
+                // A watched folder on an unplugged drive shouldn't stop the app from starting
+                if let Err(e) = watcher.watch(std::path::Path::new(&folder.path), folder.recursive) {
+                    tracing::warn!("{}", e);
+                }
+            }
+
+            app.manage(hooks::Hooks::new(&handle));
+            let notes = notes::NotesAutosave::start(handle.clone(), pool.clone());
+            let autobackup = autobackup::AutoBackup::start(handle.clone(), pool.clone());
+            let maintenance = maintenance::Maintenance::start(handle.clone(), pool.clone(), db_path.clone());
+            let zotero_sync = zotero_sync::ZoteroSync::start(handle.clone(), pool.clone(), http.clone());
+            let thumbnails = thumbnails::Thumbnails::start(handle.clone(), pool.clone());
+            let figures = figures::Figures::start(handle.clone(), pool.clone());
+            let clipboard = clipboard::ClipboardWatcher::start(handle.clone(), pool.clone());
+            let companion = companion::Companion::start(handle.clone());
+            let downloads = downloads::Downloads::new(handle.clone(), http.clone());
+            if let Err(e) = tauri::async_runtime::block_on(db::jobs::mark_interrupted(&pool)) {
+                tracing::warn!("{}", e);
+            }
+            if let Err(e) = tauri::async_runtime::block_on(db::reading::discard_open_sessions(&pool)) {
+                tracing::warn!("{}", e);
+            }
+            let jobs = jobs::Jobs::new(handle.clone(), pool.clone());
+            let models_dir = handle
+                .path()
+                .app_local_data_dir()
+                .expect("Failed to resolve app data directory")
+                .join("models");
+            let semantic = semantic::Semantic::new(&models_dir);
+            saved_searches::start(handle.clone(), pool.clone());
+            arxiv_updates::start(handle.clone(), pool.clone(), http.clone());
+            feeds::start(handle.clone(), pool.clone(), http.clone());
+            lookups::start(handle.clone(), pool.clone(), http.clone());
+
+            app.manage(AppState { db: pool, db_path, http, watcher, notes, autobackup, maintenance, zotero_sync, thumbnails, figures, clipboard, companion, downloads, jobs, operations: operations::Operations::default(), semantic });
+
+            // A PDF double-clicked, or a link opened, while the app wasn't running
+            let cwd = std::env::current_dir().unwrap_or_default();
+            tauri::async_runtime::spawn(async move { open_launch_args(&handle, args, &cwd).await });
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![greet, db_test, add_paper, get_papers, read_pdf_file])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![greet, db_test, add_paper, get_papers, count_papers, update_paper, update_papers_bulk, delete_paper, trash_paper, restore_paper, empty_trash, verify_files, relink_paper, relink_missing_files, search_papers, search_paper_pages, search_in_paper, get_similar_papers, get_semantic_status, build_semantic_index, semantic_search, rebuild_search_index, list_jobs, cancel_job, refresh_file_stats, fetch_metadata_by_doi, list_pending_lookups, retry_pending_lookups, search_crossref, list_metadata_providers, lookup_metadata, search_metadata, import_crossref_work, import_from_doi, fetch_open_access_pdf, fetch_citations, get_citations, get_references, get_citing_papers, get_extracted_references, extract_references, match_references, ocr_paper, ocr_library, get_ocr_text, apply_metadata, resolve_missing_metadata, get_metadata_matches, accept_metadata_match, reject_metadata_match, import_from_arxiv, get_arxiv_updates, check_arxiv_updates, update_arxiv_version, get_paper_revisions, list_feeds, subscribe_feed, unsubscribe_feed, refresh_feeds, get_feed_entries, dismiss_feed_entries, import_feed_entry, import_from_pubmed, copy_cite_key, format_citation, format_bibliography, list_citation_styles, copy_citation, get_thumbnail, get_figures, rescan_figures, import_bibtex, export_bibtex, import_ris, export_ris, import_csv, export_csv, export_library_json, import_library_json, export_vault, add_watched_folder, remove_watched_folder, list_watched_folders, add_paper_from_path, add_papers_from_paths, import_from_url, list_downloads, cancel_download, cancel_operation, import_directory, list_collections, create_collection, rename_collection, delete_collection, add_to_collection, remove_from_collection, list_smart_collections, create_smart_collection, update_smart_collection, delete_smart_collection, list_saved_searches, save_search, run_saved_search, delete_saved_search, get_authors, get_papers_by_author, merge_authors, get_venues, get_papers_by_venue, update_venue, merge_venues, list_custom_fields, create_custom_field, update_custom_field, delete_custom_field, get_custom_values, set_custom_value, list_tags, create_tag, delete_tag, tag_papers, untag_papers, get_tag_suggestions, accept_tag_suggestions, list_operations, undo_last_operation, get_annotations, add_annotation, update_annotation, delete_annotation, export_annotations, import_pdf_annotations, export_annotated_pdf, get_outline, get_notes, save_notes, flush_notes, get_note_revisions, restore_note_revision, set_reading_status, set_reading_position, get_view_state, save_view_state, start_reading_session, end_reading_session, get_reading_stats, get_recent_papers, get_library_stats, open_external, reveal_in_folder, toggle_star, set_rating, set_priority, get_schema_version, run_diagnostics, get_database_path, set_database_path, get_database_pool_size, set_database_pool_size, set_papers_dir, get_settings, update_settings, create_backup, restore_backup, get_backup_status, set_backup_schedule, maintain_database, get_maintenance_status, set_maintenance_schedule, get_clipboard_watch, set_clipboard_watch, get_companion_status, set_companion_settings, regenerate_companion_token, get_hooks, set_hooks, test_hook, take_deep_links, open_deep_link, get_file_naming, set_file_naming, get_summarizer_settings, set_summarizer_settings, get_network_settings, set_network_settings, get_recent_logs, get_log_level, set_log_level, summarize_paper, get_summary, import_zotero, import_mendeley, get_zotero_sync_status, set_zotero_sync, sync_zotero, resolve_zotero_conflict])
+        .build(context)
+        .expect("error while running tauri application")
+        .run(|handle, event| match event {
+            tauri::RunEvent::Exit => {
+                // Don't lose notes typed in the last couple of seconds
+                let state = handle.state::<AppState>();
+                if let Err(e) = tauri::async_runtime::block_on(state.notes.flush_all()) {
+                    tracing::error!("Failed to save notes on exit: {}", e);
+                }
+            }
+            // macOS hands links and files to the running app rather than launching it again
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                let args = urls
+                    .into_iter()
+                    .map(|url| match url.to_file_path() {
+                        Ok(path) => path.to_string_lossy().into_owned(),
+                        Err(()) => String::from(url),
+                    })
+                    .collect();
+                let handle = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    open_launch_args(&handle, args, std::path::Path::new("")).await
+                });
+            }
+            _ => {}
+        });
 }
 
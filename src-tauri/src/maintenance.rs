@@ -0,0 +1,185 @@
+// src-tauri/src/maintenance.rs
+
+// Checks the database for corruption, then VACUUMs it to give back the space left behind by
+// deleted papers and re-runs ANALYZE so the query planner has fresh statistics. Runs on demand
+// or on a schedule; the last report is kept in maintenance.json in the app data directory, so
+// the schedule survives restarts.
+
+use crate::config::{self, MaintenanceSchedule};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const REPORT_FILE: &str = "maintenance.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Leave startup alone; VACUUM locks the database while it runs
+const STARTUP_DELAY: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MaintenanceReport {
+    pub finished_at: String,
+    // What PRAGMA integrity_check found, empty when the database is sound
+    pub integrity_errors: Vec<String>,
+    // A damaged database isn't vacuumed, so there is still something to recover from
+    pub vacuumed: bool,
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+#[derive(Serialize)]
+pub struct MaintenanceStatus {
+    pub schedule: MaintenanceSchedule,
+    pub last_run: Option<MaintenanceReport>,
+    pub next_run_at: Option<String>,
+    pub running: bool,
+    pub last_error: Option<String>,
+}
+
+pub struct Maintenance {
+    handle: AppHandle,
+    pool: SqlitePool,
+    db_path: PathBuf,
+    running: tokio::sync::Mutex<()>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl Maintenance {
+    // The schedule is re-read from the config on every check, so changes apply without a restart
+    pub fn start(handle: AppHandle, pool: SqlitePool, db_path: PathBuf) -> Arc<Self> {
+        let maintenance = Arc::new(Maintenance {
+            handle,
+            pool,
+            db_path,
+            running: tokio::sync::Mutex::new(()),
+            last_error: Mutex::new(None),
+        });
+        tauri::async_runtime::spawn(schedule_loop(maintenance.clone()));
+        maintenance
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        let schedule = config::load(&self.handle).maintenance;
+        let last_run = load_report(&self.handle);
+        let next_run_at = schedule.enabled.then(|| next_run(&schedule, last_run.as_ref()).to_rfc3339());
+        MaintenanceStatus {
+            schedule,
+            last_run,
+            next_run_at,
+            running: self.running.try_lock().is_err(),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+        }
+    }
+
+    pub async fn run(&self) -> Result<MaintenanceReport, String> {
+        let _running = self
+            .running
+            .try_lock()
+            .map_err(|_| "Database maintenance is already running".to_string())?;
+
+        let result = maintain(&self.pool, &self.db_path).await;
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = result.as_ref().err().cloned();
+        }
+        let report = result?;
+
+        if let Err(e) = save_report(&self.handle, &report) {
+            tracing::warn!("{}", e);
+        }
+        let _ = self.handle.emit("maintenance:finished", &report);
+        Ok(report)
+    }
+}
+
+async fn schedule_loop(maintenance: Arc<Maintenance>) {
+    let start = tokio::time::Instant::now() + STARTUP_DELAY;
+    let mut tick = tokio::time::interval_at(start, CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+
+        let schedule = config::load(&maintenance.handle).maintenance;
+        if !schedule.enabled || next_run(&schedule, load_report(&maintenance.handle).as_ref()) > Utc::now() {
+            continue;
+        }
+        match maintenance.run().await {
+            Ok(report) => tracing::info!(
+                "Database maintenance done, {} bytes before, {} after",
+                report.size_before,
+                report.size_after
+            ),
+            Err(e) => tracing::warn!("Database maintenance failed: {}", e),
+        }
+    }
+}
+
+fn next_run(schedule: &MaintenanceSchedule, last_run: Option<&MaintenanceReport>) -> DateTime<Utc> {
+    last_run
+        .and_then(|report| DateTime::parse_from_rfc3339(&report.finished_at).ok())
+        .map_or_else(Utc::now, |finished| {
+            finished.with_timezone(&Utc) + ChronoDuration::days(schedule.interval_days as i64)
+        })
+}
+
+pub async fn maintain(pool: &SqlitePool, db_path: &Path) -> Result<MaintenanceReport, String> {
+    let size_before = file_size(db_path);
+
+    let integrity: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+    let integrity_errors: Vec<String> = integrity.into_iter().filter(|line| line != "ok").collect();
+
+    let vacuumed = integrity_errors.is_empty();
+    if vacuumed {
+        sqlx::query("VACUUM")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("VACUUM failed: {}", e))?;
+    } else {
+        tracing::error!("Database integrity check failed: {}", integrity_errors.join("; "));
+    }
+    sqlx::query("ANALYZE")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("ANALYZE failed: {}", e))?;
+    // In WAL mode the vacuumed pages only land in the database file at a checkpoint
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Checkpoint failed: {}", e))?;
+
+    Ok(MaintenanceReport {
+        finished_at: Utc::now().to_rfc3339(),
+        integrity_errors,
+        vacuumed,
+        size_before,
+        size_after: file_size(db_path),
+    })
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |m| m.len())
+}
+
+fn report_path(handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))?;
+    Ok(dir.join(REPORT_FILE))
+}
+
+fn load_report(handle: &AppHandle) -> Option<MaintenanceReport> {
+    let contents = fs::read_to_string(report_path(handle).ok()?).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_report(handle: &AppHandle, report: &MaintenanceReport) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    fs::write(report_path(handle)?, contents).map_err(|e| format!("Failed to save maintenance report: {}", e))
+}
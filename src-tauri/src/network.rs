@@ -0,0 +1,95 @@
+// src-tauri/src/network.rs
+
+// The HTTP client every network feature shares, built at startup from the proxy and
+// certificate settings. Institutional networks often only let traffic out through a proxy, and
+// some inspect TLS with a certificate of their own that has to be trusted explicitly.
+
+use crate::config::{NetworkSettings, ProxyMode};
+use crate::secrets;
+use serde::Serialize;
+use std::fs;
+use std::time::Duration;
+
+pub const PROXY_PASSWORD_SECRET: &str = "proxy-password";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Local model servers for summaries must not be sent through the proxy
+const ALWAYS_DIRECT: &str = "localhost,127.0.0.1,::1";
+
+#[derive(Serialize)]
+pub struct NetworkStatus {
+    #[serde(flatten)]
+    pub settings: NetworkSettings,
+    // The password itself never leaves the keychain
+    pub proxy_password_set: bool,
+}
+
+pub fn build_client(settings: &NetworkSettings) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(concat!(
+            "paper-master/",
+            env!("CARGO_PKG_VERSION"),
+            " (https://github.com/LazyBeaver007/paper-master)"
+        ))
+        .timeout(REQUEST_TIMEOUT);
+
+    // reqwest picks up HTTP_PROXY, HTTPS_PROXY and NO_PROXY by itself, which is ProxyMode::System
+    match settings.proxy.mode {
+        ProxyMode::System => {}
+        ProxyMode::None => builder = builder.no_proxy(),
+        ProxyMode::Manual => builder = builder.proxy(manual_proxy(settings)?),
+    }
+
+    if let Some(path) = settings.ca_certificate.as_deref().filter(|p| !p.trim().is_empty()) {
+        let pem = fs::read(path).map_err(|e| format!("Failed to read certificate {}: {}", path, e))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Not a PEM certificate: {}: {}", path, e))?;
+        if certificates.is_empty() {
+            return Err(format!("No certificates in {}", path));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn manual_proxy(settings: &NetworkSettings) -> Result<reqwest::Proxy, String> {
+    let proxy = &settings.proxy;
+    let host = proxy
+        .host
+        .as_deref()
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .ok_or("Enter the proxy host")?;
+    // "proxy.example.edu" is taken as an HTTP proxy; an https:// proxy is given as a URL
+    let url = match (host.contains("://"), proxy.port) {
+        (true, Some(port)) => format!("{}:{}", host.trim_end_matches('/'), port),
+        (true, None) => host.to_string(),
+        (false, Some(port)) => format!("http://{}:{}", host, port),
+        (false, None) => format!("http://{}", host),
+    };
+
+    let mut built = reqwest::Proxy::all(&url).map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+    if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+        let password = secrets::get(PROXY_PASSWORD_SECRET)?.unwrap_or_default();
+        built = built.basic_auth(username, &password);
+    }
+
+    let bypass = match proxy.no_proxy.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(list) => format!("{},{}", ALWAYS_DIRECT, list),
+        None => ALWAYS_DIRECT.to_string(),
+    };
+    Ok(built.no_proxy(reqwest::NoProxy::from_string(&bypass)))
+}
+
+// Whether `url` answers at all; any HTTP status counts. Tells "offline" apart from errors the
+// service itself returned.
+pub async fn is_reachable(client: &reqwest::Client, url: &str) -> bool {
+    client
+        .head(url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .is_ok()
+}
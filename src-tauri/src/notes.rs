@@ -0,0 +1,100 @@
+// src-tauri/src/notes.rs
+
+use crate::db;
+use crate::hooks;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+// The editor saves on every change; only write once typing has paused this long
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+type Pending = Arc<Mutex<HashMap<i64, (String, Instant)>>>;
+
+pub struct NotesAutosave {
+    handle: AppHandle,
+    pool: SqlitePool,
+    pending: Pending,
+}
+
+impl NotesAutosave {
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Self {
+        let pending: Pending = Arc::default();
+        tauri::async_runtime::spawn(save_loop(handle.clone(), pool.clone(), pending.clone()));
+        NotesAutosave { handle, pool, pending }
+    }
+
+    // Queues the notes; they are written after SAVE_DELAY without further changes
+    pub fn save(&self, paper_id: i64, markdown: String) -> Result<(), String> {
+        self.pending
+            .lock()
+            .map_err(|_| "Notes autosave is unavailable".to_string())?
+            .insert(paper_id, (markdown, Instant::now()));
+        Ok(())
+    }
+
+    // Writes queued notes for one paper right away
+    pub async fn flush(&self, paper_id: i64) -> Result<(), String> {
+        let queued = self
+            .pending
+            .lock()
+            .map_err(|_| "Notes autosave is unavailable".to_string())?
+            .remove(&paper_id);
+
+        match queued {
+            Some((markdown, _)) => write(&self.handle, &self.pool, paper_id, &markdown).await,
+            None => Ok(()),
+        }
+    }
+
+    pub async fn flush_all(&self) -> Result<(), String> {
+        let queued: Vec<(i64, String)> = self
+            .pending
+            .lock()
+            .map_err(|_| "Notes autosave is unavailable".to_string())?
+            .drain()
+            .map(|(paper_id, (markdown, _))| (paper_id, markdown))
+            .collect();
+
+        for (paper_id, markdown) in queued {
+            write(&self.handle, &self.pool, paper_id, &markdown).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn save_loop(handle: AppHandle, pool: SqlitePool, pending: Pending) {
+    let mut tick = tokio::time::interval(Duration::from_millis(500));
+
+    loop {
+        tick.tick().await;
+
+        let ready: Vec<(i64, String)> = match pending.lock() {
+            Ok(mut pending) => {
+                let ids: Vec<i64> = pending
+                    .iter()
+                    .filter(|(_, (_, changed))| changed.elapsed() >= SAVE_DELAY)
+                    .map(|(id, _)| *id)
+                    .collect();
+                ids.into_iter()
+                    .filter_map(|id| pending.remove(&id).map(|(markdown, _)| (id, markdown)))
+                    .collect()
+            }
+            Err(_) => break,
+        };
+
+        for (paper_id, markdown) in ready {
+            if let Err(e) = write(&handle, &pool, paper_id, &markdown).await {
+                tracing::warn!("Autosave of notes for paper {} failed: {}", paper_id, e);
+            }
+        }
+    }
+}
+
+async fn write(handle: &AppHandle, pool: &SqlitePool, paper_id: i64, markdown: &str) -> Result<(), String> {
+    db::notes::save_notes(pool, paper_id, markdown).await?;
+    hooks::notes_saved(handle, pool, paper_id, markdown).await;
+    Ok(())
+}
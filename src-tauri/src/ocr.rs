@@ -0,0 +1,178 @@
+// src-tauri/src/ocr.rs
+
+// OCR for image-only scans. Pages are rasterised with poppler's pdftoppm and read by the
+// tesseract command-line tool, so both have to be installed for this to work; nothing
+// here links against them.
+
+use crate::db::{self, ocr::OcrText};
+use crate::jobs::JobContext;
+use crate::keywords;
+use crate::operations;
+use crate::references;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+pub const DEFAULT_LANGUAGE: &str = "eng";
+// PDFs with less extracted text than this are treated as scans by the bulk job
+const MIN_TEXT_CHARS: i64 = 200;
+const DPI: &str = "300";
+
+#[derive(Serialize, Clone)]
+pub struct OcrProgress {
+    pub current: usize,
+    pub total: usize,
+    pub paper_id: i64,
+    pub title: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct OcrSummary {
+    pub processed: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+// OCR for every paper that looks like a scan and hasn't been through OCR yet, run as a job.
+// Each paper is also reported with "ocr:progress", and the tally with "ocr:finished".
+pub async fn ocr_library(context: JobContext, language: String) -> Result<String, String> {
+    if !is_valid_language(&language) {
+        return Err(format!("Not a tesseract language code: {}", language));
+    }
+    let papers = db::ocr::get_papers_needing_ocr(&context.pool, MIN_TEXT_CHARS).await?;
+
+    let total = papers.len();
+    let mut summary = OcrSummary::default();
+    for (index, (paper_id, title, pdf_path)) in papers.into_iter().enumerate() {
+        if context.is_cancelled() {
+            break;
+        }
+        context.progress(index, Some(total), Some(&title)).await;
+
+        let result = ocr_paper(&context.pool, paper_id, &title, Path::new(&pdf_path), &language, &context.token).await;
+        let error = result.err();
+        match &error {
+            None => summary.processed += 1,
+            Some(e) => {
+                summary.failed += 1;
+                summary.errors.push(format!("{}: {}", title, e));
+            }
+        }
+
+        let _ = context.handle.emit(
+            "ocr:progress",
+            OcrProgress { current: index + 1, total, paper_id, title, error },
+        );
+    }
+
+    let _ = context.handle.emit("ocr:finished", &summary);
+    Ok(format!("Recognised {} of {} papers", summary.processed, total))
+}
+
+// Recognises the PDF's text and indexes it in place of whatever extraction found. Scans
+// come in without a bibliography, so references are taken from the OCR text as well.
+pub async fn ocr_paper(
+    pool: &SqlitePool,
+    paper_id: i64,
+    title: &str,
+    pdf_path: &Path,
+    language: &str,
+    cancel: &CancellationToken,
+) -> Result<OcrText, String> {
+    if !is_valid_language(language) {
+        return Err(format!("Not a tesseract language code: {}", language));
+    }
+
+    let pages = recognise(pdf_path, language, cancel).await?;
+    let content = pages.join(&db::ocr::PAGE_BREAK.to_string());
+    let saved = db::ocr::save_ocr_text(pool, paper_id, &content, language, pages.len() as i64).await?;
+    db::index_paper_text(pool, paper_id, title, &pages).await?;
+    keywords::update_keywords(pool, paper_id, &db::get_paper_text(pool, paper_id).await?).await?;
+
+    if db::references::get_extracted_references(pool, paper_id).await?.is_empty() {
+        let extracted = references::extract_references(&content);
+        if !extracted.is_empty() {
+            db::references::save_extracted_references(pool, paper_id, &extracted).await?;
+        }
+    }
+
+    Ok(saved)
+}
+
+// "eng", "deu+eng", "chi_sim"
+fn is_valid_language(language: &str) -> bool {
+    !language.is_empty()
+        && language
+            .split('+')
+            .all(|code| !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+// One string per page, in page order
+async fn recognise(pdf_path: &Path, language: &str, cancel: &CancellationToken) -> Result<Vec<String>, String> {
+    let work_dir = std::env::temp_dir().join(format!("paper-master-ocr-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&work_dir)
+        .await
+        .map_err(|e| format!("Failed to create OCR work folder: {}", e))?;
+
+    let result = recognise_in(pdf_path, language, &work_dir, cancel).await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+    result
+}
+
+async fn recognise_in(
+    pdf_path: &Path,
+    language: &str,
+    work_dir: &Path,
+    cancel: &CancellationToken,
+) -> Result<Vec<String>, String> {
+    run(
+        Command::new("pdftoppm")
+            .args(["-r", DPI, "-gray", "-png"])
+            .arg(pdf_path)
+            .arg(work_dir.join("page")),
+        cancel,
+    )
+    .await?;
+
+    // pdftoppm zero-pads page numbers, so the names sort in page order
+    let mut images: Vec<PathBuf> = std::fs::read_dir(work_dir)
+        .map_err(|e| format!("Failed to read OCR work folder: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "png"))
+        .collect();
+    images.sort();
+    if images.is_empty() {
+        return Err("PDF has no pages to recognise".to_string());
+    }
+
+    let mut pages = Vec::with_capacity(images.len());
+    for image in images {
+        let text = run(Command::new("tesseract").arg(&image).arg("stdout").args(["-l", language]), cancel).await?;
+        pages.push(text.trim().to_string());
+    }
+    Ok(pages)
+}
+
+// A cancelled run kills the process rather than waiting for it
+async fn run(command: &mut Command, cancel: &CancellationToken) -> Result<String, String> {
+    let program = command.as_std().get_program().to_string_lossy().into_owned();
+    let output = tokio::select! {
+        output = command.kill_on_drop(true).output() => output,
+        _ = cancel.cancelled() => return Err(operations::CANCELLED.to_string()),
+    };
+    let output = output.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            format!("{} not found; OCR needs tesseract and poppler's pdftoppm installed", program)
+        }
+        _ => format!("Failed to run {}: {}", program, e),
+    })?;
+
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,59 @@
+// src-tauri/src/operations.rs
+
+// Cancellation for commands the user may want to stop halfway, such as importing a folder or
+// downloading a large PDF. The frontend picks an id for the operation when it invokes the
+// command and passes the same id to cancel_operation; the command checks its token between
+// steps and hands it down to downloads and OCR, which stop mid-way.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+// The error of a command that was cancelled
+pub const CANCELLED: &str = "Cancelled";
+
+#[derive(Default)]
+pub struct Operations {
+    tokens: Mutex<HashMap<String, CancellationToken>>,
+}
+
+// Registered until dropped, which happens when the command returns
+pub struct Operation<'a> {
+    operations: &'a Operations,
+    op_id: Option<String>,
+    pub token: CancellationToken,
+}
+
+impl Operations {
+    // Without an id the operation can't be cancelled, but gets a token all the same
+    pub fn begin(&self, op_id: Option<String>) -> Result<Operation<'_>, String> {
+        let token = CancellationToken::new();
+        if let Some(op_id) = &op_id {
+            let mut tokens = self.tokens.lock().map_err(|_| "Operation list unavailable")?;
+            if tokens.contains_key(op_id) {
+                return Err(format!("Operation {} is already running", op_id));
+            }
+            tokens.insert(op_id.clone(), token.clone());
+        }
+        Ok(Operation {
+            operations: self,
+            op_id,
+            token,
+        })
+    }
+
+    pub fn cancel(&self, op_id: &str) -> Result<(), String> {
+        let tokens = self.tokens.lock().map_err(|_| "Operation list unavailable")?;
+        let token = tokens.get(op_id).ok_or("The operation has already finished")?;
+        token.cancel();
+        Ok(())
+    }
+}
+
+impl Drop for Operation<'_> {
+    fn drop(&mut self) {
+        if let (Some(op_id), Ok(mut tokens)) = (&self.op_id, self.operations.tokens.lock()) {
+            tokens.remove(op_id);
+        }
+    }
+}
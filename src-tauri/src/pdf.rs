@@ -0,0 +1,764 @@
+// src-tauri/src/pdf.rs
+
+use crate::db::outline::OutlineEntry;
+use std::path::Path;
+
+pub fn extract_text(path: &Path) -> Result<String, String> {
+    pdf_extract::extract_text(path).map_err(|e| format!("Failed to extract text: {}", e))
+}
+
+// pdf-extract can panic on malformed files, so keep it off the async runtime
+pub async fn extract_text_async(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_text(&path))
+        .await
+        .map_err(|e| format!("Text extraction aborted: {}", e))?
+}
+
+// One string per page, in page order
+pub fn extract_pages(path: &Path) -> Result<Vec<String>, String> {
+    pdf_extract::extract_text_by_pages(path).map_err(|e| format!("Failed to extract text: {}", e))
+}
+
+pub async fn extract_pages_async(path: &Path) -> Result<Vec<String>, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_pages(&path))
+        .await
+        .map_err(|e| format!("Text extraction aborted: {}", e))?
+}
+
+#[derive(Debug, Default)]
+pub struct PdfMetadata {
+    pub title: Option<String>,
+    pub authors: Option<String>,
+    pub year: Option<i64>,
+    pub r#abstract: Option<String>,
+    // Comma separated
+    pub keywords: Option<String>,
+}
+
+// Prefers XMP, then the Info dictionary, then guesses from the first page
+pub fn extract_metadata(path: &Path) -> Result<PdfMetadata, String> {
+    let doc = lopdf::Document::load(path).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+
+    let xmp = xmp_metadata(&doc).unwrap_or_default();
+    let info = info_metadata(&doc).unwrap_or_default();
+    let first_page = doc
+        .extract_text(&[1])
+        .map(|text| first_page_metadata(&text))
+        .unwrap_or_default();
+
+    Ok(PdfMetadata {
+        title: xmp.title.or(info.title).or(first_page.title),
+        authors: xmp.authors.or(info.authors).or(first_page.authors),
+        year: xmp.year.or(info.year).or(first_page.year),
+        r#abstract: first_page.r#abstract,
+        keywords: xmp.keywords.or(info.keywords).or(first_page.keywords),
+    })
+}
+
+pub async fn extract_metadata_async(path: &Path) -> Result<PdfMetadata, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_metadata(&path))
+        .await
+        .map_err(|e| format!("Metadata extraction aborted: {}", e))?
+}
+
+#[derive(Debug, Default)]
+pub struct FileStats {
+    pub page_count: Option<i64>,
+    pub file_size: i64,
+    pub pdf_version: Option<String>,
+}
+
+// The size is always known; page count and version are None if the file doesn't parse
+pub fn file_stats(path: &Path) -> Result<FileStats, String> {
+    let file_size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read PDF: {}", e))?
+        .len() as i64;
+    let doc = lopdf::Document::load(path).ok();
+
+    Ok(FileStats {
+        page_count: doc.as_ref().map(|doc| doc.get_pages().len() as i64),
+        file_size,
+        pdf_version: doc.map(|doc| doc.version).filter(|v| !v.is_empty()),
+    })
+}
+
+pub async fn file_stats_async(path: &Path) -> Result<FileStats, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || file_stats(&path))
+        .await
+        .map_err(|e| format!("PDF parsing aborted: {}", e))?
+}
+
+// Height in points of each page, in page order. Used to flip coordinates from PDF space
+// (origin bottom-left) to the top-left origin annotations use.
+pub fn page_heights(path: &Path) -> Result<Vec<f64>, String> {
+    let doc = lopdf::Document::load(path).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+    Ok(doc.page_iter().map(|page| page_height(&doc, page).unwrap_or(US_LETTER_HEIGHT)).collect())
+}
+
+pub async fn page_heights_async(path: &Path) -> Result<Vec<f64>, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || page_heights(&path))
+        .await
+        .map_err(|e| format!("PDF parsing aborted: {}", e))?
+}
+
+// A highlight or note made in another viewer, in the top-left origin annotations use
+#[derive(Debug)]
+pub struct PdfAnnotation {
+    // 1-based
+    pub page: i64,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    // "#rrggbb", None when the annotation has no colour of its own
+    pub color: Option<String>,
+    pub comment: Option<String>,
+    // A sticky note rather than marked-up text
+    pub note: bool,
+}
+
+// Sticky notes only have an icon's worth of Rect
+pub const NOTE_SIZE: f64 = 16.0;
+
+// Text markup (highlight, underline, strike-out, squiggly) and notes. Replies made in
+// Acrobat or Preview are separate annotations pointing at their parent with /IRT; their
+// text is appended to the parent's comment.
+pub fn extract_annotations(path: &Path) -> Result<Vec<PdfAnnotation>, String> {
+    let doc = lopdf::Document::load(path).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+    let mut annotations = Vec::new();
+    let mut by_id = std::collections::HashMap::new();
+    let mut replies = Vec::new();
+
+    for (page_number, page_id) in doc.get_pages() {
+        let page_height = page_height(&doc, page_id).unwrap_or(US_LETTER_HEIGHT);
+        let Ok(annots) = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get_deref(b"Annots", &doc))
+            .and_then(|a| a.as_array())
+        else {
+            continue;
+        };
+
+        for annot in annots {
+            let Ok((id, dict)) = doc.dereference(annot).and_then(|(id, obj)| Ok((id, obj.as_dict()?))) else {
+                continue;
+            };
+            let comment = dict
+                .get_deref(b"Contents", &doc)
+                .ok()
+                .and_then(|c| lopdf::decode_text_string(c).ok())
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty());
+
+            if let Ok(parent) = dict.get(b"IRT").and_then(|p| p.as_reference()) {
+                if let Some(reply) = comment {
+                    replies.push((parent, reply));
+                }
+                continue;
+            }
+
+            let subtype = dict.get(b"Subtype").and_then(|s| s.as_name()).unwrap_or_default();
+            let bounds = match subtype {
+                b"Highlight" | b"Underline" | b"StrikeOut" | b"Squiggly" => {
+                    numbers(&doc, dict, b"QuadPoints").and_then(|q| bounding_box(&q)).or_else(|| {
+                        numbers(&doc, dict, b"Rect").and_then(|r| bounding_box(&r))
+                    })
+                }
+                b"FreeText" => numbers(&doc, dict, b"Rect").and_then(|r| bounding_box(&r)),
+                b"Text" => numbers(&doc, dict, b"Rect")
+                    .and_then(|r| bounding_box(&r))
+                    .map(|(x1, _, _, y2)| (x1, y2 - NOTE_SIZE, x1 + NOTE_SIZE, y2)),
+                _ => None,
+            };
+            let Some((x1, y1, x2, y2)) = bounds else {
+                continue;
+            };
+
+            if let Some(id) = id {
+                by_id.insert(id, annotations.len());
+            }
+            annotations.push(PdfAnnotation {
+                page: page_number as i64,
+                x: x1,
+                y: page_height - y2,
+                width: x2 - x1,
+                height: y2 - y1,
+                color: numbers(&doc, dict, b"C").and_then(|c| hex_color(&c)),
+                comment,
+                note: subtype == b"Text",
+            });
+        }
+    }
+
+    for (parent, reply) in replies {
+        if let Some(annotation) = by_id.get(&parent).map(|&i| &mut annotations[i]) {
+            annotation.comment = Some(match annotation.comment.take() {
+                Some(comment) => format!("{}\n\n{}", comment, reply),
+                None => reply,
+            });
+        }
+    }
+
+    Ok(annotations)
+}
+
+pub async fn extract_annotations_async(path: &Path) -> Result<Vec<PdfAnnotation>, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_annotations(&path))
+        .await
+        .map_err(|e| format!("PDF parsing aborted: {}", e))?
+}
+
+// Outlines nested deeper than this are cut off; it also bounds the walk through a broken tree
+const MAX_OUTLINE_DEPTH: i64 = 16;
+
+// The outline (bookmarks) in document order. Empty if the PDF has none.
+pub fn extract_outline(path: &Path) -> Result<Vec<OutlineEntry>, String> {
+    let doc = lopdf::Document::load(path).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+    let pages: std::collections::HashMap<lopdf::ObjectId, u32> =
+        doc.get_pages().into_iter().map(|(number, id)| (id, number)).collect();
+    let mut entries = Vec::new();
+
+    let first = doc
+        .catalog()
+        .and_then(|catalog| catalog.get_deref(b"Outlines", &doc))
+        .and_then(|outlines| outlines.as_dict())
+        .and_then(|outlines| outlines.get(b"First"))
+        .ok();
+    if let Some(first) = first {
+        let mut seen = std::collections::HashSet::new();
+        outline_siblings(&doc, &pages, first, 0, &mut seen, &mut entries);
+    }
+    Ok(entries)
+}
+
+pub async fn extract_outline_async(path: &Path) -> Result<Vec<OutlineEntry>, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_outline(&path))
+        .await
+        .map_err(|e| format!("PDF parsing aborted: {}", e))?
+}
+
+// Walks an item and its /Next siblings, descending into /First children. `seen` guards
+// against outlines whose links loop back on themselves.
+fn outline_siblings(
+    doc: &lopdf::Document,
+    pages: &std::collections::HashMap<lopdf::ObjectId, u32>,
+    first: &lopdf::Object,
+    depth: i64,
+    seen: &mut std::collections::HashSet<lopdf::ObjectId>,
+    entries: &mut Vec<OutlineEntry>,
+) {
+    if depth >= MAX_OUTLINE_DEPTH {
+        return;
+    }
+    let mut next = Some(first);
+    while let Some(object) = next.take() {
+        let Ok((id, item)) = doc.dereference(object) else {
+            break;
+        };
+        if id.is_some_and(|id| !seen.insert(id)) {
+            break;
+        }
+        let Ok(item) = item.as_dict() else {
+            break;
+        };
+
+        let title = item
+            .get_deref(b"Title", doc)
+            .ok()
+            .and_then(|t| lopdf::decode_text_string(t).ok())
+            .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        // Either a destination of its own or a GoTo action carrying one
+        let dest = item.get(b"Dest").ok().or_else(|| {
+            let action = item.get_deref(b"A", doc).ok()?.as_dict().ok()?;
+            (action.get(b"S").ok()?.as_name().ok()? == b"GoTo").then(|| action.get(b"D").ok())?
+        });
+        let (page, y) = dest.and_then(|d| resolve_destination(doc, pages, d, 0)).unwrap_or((None, None));
+
+        if !title.is_empty() {
+            entries.push(OutlineEntry { depth, title, page, y });
+        }
+        if let Ok(child) = item.get(b"First") {
+            outline_siblings(doc, pages, child, depth + 1, seen, entries);
+        }
+        next = item.get(b"Next").ok();
+    }
+}
+
+// A destination is [page /XYZ left top zoom] and the like, or the name of one listed in
+// the catalog's /Dests or its /Names tree
+fn resolve_destination(
+    doc: &lopdf::Document,
+    pages: &std::collections::HashMap<lopdf::ObjectId, u32>,
+    dest: &lopdf::Object,
+    hops: u8,
+) -> Option<(Option<i64>, Option<f64>)> {
+    use lopdf::Object;
+
+    if hops > 4 {
+        return None;
+    }
+    let (_, dest) = doc.dereference(dest).ok()?;
+    match dest {
+        Object::Array(parts) => {
+            let page_id = parts.first()?.as_reference().ok()?;
+            let page = pages.get(&page_id).map(|&n| n as i64);
+            let view = parts.get(1).and_then(|v| v.as_name().ok()).unwrap_or_default();
+            // Only these views carry a top coordinate, as the third or fourth element
+            let top = match view {
+                b"XYZ" | b"FitH" | b"FitBH" => parts.get(if view == b"XYZ" { 3 } else { 2 }),
+                _ => None,
+            };
+            let y = top.and_then(|t| t.as_float().ok()).map(|top| {
+                page_height(doc, page_id).unwrap_or(US_LETTER_HEIGHT) - f64::from(top)
+            });
+            Some((page, y.map(|y| y.max(0.0))))
+        }
+        // A dictionary entry from /Dests may wrap the array in /D
+        Object::Dictionary(dict) => resolve_destination(doc, pages, dict.get(b"D").ok()?, hops + 1),
+        Object::Name(name) | Object::String(name, _) => {
+            let target = named_destination(doc, name)?;
+            resolve_destination(doc, pages, target, hops + 1)
+        }
+        _ => None,
+    }
+}
+
+fn named_destination<'a>(doc: &'a lopdf::Document, name: &[u8]) -> Option<&'a lopdf::Object> {
+    let catalog = doc.catalog().ok()?;
+    if let Ok(found) = catalog
+        .get_deref(b"Dests", doc)
+        .and_then(|dests| dests.as_dict())
+        .and_then(|dests| dests.get(name))
+    {
+        return Some(found);
+    }
+    let tree = catalog
+        .get_deref(b"Names", doc)
+        .and_then(|names| names.as_dict())
+        .and_then(|names| names.get_deref(b"Dests", doc))
+        .and_then(|dests| dests.as_dict())
+        .ok()?;
+    name_tree_lookup(doc, tree, name, 0)
+}
+
+// Searches a name tree; leaves hold /Names [key value key value ...], other nodes /Kids
+fn name_tree_lookup<'a>(
+    doc: &'a lopdf::Document,
+    node: &'a lopdf::Dictionary,
+    name: &[u8],
+    depth: u8,
+) -> Option<&'a lopdf::Object> {
+    if depth > 16 {
+        return None;
+    }
+    if let Ok(names) = node.get_deref(b"Names", doc).and_then(|n| n.as_array()) {
+        return names
+            .chunks(2)
+            .find(|pair| doc.dereference(&pair[0]).ok().and_then(|(_, k)| k.as_str().ok()) == Some(name))
+            .and_then(|pair| pair.get(1));
+    }
+    let kids = node.get_deref(b"Kids", doc).and_then(|k| k.as_array()).ok()?;
+    kids.iter().find_map(|kid| {
+        let kid = doc.dereference(kid).ok()?.1.as_dict().ok()?;
+        name_tree_lookup(doc, kid, name, depth + 1)
+    })
+}
+
+// Copies `src` to `dest` with the annotations added as standard Highlight and Text
+// (sticky note) annotations, so any viewer shows them. No appearance streams are written;
+// viewers draw highlights and note icons themselves. Returns how many were written.
+pub fn write_annotations(src: &Path, dest: &Path, annotations: &[PdfAnnotation]) -> Result<usize, String> {
+    use lopdf::{Dictionary, Object};
+
+    let mut doc = lopdf::Document::load(src).map_err(|e| format!("Failed to parse PDF: {}", e))?;
+    let pages = doc.get_pages();
+    let mut written = 0;
+
+    for annotation in annotations {
+        let Some(&page_id) = u32::try_from(annotation.page).ok().and_then(|n| pages.get(&n)) else {
+            continue;
+        };
+        let page_height = page_height(&doc, page_id).unwrap_or(US_LETTER_HEIGHT);
+        let left = annotation.x;
+        let right = annotation.x + annotation.width;
+        let top = page_height - annotation.y;
+        let bottom = top - annotation.height;
+        let reals = |values: &[f64]| Object::Array(values.iter().map(|&v| Object::Real(v as f32)).collect());
+
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::Name(b"Annot".to_vec()));
+        dict.set("Rect", reals(&[left, bottom, right, top]));
+        // Printable
+        dict.set("F", Object::Integer(4));
+        if let Some(components) = annotation.color.as_deref().and_then(rgb_components) {
+            dict.set("C", reals(&components));
+        }
+        if let Some(comment) = &annotation.comment {
+            dict.set("Contents", text_string(comment));
+        }
+        if annotation.note {
+            dict.set("Subtype", Object::Name(b"Text".to_vec()));
+            dict.set("Name", Object::Name(b"Comment".to_vec()));
+        } else {
+            dict.set("Subtype", Object::Name(b"Highlight".to_vec()));
+            dict.set("QuadPoints", reals(&[left, top, right, top, left, bottom, right, bottom]));
+        }
+        let annot_id = doc.add_object(dict);
+        add_to_page(&mut doc, page_id, annot_id)?;
+        written += 1;
+    }
+
+    doc.save(dest).map_err(|e| format!("Failed to write PDF: {}", e))?;
+    Ok(written)
+}
+
+pub async fn write_annotations_async(
+    src: &Path,
+    dest: &Path,
+    annotations: Vec<PdfAnnotation>,
+) -> Result<usize, String> {
+    let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+    tokio::task::spawn_blocking(move || write_annotations(&src, &dest, &annotations))
+        .await
+        .map_err(|e| format!("PDF writing aborted: {}", e))?
+}
+
+// /Annots is either an array in the page dictionary or a reference to one
+fn add_to_page(doc: &mut lopdf::Document, page_id: lopdf::ObjectId, annot_id: lopdf::ObjectId) -> Result<(), String> {
+    use lopdf::Object;
+
+    let shared = doc
+        .get_dictionary(page_id)
+        .and_then(|page| page.get(b"Annots"))
+        .and_then(|annots| annots.as_reference())
+        .ok();
+    let annots = match shared {
+        Some(id) => doc.get_object_mut(id),
+        None => {
+            let page = doc.get_dictionary_mut(page_id).map_err(|e| format!("Broken page: {}", e))?;
+            if !page.has(b"Annots") {
+                page.set("Annots", Object::Array(Vec::new()));
+            }
+            page.get_mut(b"Annots")
+        }
+    };
+    annots
+        .and_then(|annots| annots.as_array_mut())
+        .map(|annots| annots.push(Object::Reference(annot_id)))
+        .map_err(|e| format!("Broken annotation list: {}", e))
+}
+
+// PDFDocEncoding covers ASCII; anything else goes in as UTF-16BE with a byte order mark
+fn text_string(text: &str) -> lopdf::Object {
+    if text.is_ascii() {
+        return lopdf::Object::string_literal(text);
+    }
+    let mut bytes = vec![0xfe, 0xff];
+    bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+    lopdf::Object::String(bytes, lopdf::StringFormat::Hexadecimal)
+}
+
+fn rgb_components(color: &str) -> Option<[f64; 3]> {
+    let hex = color.strip_prefix('#').filter(|h| h.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|v| v as f64 / 255.0);
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn numbers(doc: &lopdf::Document, dict: &lopdf::Dictionary, key: &[u8]) -> Option<Vec<f64>> {
+    let array = dict.get_deref(key, doc).ok()?.as_array().ok()?;
+    array
+        .iter()
+        .map(|n| doc.dereference(n).ok()?.1.as_float().ok().map(f64::from))
+        .collect()
+}
+
+// (left, bottom, right, top) around a Rect or a run of QuadPoints
+fn bounding_box(coords: &[f64]) -> Option<(f64, f64, f64, f64)> {
+    if coords.len() < 4 || !coords.len().is_multiple_of(2) {
+        return None;
+    }
+    let xs = coords.iter().step_by(2);
+    let ys = coords.iter().skip(1).step_by(2);
+    let (left, right) = xs.fold((f64::MAX, f64::MIN), |(lo, hi), &x| (lo.min(x), hi.max(x)));
+    let (bottom, top) = ys.fold((f64::MAX, f64::MIN), |(lo, hi), &y| (lo.min(y), hi.max(y)));
+    (right > left && top > bottom).then_some((left, bottom, right, top))
+}
+
+// /C holds gray, RGB or CMYK components between 0 and 1; an empty array means transparent
+fn hex_color(components: &[f64]) -> Option<String> {
+    let (r, g, b) = match *components {
+        [gray] => (gray, gray, gray),
+        [r, g, b] => (r, g, b),
+        [c, m, y, k] => ((1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k)),
+        _ => return None,
+    };
+    let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Some(format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b)))
+}
+
+const US_LETTER_HEIGHT: f64 = 792.0;
+
+// MediaBox may be inherited from any ancestor in the page tree
+fn page_height(doc: &lopdf::Document, page: lopdf::ObjectId) -> Option<f64> {
+    let mut node = doc.get_dictionary(page).ok()?;
+    for _ in 0..32 {
+        if let Ok(media_box) = node.get_deref(b"MediaBox", doc).and_then(|b| b.as_array()) {
+            let coords: Vec<f64> = media_box
+                .iter()
+                .filter_map(|c| doc.dereference(c).ok()?.1.as_float().ok())
+                .map(f64::from)
+                .collect();
+            return (coords.len() == 4).then(|| (coords[3] - coords[1]).abs());
+        }
+        node = node.get_deref(b"Parent", doc).and_then(|p| p.as_dict()).ok()?;
+    }
+    None
+}
+
+fn info_metadata(doc: &lopdf::Document) -> Option<PdfMetadata> {
+    let info = doc.trailer.get(b"Info").ok()?;
+    let (_, info) = doc.dereference(info).ok()?;
+    let info = info.as_dict().ok()?;
+
+    let text = |key: &[u8]| {
+        info.get(key)
+            .ok()
+            .and_then(|obj| lopdf::decode_text_string(obj).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    Some(PdfMetadata {
+        title: text(b"Title").filter(|t| is_plausible_title(t)),
+        authors: text(b"Author"),
+        year: text(b"CreationDate").and_then(|d| pdf_date_year(&d)),
+        keywords: text(b"Keywords").and_then(|k| join_keywords([k.as_str()])),
+        ..Default::default()
+    })
+}
+
+fn xmp_metadata(doc: &lopdf::Document) -> Option<PdfMetadata> {
+    let catalog = doc.catalog().ok()?;
+    let stream = catalog.get_deref(b"Metadata", doc).ok()?.as_stream().ok()?;
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+    let xml = String::from_utf8_lossy(&content);
+
+    let creators = xml_element(&xml, "dc:creator")
+        .map(xml_list_items)
+        .unwrap_or_default();
+
+    Some(PdfMetadata {
+        title: xml_element(&xml, "dc:title")
+            .and_then(|t| xml_list_items(t).into_iter().next())
+            .filter(|t| is_plausible_title(t)),
+        authors: (!creators.is_empty()).then(|| creators.join(", ")),
+        year: ["prism:publicationDate", "prism:coverDate", "xmp:CreateDate"]
+            .iter()
+            .find_map(|tag| xml_element(&xml, tag).and_then(find_year)),
+        keywords: xml_element(&xml, "dc:subject").and_then(|k| join_keywords(xml_list_items(k).iter().map(String::as_str))),
+        ..Default::default()
+    })
+}
+
+fn first_page_metadata(text: &str) -> PdfMetadata {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let title_idx = lines.iter().take(15).position(|l| is_plausible_title(l) && !is_header_line(l));
+    let title = title_idx.map(|i| lines[i].to_string());
+    let authors = title_idx
+        .and_then(|i| lines.get(i + 1))
+        .filter(|l| looks_like_author_line(l))
+        .map(|l| l.to_string());
+
+    PdfMetadata {
+        title,
+        authors,
+        year: lines.iter().take(40).find_map(|l| find_year(l)),
+        r#abstract: first_page_abstract(&lines),
+        keywords: first_page_keywords(&lines),
+    }
+}
+
+const MAX_ABSTRACT_CHARS: usize = 5000;
+
+// The paragraph after an "Abstract" heading, up to the keywords or the first section
+fn first_page_abstract(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|l| l.to_lowercase().starts_with("abstract"))?;
+    // "Abstract—We present ..." puts the first words on the heading line
+    let first = lines[start].get("abstract".len()..).unwrap_or_default().trim_start_matches(|c: char| ".:—–- ".contains(c));
+
+    let mut text = first.to_string();
+    for line in &lines[start + 1..] {
+        if keywords_label(line).is_some() || is_section_heading(line) || text.len() > MAX_ABSTRACT_CHARS {
+            break;
+        }
+        // Words broken across lines by hyphenation
+        match text.strip_suffix('-') {
+            Some(joined) => text.truncate(joined.len()),
+            None if !text.is_empty() => text.push(' '),
+            None => {}
+        }
+        text.push_str(line);
+    }
+
+    // A lone "Abstract" in a table of contents or running header isn't one
+    (text.len() >= 100).then_some(text)
+}
+
+// "Keywords: a, b; c" or "Index Terms—a, b", which may run on over a few lines
+fn first_page_keywords(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|l| keywords_label(l).is_some())?;
+    let mut text = lines[start][keywords_label(lines[start])?..].to_string();
+    for line in lines[start + 1..].iter().take(3) {
+        if !text.trim_end().ends_with([',', ';']) {
+            break;
+        }
+        text.push(' ');
+        text.push_str(line);
+    }
+    join_keywords([text.as_str()])
+}
+
+// Byte length of a keywords label at the start of the line, separator included
+fn keywords_label(line: &str) -> Option<usize> {
+    let lower = line.to_lowercase();
+    let label = ["keywords", "key words", "index terms"].iter().find(|l| lower.starts_with(*l))?;
+    let rest = line.get(label.len()..)?;
+    Some(line.len() - rest.trim_start_matches(|c: char| ".:—–- ".contains(c)).len())
+}
+
+fn is_section_heading(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    // "Introduction", "1 Introduction", "I. INTRODUCTION"
+    let heading = match words.as_slice() {
+        [word] => word,
+        [number, word] if number.trim_end_matches('.').chars().all(|c| c.is_ascii_digit() || "ivx".contains(c)) => word,
+        _ => return lower.starts_with("ccs concepts"),
+    };
+    ["introduction", "background"].contains(heading)
+}
+
+// Keyword lists split on commas, semicolons or bullets, joined with ", "
+fn join_keywords<'a>(lists: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let keywords: Vec<&str> = lists
+        .into_iter()
+        .flat_map(|list| list.split([',', ';', '·', '•']))
+        .map(|k| k.trim().trim_end_matches('.'))
+        .filter(|k| !k.is_empty() && k.chars().count() <= 60)
+        .collect();
+    (!keywords.is_empty()).then(|| keywords.join(", "))
+}
+
+fn xml_element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)?;
+    let body_start = start + xml[start..].find('>')? + 1;
+    let end = body_start + xml[body_start..].find(&close)?;
+    Some(&xml[body_start..end])
+}
+
+// Collects the <rdf:li> values of an rdf:Seq/Alt/Bag, or the plain text if there are none
+fn xml_list_items(element: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut rest = element;
+    while let Some(start) = rest.find("<rdf:li") {
+        let Some(body) = rest[start..].find('>').map(|i| start + i + 1) else {
+            break;
+        };
+        let Some(end) = rest[body..].find("</rdf:li>").map(|i| body + i) else {
+            break;
+        };
+        let value = unescape_xml(rest[body..end].trim());
+        if !value.is_empty() {
+            items.push(value);
+        }
+        rest = &rest[end..];
+    }
+
+    if items.is_empty() {
+        let value = unescape_xml(element.trim());
+        if !value.is_empty() && !value.contains('<') {
+            items.push(value);
+        }
+    }
+
+    items
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub fn find_year(s: &str) -> Option<i64> {
+    let bytes = s.as_bytes();
+    (0..bytes.len().saturating_sub(3)).find_map(|i| {
+        let candidate = &s.get(i..i + 4)?;
+        let boundary_before = i == 0 || !bytes[i - 1].is_ascii_digit();
+        let boundary_after = bytes.get(i + 4).is_none_or(|b| !b.is_ascii_digit());
+        if !boundary_before || !boundary_after || !candidate.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let year: i64 = candidate.parse().ok()?;
+        (1900..=2100).contains(&year).then_some(year)
+    })
+}
+
+// PDF dates look like "D:20170612093000+02'00'"
+fn pdf_date_year(date: &str) -> Option<i64> {
+    let digits = date.trim_start_matches("D:");
+    let year: i64 = digits.get(..4)?.parse().ok()?;
+    (1900..=2100).contains(&year).then_some(year)
+}
+
+fn is_plausible_title(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    let letters = s.chars().filter(|c| c.is_alphabetic()).count();
+    (8..=300).contains(&s.len())
+        && letters * 2 > s.chars().count()
+        && !lower.starts_with("microsoft word")
+        && !lower.starts_with("untitled")
+        && ![".pdf", ".doc", ".docx", ".tex", ".dvi"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+}
+
+fn is_header_line(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    ["arxiv", "doi", "http", "www.", "journal", "proceedings", "vol.", "volume", "issn", "preprint", "copyright", "©"]
+        .iter()
+        .any(|marker| lower.contains(marker))
+        || s.contains('@')
+}
+
+fn looks_like_author_line(s: &str) -> bool {
+    let words: Vec<&str> = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty() && *w != "and" && *w != "&")
+        .collect();
+    !words.is_empty()
+        && words.len() <= 40
+        && !is_header_line(s)
+        && words
+            .iter()
+            .all(|w| w.chars().next().is_some_and(|c| c.is_uppercase() || c.is_ascii_digit() || c == '*'))
+}
@@ -0,0 +1,173 @@
+// src-tauri/src/pdf_protocol.rs
+
+// Serves library PDFs to the viewer at paper://localhost/<paper id> (http://paper.localhost/<id>
+// on Windows; convertFileSrc picks the right one). Range requests are honoured so pdf.js
+// only loads the parts of a large file it's displaying. Loading a PDF counts as opening the
+// paper (last_opened_at, open_count).
+
+use crate::db;
+use sqlx::SqlitePool;
+use std::io::SeekFrom;
+use std::path::Path;
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+pub const SCHEME: &str = "paper";
+// Caps one range response so an open-ended "bytes=0-" doesn't read a whole 100 MB scan into
+// memory. A request without a range gets the whole file: pdf.js takes that response's length
+// as the document's, and it can't fall back to range requests on the paper:// scheme.
+const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+pub async fn respond(pool: &SqlitePool, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    match serve(pool, request).await {
+        Ok(response) => response,
+        Err((status, message)) => {
+            tracing::warn!("{} {}: {}", request.method(), request.uri(), message);
+            build(status, &[], message.into_bytes())
+        }
+    }
+}
+
+async fn serve(pool: &SqlitePool, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>, (StatusCode, String)> {
+    // The dev server's origin differs from the protocol's, so pdf.js's Range header needs a preflight
+    if request.method() == Method::OPTIONS {
+        return Ok(build(StatusCode::NO_CONTENT, &[], Vec::new()));
+    }
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return Err((StatusCode::METHOD_NOT_ALLOWED, "Only GET and HEAD are supported".to_string()));
+    }
+
+    let paper_id: i64 = request
+        .uri()
+        .path()
+        .trim_matches('/')
+        .parse()
+        .map_err(|_| (StatusCode::NOT_FOUND, format!("Not a paper URL: {}", request.uri())))?;
+    let paper = db::get_paper(pool, paper_id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+    if paper.pdf_path.is_empty() {
+        return Err((StatusCode::NOT_FOUND, format!("Paper {} has no PDF", paper_id)));
+    }
+
+    let mut file = tokio::fs::File::open(Path::new(&paper.pdf_path))
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, format!("Failed to read PDF: {}", e)))?;
+    let len = file
+        .metadata()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read PDF: {}", e)))?
+        .len();
+
+    let range = request.headers().get(header::RANGE).and_then(|value| value.to_str().ok());
+    let Some((status, start, end)) = span(range, len) else {
+        return Ok(build(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            &[(header::CONTENT_RANGE, format!("bytes */{}", len))],
+            Vec::new(),
+        ));
+    };
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+
+    let mut headers = vec![
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (header::CONTENT_LENGTH, body_len.to_string()),
+    ];
+    if status == StatusCode::PARTIAL_CONTENT {
+        headers.push((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)));
+    }
+    if request.method() == Method::HEAD {
+        return Ok(build(status, &headers, Vec::new()));
+    }
+
+    // Custom protocol responses can't stream, so the body is read whole; range requests keep it small
+    let read_err = |e: std::io::Error| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read PDF: {}", e));
+    let mut body = vec![0; body_len as usize];
+    file.seek(SeekFrom::Start(start)).await.map_err(read_err)?;
+    file.read_exact(&mut body).await.map_err(read_err)?;
+
+    // Every load starts with the first bytes; later range requests fill in the rest
+    if start == 0 {
+        if let Err(e) = db::record_open(pool, paper_id).await {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    Ok(build(status, &headers, body))
+}
+
+// Status and first and last byte of the response: the whole file without a range, or the
+// requested range cut to MAX_RANGE_LEN, with a Content-Range saying how much there is. None if
+// the range can't be satisfied.
+fn span(range: Option<&str>, len: u64) -> Option<(StatusCode, u64, u64)> {
+    let Some(value) = range else {
+        return Some((StatusCode::OK, 0, len.saturating_sub(1)));
+    };
+    let (start, end) = parse_range(value, len)?;
+    Some((StatusCode::PARTIAL_CONTENT, start, end.min(start + MAX_RANGE_LEN - 1)))
+}
+
+// The first range of "bytes=500-999", "bytes=500-" or "bytes=-500", clamped to the file.
+// None if it can't be satisfied.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len.checked_sub(1)?)),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn build(status: StatusCode, headers: &[(header::HeaderName, String)], body: Vec<u8>) -> Response<Vec<u8>> {
+    let mut builder = Response::builder()
+        .status(status)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(header::ACCESS_CONTROL_ALLOW_METHODS, "GET, HEAD")
+        .header(header::ACCESS_CONTROL_ALLOW_HEADERS, "Range")
+        .header(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            "Accept-Ranges, Content-Length, Content-Range",
+        );
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(body).unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCAN_LEN: u64 = 100 * 1024 * 1024;
+
+    #[test]
+    fn never_reads_more_than_the_cap() {
+        for range in ["bytes=0-", "bytes=1000-", "bytes=-50000000", "bytes=0-99999999"] {
+            let (status, start, end) = span(Some(range), SCAN_LEN).unwrap();
+            assert_eq!(status, StatusCode::PARTIAL_CONTENT, "{:?}", range);
+            assert!(end - start < MAX_RANGE_LEN, "{:?} gave {}-{}", range, start, end);
+        }
+    }
+
+    #[test]
+    fn whole_file_without_a_range_starts_at_zero() {
+        assert_eq!(span(None, SCAN_LEN), Some((StatusCode::OK, 0, SCAN_LEN - 1)));
+        assert_eq!(span(None, 1000), Some((StatusCode::OK, 0, 999)));
+        assert_eq!(span(None, 0), Some((StatusCode::OK, 0, 0)));
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(span(Some("bytes=500-999"), 1000), Some((StatusCode::PARTIAL_CONTENT, 500, 999)));
+        assert_eq!(span(Some("bytes=-100"), 1000), Some((StatusCode::PARTIAL_CONTENT, 900, 999)));
+        assert_eq!(span(Some("bytes=500-5000"), 1000), Some((StatusCode::PARTIAL_CONTENT, 500, 999)));
+        assert_eq!(span(Some("bytes=1000-"), 1000), None);
+        assert_eq!(span(Some("pages=1-2"), 1000), None);
+    }
+}
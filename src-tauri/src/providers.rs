@@ -0,0 +1,254 @@
+// src-tauri/src/providers.rs
+
+// Metadata providers: where a record for an identifier or a title comes from. Crossref,
+// arXiv and PubMed are built in; more can be added as scripts (see providers/script.rs)
+// for databases the app doesn't know, without changing it. Identifier kinds are plain
+// names: "doi", "arxiv" and "pmid" for the built-in ones, anything a script declares for
+// the rest, e.g. "inspire" or "bibcode".
+
+mod script;
+
+use crate::arxiv;
+use crate::config;
+use crate::crossref::{self, WorkMetadata};
+use crate::pubmed;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'a>>;
+
+pub trait MetadataProvider: Send + Sync {
+    fn name(&self) -> &str;
+    // Identifier kinds `lookup` accepts
+    fn identifiers(&self) -> &[String];
+    fn can_search(&self) -> bool;
+    fn lookup<'a>(&'a self, kind: &'a str, id: &'a str) -> ProviderFuture<'a, WorkMetadata>;
+    // Best match first
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> ProviderFuture<'a, Vec<WorkMetadata>>;
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProviderInfo {
+    pub name: String,
+    pub identifiers: Vec<String>,
+    pub can_search: bool,
+    // The manifest of a script provider; None for the built-in ones
+    pub manifest: Option<PathBuf>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ProviderList {
+    pub providers: Vec<ProviderInfo>,
+    // Manifests that couldn't be loaded, with why
+    pub errors: Vec<String>,
+    pub directory: Option<PathBuf>,
+}
+
+pub struct Providers {
+    providers: Vec<(Arc<dyn MetadataProvider>, Option<PathBuf>)>,
+    errors: Vec<String>,
+    directory: Option<PathBuf>,
+}
+
+impl Providers {
+    // The built-in providers, then every script in <app local data>/providers. Loaded again
+    // for each use, so added or edited scripts count right away.
+    pub fn load(handle: &AppHandle, http: &reqwest::Client) -> Self {
+        let mailto = config::load(handle).network.contact_email;
+        let mut providers: Vec<(Arc<dyn MetadataProvider>, Option<PathBuf>)> = vec![
+            (Arc::new(Crossref::new(http.clone(), mailto)), None),
+            (Arc::new(Arxiv::new(http.clone())), None),
+            (Arc::new(Pubmed::new(http.clone())), None),
+        ];
+        let mut errors = Vec::new();
+
+        let directory = handle.path().app_local_data_dir().ok().map(|dir| dir.join("providers"));
+        if let Some(directory) = &directory {
+            for loaded in script::load_all(directory) {
+                match loaded {
+                    Ok(provider) if providers.iter().any(|(p, _)| p.name().eq_ignore_ascii_case(provider.name())) => {
+                        errors.push(format!("{}: a provider named {} is already loaded", provider.manifest().display(), provider.name()));
+                    }
+                    Ok(provider) => {
+                        let manifest = provider.manifest().to_path_buf();
+                        providers.push((Arc::new(provider), Some(manifest)));
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        Providers { providers, errors, directory }
+    }
+
+    pub fn list(&self) -> ProviderList {
+        ProviderList {
+            providers: self
+                .providers
+                .iter()
+                .map(|(provider, manifest)| ProviderInfo {
+                    name: provider.name().to_string(),
+                    identifiers: provider.identifiers().to_vec(),
+                    can_search: provider.can_search(),
+                    manifest: manifest.clone(),
+                })
+                .collect(),
+            errors: self.errors.clone(),
+            directory: self.directory.clone(),
+        }
+    }
+
+    fn named(&self, name: &str) -> Result<&Arc<dyn MetadataProvider>, String> {
+        self.providers
+            .iter()
+            .map(|(provider, _)| provider)
+            .find(|provider| provider.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("No metadata provider named {}", name))
+    }
+
+    // Asks the named provider, or else each one that takes this kind of identifier in turn
+    // until one has a record
+    pub async fn lookup(&self, kind: &str, id: &str, provider: Option<&str>) -> Result<WorkMetadata, String> {
+        let kind = kind.trim().to_lowercase();
+        if let Some(name) = provider {
+            return self.named(name)?.lookup(&kind, id.trim()).await;
+        }
+
+        let mut errors = Vec::new();
+        for (provider, _) in self.providers.iter().filter(|(p, _)| p.identifiers().contains(&kind)) {
+            match provider.lookup(&kind, id.trim()).await {
+                Ok(metadata) => return Ok(metadata),
+                Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+            }
+        }
+        match errors.is_empty() {
+            true => Err(format!("No metadata provider looks up {} identifiers", kind)),
+            false => Err(errors.join("; ")),
+        }
+    }
+
+    // Crossref unless another provider is named
+    pub async fn search(&self, query: &str, limit: usize, provider: Option<&str>) -> Result<Vec<WorkMetadata>, String> {
+        let provider = self.named(provider.unwrap_or(Crossref::NAME))?;
+        if !provider.can_search() {
+            return Err(format!("{} can't search by title", provider.name()));
+        }
+        provider.search(query.trim(), limit).await
+    }
+}
+
+fn unsupported<'a, T: Send + 'a>(provider: &dyn MetadataProvider, what: &str) -> ProviderFuture<'a, T> {
+    let message = format!("{} can't {}", provider.name(), what);
+    Box::pin(async move { Err(message) })
+}
+
+struct Crossref {
+    http: reqwest::Client,
+    mailto: Option<String>,
+    identifiers: Vec<String>,
+}
+
+impl Crossref {
+    const NAME: &'static str = "Crossref";
+
+    fn new(http: reqwest::Client, mailto: Option<String>) -> Self {
+        Crossref { http, mailto, identifiers: vec!["doi".to_string()] }
+    }
+}
+
+impl MetadataProvider for Crossref {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    fn can_search(&self) -> bool {
+        true
+    }
+
+    fn lookup<'a>(&'a self, _kind: &'a str, id: &'a str) -> ProviderFuture<'a, WorkMetadata> {
+        Box::pin(crossref::fetch_by_doi(&self.http, id, self.mailto.as_deref()))
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> ProviderFuture<'a, Vec<WorkMetadata>> {
+        Box::pin(crossref::search(&self.http, query, limit, self.mailto.as_deref()))
+    }
+}
+
+struct Arxiv {
+    http: reqwest::Client,
+    identifiers: Vec<String>,
+}
+
+impl Arxiv {
+    fn new(http: reqwest::Client) -> Self {
+        Arxiv { http, identifiers: vec!["arxiv".to_string()] }
+    }
+}
+
+impl MetadataProvider for Arxiv {
+    fn name(&self) -> &str {
+        "arXiv"
+    }
+
+    fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    fn can_search(&self) -> bool {
+        false
+    }
+
+    fn lookup<'a>(&'a self, _kind: &'a str, id: &'a str) -> ProviderFuture<'a, WorkMetadata> {
+        Box::pin(async move {
+            let id = arxiv::parse_id(id).ok_or_else(|| format!("Not a valid arXiv id: {}", id))?;
+            arxiv::fetch_entry(&self.http, &id).await.map(WorkMetadata::from)
+        })
+    }
+
+    fn search<'a>(&'a self, _query: &'a str, _limit: usize) -> ProviderFuture<'a, Vec<WorkMetadata>> {
+        unsupported(self, "search by title")
+    }
+}
+
+struct Pubmed {
+    http: reqwest::Client,
+    identifiers: Vec<String>,
+}
+
+impl Pubmed {
+    fn new(http: reqwest::Client) -> Self {
+        Pubmed { http, identifiers: vec!["pmid".to_string()] }
+    }
+}
+
+impl MetadataProvider for Pubmed {
+    fn name(&self) -> &str {
+        "PubMed"
+    }
+
+    fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    fn can_search(&self) -> bool {
+        false
+    }
+
+    fn lookup<'a>(&'a self, _kind: &'a str, id: &'a str) -> ProviderFuture<'a, WorkMetadata> {
+        Box::pin(async move {
+            let pmid = pubmed::parse_pmid(id).ok_or_else(|| format!("Not a valid PMID: {}", id))?;
+            pubmed::fetch_article(&self.http, &pmid).await.map(WorkMetadata::from)
+        })
+    }
+
+    fn search<'a>(&'a self, _query: &'a str, _limit: usize) -> ProviderFuture<'a, Vec<WorkMetadata>> {
+        unsupported(self, "search by title")
+    }
+}
@@ -0,0 +1,173 @@
+// src-tauri/src/providers/script.rs
+
+// Providers backed by a user's script. Each is described by a JSON manifest in the
+// providers directory:
+//
+//     { "name": "INSPIRE", "command": "python3", "args": ["inspire.py"],
+//       "identifiers": ["inspire"], "search": true }
+//
+// The command runs in that directory, once per request. It gets one JSON line on stdin,
+//
+//     {"method": "lookup", "kind": "inspire", "id": "Maldacena:1997re"}
+//     {"method": "search", "query": "large N limit", "limit": 5}
+//
+// and answers on stdout with {"works": [...]}, each work shaped like WorkMetadata (only
+// "title" is required), or with {"error": "why"}. A lookup takes the first work.
+
+use super::{MetadataProvider, ProviderFuture};
+use crate::crossref::WorkMetadata;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    identifiers: Vec<String>,
+    #[serde(default)]
+    search: bool,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "lowercase")]
+enum Request<'a> {
+    Lookup { kind: &'a str, id: &'a str },
+    Search { query: &'a str, limit: usize },
+}
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    works: Vec<WorkMetadata>,
+    error: Option<String>,
+}
+
+pub struct ScriptProvider {
+    manifest: Manifest,
+    path: PathBuf,
+    directory: PathBuf,
+}
+
+// Every *.json manifest in the directory, by file name; a missing directory has none
+pub fn load_all(directory: &Path) -> Vec<Result<ScriptProvider, String>> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")))
+        .collect();
+    paths.sort();
+    paths.into_iter().map(|path| load(directory, path)).collect()
+}
+
+fn load(directory: &Path, path: PathBuf) -> Result<ScriptProvider, String> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut manifest: Manifest =
+        serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+    manifest.name = manifest.name.trim().to_string();
+    if manifest.name.is_empty() || manifest.command.trim().is_empty() {
+        return Err(format!("{}: a provider needs a name and a command", path.display()));
+    }
+    if manifest.identifiers.is_empty() && !manifest.search {
+        return Err(format!("{}: {} neither looks up identifiers nor searches", path.display(), manifest.name));
+    }
+    manifest.identifiers = manifest.identifiers.iter().map(|kind| kind.trim().to_lowercase()).collect();
+
+    Ok(ScriptProvider { manifest, path, directory: directory.to_path_buf() })
+}
+
+impl ScriptProvider {
+    pub fn manifest(&self) -> &Path {
+        &self.path
+    }
+
+    async fn call(&self, request: Request<'_>) -> Result<Vec<WorkMetadata>, String> {
+        let name = &self.manifest.name;
+        // A command given as a relative path is one next to the manifest
+        let program = match Path::new(&self.manifest.command) {
+            command if command.is_relative() && command.components().count() > 1 => self.directory.join(command),
+            command => command.to_path_buf(),
+        };
+        let mut child = Command::new(&program)
+            .args(&self.manifest.args)
+            .current_dir(&self.directory)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to run {} for {}: {}", program.display(), name, e))?;
+
+        let mut line = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        // Written alongside the wait, so a script that never reads its input still times out;
+        // one that answers without reading it is fine
+        if let Some(mut stdin) = child.stdin.take() {
+            tauri::async_runtime::spawn(async move {
+                let _ = stdin.write_all(&line).await;
+            });
+        }
+
+        let timeout = Duration::from_secs(self.manifest.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| format!("{} didn't answer within {} seconds", name, timeout.as_secs()))?
+            .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+        if !output.status.success() {
+            return Err(format!("{} failed: {}", name, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+
+        let response: Response = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Unexpected answer from {}: {}", name, e))?;
+        match response.error {
+            Some(error) => Err(error),
+            None => Ok(response.works.into_iter().filter(|work| !work.title.trim().is_empty()).collect()),
+        }
+    }
+}
+
+impl MetadataProvider for ScriptProvider {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn identifiers(&self) -> &[String] {
+        &self.manifest.identifiers
+    }
+
+    fn can_search(&self) -> bool {
+        self.manifest.search
+    }
+
+    fn lookup<'a>(&'a self, kind: &'a str, id: &'a str) -> ProviderFuture<'a, WorkMetadata> {
+        Box::pin(async move {
+            if !self.manifest.identifiers.iter().any(|k| k == kind) {
+                return Err(format!("{} doesn't look up {} identifiers", self.manifest.name, kind));
+            }
+            self.call(Request::Lookup { kind, id })
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("{} has no record for {}", self.manifest.name, id))
+        })
+    }
+
+    fn search<'a>(&'a self, query: &'a str, limit: usize) -> ProviderFuture<'a, Vec<WorkMetadata>> {
+        Box::pin(async move {
+            let mut works = self.call(Request::Search { query, limit }).await?;
+            works.truncate(limit);
+            Ok(works)
+        })
+    }
+}
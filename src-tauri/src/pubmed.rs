@@ -0,0 +1,190 @@
+// src-tauri/src/pubmed.rs
+
+// Looks up PubMed records through NCBI E-utilities (efetch returns PubMed XML)
+
+use crate::crossref::{normalize_doi, WorkMetadata};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+const EFETCH_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi";
+
+#[derive(Debug, Default)]
+pub struct PubmedArticle {
+    pub pmid: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub journal: Option<String>,
+    pub year: Option<i64>,
+    pub doi: Option<String>,
+    pub r#abstract: Option<String>,
+    // MeSH descriptor names, e.g. "Neoplasms"
+    pub mesh_terms: Vec<String>,
+}
+
+impl From<PubmedArticle> for WorkMetadata {
+    fn from(article: PubmedArticle) -> Self {
+        // Keywords are comma separated, and some headings contain commas ("Neoplasms, Experimental")
+        let mesh_terms: Vec<String> = article.mesh_terms.iter().map(|t| t.replace(',', "")).collect();
+        WorkMetadata {
+            title: article.title,
+            authors: (!article.authors.is_empty()).then(|| article.authors.join(", ")),
+            journal: article.journal,
+            year: article.year,
+            doi: article.doi,
+            r#abstract: article.r#abstract,
+            keywords: (!mesh_terms.is_empty()).then(|| mesh_terms.join(", ")),
+            ..Default::default()
+        }
+    }
+}
+
+// Accepts "31452104", "PMID: 31452104" and pubmed.ncbi.nlm.nih.gov URLs
+pub fn parse_pmid(input: &str) -> Option<String> {
+    let trimmed = input.trim().trim_end_matches('/');
+    let id = trimmed
+        .rsplit(['/', ':', '='])
+        .next()
+        .unwrap_or(trimmed)
+        .trim();
+    (!id.is_empty() && id.len() <= 9 && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+}
+
+pub async fn fetch_article(client: &reqwest::Client, pmid: &str) -> Result<PubmedArticle, String> {
+    let body = client
+        .get(EFETCH_URL)
+        .query(&[("db", "pubmed"), ("id", pmid), ("retmode", "xml"), ("tool", "paper-master")])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("PubMed request failed: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("PubMed request failed: {}", e))?;
+
+    parse_article(&body)?.ok_or_else(|| format!("PMID not found on PubMed: {}", pmid))
+}
+
+// Elements whose text we keep. Titles and abstracts may contain inline markup (<i>, <sup>),
+// so text is gathered from everything nested inside them.
+const CAPTURED: &[&str] = &[
+    "PMID",
+    "ArticleTitle",
+    "AbstractText",
+    "LastName",
+    "ForeName",
+    "CollectiveName",
+    "Title",
+    "Year",
+    "MedlineDate",
+    "ELocationID",
+    "ArticleId",
+    "DescriptorName",
+];
+
+// Reads the first PubmedArticle in an efetch response
+fn parse_article(xml: &str) -> Result<Option<PubmedArticle>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut article = PubmedArticle::default();
+    let mut found = false;
+
+    let mut path: Vec<String> = Vec::new();
+    // Text of the captured element we're in, with its attribute of interest
+    let mut captured: Option<(String, Option<String>)> = None;
+    let mut abstract_parts: Vec<String> = Vec::new();
+    let (mut fore_name, mut last_name, mut collective) = (None, None, None);
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Unexpected PubMed response: {}", e))?;
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if name == "PubmedArticle" {
+                    if found {
+                        break;
+                    }
+                    found = true;
+                }
+                if captured.is_none() && CAPTURED.contains(&name.as_str()) {
+                    let attribute = ["Label", "EIdType", "IdType"].iter().find_map(|key| {
+                        let value = e.try_get_attribute(*key).ok().flatten()?;
+                        value.unescape_value().ok().map(|v| v.into_owned())
+                    });
+                    captured = Some((String::new(), attribute));
+                }
+                path.push(name);
+            }
+            Event::Text(e) => {
+                if let Some((text, _)) = captured.as_mut() {
+                    text.push_str(&e.unescape().map_err(|e| format!("Unexpected PubMed response: {}", e))?);
+                }
+            }
+            Event::CData(e) => {
+                if let Some((text, _)) = captured.as_mut() {
+                    text.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::End(_) => {
+                let Some(name) = path.pop() else {
+                    continue;
+                };
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let grandparent = path.iter().rev().nth(1).map(String::as_str).unwrap_or_default();
+
+                if name == "Author" && parent == "AuthorList" {
+                    let author = match (fore_name.take(), last_name.take(), collective.take()) {
+                        (Some(fore), Some(last), _) => Some(format!("{} {}", fore, last)),
+                        (None, Some(last), _) => Some(last),
+                        (_, None, collective) => collective,
+                    };
+                    article.authors.extend(author);
+                    continue;
+                }
+                if !CAPTURED.contains(&name.as_str()) || path.iter().any(|p| CAPTURED.contains(&p.as_str())) {
+                    continue;
+                }
+                let Some((text, attribute)) = captured.take() else {
+                    continue;
+                };
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    continue;
+                }
+
+                match (name.as_str(), parent) {
+                    // Comments and corrections list other articles' PMIDs deeper down
+                    ("PMID", "MedlineCitation") => article.pmid = text,
+                    ("ArticleTitle", "Article") => article.title = text.trim_end_matches('.').to_string(),
+                    ("AbstractText", "Abstract") => abstract_parts.push(match attribute {
+                        Some(label) => format!("{}: {}", label, text),
+                        None => text,
+                    }),
+                    ("LastName", "Author") => last_name = Some(text),
+                    ("ForeName", "Author") => fore_name = Some(text),
+                    ("CollectiveName", "Author") => collective = Some(text),
+                    ("Title", "Journal") => article.journal = Some(text),
+                    ("Year", "PubDate") => article.year = text.parse().ok(),
+                    // e.g. "2019 Jan-Feb"
+                    ("MedlineDate", "PubDate") => article.year = text.get(..4).and_then(|y| y.parse().ok()),
+                    ("ELocationID", "Article") | ("ArticleId", "ArticleIdList")
+                        if attribute.as_deref() == Some("doi")
+                            && (parent == "Article" || grandparent == "PubmedData") =>
+                    {
+                        article.doi = article.doi.take().or_else(|| normalize_doi(&text));
+                    }
+                    ("DescriptorName", "MeshHeading") => article.mesh_terms.push(text),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !found || article.pmid.is_empty() {
+        return Ok(None);
+    }
+    article.r#abstract = (!abstract_parts.is_empty()).then(|| abstract_parts.join("\n\n"));
+    Ok(Some(article))
+}
@@ -0,0 +1,400 @@
+// src-tauri/src/query.rs
+
+// The advanced search syntax: terms separated by spaces, all of which must hold, e.g.
+//
+//     author:smith year:2019..2022 tag:transformers -tag:survey "attention"
+//
+// `field:value` narrows one field, a leading `-` negates a term and quotes keep spaces
+// together ("attention is all", author:"van der berg"). Bare words and phrases are looked up
+// like the search box does: in the title and PDF text, or the authors. Any other field name
+// is taken to be a custom field (grant:ERC, samples:10..50). Values only ever reach SQL as
+// bound parameters.
+
+use crate::crossref::normalize_doi;
+use crate::db::{like_pattern, ItemType, ReadingStatus};
+use sqlx::{QueryBuilder, Sqlite};
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    pub negated: bool,
+    pub condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    // A word or phrase in the title, PDF text or authors
+    Text(String),
+    // Substrings, ignoring case
+    Author(String),
+    Title(String),
+    Journal(String),
+    // Exact names, ignoring case
+    Tag(String),
+    Collection(String),
+    Doi(String),
+    Year(Range),
+    Rating(Range),
+    Status(ReadingStatus),
+    Type(ItemType),
+    // is:starred
+    Starred,
+    // A user-defined field: text fields match substrings, the others whole values; ranges
+    // compare numbers as numbers and dates as text
+    Custom { field: String, value: CustomMatch },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomMatch {
+    Value(String),
+    Between(Option<String>, Option<String>),
+}
+
+// Built-in field names; custom fields can't take these
+pub const FIELDS: &[&str] = &[
+    "author", "authors", "title", "journal", "venue", "tag", "collection", "doi", "year", "rating", "status", "type", "is",
+];
+
+// Inclusive; at least one bound is set
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+pub fn parse(input: &str) -> Result<Query, String> {
+    let mut chars = input.chars().peekable();
+    let mut terms = Vec::new();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let negated = chars.next_if_eq(&'-').is_some();
+
+        let mut word = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '"') {
+            word.push(c);
+        }
+        // A quote only belongs to this term at its start or right after `field:`
+        let quoted = if (word.is_empty() || word.ends_with(':')) && chars.next_if_eq(&'"').is_some() {
+            Some(read_quoted(&mut chars)?)
+        } else {
+            None
+        };
+
+        let condition = match (word.split_once(':'), quoted) {
+            (Some((field, "")), Some(value)) if !field.is_empty() => field_condition(field, &value)?,
+            (Some((field, value)), None) if !field.is_empty() => field_condition(field, value)?,
+            (_, Some(phrase)) => Condition::Text(phrase),
+            (_, None) => Condition::Text(word),
+        };
+        // A lone "-" or empty quotes
+        if condition == Condition::Text(String::new()) {
+            continue;
+        }
+        terms.push(Term { negated, condition });
+    }
+
+    Ok(Query { terms })
+}
+
+// After the opening quote; "" inside stands for a quote
+fn read_quoted(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        if c == '"' && chars.next_if_eq(&'"').is_none() {
+            return Ok(value.trim().to_string());
+        }
+        value.push(c);
+    }
+    Err("Missing closing quote".to_string())
+}
+
+fn field_condition(field: &str, value: &str) -> Result<Condition, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("No value given for {}:", field));
+    }
+    let text = || value.to_string();
+
+    Ok(match field.to_lowercase().as_str() {
+        "author" | "authors" => Condition::Author(text()),
+        "title" => Condition::Title(text()),
+        "journal" | "venue" => Condition::Journal(text()),
+        "tag" => Condition::Tag(text()),
+        "collection" => Condition::Collection(text()),
+        "doi" => Condition::Doi(normalize_doi(value).unwrap_or_else(text)),
+        "year" => Condition::Year(parse_range(value).ok_or_else(|| {
+            format!("Invalid year \"{}\"; use e.g. year:2019, year:2019..2022 or year:2019..", value)
+        })?),
+        "rating" => Condition::Rating(parse_range(value).ok_or_else(|| {
+            format!("Invalid rating \"{}\"; use e.g. rating:4 or rating:3..5", value)
+        })?),
+        "status" => Condition::Status(match value.to_lowercase().as_str() {
+            "unread" => ReadingStatus::Unread,
+            "reading" => ReadingStatus::Reading,
+            "read" => ReadingStatus::Read,
+            _ => return Err(format!("Unknown status \"{}\"; use unread, reading or read", value)),
+        }),
+        "type" => Condition::Type(ItemType::parse(value).ok_or_else(|| {
+            format!("Unknown type \"{}\"; use article, book, thesis, preprint or report", value)
+        })?),
+        "is" if value.eq_ignore_ascii_case("starred") => Condition::Starred,
+        "is" => return Err(format!("Unknown is:{}; use is:starred", value)),
+        _ => Condition::Custom {
+            field: field.to_string(),
+            value: match value.split_once("..") {
+                Some((from, to)) if !from.is_empty() || !to.is_empty() => {
+                    let bound = |b: &str| (!b.is_empty()).then(|| b.to_string());
+                    CustomMatch::Between(bound(from), bound(to))
+                }
+                _ => CustomMatch::Value(text()),
+            },
+        },
+    })
+}
+
+// "2019", "2019..2022", "2019.." or "..2022"
+fn parse_range(value: &str) -> Option<Range> {
+    let bound = |b: &str| if b.is_empty() { Ok(None) } else { b.parse().map(Some) };
+    let range = match value.split_once("..") {
+        Some((from, to)) => Range { from: bound(from).ok()?, to: bound(to).ok()? },
+        None => {
+            let exact = value.parse().ok()?;
+            Range { from: Some(exact), to: Some(exact) }
+        }
+    };
+    (range.from.is_some() || range.to.is_some()).then_some(range)
+}
+
+// Appends " AND ..." for every term
+pub fn push_conditions(builder: &mut QueryBuilder<'_, Sqlite>, query: &Query) {
+    for term in &query.terms {
+        if term.negated {
+            // A condition on an empty column is NULL, and those papers don't match it either
+            builder.push(" AND NOT IFNULL((");
+            push_condition(builder, &term.condition);
+            builder.push("), 0)");
+        } else {
+            builder.push(" AND (");
+            push_condition(builder, &term.condition);
+            builder.push(")");
+        }
+    }
+}
+
+fn push_condition(builder: &mut QueryBuilder<'_, Sqlite>, condition: &Condition) {
+    match condition {
+        Condition::Text(text) => {
+            // One FTS5 phrase, so the words have to appear together
+            builder
+                .push("id IN (SELECT rowid FROM papers_fts WHERE papers_fts MATCH ")
+                .push_bind(format!("\"{}\"", text.replace('"', "\"\"")))
+                .push(") OR authors LIKE ")
+                .push_bind(like_pattern(text))
+                .push(" ESCAPE '\\'");
+        }
+        Condition::Author(author) => push_like(builder, "authors", author),
+        Condition::Title(title) => push_like(builder, "title", title),
+        Condition::Journal(journal) => push_like(builder, "journal", journal),
+        Condition::Tag(tag) => {
+            builder
+                .push("id IN (SELECT pt.paper_id FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name = ")
+                .push_bind(tag.clone())
+                .push(")");
+        }
+        Condition::Collection(name) => {
+            builder
+                .push(
+                    "id IN (SELECT pc.paper_id FROM paper_collections pc \
+                     JOIN collections c ON c.id = pc.collection_id WHERE c.name = ",
+                )
+                .push_bind(name.clone())
+                .push(" COLLATE NOCASE)");
+        }
+        Condition::Doi(doi) => {
+            builder.push("doi = ").push_bind(doi.clone()).push(" COLLATE NOCASE");
+        }
+        Condition::Year(range) => push_range(builder, "year", *range),
+        Condition::Rating(range) => push_range(builder, "rating", *range),
+        Condition::Status(status) => {
+            builder.push("reading_status = ").push_bind(*status);
+        }
+        Condition::Type(item_type) => {
+            builder.push("item_type = ").push_bind(*item_type);
+        }
+        Condition::Starred => {
+            builder.push("starred = 1");
+        }
+        Condition::Custom { field, value } => {
+            builder
+                .push(
+                    "id IN (SELECT v.paper_id FROM custom_values v JOIN custom_fields f ON f.id = v.field_id \
+                     WHERE f.name = ",
+                )
+                .push_bind(field.clone())
+                .push(" COLLATE NOCASE AND ");
+            push_custom_match(builder, value);
+            builder.push(")");
+        }
+    }
+}
+
+fn push_custom_match(builder: &mut QueryBuilder<'_, Sqlite>, value: &CustomMatch) {
+    match value {
+        CustomMatch::Value(value) => {
+            builder
+                .push("CASE f.field_type WHEN 'text' THEN v.value LIKE ")
+                .push_bind(like_pattern(value))
+                .push(" ESCAPE '\\' WHEN 'number' THEN CAST(v.value AS REAL) = CAST(")
+                .push_bind(value.clone())
+                .push(" AS REAL) ELSE v.value = ")
+                .push_bind(value.clone())
+                .push(" COLLATE NOCASE END");
+        }
+        CustomMatch::Between(from, to) => {
+            builder.push("(");
+            for (bound, op) in [(from, ">="), (to, "<=")] {
+                match bound {
+                    Some(bound) => {
+                        builder
+                            .push(format!("CASE f.field_type WHEN 'number' THEN CAST(v.value AS REAL) {} CAST(", op))
+                            .push_bind(bound.clone())
+                            .push(format!(" AS REAL) ELSE v.value {} ", op))
+                            .push_bind(bound.clone())
+                            .push(" END");
+                    }
+                    None => {
+                        builder.push("1 = 1");
+                    }
+                }
+                if op == ">=" {
+                    builder.push(" AND ");
+                }
+            }
+            builder.push(")");
+        }
+    }
+}
+
+fn push_like(builder: &mut QueryBuilder<'_, Sqlite>, column: &str, value: &str) {
+    builder
+        .push(format!("{} LIKE ", column))
+        .push_bind(like_pattern(value))
+        .push(" ESCAPE '\\'");
+}
+
+fn push_range(builder: &mut QueryBuilder<'_, Sqlite>, column: &str, range: Range) {
+    match (range.from, range.to) {
+        (Some(from), Some(to)) => {
+            builder.push(format!("{} BETWEEN ", column)).push_bind(from).push(" AND ").push_bind(to);
+        }
+        (Some(from), None) => {
+            builder.push(format!("{} >= ", column)).push_bind(from);
+        }
+        (None, Some(to)) => {
+            builder.push(format!("{} <= ", column)).push_bind(to);
+        }
+        (None, None) => {
+            builder.push("1 = 1");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(input: &str) -> Vec<(bool, Condition)> {
+        parse(input).unwrap().terms.into_iter().map(|term| (term.negated, term.condition)).collect()
+    }
+
+    fn text(value: &str) -> Condition {
+        Condition::Text(value.to_string())
+    }
+
+    #[test]
+    fn keeps_quoted_phrases_together() {
+        assert_eq!(
+            conditions(r#""attention is all"  transformers"#),
+            [(false, text("attention is all")), (false, text("transformers"))]
+        );
+        assert_eq!(conditions(r#"author:"van der berg""#), [(false, Condition::Author("van der berg".to_string()))]);
+        assert_eq!(conditions(r#""say ""hi"" twice""#), [(false, text(r#"say "hi" twice"#))]);
+    }
+
+    #[test]
+    fn reads_field_values() {
+        assert_eq!(
+            conditions("Author:smith year:2019..2022 rating:4 status:Read type:thesis is:starred doi:https://doi.org/10.1000/XYZ"),
+            [
+                (false, Condition::Author("smith".to_string())),
+                (false, Condition::Year(Range { from: Some(2019), to: Some(2022) })),
+                (false, Condition::Rating(Range { from: Some(4), to: Some(4) })),
+                (false, Condition::Status(ReadingStatus::Read)),
+                (false, Condition::Type(ItemType::Thesis)),
+                (false, Condition::Starred),
+                (false, Condition::Doi("10.1000/XYZ".to_string())),
+            ]
+        );
+        assert_eq!(conditions("year:..2010"), [(false, Condition::Year(Range { from: None, to: Some(2010) }))]);
+        assert!(parse("year:soon").is_err());
+        assert!(parse("year:..").is_err());
+        assert!(parse("status:skimmed").is_err());
+        assert!(parse("is:read").is_err());
+    }
+
+    #[test]
+    fn takes_unknown_fields_as_custom_fields() {
+        let custom = |value| Condition::Custom { field: "grant".to_string(), value };
+        assert_eq!(conditions("grant:ERC"), [(false, custom(CustomMatch::Value("ERC".to_string())))]);
+        assert_eq!(
+            conditions("grant:10..50 grant:10.. grant:.."),
+            [
+                (false, custom(CustomMatch::Between(Some("10".to_string()), Some("50".to_string())))),
+                (false, custom(CustomMatch::Between(Some("10".to_string()), None))),
+                (false, custom(CustomMatch::Value("..".to_string()))),
+            ]
+        );
+        // Nothing before the colon is no field at all
+        assert_eq!(conditions(":value"), [(false, text(":value"))]);
+    }
+
+    #[test]
+    fn skips_empty_terms() {
+        assert_eq!(parse("").unwrap().terms, []);
+        assert_eq!(parse("   ").unwrap().terms, []);
+        assert_eq!(conditions(r#""" - "  " word"#), [(false, text("word"))]);
+    }
+
+    #[test]
+    fn refuses_unbalanced_quotes_and_empty_values() {
+        assert_eq!(parse(r#""attention is"#), Err("Missing closing quote".to_string()));
+        assert!(parse(r#"author:"smith"#).is_err());
+        assert!(parse("tag:").is_err());
+        assert!(parse(r#"tag:"""#).is_err());
+    }
+
+    #[test]
+    fn negates_terms() {
+        assert_eq!(
+            conditions(r#"-tag:survey -"deep learning" -word keep"#),
+            [
+                (true, Condition::Tag("survey".to_string())),
+                (true, text("deep learning")),
+                (true, text("word")),
+                (false, text("keep")),
+            ]
+        );
+        // Only a leading dash negates
+        assert_eq!(conditions("state-of-the-art"), [(false, text("state-of-the-art"))]);
+    }
+}
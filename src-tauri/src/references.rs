@@ -0,0 +1,391 @@
+// src-tauri/src/references.rs
+
+// Pulls the bibliography out of a PDF's text with layout heuristics. Numbered ("[12]",
+// "12.") and author-year lists are supported; fields that can't be found are left empty
+// and the raw entry is always kept.
+
+use crate::arxiv;
+use crate::crossref::normalize_doi;
+use crate::db::references::ExtractedReference;
+use crate::db::Paper;
+use crate::pdf::find_year;
+use std::collections::HashMap;
+
+const SECTION_HEADINGS: &[&str] = &[
+    "references",
+    "bibliography",
+    "literaturecited",
+    "workscited",
+    "referencesandnotes",
+    "citedliterature",
+];
+const END_HEADINGS: &[&str] = &[
+    "appendix",
+    "appendices",
+    "supplementarymaterial",
+    "supplementaryinformation",
+    "supplementalmaterial",
+];
+// Anything longer is two or more entries run together
+const MAX_ENTRY_LEN: usize = 1500;
+// Words that end in a period without ending a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "vol", "no", "pp", "proc", "conf", "eds", "ed", "jr", "int", "trans", "inc", "univ", "dept", "st", "ch", "eq",
+];
+
+pub fn extract_references(text: &str) -> Vec<ExtractedReference> {
+    let lines = reference_section(text);
+    split_entries(&lines)
+        .iter()
+        .filter_map(|entry| parse_entry(entry))
+        .collect()
+}
+
+// Lines after the last "References" heading, up to an appendix if there is one
+fn reference_section(text: &str) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let Some(start) = lines.iter().rposition(|l| is_heading(l, SECTION_HEADINGS)) else {
+        return Vec::new();
+    };
+
+    lines[start + 1..]
+        .iter()
+        .take_while(|l| !is_heading(l, END_HEADINGS))
+        // Page numbers and running headers
+        .filter(|l| !l.is_empty() && !l.bytes().all(|b| b.is_ascii_digit()))
+        .copied()
+        .collect()
+}
+
+// Matches "References", "7 References", "VII. REFERENCES" and letter-spaced "R EFERENCES"
+fn is_heading(line: &str, headings: &[&str]) -> bool {
+    if line.len() > 40 {
+        return false;
+    }
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let skip = match words.first() {
+        Some(first) if first.trim_end_matches('.').chars().all(|c| c.is_ascii_digit() || "IVX".contains(c)) => 1,
+        _ => 0,
+    };
+    let letters: String = words
+        .iter()
+        .skip(skip)
+        .flat_map(|w| w.chars())
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let rest_is_letters = words
+        .iter()
+        .skip(skip)
+        .all(|w| w.chars().all(|c| c.is_alphabetic() || c == ':'));
+
+    rest_is_letters && headings.contains(&letters.as_str())
+}
+
+fn split_entries(lines: &[&str]) -> Vec<String> {
+    let Some(first) = lines.first() else {
+        return Vec::new();
+    };
+
+    if first.starts_with("[1]") {
+        split_bracketed(&join_lines(lines))
+    } else if first.starts_with('[') {
+        // Alphanumeric keys like "[Vas+17]"
+        split_lines(lines, |line, _| line.starts_with('['))
+    } else if starts_with_number(first, 1) {
+        let mut expected = 1;
+        split_lines(lines, |line, _| {
+            let starts = starts_with_number(line, expected);
+            if starts {
+                expected += 1;
+            }
+            starts
+        })
+    } else {
+        split_lines(lines, |line, previous| {
+            previous.trim_end().ends_with('.') && looks_like_author_start(line)
+        })
+    }
+}
+
+// "[n]" markers are found in sequence so a bracketed number inside an entry doesn't split it
+fn split_bracketed(text: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut number = 1;
+    while let Some(found) = text[start..].find(&format!("[{}]", number)).map(|i| start + i) {
+        if number > 1 {
+            entries.push(text[start..found].trim().to_string());
+        }
+        start = found + format!("[{}]", number).len();
+        number += 1;
+    }
+    if number > 1 {
+        entries.push(text[start..].trim().to_string());
+    }
+    entries
+}
+
+// Starts a new entry on each line `starts_entry` accepts; it also sees the entry so far
+fn split_lines(lines: &[&str], mut starts_entry: impl FnMut(&str, &str) -> bool) -> Vec<String> {
+    let mut entries: Vec<Vec<&str>> = Vec::new();
+    for line in lines {
+        let previous = entries.last().and_then(|e| e.last()).copied().unwrap_or_default();
+        // Always asked, so stateful predicates see the first line too
+        let starts = starts_entry(line, previous);
+        if starts || entries.is_empty() {
+            entries.push(Vec::new());
+        }
+        if let Some(entry) = entries.last_mut() {
+            entry.push(line);
+        }
+    }
+    entries.iter().map(|entry| join_lines(entry)).collect()
+}
+
+fn starts_with_number(line: &str, number: usize) -> bool {
+    let Some(rest) = line.strip_prefix(&number.to_string()) else {
+        return false;
+    };
+    rest.starts_with(". ") || rest == "." || rest.starts_with(' ')
+}
+
+// "Smith, J.", "Smith J", "J. Smith", "van der Berg, A."
+fn looks_like_author_start(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    let Some(first) = words.next() else {
+        return false;
+    };
+    let is_initial = first.len() <= 3 && first.ends_with('.') && first.starts_with(char::is_uppercase);
+    let is_surname = first.starts_with(char::is_uppercase)
+        && first.trim_end_matches(',').chars().all(|c| c.is_alphabetic() || c == '-' || c == '\'')
+        && (first.ends_with(',') || words.next().is_some_and(|w| w.ends_with('.') || w.ends_with(',')));
+    let is_particle = ["van", "von", "de", "der", "da", "di", "le", "la"].contains(&first);
+    is_initial || is_surname || is_particle
+}
+
+// Joins wrapped lines, undoing end-of-line hyphenation and rejoining split DOIs and URLs
+fn join_lines(lines: &[&str]) -> String {
+    let mut joined = String::new();
+    for line in lines {
+        let line = line.trim();
+        if joined.ends_with('-') && line.starts_with(char::is_lowercase) {
+            joined.pop();
+        } else if !joined.is_empty() && !joined.ends_with('/') {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+    }
+    joined.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_entry(entry: &str) -> Option<ExtractedReference> {
+    let raw = strip_marker(entry.trim()).trim();
+    if raw.len() < 15 || raw.len() > MAX_ENTRY_LEN || !raw.chars().any(char::is_alphabetic) {
+        return None;
+    }
+
+    let doi = find_doi(raw);
+    let arxiv_id = find_arxiv_id(raw);
+    // Keep identifiers out of the year search; "2012.12345" is an arXiv id, not 2012
+    let mut without_ids = raw.to_string();
+    for id in doi.iter().chain(arxiv_id.iter()) {
+        without_ids = without_ids.replace(id.as_str(), " ");
+    }
+
+    let (authors, title) = quoted_title(raw).unwrap_or_else(|| sentence_fields(raw));
+
+    Some(ExtractedReference {
+        raw: raw.to_string(),
+        title,
+        authors,
+        year: find_year(&without_ids),
+        doi,
+        arxiv_id,
+        ..Default::default()
+    })
+}
+
+fn strip_marker(entry: &str) -> &str {
+    if entry.starts_with('[') {
+        if let Some(end) = entry.find(']').filter(|end| *end < 20) {
+            return &entry[end + 1..];
+        }
+    }
+    let digits = entry.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if digits > 0 && digits < 4 {
+        return entry[digits..].trim_start_matches('.');
+    }
+    entry
+}
+
+pub fn find_doi(raw: &str) -> Option<String> {
+    raw.match_indices("10.").find_map(|(i, _)| {
+        let before = raw[..i].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '.') {
+            return None;
+        }
+        let candidate = raw[i..]
+            .split_whitespace()
+            .next()?
+            .trim_end_matches(['.', ',', ';', ')', ']']);
+        normalize_doi(candidate).filter(|doi| doi.split_once('/').is_some_and(|(_, suffix)| !suffix.is_empty()))
+    })
+}
+
+pub fn find_arxiv_id(raw: &str) -> Option<String> {
+    let lower = raw.to_ascii_lowercase();
+    ["arxiv:", "arxiv.org/abs/", "arxiv.org/pdf/", "arxiv preprint arxiv:"]
+        .iter()
+        .filter_map(|marker| lower.find(marker).map(|i| i + marker.len()))
+        .find_map(|start| {
+            let token = raw[start..].split_whitespace().next()?;
+            arxiv::parse_id(token.trim_end_matches(['.', ',', ';', ')', ']']))
+        })
+}
+
+// IEEE style: J. Smith and K. Doe, "Title of the paper," in Proc. ...
+fn quoted_title(raw: &str) -> Option<(Option<String>, Option<String>)> {
+    let (open, close) = [('“', '”'), ('"', '"')]
+        .into_iter()
+        .find(|(open, _)| raw.contains(*open))?;
+    let start = raw.find(open)?;
+    let end = start + open.len_utf8() + raw[start + open.len_utf8()..].find(close)?;
+    let title = clean_field(&raw[start + open.len_utf8()..end]).filter(|t| t.len() >= 8)?;
+    Some((clean_authors(&raw[..start]), Some(title)))
+}
+
+// Author-year and ACM styles: authors, (year,) title and venue are separated by periods
+fn sentence_fields(raw: &str) -> (Option<String>, Option<String>) {
+    let segments = sentences(raw);
+    let authors = segments.first().and_then(|s| clean_authors(s));
+    let title = segments
+        .iter()
+        .skip(1)
+        .map(|s| s.trim())
+        .find(|s| !is_year_only(s))
+        .filter(|s| !s.starts_with("In ") && !s.starts_with("http"))
+        .and_then(clean_field)
+        .filter(|t| t.len() >= 8);
+    (authors, title)
+}
+
+fn sentences(raw: &str) -> Vec<String> {
+    let words: Vec<&str> = raw.split_whitespace().collect();
+    let mut segments: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        current.push(word);
+        let Some(stem) = word.strip_suffix('.') else {
+            continue;
+        };
+        let next = words.get(i + 1).copied();
+        if next.is_none() {
+            break;
+        }
+        if ABBREVIATIONS.contains(&stem.to_lowercase().as_str()) {
+            continue;
+        }
+        if is_initial(stem) {
+            // "Smith, J. A. Title": a run of initials after "Surname," ends the name, but
+            // "J. Smith" and "Smith, J. and" are still inside the author list
+            let run_start = current.iter().rposition(|w| !is_initial(w.trim_end_matches('.')));
+            let after_surname = run_start.is_some_and(|j| current[j].ends_with(','));
+            let next_continues = next.is_some_and(|n| {
+                is_initial(n.trim_end_matches(['.', ','])) || n == "and" || n == "&" || n.starts_with(char::is_lowercase)
+            });
+            if !after_surname || next_continues {
+                continue;
+            }
+        }
+        segments.push(current.join(" "));
+        current.clear();
+    }
+    if !current.is_empty() {
+        segments.push(current.join(" "));
+    }
+    segments
+}
+
+fn is_initial(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| *c != '-' && *c != '.').collect();
+    !letters.is_empty() && letters.len() <= 2 && letters.iter().all(|c| c.is_uppercase())
+}
+
+// "2019", "(2019)" or "2019a"
+fn is_year_only(segment: &str) -> bool {
+    let trimmed = segment.trim_matches(|c: char| !c.is_alphanumeric());
+    trimmed.len() <= 5 && find_year(trimmed).is_some()
+}
+
+fn clean_authors(segment: &str) -> Option<String> {
+    // Drop a trailing "(2019)" or ", 2019"
+    let authors = match segment.find('(') {
+        Some(i) if find_year(&segment[i..]).is_some() => &segment[..i],
+        _ => segment,
+    };
+    clean_field(authors).filter(|a| a.len() <= 500 && a.chars().any(char::is_alphabetic))
+}
+
+fn clean_field(field: &str) -> Option<String> {
+    let cleaned = field.trim().trim_matches([',', '.', ';', ':', ' ']).trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+// Pairs each reference id with the library paper it refers to, by DOI, then arXiv id,
+// then title. `paper_id` is the citing paper, which never matches itself.
+pub fn match_references(references: &[ExtractedReference], papers: &[Paper], paper_id: i64) -> Vec<(i64, Option<i64>)> {
+    let candidates: Vec<&Paper> = papers.iter().filter(|p| p.id != paper_id).collect();
+    let by_doi: HashMap<String, i64> = candidates
+        .iter()
+        .filter_map(|p| Some((p.doi.as_deref()?.to_lowercase(), p.id)))
+        .collect();
+    let by_arxiv: HashMap<&str, i64> = candidates
+        .iter()
+        .filter_map(|p| Some((arxiv_base(p.arxiv_id.as_deref()?), p.id)))
+        .collect();
+    // Short titles ("Introduction", "Attention") would match all over the place
+    let titles: Vec<(String, i64)> = candidates
+        .iter()
+        .map(|p| (title_key(&p.title), p.id))
+        .filter(|(key, _)| key.len() >= 15)
+        .collect();
+
+    references
+        .iter()
+        .map(|reference| {
+            let by_id = reference
+                .doi
+                .as_ref()
+                .and_then(|doi| by_doi.get(&doi.to_lowercase()))
+                .or_else(|| reference.arxiv_id.as_deref().and_then(|id| by_arxiv.get(arxiv_base(id))))
+                .copied();
+            let by_title = || {
+                let title = reference.title.as_deref().map(title_key).unwrap_or_default();
+                let raw = title_key(&reference.raw);
+                titles
+                    .iter()
+                    .find(|(key, _)| *key == title || raw.contains(key.as_str()))
+                    .map(|(_, id)| *id)
+            };
+            (reference.id, by_id.or_else(by_title))
+        })
+        .collect()
+}
+
+// "1706.03762v5" and "1706.03762" are the same paper
+fn arxiv_base(id: &str) -> &str {
+    match id.rfind('v') {
+        Some(i) if i + 1 < id.len() && id[i + 1..].bytes().all(|b| b.is_ascii_digit()) => &id[..i],
+        _ => id,
+    }
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
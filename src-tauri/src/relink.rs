@@ -0,0 +1,146 @@
+// src-tauri/src/relink.rs
+
+// Finds papers whose PDF has gone missing (moved or deleted outside the app) and points
+// them at the file's new location.
+
+use crate::db::{self, FileMode, Paper};
+use crate::events;
+use crate::import;
+use crate::pdf;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Serialize)]
+pub struct MissingFile {
+    pub paper_id: i64,
+    pub title: String,
+    pub pdf_path: String,
+    pub file_mode: FileMode,
+}
+
+#[derive(Serialize, Default)]
+pub struct RelinkSummary {
+    pub relinked: usize,
+    pub still_missing: usize,
+    pub errors: Vec<String>,
+}
+
+// Papers with a pdf_path that no longer points at a file
+pub async fn find_missing(pool: &SqlitePool) -> Result<Vec<MissingFile>, String> {
+    let papers = db::get_all_papers(pool).await?;
+    Ok(papers
+        .into_iter()
+        .filter(|paper| !paper.pdf_path.is_empty() && !Path::new(&paper.pdf_path).is_file())
+        .map(|paper| MissingFile {
+            paper_id: paper.id,
+            title: paper.title,
+            pdf_path: paper.pdf_path,
+            file_mode: paper.file_mode,
+        })
+        .collect())
+}
+
+// Points the paper at `source`. A stored paper gets a fresh copy in app storage; a linked
+// one links the new location. Hash and file stats are refreshed, since the file may not be
+// byte-identical to the one that went missing.
+pub async fn relink(handle: &AppHandle, pool: &SqlitePool, paper: &Paper, source: &Path) -> Result<Paper, String> {
+    if !source.is_file() {
+        return Err(format!("File not found: {}", source.display()));
+    }
+    if !source.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")) {
+        return Err(format!("Not a PDF: {}", source.display()));
+    }
+
+    let (dest, copied) = match paper.file_mode {
+        FileMode::Linked => (std::path::absolute(source).map_err(|e| format!("Invalid file path: {}", e))?, false),
+        FileMode::Stored => {
+            let file_name = source.file_name().and_then(|n| n.to_str()).ok_or("Invalid file name")?;
+            let dest = import::unique_destination(&import::papers_dir(handle)?, file_name);
+            fs::copy(source, &dest).map_err(|e| format!("Copy failed: {}", e))?;
+            (dest, true)
+        }
+    };
+
+    let result = update_file(pool, paper.id, &dest).await;
+    if result.is_err() && copied {
+        let _ = fs::remove_file(&dest);
+    }
+    result?;
+
+    Ok(db::get_paper(pool, paper.id).await?)
+}
+
+async fn update_file(pool: &SqlitePool, paper_id: i64, path: &Path) -> Result<(), String> {
+    let file_hash = import::file_sha256_async(path).await?;
+    let stats = pdf::file_stats_async(path).await?;
+
+    db::set_pdf_path(pool, paper_id, path.to_str().ok_or("Invalid file path")?).await?;
+    db::set_file_hash(pool, paper_id, &file_hash).await?;
+    Ok(db::set_file_stats(pool, paper_id, stats.page_count, stats.file_size, stats.pdf_version.as_deref()).await?)
+}
+
+// Looks for every missing file under `folder`, first by content hash, then by file name
+// when exactly one candidate has the old name
+pub async fn relink_from_folder(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    folder: &Path,
+    recursive: bool,
+) -> Result<RelinkSummary, String> {
+    let mut summary = RelinkSummary::default();
+    let missing = find_missing(pool).await?;
+    if missing.is_empty() {
+        return Ok(summary);
+    }
+
+    let dir = folder.to_path_buf();
+    let candidates = tokio::task::spawn_blocking(move || import::find_pdfs(&dir, recursive))
+        .await
+        .map_err(|e| format!("Failed to scan folder: {}", e))?;
+
+    let mut by_hash: HashMap<String, PathBuf> = HashMap::new();
+    let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for candidate in candidates {
+        if let Some(name) = candidate.file_name().and_then(|n| n.to_str()) {
+            by_name.entry(name.to_lowercase()).or_default().push(candidate.clone());
+        }
+        match import::file_sha256_async(&candidate).await {
+            Ok(hash) => {
+                by_hash.entry(hash).or_insert(candidate);
+            }
+            Err(e) => summary.errors.push(e),
+        }
+    }
+
+    for file in missing {
+        let paper = db::get_paper(pool, file.paper_id).await?;
+        let by_old_name = Path::new(&file.pdf_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|name| by_name.get(&name.to_lowercase()))
+            .filter(|paths| paths.len() == 1)
+            .map(|paths| &paths[0]);
+        let found = paper.file_hash.as_ref().and_then(|hash| by_hash.get(hash)).or(by_old_name);
+
+        let Some(source) = found else {
+            summary.still_missing += 1;
+            continue;
+        };
+        match relink(handle, pool, &paper, source).await {
+            Ok(relinked) => {
+                summary.relinked += 1;
+                events::paper_updated(handle, &relinked);
+            }
+            Err(e) => {
+                summary.still_missing += 1;
+                summary.errors.push(format!("{}: {}", paper.title, e));
+            }
+        }
+    }
+
+    Ok(summary)
+}
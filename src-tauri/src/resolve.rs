@@ -0,0 +1,236 @@
+// src-tauri/src/resolve.rs
+
+// Finds DOIs for papers that came in with nothing but their file name as a title, e.g.
+// "1-s2.0-S0893608019302217-main" or "smith_final_v2". Each is searched on Crossref by the
+// file name and the start of its PDF text, and every candidate is scored by how much of it
+// turns up in that text: mostly the title, then the first authors' surnames and the year.
+// Confident matches are filled in right away; plausible ones wait in a review queue.
+
+use crate::config;
+use crate::crossref::{self, WorkMetadata};
+use crate::db::{self, metadata_matches::MatchStatus, PaperUpdate};
+use crate::events;
+use crate::file_naming;
+use crate::jobs::JobContext;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+// At or above this a match is applied without asking
+const AUTO_APPLY: f64 = 0.85;
+// Below this a candidate isn't worth reviewing
+const MIN_REVIEW: f64 = 0.5;
+const CANDIDATES: usize = 5;
+// Candidates are checked against the start of the PDF text, where the title and authors are
+const TEXT_WORDS: usize = 400;
+// Words of the PDF text sent to Crossref along with the file name
+const QUERY_WORDS: usize = 40;
+
+#[derive(Serialize, Clone, Default)]
+pub struct ResolveSummary {
+    pub applied: usize,
+    pub queued: usize,
+    pub unmatched: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+// Looks up every paper with a file-name title that hasn't been looked up before, run as a
+// job. The tally is emitted with "resolve:finished".
+pub async fn resolve_missing_metadata(context: JobContext, http: reqwest::Client) -> Result<String, String> {
+    let mailto = config::load(&context.handle).network.contact_email;
+    let papers: Vec<_> = db::metadata_matches::get_unresolved_papers(&context.pool)
+        .await?
+        .into_iter()
+        .filter(|(_, title, pdf_path, _)| is_file_name_title(title, pdf_path))
+        .collect();
+
+    let total = papers.len();
+    let mut summary = ResolveSummary::default();
+    for (index, (paper_id, title, _, content)) in papers.into_iter().enumerate() {
+        if context.is_cancelled() {
+            break;
+        }
+        context.progress(index, Some(total), Some(&title)).await;
+
+        let text = content.as_deref().unwrap_or_default();
+        match resolve_paper(&context.handle, &context.pool, &http, paper_id, &title, text, mailto.as_deref()).await {
+            Ok(MatchStatus::Applied) => summary.applied += 1,
+            Ok(MatchStatus::Pending) => summary.queued += 1,
+            Ok(_) => summary.unmatched += 1,
+            Err(e) => {
+                summary.failed += 1;
+                summary.errors.push(format!("{}: {}", title, e));
+            }
+        }
+    }
+
+    let _ = context.handle.emit("resolve:finished", &summary);
+    Ok(format!(
+        "Filled in {} of {} papers, {} left to review",
+        summary.applied, total, summary.queued
+    ))
+}
+
+async fn resolve_paper(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    http: &reqwest::Client,
+    paper_id: i64,
+    title: &str,
+    content: &str,
+    mailto: Option<&str>,
+) -> Result<MatchStatus, String> {
+    let text: Vec<String> = words(content).into_iter().take(TEXT_WORDS).collect();
+    let name = words(title);
+    // Numbering such as "1-s2.0-S0893608019302217" only confuses the search
+    let query = name
+        .iter()
+        .filter(|w| !w.chars().any(|c| c.is_ascii_digit()))
+        .chain(text.iter().take(QUERY_WORDS))
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if query.is_empty() {
+        db::metadata_matches::save_match(pool, paper_id, MatchStatus::Unmatched, None, None).await?;
+        return Ok(MatchStatus::Unmatched);
+    }
+
+    let haystack: Vec<String> = name.into_iter().chain(text).collect();
+    let best = crossref::search(http, &query, CANDIDATES, mailto)
+        .await?
+        .into_iter()
+        .map(|candidate| (confidence(&candidate, &haystack), candidate))
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+    let Some((confidence, metadata)) = best else {
+        db::metadata_matches::save_match(pool, paper_id, MatchStatus::Unmatched, None, None).await?;
+        return Ok(MatchStatus::Unmatched);
+    };
+
+    // A DOI another paper already has means this one is likely a duplicate; the user decides
+    let taken = match metadata.doi.as_deref() {
+        Some(doi) => db::find_matching_paper(pool, Some(doi), None, None, None)
+            .await?
+            .is_some_and(|other| other.id != paper_id),
+        None => true,
+    };
+
+    let status = if confidence >= AUTO_APPLY && !taken {
+        MatchStatus::Applied
+    } else if confidence >= MIN_REVIEW {
+        MatchStatus::Pending
+    } else {
+        MatchStatus::Unmatched
+    };
+    if status == MatchStatus::Applied {
+        apply_match(handle, pool, paper_id, &metadata).await?;
+    }
+    db::metadata_matches::save_match(pool, paper_id, status, Some(confidence), Some(&metadata)).await?;
+    Ok(status)
+}
+
+// The title is replaced, since it was only the file name; the other fields are filled in
+// where the paper has none. Undoable like any other edit.
+pub async fn apply_match(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    paper_id: i64,
+    metadata: &WorkMetadata,
+) -> Result<db::Paper, String> {
+    let paper = db::get_paper(pool, paper_id).await?;
+    let missing = |current: &Option<String>, found: &Option<String>| match current.as_deref() {
+        Some(value) if !value.trim().is_empty() => None,
+        _ => found.clone(),
+    };
+
+    let changes = PaperUpdate {
+        title: Some(metadata.title.clone()).filter(|t| !t.trim().is_empty()),
+        authors: missing(&paper.authors, &metadata.authors),
+        journal: missing(&paper.journal, &metadata.journal),
+        year: paper.year.is_none().then_some(metadata.year).flatten(),
+        doi: metadata.doi.clone(),
+        r#abstract: missing(&paper.r#abstract, &metadata.r#abstract),
+        keywords: missing(&paper.keywords, &metadata.keywords),
+        ..Default::default()
+    };
+
+    let before = db::journal::snapshot_papers(pool, &[paper_id]).await?;
+    db::update_paper(pool, paper_id, &changes).await?;
+    let description = format!("Fill in metadata for \"{}\"", paper.title);
+    if let Err(e) = db::journal::record_papers(pool, &description, before).await {
+        tracing::warn!("Failed to journal \"{}\": {}", description, e);
+    }
+
+    let naming = config::load(handle).file_naming;
+    if naming.rename_on_edit {
+        let paper = db::get_paper(pool, paper_id).await?;
+        if let Err(e) = file_naming::rename_stored_file(handle, pool, &paper, &naming.pattern).await {
+            tracing::warn!("Failed to rename PDF for paper {}: {}", paper_id, e);
+        }
+    }
+
+    let paper = db::get_paper(pool, paper_id).await?;
+    events::paper_updated(handle, &paper);
+    Ok(paper)
+}
+
+// Titles that are really the file name because the PDF had no usable one: the file's stem,
+// or anything still ending in .pdf or without a single space
+fn is_file_name_title(title: &str, pdf_path: &str) -> bool {
+    let title = title.trim();
+    let stem = Path::new(pdf_path).file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    title.is_empty()
+        || title.eq_ignore_ascii_case(stem)
+        || title.to_lowercase().ends_with(".pdf")
+        || !title.contains(char::is_whitespace)
+}
+
+// From 0 to 1. The title counts most: the share of its consecutive word pairs found in the
+// text, which a different paper rarely reaches by chance. Then how many of the first three
+// authors' surnames appear, and whether the year does.
+fn confidence(candidate: &WorkMetadata, text: &[String]) -> f64 {
+    let title = words(&candidate.title);
+    let title_score = match title.as_slice() {
+        [] => 0.0,
+        [word] => f64::from(u8::from(text.contains(word))),
+        _ => {
+            let pairs: HashSet<(&str, &str)> = text.windows(2).map(|w| (w[0].as_str(), w[1].as_str())).collect();
+            let found = title
+                .windows(2)
+                .filter(|w| pairs.contains(&(w[0].as_str(), w[1].as_str())))
+                .count();
+            found as f64 / (title.len() - 1) as f64
+        }
+    };
+
+    let surnames: Vec<String> = candidate
+        .authors
+        .as_deref()
+        .unwrap_or_default()
+        .split(", ")
+        .filter_map(|name| words(name).pop())
+        .take(3)
+        .collect();
+    let author_score = if surnames.is_empty() {
+        0.0
+    } else {
+        surnames.iter().filter(|s| text.contains(s)).count() as f64 / surnames.len() as f64
+    };
+
+    let year_score = candidate
+        .year
+        .is_some_and(|year| text.contains(&year.to_string()));
+
+    0.7 * title_score + 0.2 * author_score + 0.1 * f64::from(u8::from(year_score))
+}
+
+// Lowercased runs of letters and digits
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,34 @@
+// src-tauri/src/saved_searches.rs
+
+// Keeps the result counts of saved searches in the sidebar current. Papers change in bursts
+// (an import, a bulk edit), so the counts are redone at most once per interval.
+
+use crate::db;
+use crate::events;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn start(handle: AppHandle, pool: SqlitePool) {
+    tauri::async_runtime::spawn(refresh_loop(handle, pool));
+}
+
+async fn refresh_loop(handle: AppHandle, pool: SqlitePool) {
+    let mut tick = tokio::time::interval(REFRESH_INTERVAL);
+
+    loop {
+        tick.tick().await;
+        if events::take_library_changed() {
+            emit_counts(&handle, &pool).await;
+        }
+    }
+}
+
+pub async fn emit_counts(handle: &AppHandle, pool: &SqlitePool) {
+    match db::saved_searches::get_result_counts(pool).await {
+        Ok(counts) => events::saved_search_counts(handle, &counts),
+        Err(e) => tracing::warn!("Failed to count saved search results: {}", e),
+    }
+}
@@ -0,0 +1,31 @@
+// src-tauri/src/secrets.rs
+
+// Credentials kept in the operating system's keychain (Keychain on macOS, Credential Manager
+// on Windows, the Secret Service on Linux) instead of the plain config file
+
+const SERVICE: &str = "paper-master";
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, name).map_err(|e| format!("Failed to open the keychain: {}", e))
+}
+
+pub fn get(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read from the keychain: {}", e)),
+    }
+}
+
+pub fn set(name: &str, secret: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to save to the keychain: {}", e))
+}
+
+pub fn delete(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove from the keychain: {}", e)),
+    }
+}
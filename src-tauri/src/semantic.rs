@@ -0,0 +1,273 @@
+// src-tauri/src/semantic.rs
+
+// Semantic search: each paper is split into chunks of text, a local sentence embedding model
+// turns every chunk into a vector, and queries match chunks by meaning rather than by words.
+// It all runs offline. The model runtime is large, so it is only built with the
+// semantic-search feature, and the model itself (all-MiniLM-L6-v2 as published on Hugging
+// Face: config.json, tokenizer.json and model.safetensors) is read from
+// <app local data>/models/all-MiniLM-L6-v2 rather than shipped with the app.
+
+#[cfg(feature = "semantic-search")]
+mod embedder;
+
+use crate::db::embeddings::StoredChunk;
+use crate::db::{self, Paper, PaperText};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+pub const MODEL_NAME: &str = "all-MiniLM-L6-v2";
+const MODEL_FILES: [&str; 3] = ["config.json", "tokenizer.json", "model.safetensors"];
+// The model reads at most 256 word pieces, which this many words stays under
+const CHUNK_WORDS: usize = 150;
+const MAX_CHUNKS: usize = 64;
+// Chunks per model call
+const BATCH_SIZE: usize = 16;
+
+#[derive(Serialize)]
+pub struct SemanticStatus {
+    // Whether this build includes semantic search at all
+    pub available: bool,
+    pub model_dir: String,
+    // Whether the model files are in model_dir
+    pub model_found: bool,
+    pub indexed_papers: i64,
+    pub indexing: bool,
+}
+
+#[derive(Serialize)]
+pub struct SemanticMatch {
+    pub paper: Paper,
+    // Cosine similarity of the best chunk, from -1 to 1
+    pub score: f32,
+    // The chunk that matched best
+    pub text: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SemanticProgress {
+    pub current: usize,
+    pub total: usize,
+    pub paper_id: i64,
+    pub title: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Default)]
+pub struct SemanticSummary {
+    pub indexed: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+// Cheap to clone; clones share the loaded model
+#[derive(Clone)]
+pub struct Semantic {
+    model_dir: PathBuf,
+    running: Arc<AtomicBool>,
+    // The stored vectors, read on the first search and dropped whenever indexing writes new ones
+    vectors: Arc<tokio::sync::Mutex<Option<Arc<Vec<StoredChunk>>>>>,
+    // Loaded on first use, which takes a few seconds
+    #[cfg(feature = "semantic-search")]
+    embedder: Arc<tokio::sync::OnceCell<Arc<embedder::Embedder>>>,
+}
+
+impl Semantic {
+    pub fn new(models_dir: &Path) -> Self {
+        Semantic {
+            model_dir: models_dir.join(MODEL_NAME),
+            running: Arc::default(),
+            vectors: Arc::default(),
+            #[cfg(feature = "semantic-search")]
+            embedder: Arc::default(),
+        }
+    }
+
+    pub async fn status(&self, pool: &SqlitePool) -> Result<SemanticStatus, String> {
+        Ok(SemanticStatus {
+            available: cfg!(feature = "semantic-search"),
+            model_dir: self.model_dir.to_string_lossy().to_string(),
+            model_found: self.check_model().is_ok(),
+            indexed_papers: db::embeddings::count_embedded_papers(pool, MODEL_NAME).await?,
+            indexing: self.running.load(Ordering::SeqCst),
+        })
+    }
+
+    fn check_model(&self) -> Result<(), String> {
+        if !cfg!(feature = "semantic-search") {
+            return Err("This build doesn't include semantic search".to_string());
+        }
+        let missing: Vec<&str> = MODEL_FILES.into_iter().filter(|f| !self.model_dir.join(f).is_file()).collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "The {} model is missing {} in {}",
+                MODEL_NAME,
+                missing.join(", "),
+                self.model_dir.display()
+            ));
+        }
+        Ok(())
+    }
+
+    // One unit length vector per text
+    #[cfg(feature = "semantic-search")]
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+        self.check_model()?;
+        let model_dir = self.model_dir.clone();
+        let embedder = self
+            .embedder
+            .get_or_try_init(|| async move {
+                tokio::task::spawn_blocking(move || embedder::Embedder::load(&model_dir))
+                    .await
+                    .map_err(|e| format!("Failed to load the {} model: {}", MODEL_NAME, e))?
+                    .map(Arc::new)
+            })
+            .await?
+            .clone();
+        tokio::task::spawn_blocking(move || embedder.embed(&texts))
+            .await
+            .map_err(|e| format!("Failed to compute embeddings: {}", e))?
+    }
+
+    #[cfg(not(feature = "semantic-search"))]
+    async fn embed(&self, _texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+        self.check_model().map(|_| Vec::new())
+    }
+
+    // Embeds every paper that has no vectors yet in the background, returning how many were
+    // queued. With `rebuild` all papers are done again, e.g. after their text was re-extracted.
+    // Progress arrives as "semantic:progress" events, then "semantic:finished".
+    pub async fn start_indexing(&self, handle: AppHandle, pool: SqlitePool, rebuild: bool) -> Result<usize, String> {
+        self.check_model()?;
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err("Semantic indexing is already running".to_string());
+        }
+        let papers = async {
+            if rebuild {
+                db::embeddings::clear_embeddings(&pool).await?;
+                self.forget_vectors().await;
+            }
+            Ok::<_, String>(db::embeddings::get_papers_missing_embeddings(&pool, MODEL_NAME).await?)
+        }
+        .await;
+        let papers = match papers {
+            Ok(papers) => papers,
+            Err(e) => {
+                self.running.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+
+        let total = papers.len();
+        let semantic = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut summary = SemanticSummary::default();
+            for (index, (paper_id, title)) in papers.into_iter().enumerate() {
+                let error = semantic.index_paper(&pool, paper_id).await.err();
+                match &error {
+                    None => summary.indexed += 1,
+                    Some(e) => {
+                        summary.failed += 1;
+                        summary.errors.push(format!("{}: {}", title, e));
+                    }
+                }
+
+                let _ = handle.emit(
+                    "semantic:progress",
+                    SemanticProgress { current: index + 1, total, paper_id, title, error },
+                );
+            }
+
+            semantic.running.store(false, Ordering::SeqCst);
+            let _ = handle.emit("semantic:finished", &summary);
+        });
+
+        Ok(total)
+    }
+
+    async fn index_paper(&self, pool: &SqlitePool, paper_id: i64) -> Result<(), String> {
+        let chunks = chunk_text(&db::get_paper_text(pool, paper_id).await?);
+        let mut embedded = Vec::with_capacity(chunks.len());
+        for batch in chunks.chunks(BATCH_SIZE) {
+            let vectors = self.embed(batch.to_vec()).await?;
+            embedded.extend(batch.iter().cloned().zip(vectors));
+        }
+        db::embeddings::save_embeddings(pool, paper_id, MODEL_NAME, &embedded).await?;
+        self.forget_vectors().await;
+        Ok(())
+    }
+
+    async fn stored_vectors(&self, pool: &SqlitePool) -> Result<Arc<Vec<StoredChunk>>, String> {
+        // Held while loading, so vectors saved meanwhile drop the result rather than being missed
+        let mut cached = self.vectors.lock().await;
+        if let Some(chunks) = cached.as_ref() {
+            return Ok(chunks.clone());
+        }
+        let chunks = Arc::new(db::embeddings::get_embeddings(pool, MODEL_NAME).await?);
+        *cached = Some(chunks.clone());
+        Ok(chunks)
+    }
+
+    async fn forget_vectors(&self) {
+        *self.vectors.lock().await = None;
+    }
+
+    // Papers outside the Trash whose text is closest in meaning to `query`, best first
+    pub async fn search(&self, pool: &SqlitePool, query: &str, limit: usize) -> Result<Vec<SemanticMatch>, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let Some(query_vector) = self.embed(vec![query.to_string()]).await?.pop() else {
+            return Ok(Vec::new());
+        };
+
+        // The cached vectors outlive trashing and deleting papers, so those are left out here
+        let searchable: HashSet<i64> = db::embeddings::get_searchable_ids(pool).await?.into_iter().collect();
+        let chunks = self.stored_vectors(pool).await?;
+
+        // The best chunk of each paper
+        let mut best: HashMap<i64, (f32, &str)> = HashMap::new();
+        for chunk in chunks.iter().filter(|chunk| searchable.contains(&chunk.paper_id)) {
+            // Both are unit length, so the dot product is the cosine
+            let score: f32 = chunk.vector.iter().zip(&query_vector).map(|(a, b)| a * b).sum();
+            if best.get(&chunk.paper_id).is_none_or(|(current, _)| score > *current) {
+                best.insert(chunk.paper_id, (score, &chunk.text));
+            }
+        }
+
+        let mut ranked: Vec<(i64, f32, String)> =
+            best.into_iter().map(|(id, (score, text))| (id, score, text.to_string())).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(limit);
+
+        let ids: Vec<i64> = ranked.iter().map(|(id, ..)| *id).collect();
+        let mut papers: HashMap<i64, Paper> =
+            db::get_papers_by_ids(pool, &ids).await?.into_iter().map(|p| (p.id, p)).collect();
+        Ok(ranked
+            .into_iter()
+            .filter_map(|(id, score, text)| Some(SemanticMatch { paper: papers.remove(&id)?, score, text }))
+            .collect())
+    }
+}
+
+// The title and abstract first, then the text in runs of CHUNK_WORDS words
+fn chunk_text(source: &PaperText) -> Vec<String> {
+    let mut chunks = vec![match source.r#abstract.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(r#abstract) => format!("{}. {}", source.title.trim(), r#abstract),
+        None => source.title.trim().to_string(),
+    }];
+
+    let words: Vec<&str> = source.content.as_deref().unwrap_or_default().split_whitespace().collect();
+    chunks.extend(
+        words
+            .chunks(CHUNK_WORDS)
+            .take(MAX_CHUNKS - 1)
+            .map(|words| words.join(" ")),
+    );
+    chunks
+}
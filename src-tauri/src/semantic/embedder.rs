@@ -0,0 +1,77 @@
+// src-tauri/src/semantic/embedder.rs
+
+// Sentence embeddings from a BERT-style model run on the CPU with candle: the token vectors
+// are averaged over the real (non-padding) tokens and scaled to unit length, the way
+// sentence-transformers does it for all-MiniLM-L6-v2.
+
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use std::path::Path;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+const MAX_TOKENS: usize = 256;
+
+pub struct Embedder {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl Embedder {
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let device = Device::Cpu;
+
+        let config = std::fs::read_to_string(dir.join("config.json"))
+            .map_err(|e| format!("Failed to read config.json: {}", e))?;
+        let config: Config = serde_json::from_str(&config).map_err(|e| format!("Invalid config.json: {}", e))?;
+
+        let mut tokenizer =
+            Tokenizer::from_file(dir.join("tokenizer.json")).map_err(|e| format!("Invalid tokenizer.json: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams { strategy: PaddingStrategy::BatchLongest, ..Default::default() }));
+        tokenizer
+            .with_truncation(Some(TruncationParams { max_length: MAX_TOKENS, ..Default::default() }))
+            .map_err(|e| format!("Invalid tokenizer.json: {}", e))?;
+
+        // Safety: the file is only read, and nothing else writes to the model folder while the app runs
+        let weights = unsafe { VarBuilder::from_mmaped_safetensors(&[dir.join("model.safetensors")], DTYPE, &device) }
+            .map_err(|e| format!("Failed to read model.safetensors: {}", e))?;
+        let model = BertModel::load(weights, &config).map_err(|e| format!("Failed to load the model: {}", e))?;
+
+        Ok(Embedder { model, tokenizer, device })
+    }
+
+    pub fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| format!("Failed to tokenize: {}", e))?;
+        self.forward(&encodings).map_err(|e| format!("Failed to compute embeddings: {}", e))
+    }
+
+    fn forward(&self, encodings: &[tokenizers::Encoding]) -> candle_core::Result<Vec<Vec<f32>>> {
+        let ids = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_ids(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let masks = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let ids = Tensor::stack(&ids, 0)?;
+        let mask = Tensor::stack(&masks, 0)?;
+        let token_types = ids.zeros_like()?;
+
+        // (batch, tokens, hidden)
+        let output = self.model.forward(&ids, &token_types, Some(&mask))?;
+
+        let mask = mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+        let summed = output.broadcast_mul(&mask)?.sum(1)?;
+        let pooled = summed.broadcast_div(&mask.sum(1)?)?;
+        let normalized = pooled.broadcast_div(&pooled.sqr()?.sum_keepdim(1)?.sqrt()?)?;
+        normalized.to_vec2::<f32>()
+    }
+}
@@ -0,0 +1,179 @@
+// src-tauri/src/semantic_scholar.rs
+
+use crate::crossref::normalize_doi;
+use crate::db::citations::CitedWork;
+use serde::Deserialize;
+
+const API_BASE: &str = "https://api.semanticscholar.org/graph/v1/paper";
+const WORK_FIELDS: &str = "paperId,title,authors,year,externalIds";
+const PAGE_SIZE: usize = 1000;
+// Heavily cited papers have tens of thousands of citing works; the first pages are plenty
+const MAX_CITATIONS: usize = 2000;
+
+pub struct CitationLookup {
+    pub s2_paper_id: String,
+    pub citation_count: i64,
+    pub influential_citation_count: i64,
+    pub reference_count: i64,
+    pub references: Vec<CitedWork>,
+    pub citations: Vec<CitedWork>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PaperResponse {
+    paper_id: String,
+    #[serde(default)]
+    citation_count: i64,
+    #[serde(default)]
+    influential_citation_count: i64,
+    #[serde(default)]
+    reference_count: i64,
+}
+
+#[derive(Deserialize)]
+struct Page {
+    #[serde(default)]
+    data: Vec<Edge>,
+    next: Option<usize>,
+}
+
+// References come back as {"citedPaper": ...}, citations as {"citingPaper": ...}
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Edge {
+    cited_paper: Option<Work>,
+    citing_paper: Option<Work>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Work {
+    paper_id: Option<String>,
+    title: Option<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    year: Option<i64>,
+    external_ids: Option<ExternalIds>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExternalIds {
+    #[serde(rename = "DOI")]
+    doi: Option<String>,
+    #[serde(rename = "ArXiv")]
+    arxiv: Option<String>,
+}
+
+impl From<Work> for CitedWork {
+    fn from(work: Work) -> Self {
+        let authors: Vec<String> = work.authors.into_iter().filter_map(|a| a.name).collect();
+        let ids = work.external_ids;
+        CitedWork {
+            s2_paper_id: work.paper_id,
+            title: work.title.unwrap_or_default(),
+            authors: (!authors.is_empty()).then(|| authors.join(", ")),
+            year: work.year,
+            doi: ids.as_ref().and_then(|ids| ids.doi.as_deref()).and_then(normalize_doi),
+            arxiv_id: ids.and_then(|ids| ids.arxiv),
+        }
+    }
+}
+
+// Semantic Scholar accepts "DOI:10.1234/x" and "ARXIV:1706.03762" in place of its own ids
+pub fn lookup_id(doi: Option<&str>, arxiv_id: Option<&str>) -> Option<String> {
+    doi.and_then(normalize_doi)
+        .map(|doi| format!("DOI:{}", doi))
+        .or_else(|| arxiv_id.map(|id| format!("ARXIV:{}", id.trim())))
+}
+
+pub async fn fetch_citations(client: &reqwest::Client, lookup_id: &str) -> Result<CitationLookup, String> {
+    let paper: PaperResponse = get_json(
+        client,
+        &format!("{}/{}", API_BASE, lookup_id),
+        &[("fields", "paperId,citationCount,influentialCitationCount,referenceCount")],
+    )
+    .await?;
+
+    // Later calls use Semantic Scholar's own id so they can't resolve to a different paper
+    let references = fetch_edges(client, &paper.paper_id, "references", usize::MAX).await?;
+    let citations = fetch_edges(client, &paper.paper_id, "citations", MAX_CITATIONS).await?;
+
+    Ok(CitationLookup {
+        s2_paper_id: paper.paper_id,
+        citation_count: paper.citation_count,
+        influential_citation_count: paper.influential_citation_count,
+        reference_count: paper.reference_count,
+        references,
+        citations,
+    })
+}
+
+async fn fetch_edges(client: &reqwest::Client, s2_id: &str, edge: &str, max: usize) -> Result<Vec<CitedWork>, String> {
+    let url = format!("{}/{}/{}", API_BASE, s2_id, edge);
+    let mut works: Vec<CitedWork> = Vec::new();
+    let mut offset = 0;
+
+    while works.len() < max {
+        let page: Page = get_json(
+            client,
+            &url,
+            &[
+                ("fields", WORK_FIELDS),
+                ("limit", &PAGE_SIZE.min(max - works.len()).to_string()),
+                ("offset", &offset.to_string()),
+            ],
+        )
+        .await?;
+
+        let received = page.data.len();
+        // Publishers can ask for references to be hidden; those come back without a title
+        works.extend(
+            page.data
+                .into_iter()
+                .filter_map(|edge| edge.cited_paper.or(edge.citing_paper))
+                .map(CitedWork::from)
+                .filter(|work| !work.title.trim().is_empty()),
+        );
+
+        match page.next {
+            Some(next) if received > 0 => offset = next,
+            _ => break,
+        }
+    }
+
+    Ok(works)
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    query: &[(&str, &str)],
+) -> Result<T, String> {
+    let response = client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| format!("Semantic Scholar request failed: {}", e))?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => return Err("Paper not found on Semantic Scholar".to_string()),
+        reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            return Err("Semantic Scholar rate limit reached; try again in a minute".to_string())
+        }
+        _ => {}
+    }
+
+    response
+        .error_for_status()
+        .map_err(|e| format!("Semantic Scholar request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Semantic Scholar response: {}", e))
+}
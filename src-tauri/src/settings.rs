@@ -0,0 +1,171 @@
+// src-tauri/src/settings.rs
+
+// All of config.rs at once, for a settings screen that edits several sections together.
+// get_settings returns the whole config; update_settings takes part of one and merges it in
+// like a JSON merge patch (RFC 7386), so {"network": {"contact_email": "me@example.org"}}
+// changes just that field and a null puts one back to its default. The result is checked
+// the way the per-section commands check it, saved, and applied to what is running.
+//
+// The database and papers locations, and the Zotero key, have their own commands: moving the
+// library and verifying the key don't fit a plain save. The key itself is in the keychain and
+// never part of the config.
+
+use crate::config::{self, AppConfig, BackupSchedule, CompanionSettings, MaintenanceSchedule, SummarizerSettings};
+use crate::error::AppError;
+use crate::file_naming;
+use crate::hooks;
+use crate::logging;
+use crate::network;
+use crate::AppState;
+use serde_json::Value;
+use std::fs;
+use tauri::{AppHandle, Manager};
+
+// Top-level keys, and keys within a section, that update_settings refuses
+const MANAGED_ELSEWHERE: &[(&str, &str)] = &[
+    ("database_path", "set_database_path"),
+    ("papers_dir", "set_papers_dir"),
+    ("zotero.api_key", "set_zotero_sync"),
+    ("zotero.has_api_key", "set_zotero_sync"),
+    ("zotero.user_id", "set_zotero_sync"),
+    ("zotero.username", "set_zotero_sync"),
+];
+
+pub async fn update(handle: &AppHandle, patch: Value) -> Result<AppConfig, AppError> {
+    if !patch.is_object() {
+        return Err(AppError::Validation("Settings must be an object".to_string()));
+    }
+    for (key, command) in MANAGED_ELSEWHERE {
+        let pointer = format!("/{}", key.replace('.', "/"));
+        if patch.pointer(&pointer).is_some() {
+            return Err(AppError::Validation(format!("{} is changed with {}", key, command)));
+        }
+    }
+
+    let current = config::load(handle);
+    let mut merged = serde_json::to_value(&current).map_err(|e| e.to_string())?;
+    merge_patch(&mut merged, patch);
+    let mut updated: AppConfig =
+        serde_json::from_value(merged).map_err(|e| AppError::Validation(format!("Invalid settings: {}", e)))?;
+
+    updated.network.contact_email = normalize_email(updated.network.contact_email.as_deref())?;
+    validate(&updated)?;
+    config::save(handle, &updated)?;
+    apply(handle, &current, &updated).await;
+    Ok(updated)
+}
+
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_patch(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+fn validate(config: &AppConfig) -> Result<(), AppError> {
+    validate_pool_size(config.database_pool_size)?;
+    validate_backup(&config.backup)?;
+    validate_maintenance(&config.maintenance)?;
+    validate_zotero_interval(config.zotero.interval_minutes)?;
+    if config.zotero.enabled && !config.zotero.has_api_key {
+        return Err(AppError::Validation("Add a Zotero API key before turning on sync".to_string()));
+    }
+    file_naming::validate_pattern(&config.file_naming.pattern)?;
+    validate_summarizer(&config.summarizer)?;
+    // Refused here rather than found out at the next start
+    network::build_client(&config.network)?;
+    validate_companion(&config.companion)?;
+    Ok(())
+}
+
+// Only what can change while the app runs; the rest is read where it's used
+async fn apply(handle: &AppHandle, old: &AppConfig, new: &AppConfig) {
+    if old.log_level != new.log_level {
+        logging::set_level(new.log_level);
+        tracing::info!("Log level set to {:?}", new.log_level);
+    }
+
+    let state = handle.state::<AppState>();
+    state.clipboard.set_enabled(new.watch_clipboard);
+    handle.state::<hooks::Hooks>().set(new.hooks.clone());
+    let companion_changed = old.companion.enabled != new.companion.enabled || old.companion.port != new.companion.port;
+    if companion_changed {
+        state.companion.restart().await;
+    }
+}
+
+pub fn normalize_email(email: Option<&str>) -> Result<Option<String>, AppError> {
+    match email.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(email) if !email.contains('@') => Err(AppError::Validation("Not an email address".to_string())),
+        email => Ok(email.map(str::to_string)),
+    }
+}
+
+pub fn validate_pool_size(size: Option<u32>) -> Result<(), AppError> {
+    if size.is_some_and(|size| !(1..=32).contains(&size)) {
+        return Err(AppError::Validation("Pool size must be between 1 and 32".to_string()));
+    }
+    Ok(())
+}
+
+// Creates the folder of an enabled schedule, so a bad one is found now
+pub fn validate_backup(schedule: &BackupSchedule) -> Result<(), AppError> {
+    if schedule.interval_hours == 0 {
+        return Err(AppError::Validation("Backup interval must be at least one hour".to_string()));
+    }
+    if schedule.keep == 0 {
+        return Err(AppError::Validation("Keep at least one backup".to_string()));
+    }
+    if schedule.enabled {
+        let folder = schedule
+            .folder
+            .as_ref()
+            .ok_or_else(|| AppError::Validation("Choose a folder for automatic backups".to_string()))?;
+        if !folder.is_absolute() {
+            return Err(AppError::Validation("Backup folder must be an absolute path".to_string()));
+        }
+        fs::create_dir_all(folder).map_err(|e| format!("Failed to create backup folder: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn validate_maintenance(schedule: &MaintenanceSchedule) -> Result<(), AppError> {
+    if schedule.interval_days == 0 {
+        return Err(AppError::Validation("Maintenance interval must be at least one day".to_string()));
+    }
+    Ok(())
+}
+
+pub fn validate_zotero_interval(minutes: u64) -> Result<(), AppError> {
+    if minutes < 5 {
+        return Err(AppError::Validation("Sync interval must be at least 5 minutes".to_string()));
+    }
+    Ok(())
+}
+
+pub fn validate_summarizer(settings: &SummarizerSettings) -> Result<(), AppError> {
+    if settings.enabled && settings.model.as_deref().is_none_or(|m| m.trim().is_empty()) {
+        return Err(AppError::Validation("Choose a model before turning on summaries".to_string()));
+    }
+    Ok(())
+}
+
+pub fn validate_companion(settings: &CompanionSettings) -> Result<(), AppError> {
+    if settings.port < 1024 {
+        return Err(AppError::Validation("Choose a port from 1024 up".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+// src-tauri/src/summarize.rs
+
+// Paper summaries from a language model, through either an OpenAI-compatible chat
+// completions API or a local Ollama server. The model is asked for JSON with a summary and
+// key points; a reply that isn't JSON is kept whole as the summary.
+
+use crate::config::{SummarizerProvider, SummarizerSettings};
+use crate::db::PaperText;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+// Keychain entry holding the API key
+pub const API_KEY_SECRET: &str = "summarizer-api-key";
+// Enough for the abstract, introduction and conclusions of most papers while staying within
+// the context of small local models
+const MAX_INPUT_CHARS: usize = 24_000;
+// Local models on a laptop can take minutes
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+const INSTRUCTIONS: &str = "You summarize research papers for a researcher's reading notes. \
+Reply with only a JSON object of the form {\"summary\": \"...\", \"key_points\": [\"...\"]}: \
+a summary of one or two paragraphs covering the problem, approach and main findings, and \
+three to seven key points of one sentence each. Use only what the paper says.";
+
+#[derive(Serialize)]
+pub struct SummarizerStatus {
+    #[serde(flatten)]
+    pub settings: SummarizerSettings,
+    // The key itself never leaves the keychain
+    pub api_key_set: bool,
+}
+
+pub struct GeneratedSummary {
+    pub summary: String,
+    pub key_points: Vec<String>,
+    pub model: String,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    summary: String,
+    #[serde(default)]
+    key_points: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletion {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaChat {
+    message: Message,
+}
+
+pub async fn summarize(
+    client: &reqwest::Client,
+    settings: &SummarizerSettings,
+    api_key: Option<&str>,
+    text: &PaperText,
+) -> Result<GeneratedSummary, String> {
+    let model = settings
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .ok_or("No summarization model is set")?;
+    let endpoint = settings
+        .endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .unwrap_or(settings.provider.default_endpoint())
+        .trim_end_matches('/');
+
+    let messages = json!([
+        { "role": "system", "content": INSTRUCTIONS },
+        { "role": "user", "content": prompt(text)? },
+    ]);
+    let request = match settings.provider {
+        SummarizerProvider::OpenAi => client
+            .post(format!("{}/chat/completions", endpoint))
+            .json(&json!({ "model": model, "messages": messages, "temperature": 0.2 })),
+        SummarizerProvider::Ollama => client
+            .post(format!("{}/api/chat", endpoint))
+            .json(&json!({ "model": model, "messages": messages, "stream": false, "format": "json" })),
+    };
+    let request = match api_key {
+        Some(key) => request.bearer_auth(key),
+        None => request,
+    };
+
+    let response = request
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Summary request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Summary request failed ({}): {}", status, body.trim()));
+    }
+
+    let content = match settings.provider {
+        SummarizerProvider::OpenAi => response
+            .json::<ChatCompletion>()
+            .await
+            .map_err(|e| format!("Unexpected summary response: {}", e))?
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .unwrap_or_default(),
+        SummarizerProvider::Ollama => response
+            .json::<OllamaChat>()
+            .await
+            .map_err(|e| format!("Unexpected summary response: {}", e))?
+            .message
+            .content,
+    };
+
+    let reply = parse_reply(&content).ok_or("The model returned an empty summary")?;
+    Ok(GeneratedSummary { summary: reply.summary, key_points: reply.key_points, model: model.to_string() })
+}
+
+fn prompt(text: &PaperText) -> Result<String, String> {
+    let r#abstract = text.r#abstract.as_deref().map(str::trim).filter(|a| !a.is_empty());
+    let content = text.content.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if r#abstract.is_none() && content.is_none() {
+        return Err("The paper has no text or abstract to summarize".to_string());
+    }
+
+    let mut prompt = format!("Title: {}\n", text.title.trim());
+    if let Some(r#abstract) = r#abstract {
+        prompt.push_str(&format!("\nAbstract:\n{}\n", r#abstract));
+    }
+    if let Some(content) = content {
+        let content: String = content.chars().take(MAX_INPUT_CHARS).collect();
+        prompt.push_str(&format!("\nText:\n{}\n", content));
+    }
+    Ok(prompt)
+}
+
+// Models wrap JSON in code fences or add a sentence around it, so the outermost braces are used
+fn parse_reply(content: &str) -> Option<Reply> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str::<Reply>(&content[start..=end]).ok(),
+        _ => None,
+    };
+    let reply = json.unwrap_or_else(|| Reply { summary: content.trim().to_string(), key_points: Vec::new() });
+
+    let key_points: Vec<String> =
+        reply.key_points.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+    let summary = reply.summary.trim().to_string();
+    (!summary.is_empty()).then_some(Reply { summary, key_points })
+}
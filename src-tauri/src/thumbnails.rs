@@ -0,0 +1,295 @@
+// src-tauri/src/thumbnails.rs
+
+// First-page thumbnails for the library grid, rendered with PDFium and cached as
+// <app data>/thumbnails/<paper id>.png. A background thread renders whatever is missing
+// and emits "thumbnail:ready" for each one. Being the thread that owns PDFium, it also lays
+// out page text with word positions on request, for searching inside a paper.
+
+use crate::db;
+use crate::db::pages::{PageLayout, WordBox};
+use pdfium_render::prelude::*;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const THUMBNAIL_WIDTH: i32 = 320;
+const MAX_HEIGHT: i32 = 480;
+// Papers added by the folder watcher or a sync are picked up on the next check
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Clone)]
+pub struct ThumbnailReady {
+    pub paper_id: i64,
+    pub path: String,
+}
+
+enum Request {
+    Wake,
+    Layout(PathBuf, tokio::sync::oneshot::Sender<Result<Vec<PageLayout>, String>>),
+}
+
+pub struct Thumbnails {
+    wake: Mutex<mpsc::Sender<Request>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Thumbnails {
+    // PDFium isn't safe to drive from several threads, so one thread owns it
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Self {
+        let (wake, woken) = mpsc::channel();
+        let last_error: Arc<Mutex<Option<String>>> = Arc::default();
+        let worker_error = last_error.clone();
+        std::thread::spawn(move || render_loop(handle, pool, woken, worker_error));
+        Thumbnails { wake: Mutex::new(wake), last_error }
+    }
+
+    // Checks for missing thumbnails now instead of at the next interval
+    pub fn wake(&self) {
+        if let Ok(wake) = self.wake.lock() {
+            let _ = wake.send(Request::Wake);
+        }
+    }
+
+    // Every page's text with word boxes. Served between thumbnails, so a long rendering
+    // pass doesn't hold it up.
+    pub async fn page_layout(&self, pdf_path: &Path) -> Result<Vec<PageLayout>, String> {
+        let (reply, response) = tokio::sync::oneshot::channel();
+        let sent = self
+            .wake
+            .lock()
+            .map_err(|e| e.to_string())?
+            .send(Request::Layout(pdf_path.to_path_buf(), reply));
+        let unavailable = || self.last_error().unwrap_or_else(|| "PDFium is not available".to_string());
+        if sent.is_err() {
+            return Err(unavailable());
+        }
+        response.await.map_err(|_| unavailable())?
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+}
+
+pub fn thumbnails_dir(handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| format!("Path resolve error: {}", e))?
+        .join("thumbnails");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+pub fn thumbnail_path(dir: &Path, paper_id: i64) -> PathBuf {
+    dir.join(format!("{}.png", paper_id))
+}
+
+// The cached thumbnail, if it has been rendered
+pub fn find(handle: &AppHandle, paper_id: i64) -> Result<Option<PathBuf>, String> {
+    let path = thumbnail_path(&thumbnails_dir(handle)?, paper_id);
+    Ok(path.is_file().then_some(path))
+}
+
+pub fn remove(handle: &AppHandle, paper_id: i64) {
+    if let Ok(Some(path)) = find(handle, paper_id) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn render_loop(
+    handle: AppHandle,
+    pool: SqlitePool,
+    woken: mpsc::Receiver<Request>,
+    last_error: Arc<Mutex<Option<String>>>,
+) {
+    let set_error = |error: Option<String>| {
+        if let Ok(mut last) = last_error.lock() {
+            *last = error;
+        }
+    };
+
+    let pdfium = match bind_pdfium(&handle) {
+        Ok(pdfium) => pdfium,
+        Err(e) => {
+            tracing::warn!("Thumbnails disabled: {}", e);
+            set_error(Some(e));
+            return;
+        }
+    };
+    // Files that failed once (encrypted, damaged) aren't retried until the next start
+    let mut failed: HashSet<i64> = HashSet::new();
+
+    loop {
+        match render_missing(&handle, &pool, &pdfium, &woken, &mut failed) {
+            Ok(()) => set_error(None),
+            Err(e) => {
+                tracing::warn!("Thumbnail generation failed: {}", e);
+                set_error(Some(e));
+            }
+        }
+
+        loop {
+            match woken.recv_timeout(CHECK_INTERVAL) {
+                Ok(Request::Layout(path, reply)) => {
+                    let _ = reply.send(read_layout(&pdfium, &path));
+                }
+                Ok(Request::Wake) | Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        // Several imports in a row only need one pass
+        serve_layouts(&pdfium, &woken);
+    }
+}
+
+// Answers layout requests that came in meanwhile; wake-ups are dropped, a pass is under way
+fn serve_layouts(pdfium: &Pdfium, woken: &mpsc::Receiver<Request>) {
+    while let Ok(request) = woken.try_recv() {
+        if let Request::Layout(path, reply) = request {
+            let _ = reply.send(read_layout(pdfium, &path));
+        }
+    }
+}
+
+fn render_missing(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    pdfium: &Pdfium,
+    woken: &mpsc::Receiver<Request>,
+    failed: &mut HashSet<i64>,
+) -> Result<(), String> {
+    let dir = thumbnails_dir(handle)?;
+    let papers = tauri::async_runtime::block_on(db::get_pdf_paths(pool))?;
+
+    for (paper_id, pdf_path) in papers {
+        serve_layouts(pdfium, woken);
+        let dest = thumbnail_path(&dir, paper_id);
+        if dest.is_file() || failed.contains(&paper_id) {
+            continue;
+        }
+
+        match render_first_page(pdfium, Path::new(&pdf_path), &dest) {
+            Ok(()) => {
+                let ready = ThumbnailReady { paper_id, path: dest.display().to_string() };
+                let _ = handle.emit("thumbnail:ready", &ready);
+            }
+            Err(e) => {
+                tracing::warn!("{}: {}", pdf_path, e);
+                failed.insert(paper_id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Prefers a PDFium library bundled in the app's resources, then one installed on the system
+fn bind_pdfium(handle: &AppHandle) -> Result<Pdfium, String> {
+    let bundled = handle
+        .path()
+        .resource_dir()
+        .ok()
+        .map(|dir| Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(&dir)));
+
+    let bindings = match bundled {
+        Some(Ok(bindings)) => bindings,
+        _ => Pdfium::bind_to_system_library().map_err(|e| format!("PDFium library not found: {}", e))?,
+    };
+    Ok(Pdfium::new(bindings))
+}
+
+fn render_first_page(pdfium: &Pdfium, pdf_path: &Path, dest: &Path) -> Result<(), String> {
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, None)
+        .map_err(|e| format!("Failed to open PDF: {}", e))?;
+    let page = document
+        .pages()
+        .first()
+        .map_err(|e| format!("Failed to read first page: {}", e))?;
+
+    let config = PdfRenderConfig::new()
+        .set_target_width(THUMBNAIL_WIDTH)
+        .set_maximum_height(MAX_HEIGHT);
+    let bitmap = page
+        .render_with_config(&config)
+        .map_err(|e| format!("Failed to render page: {}", e))?;
+
+    write_png(dest, bitmap.width() as u32, bitmap.height() as u32, &bitmap.as_rgba_bytes())
+}
+
+fn read_layout(pdfium: &Pdfium, pdf_path: &Path) -> Result<Vec<PageLayout>, String> {
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, None)
+        .map_err(|e| format!("Failed to open PDF: {}", e))?;
+
+    let mut pages = Vec::new();
+    for (index, page) in document.pages().iter().enumerate() {
+        let page_height = page.height().value as f64;
+        let mut text = String::new();
+        let mut words = Vec::new();
+
+        if let Ok(page_text) = page.text() {
+            // Chars pushed so far, and the word being built as (start, left, bottom, right, top)
+            let mut count = 0;
+            let mut word: Option<(usize, f32, f32, f32, f32)> = None;
+            let mut finish = |word: &mut Option<(usize, f32, f32, f32, f32)>, end: usize| {
+                if let Some((start, left, bottom, right, top)) = word.take() {
+                    words.push(WordBox {
+                        start,
+                        end,
+                        x: left as f64,
+                        y: page_height - top as f64,
+                        width: (right - left) as f64,
+                        height: (top - bottom) as f64,
+                    });
+                }
+            };
+
+            for ch in page_text.chars().iter() {
+                // PDFium ends lines with \r\n; one break is enough
+                let Some(c) = ch.unicode_char().filter(|&c| c != '\r') else {
+                    continue;
+                };
+                if c.is_whitespace() {
+                    finish(&mut word, count);
+                } else if let Ok(bounds) = ch.loose_bounds() {
+                    let (left, bottom, right, top) =
+                        (bounds.left().value, bounds.bottom().value, bounds.right().value, bounds.top().value);
+                    word = Some(match word {
+                        Some((start, l, b, r, t)) => (start, l.min(left), b.min(bottom), r.max(right), t.max(top)),
+                        None => (count, left, bottom, right, top),
+                    });
+                }
+                text.push(c);
+                count += 1;
+            }
+            finish(&mut word, count);
+        }
+
+        pages.push(PageLayout { page: index as i64 + 1, text, words });
+    }
+    Ok(pages)
+}
+
+// Written under a temporary name first so a half-written file is never served
+fn write_png(dest: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    let partial = dest.with_extension("png.part");
+    let file = fs::File::create(&partial).map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    fs::rename(&partial, dest).map_err(|e| format!("Failed to write thumbnail: {}", e))
+}
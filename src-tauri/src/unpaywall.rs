@@ -0,0 +1,53 @@
+// src-tauri/src/unpaywall.rs
+
+use crate::crossref::normalize_doi;
+use serde::Deserialize;
+
+const API_BASE: &str = "https://api.unpaywall.org/v2";
+
+#[derive(Deserialize)]
+struct DoiResponse {
+    #[serde(default)]
+    is_oa: bool,
+    best_oa_location: Option<Location>,
+    #[serde(default)]
+    oa_locations: Vec<Location>,
+}
+
+#[derive(Deserialize)]
+struct Location {
+    url_for_pdf: Option<String>,
+}
+
+// A legal open-access PDF of the work, if Unpaywall knows of one. Unpaywall refuses requests
+// that don't identify the caller by email.
+pub async fn find_pdf_url(client: &reqwest::Client, doi: &str, email: &str) -> Result<Option<String>, String> {
+    let doi = normalize_doi(doi).ok_or_else(|| format!("Not a DOI: {}", doi))?;
+
+    let response = client
+        .get(format!("{}/{}", API_BASE, doi))
+        .query(&[("email", email)])
+        .send()
+        .await
+        .map_err(|e| format!("Unpaywall request failed: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let record: DoiResponse = response
+        .error_for_status()
+        .map_err(|e| format!("Unpaywall request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Unpaywall response: {}", e))?;
+
+    if !record.is_oa {
+        return Ok(None);
+    }
+    // The best location is sometimes a landing page only, while a repository copy has the PDF
+    Ok(record
+        .best_oa_location
+        .into_iter()
+        .chain(record.oa_locations)
+        .find_map(|location| location.url_for_pdf))
+}
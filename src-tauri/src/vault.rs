@@ -0,0 +1,211 @@
+// src-tauri/src/vault.rs
+
+// Writes one Markdown file per paper into a folder, for Obsidian and other Markdown note
+// apps: metadata as YAML frontmatter, then the abstract, notes and highlights. A manifest in
+// the folder records what was written, so an incremental export only rewrites papers whose
+// file would change and can clean up after papers that were renamed or removed.
+
+use crate::db::annotations::{self, Annotation};
+use crate::db::authors::split_authors;
+use crate::db::tags::split_tags;
+use crate::db::{self, Paper};
+use crate::file_naming;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+const MANIFEST_NAME: &str = ".paper-master-vault.json";
+const MAX_STEM_CHARS: usize = 120;
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    // Paper id -> the file written for it
+    files: HashMap<i64, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ManifestEntry {
+    file: String,
+    // SHA-256 of the content as written
+    hash: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct VaultExportSummary {
+    pub written: usize,
+    pub unchanged: usize,
+    // Files of papers renamed or no longer in the library
+    pub removed: usize,
+    pub errors: Vec<String>,
+}
+
+// Papers in the Trash are left out. Without `incremental` every file is rewritten.
+pub async fn export_vault(pool: &SqlitePool, folder: &Path, incremental: bool) -> Result<VaultExportSummary, String> {
+    fs::create_dir_all(folder).map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+    let manifest_path = folder.join(MANIFEST_NAME);
+    let previous: Manifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let mut papers = db::get_all_papers(pool).await?;
+    // Oldest first, so the paper that had a file name first keeps it
+    papers.sort_by_key(|p| p.id);
+
+    let mut summary = VaultExportSummary::default();
+    let mut manifest = Manifest::default();
+    let mut used_names = HashSet::new();
+    for paper in &papers {
+        let highlights = annotations::get_annotations(pool, paper.id).await?;
+        let file = unique_file_name(paper, &mut used_names);
+        let content = render(paper, &highlights);
+        let hash = content_hash(&content);
+
+        let unchanged = incremental
+            && previous
+                .files
+                .get(&paper.id)
+                .is_some_and(|entry| entry.file == file && entry.hash == hash)
+            && folder.join(&file).is_file();
+        if unchanged {
+            summary.unchanged += 1;
+        } else if let Err(e) = fs::write(folder.join(&file), &content) {
+            summary.errors.push(format!("{}: {}", file, e));
+            // Keep the old entry so its file can still be cleaned up later
+            if let Some(entry) = previous.files.get(&paper.id) {
+                manifest.files.insert(paper.id, entry.clone());
+            }
+            continue;
+        } else {
+            summary.written += 1;
+        }
+        manifest.files.insert(paper.id, ManifestEntry { file, hash });
+    }
+
+    // Files left behind by renamed, trashed or deleted papers. One that was edited since
+    // it was written belongs to the user now and stays.
+    let current: HashSet<String> = manifest.files.values().map(|e| e.file.to_lowercase()).collect();
+    for entry in previous.files.values() {
+        if current.contains(&entry.file.to_lowercase()) {
+            continue;
+        }
+        let path = folder.join(&entry.file);
+        let untouched = fs::read_to_string(&path).is_ok_and(|content| content_hash(&content) == entry.hash);
+        if untouched {
+            match fs::remove_file(&path) {
+                Ok(()) => summary.removed += 1,
+                Err(e) => summary.errors.push(format!("{}: {}", entry.file, e)),
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to save export state: {}", e))?;
+    fs::write(&manifest_path, json).map_err(|e| format!("Failed to save export state: {}", e))?;
+
+    Ok(summary)
+}
+
+// The citation key, which citation plugins link notes by, or the title. Names are compared
+// ignoring case, as the file systems on Windows and macOS do.
+fn unique_file_name(paper: &Paper, used: &mut HashSet<String>) -> String {
+    let stem = paper.cite_key.clone().unwrap_or_else(|| paper.title.clone());
+    let stem: String = file_naming::sanitize(&stem).chars().take(MAX_STEM_CHARS).collect();
+    let stem = stem.trim_matches(|c: char| c.is_whitespace() || c == '.');
+    let stem = if stem.is_empty() { "Untitled" } else { stem };
+
+    let mut name = format!("{}.md", stem);
+    if used.contains(&name.to_lowercase()) {
+        name = format!("{} ({}).md", stem, paper.id);
+    }
+    used.insert(name.to_lowercase());
+    name
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+// JSON strings are valid YAML and escape everything that needs it
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn push_field(out: &mut String, name: &str, value: Option<&str>) {
+    if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+        out.push_str(&format!("{}: {}\n", name, yaml_string(value)));
+    }
+}
+
+fn push_list(out: &mut String, name: &str, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+    out.push_str(&format!("{}:\n", name));
+    for value in values {
+        out.push_str(&format!("  - {}\n", yaml_string(value)));
+    }
+}
+
+fn render(paper: &Paper, highlights: &[Annotation]) -> String {
+    let mut out = String::from("---\n");
+    push_field(&mut out, "title", Some(&paper.title));
+    push_list(&mut out, "authors", &split_authors(paper.authors.as_deref().unwrap_or_default()));
+    if let Some(year) = paper.year {
+        out.push_str(&format!("year: {}\n", year));
+    }
+    push_field(&mut out, "journal", paper.journal.as_deref());
+    push_field(&mut out, "doi", paper.doi.as_deref());
+    push_field(&mut out, "arxiv", paper.arxiv_id.as_deref());
+    push_field(&mut out, "pmid", paper.pmid.as_deref());
+    push_field(&mut out, "citekey", paper.cite_key.as_deref());
+    // Obsidian tags can't contain spaces
+    let tags: Vec<String> = split_tags(paper.tags.as_deref().unwrap_or_default())
+        .iter()
+        .map(|tag| tag.split_whitespace().collect::<Vec<_>>().join("-"))
+        .collect();
+    push_list(&mut out, "tags", &tags);
+    out.push_str(&format!("status: {}\n", serde_json::to_string(&paper.reading_status).unwrap_or_default()));
+    if let Some(rating) = paper.rating {
+        out.push_str(&format!("rating: {}\n", rating));
+    }
+    if paper.starred {
+        out.push_str("starred: true\n");
+    }
+    out.push_str(&format!("paper_id: {}\n", paper.id));
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# {}\n\n", paper.title));
+    if let Some(r#abstract) = paper.r#abstract.as_deref().filter(|a| !a.trim().is_empty()) {
+        out.push_str(&format!("## Abstract\n\n{}\n\n", r#abstract.trim()));
+    }
+    if let Some(notes) = paper.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        out.push_str(&format!("## Notes\n\n{}\n\n", notes.trim()));
+    }
+
+    let highlights: Vec<&Annotation> = highlights
+        .iter()
+        .filter(|a| [&a.selected_text, &a.comment].iter().any(|v| v.as_deref().is_some_and(|v| !v.trim().is_empty())))
+        .collect();
+    if !highlights.is_empty() {
+        out.push_str("## Highlights\n\n");
+        for highlight in highlights {
+            match highlight.selected_text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+                Some(text) => {
+                    for line in text.lines() {
+                        out.push_str(&format!("> {}\n", line));
+                    }
+                    out.push_str(&format!("> (p. {})\n\n", highlight.page));
+                }
+                None => out.push_str(&format!("(p. {})\n\n", highlight.page)),
+            }
+            if let Some(comment) = highlight.comment.as_deref().filter(|c| !c.trim().is_empty()) {
+                out.push_str(&format!("{}\n\n", comment.trim()));
+            }
+        }
+    }
+
+    format!("{}\n", out.trim_end())
+}
@@ -0,0 +1,127 @@
+// src-tauri/src/watcher.rs
+
+use crate::db::{self, FileMode, NewPaper};
+use crate::events;
+use crate::import::{self, DuplicatePolicy};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+// Browsers write downloads in chunks, so only import once a file has been quiet this long
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+pub struct FolderWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+}
+
+impl FolderWatcher {
+    // Starts the background import loop; folders are added with `watch`
+    pub fn start(handle: AppHandle, pool: SqlitePool) -> Result<Self, String> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Folder watcher error: {}", e),
+        })
+        .map_err(|e| format!("Failed to start folder watcher: {}", e))?;
+
+        tauri::async_runtime::spawn(import_loop(handle, pool, rx));
+
+        Ok(FolderWatcher {
+            watcher: Mutex::new(watcher),
+        })
+    }
+
+    pub fn watch(&self, path: &Path, recursive: bool) -> Result<(), String> {
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        self.watcher
+            .lock()
+            .map_err(|_| "Folder watcher is unavailable".to_string())?
+            .watch(path, mode)
+            .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))
+    }
+
+    pub fn unwatch(&self, path: &Path) -> Result<(), String> {
+        self.watcher
+            .lock()
+            .map_err(|_| "Folder watcher is unavailable".to_string())?
+            .unwatch(path)
+            .map_err(|e| format!("Failed to stop watching {}: {}", path.display(), e))
+    }
+}
+
+async fn import_loop(handle: AppHandle, pool: SqlitePool, mut rx: mpsc::UnboundedReceiver<PathBuf>) {
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    // Our own copies land here; never feed them back into the importer
+    let papers_dir = import::papers_dir(&handle).ok();
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        tokio::select! {
+            path = rx.recv() => match path {
+                Some(path) if is_pdf(&path) && !papers_dir.as_ref().is_some_and(|d| path.starts_with(d)) => {
+                    pending.insert(path, Instant::now());
+                }
+                Some(_) => {}
+                None => break,
+            },
+            _ = tick.tick() => {
+                let ready: Vec<PathBuf> = pending
+                    .iter()
+                    .filter(|(_, seen)| seen.elapsed() >= SETTLE_TIME)
+                    .map(|(path, _)| path.clone())
+                    .collect();
+
+                for path in ready {
+                    pending.remove(&path);
+                    if !path.is_file() {
+                        continue;
+                    }
+
+                    let known = NewPaper {
+                        file_mode: folder_file_mode(&pool, &path).await,
+                        ..Default::default()
+                    };
+                    match import::import_file(&handle, &pool, &path, known, DuplicatePolicy::Reject).await {
+                        Ok(imported) => {
+                            tracing::info!("Auto-imported {}", path.display());
+                            events::paper_added(&handle, &imported.paper);
+                        }
+                        Err(e) => tracing::warn!("Auto-import of {} skipped: {}", path.display(), e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The setting of the innermost watched folder containing `path`
+async fn folder_file_mode(pool: &SqlitePool, path: &Path) -> FileMode {
+    db::get_watched_folders(pool)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|folder| path.starts_with(&folder.path))
+        .max_by_key(|folder| folder.path.len())
+        .map(|folder| folder.file_mode)
+        .unwrap_or_default()
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))
+}
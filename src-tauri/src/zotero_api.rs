@@ -0,0 +1,201 @@
+// src-tauri/src/zotero_api.rs
+
+// Minimal client for the Zotero Web API v3 (https://www.zotero.org/support/dev/web_api/v3)
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+const API_BASE: &str = "https://api.zotero.org";
+const API_VERSION: &str = "3";
+// Zotero caps both page size and write batches
+const PAGE_SIZE: usize = 100;
+pub const WRITE_BATCH: usize = 50;
+
+#[derive(Deserialize, Clone)]
+pub struct ApiItem {
+    pub key: String,
+    pub version: i64,
+    pub data: Map<String, Value>,
+}
+
+impl ApiItem {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.data.get(name).and_then(Value::as_str).filter(|v| !v.trim().is_empty())
+    }
+
+    pub fn item_type(&self) -> &str {
+        self.field("itemType").unwrap_or_default()
+    }
+
+    // Trashed items still show up until the trash is emptied
+    pub fn is_trashed(&self) -> bool {
+        self.data.get("deleted").is_some_and(|d| d.as_bool() == Some(true) || d.as_i64() == Some(1))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct KeyInfo {
+    #[serde(rename = "userID")]
+    pub user_id: i64,
+    pub username: String,
+}
+
+#[derive(Deserialize)]
+struct WriteResponse {
+    #[serde(default)]
+    successful: HashMap<String, ApiItem>,
+    #[serde(default)]
+    failed: HashMap<String, WriteFailure>,
+}
+
+#[derive(Deserialize)]
+struct WriteFailure {
+    message: String,
+}
+
+// Outcome of a versioned write
+pub enum UpdateResult {
+    // The item's new version
+    Updated(i64),
+    // Someone else changed the item since the version we sent
+    Conflict,
+}
+
+pub async fn key_info(client: &reqwest::Client, api_key: &str) -> Result<KeyInfo, String> {
+    let response = client
+        .get(format!("{}/keys/current", API_BASE))
+        .header("Zotero-API-Version", API_VERSION)
+        .header("Zotero-API-Key", api_key)
+        .send()
+        .await
+        .map_err(|e| format!("Zotero request failed: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::FORBIDDEN || response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err("Zotero rejected the API key".to_string());
+    }
+
+    response
+        .error_for_status()
+        .map_err(|e| format!("Zotero request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected Zotero response: {}", e))
+}
+
+pub struct ZoteroApi<'a> {
+    client: &'a reqwest::Client,
+    user_id: i64,
+    api_key: &'a str,
+}
+
+impl<'a> ZoteroApi<'a> {
+    pub fn new(client: &'a reqwest::Client, user_id: i64, api_key: &'a str) -> Self {
+        ZoteroApi { client, user_id, api_key }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}/users/{}{}", API_BASE, self.user_id, path))
+            .header("Zotero-API-Version", API_VERSION)
+            .header("Zotero-API-Key", self.api_key)
+    }
+
+    // Every item changed after library version `since`, plus the library's current version
+    pub async fn items_since(&self, since: i64) -> Result<(Vec<ApiItem>, i64), String> {
+        let mut items: Vec<ApiItem> = Vec::new();
+        let mut library_version = since;
+
+        loop {
+            let response = self
+                .request(reqwest::Method::GET, "/items")
+                .query(&[
+                    ("since", since.to_string()),
+                    ("format", "json".to_string()),
+                    ("limit", PAGE_SIZE.to_string()),
+                    ("start", items.len().to_string()),
+                ])
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Zotero request failed: {}", e))?;
+
+            library_version = header_i64(&response, "Last-Modified-Version").unwrap_or(library_version);
+            let total = header_i64(&response, "Total-Results").unwrap_or_default();
+            let page: Vec<ApiItem> = response
+                .json()
+                .await
+                .map_err(|e| format!("Unexpected Zotero response: {}", e))?;
+
+            let done = page.len() < PAGE_SIZE || (items.len() + page.len()) as i64 >= total;
+            items.extend(page);
+            if done {
+                return Ok((items, library_version));
+            }
+        }
+    }
+
+    pub async fn get_item(&self, key: &str) -> Result<ApiItem, String> {
+        self.request(reqwest::Method::GET, &format!("/items/{}", key))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Zotero request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Unexpected Zotero response: {}", e))
+    }
+
+    // Creates up to WRITE_BATCH items; the results line up with `items`
+    pub async fn create_items(&self, items: &[Value]) -> Result<Vec<Result<ApiItem, String>>, String> {
+        let response: WriteResponse = self
+            .request(reqwest::Method::POST, "/items")
+            .json(items)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Zotero request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Unexpected Zotero response: {}", e))?;
+
+        let WriteResponse { mut successful, mut failed } = response;
+        Ok((0..items.len())
+            .map(|i| {
+                let index = i.to_string();
+                match successful.remove(&index) {
+                    Some(item) => Ok(item),
+                    None => Err(failed
+                        .remove(&index)
+                        .map_or_else(|| "Zotero did not save the item".to_string(), |f| f.message)),
+                }
+            })
+            .collect())
+    }
+
+    // Writes `changes` only if the item is still at `version`
+    pub async fn update_item(&self, key: &str, version: i64, changes: &Value) -> Result<UpdateResult, String> {
+        let response = self
+            .request(reqwest::Method::PATCH, &format!("/items/{}", key))
+            .header("If-Unmodified-Since-Version", version.to_string())
+            .json(changes)
+            .send()
+            .await
+            .map_err(|e| format!("Zotero request failed: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(UpdateResult::Conflict);
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| format!("Zotero request failed: {}", e))?;
+
+        header_i64(&response, "Last-Modified-Version")
+            .map(UpdateResult::Updated)
+            .ok_or_else(|| "Unexpected Zotero response: missing version".to_string())
+    }
+}
+
+fn header_i64(response: &reqwest::Response, name: &str) -> Option<i64> {
+    response.headers().get(name)?.to_str().ok()?.parse().ok()
+}
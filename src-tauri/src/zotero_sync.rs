@@ -0,0 +1,669 @@
+// src-tauri/src/zotero_sync.rs
+
+// Two-way sync with the user's Zotero library through the Web API.
+//
+// Pulls fetch everything changed since the library version of the last sync. Each
+// linked paper remembers the item version and a snapshot of its fields as of the last
+// sync; a paper that no longer matches its snapshot has been edited here. When both
+// sides changed, neither is overwritten and the link is flagged as a conflict until the
+// user picks a side. Pushes send the item version along, so Zotero refuses them if the
+// item changed in the meantime. PDFs and deletions are not synced.
+
+use crate::config::{self, ZoteroSyncSettings};
+use crate::db::zotero::ZoteroLink;
+use crate::db::{self, Paper, PaperUpdate};
+use crate::formats::{html, zotero};
+use crate::import;
+use crate::secrets;
+use crate::zotero_api::{ApiItem, UpdateResult, ZoteroApi, WRITE_BATCH};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const API_KEY_SECRET: &str = "zotero-api-key";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// Item type used for papers created from this app
+const NEW_ITEM_TYPE: &str = "journalArticle";
+
+#[derive(Serialize, Default, Clone)]
+pub struct SyncSummary {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub conflicts: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ZoteroSyncStatus {
+    pub enabled: bool,
+    // An API key has been saved; the key itself is never sent to the frontend
+    pub connected: bool,
+    pub username: Option<String>,
+    pub interval_minutes: u64,
+    pub library_version: i64,
+    pub last_synced_at: Option<String>,
+    // Papers waiting for resolve_zotero_conflict
+    pub conflicts: Vec<i64>,
+    pub syncing: bool,
+    pub last_error: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictSide {
+    Local,
+    Remote,
+}
+
+// The fields that are synced, as they were after the last sync
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct SyncedFields {
+    title: String,
+    authors: Option<String>,
+    journal: Option<String>,
+    year: Option<i64>,
+    doi: Option<String>,
+    r#abstract: Option<String>,
+    tags: Vec<String>,
+}
+
+impl SyncedFields {
+    fn from_paper(paper: &Paper) -> Self {
+        let mut tags = db::tags::split_tags(paper.tags.as_deref().unwrap_or_default());
+        tags.sort_by_key(|t| t.to_lowercase());
+        SyncedFields {
+            title: paper.title.clone(),
+            authors: paper.authors.clone(),
+            journal: paper.journal.clone(),
+            year: paper.year,
+            doi: paper.doi.clone(),
+            r#abstract: paper.r#abstract.clone(),
+            tags,
+        }
+    }
+
+    fn parse(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+pub struct ZoteroSync {
+    running: Arc<tokio::sync::Mutex<()>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl ZoteroSync {
+    // Settings are re-read from the config on every check, so changes apply without a restart
+    pub fn start(handle: AppHandle, pool: SqlitePool, client: reqwest::Client) -> Self {
+        if let Err(e) = move_key_to_keychain(&handle) {
+            tracing::warn!("Failed to move the Zotero API key to the keychain: {}", e);
+        }
+        let sync = ZoteroSync {
+            running: Arc::default(),
+            last_error: Arc::default(),
+        };
+        tauri::async_runtime::spawn(schedule_loop(
+            handle,
+            pool,
+            client,
+            sync.running.clone(),
+            sync.last_error.clone(),
+        ));
+        sync
+    }
+
+    pub async fn sync_now(
+        &self,
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        client: &reqwest::Client,
+    ) -> Result<SyncSummary, String> {
+        let _running = self
+            .running
+            .try_lock()
+            .map_err(|_| "A Zotero sync is already running".to_string())?;
+        run_sync(handle, pool, client, &self.last_error).await
+    }
+
+    pub async fn resolve_conflict(
+        &self,
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        client: &reqwest::Client,
+        paper_id: i64,
+        keep: ConflictSide,
+    ) -> Result<(), String> {
+        let _running = self.running.lock().await;
+        let settings = config::load(handle).zotero;
+        let (user_id, api_key) = credentials(&settings)?;
+        resolve(&ZoteroApi::new(client, user_id, &api_key), pool, paper_id, keep).await
+    }
+
+    pub async fn status(&self, handle: &AppHandle, pool: &SqlitePool) -> Result<ZoteroSyncStatus, String> {
+        let settings = config::load(handle).zotero;
+        let (library_version, last_synced_at) = match settings.user_id {
+            Some(user_id) => db::zotero::get_sync_state(pool, user_id).await?,
+            None => (0, None),
+        };
+        let conflicts = db::zotero::get_conflicts(pool).await?;
+
+        Ok(ZoteroSyncStatus {
+            enabled: settings.enabled,
+            connected: settings.has_api_key && settings.user_id.is_some(),
+            username: settings.username,
+            interval_minutes: settings.interval_minutes,
+            library_version,
+            last_synced_at,
+            conflicts: conflicts.into_iter().map(|link| link.paper_id).collect(),
+            syncing: self.running.try_lock().is_err(),
+            last_error: self.last_error.lock().ok().and_then(|e| e.clone()),
+        })
+    }
+}
+
+async fn schedule_loop(
+    handle: AppHandle,
+    pool: SqlitePool,
+    client: reqwest::Client,
+    running: Arc<tokio::sync::Mutex<()>>,
+    last_error: Arc<Mutex<Option<String>>>,
+) {
+    let start = tokio::time::Instant::now() + CHECK_INTERVAL;
+    let mut tick = tokio::time::interval_at(start, CHECK_INTERVAL);
+
+    loop {
+        tick.tick().await;
+
+        let settings = config::load(&handle).zotero;
+        let Some(user_id) = settings.user_id.filter(|_| settings.enabled && settings.has_api_key) else {
+            continue;
+        };
+
+        let last_synced_at = match db::zotero::get_sync_state(&pool, user_id).await {
+            Ok((_, last_synced_at)) => last_synced_at,
+            Err(e) => {
+                tracing::warn!("{}", e);
+                continue;
+            }
+        };
+        let due = last_synced_at
+            .and_then(|t| NaiveDateTime::parse_from_str(&t, "%Y-%m-%d %H:%M:%S").ok())
+            .is_none_or(|t| Utc::now() - t.and_utc() >= ChronoDuration::minutes(settings.interval_minutes as i64));
+        if !due {
+            continue;
+        }
+
+        // A manual sync is already taking care of it
+        let Ok(_running) = running.try_lock() else {
+            continue;
+        };
+        if let Err(e) = run_sync(&handle, &pool, &client, &last_error).await {
+            tracing::warn!("Zotero sync failed: {}", e);
+        }
+    }
+}
+
+async fn run_sync(
+    handle: &AppHandle,
+    pool: &SqlitePool,
+    client: &reqwest::Client,
+    last_error: &Mutex<Option<String>>,
+) -> Result<SyncSummary, String> {
+    let settings = config::load(handle).zotero;
+    let result = match credentials(&settings) {
+        Ok((user_id, api_key)) => sync_library(&ZoteroApi::new(client, user_id, &api_key), pool, user_id).await,
+        Err(e) => Err(e),
+    };
+
+    if let Ok(mut last_error) = last_error.lock() {
+        *last_error = result.as_ref().err().cloned();
+    }
+    if let Ok(summary) = &result {
+        let _ = handle.emit("zotero:synced", summary);
+    }
+    result
+}
+
+fn credentials(settings: &ZoteroSyncSettings) -> Result<(i64, String), String> {
+    let api_key = if settings.has_api_key { secrets::get(API_KEY_SECRET)? } else { None };
+    match (settings.user_id, api_key) {
+        (Some(user_id), Some(api_key)) => Ok((user_id, api_key)),
+        _ => Err("Zotero sync is not set up; add an API key first".to_string()),
+    }
+}
+
+// Older versions saved the key in the config file
+fn move_key_to_keychain(handle: &AppHandle) -> Result<(), String> {
+    let mut app_config = config::load(handle);
+    let Some(api_key) = app_config.zotero.api_key.take() else {
+        return Ok(());
+    };
+    secrets::set(API_KEY_SECRET, &api_key)?;
+    app_config.zotero.has_api_key = true;
+    config::save(handle, &app_config)
+}
+
+pub async fn sync_library(api: &ZoteroApi<'_>, pool: &SqlitePool, user_id: i64) -> Result<SyncSummary, String> {
+    let mut summary = SyncSummary::default();
+    let (since, _) = db::zotero::get_sync_state(pool, user_id).await?;
+
+    let library_version = pull(api, pool, since, &mut summary).await?;
+    push(api, pool, &mut summary).await?;
+
+    // Our own pushes come back on the next pull and are skipped by their version. Saving the
+    // version from before the push means edits made elsewhere meanwhile are not missed.
+    db::zotero::set_sync_state(pool, user_id, library_version).await?;
+    Ok(summary)
+}
+
+async fn pull(api: &ZoteroApi<'_>, pool: &SqlitePool, since: i64, summary: &mut SyncSummary) -> Result<i64, String> {
+    let (items, library_version) = api.items_since(since).await?;
+
+    // Notes are handled after their parents so a new item's note has something to attach to
+    let (notes, items): (Vec<ApiItem>, Vec<ApiItem>) = items
+        .into_iter()
+        .filter(|item| !item.is_trashed() && !matches!(item.item_type(), "attachment" | "annotation"))
+        .partition(|item| item.item_type() == "note");
+
+    for item in items.iter().chain(&notes) {
+        let result = if item.item_type() == "note" {
+            pull_note(pool, item, summary).await
+        } else {
+            pull_item(pool, item, summary).await
+        };
+        if let Err(e) = result {
+            summary.errors.push(format!("{}: {}", item.key, e));
+        }
+    }
+
+    Ok(library_version)
+}
+
+async fn pull_item(pool: &SqlitePool, item: &ApiItem, summary: &mut SyncSummary) -> Result<(), String> {
+    let remote = remote_paper(item);
+
+    let Some(mut link) = db::zotero::find_link(pool, &item.key).await? else {
+        if remote.title.trim().is_empty() {
+            return Err("item has no title".to_string());
+        }
+        // Papers imported from this library earlier are linked rather than duplicated
+        let paper_id = match db::zotero::find_unlinked_match(pool, remote.doi.as_deref(), &remote.title).await? {
+            Some(paper_id) => {
+                db::update_paper(pool, paper_id, &to_update(remote, false)).await?;
+                paper_id
+            }
+            None => import::add_record(pool, remote).await?.id,
+        };
+        let paper = db::get_paper(pool, paper_id).await?;
+        db::zotero::save_link(
+            pool,
+            &ZoteroLink {
+                paper_id,
+                item_key: item.key.clone(),
+                item_type: item.item_type().to_string(),
+                version: item.version,
+                synced_fields: SyncedFields::from_paper(&paper).to_json(),
+                note_key: None,
+                note_version: None,
+                synced_note: None,
+                conflict_version: None,
+                note_conflict_version: None,
+            },
+        )
+        .await?;
+        summary.pulled += 1;
+        return Ok(());
+    };
+
+    if item.version <= link.version {
+        return Ok(());
+    }
+
+    let paper = db::get_paper(pool, link.paper_id).await?;
+    let edited_here = SyncedFields::from_paper(&paper) != SyncedFields::parse(&link.synced_fields);
+    if link.conflict_version.is_some() || edited_here {
+        if link.conflict_version.is_none() {
+            summary.conflicts += 1;
+        }
+        link.conflict_version = Some(item.version);
+        return Ok(db::zotero::save_link(pool, &link).await?);
+    }
+
+    db::update_paper(pool, link.paper_id, &to_update(remote, true)).await?;
+    let paper = db::get_paper(pool, link.paper_id).await?;
+    link.version = item.version;
+    link.item_type = item.item_type().to_string();
+    link.synced_fields = SyncedFields::from_paper(&paper).to_json();
+    db::zotero::save_link(pool, &link).await?;
+    summary.pulled += 1;
+    Ok(())
+}
+
+// Only one child note per item is synced with the paper's notes: the first one seen
+async fn pull_note(pool: &SqlitePool, note: &ApiItem, summary: &mut SyncSummary) -> Result<(), String> {
+    // Standalone notes have no paper to go with
+    let Some(parent_key) = note.field("parentItem") else {
+        return Ok(());
+    };
+    let Some(mut link) = db::zotero::find_link(pool, parent_key).await? else {
+        return Ok(());
+    };
+    if link.note_key.as_ref().is_some_and(|key| *key != note.key) {
+        return Ok(());
+    }
+    if note.version <= link.note_version.unwrap_or_default() {
+        return Ok(());
+    }
+
+    let remote = html::to_markdown(note.field("note").unwrap_or_default());
+    let local = db::notes::get_notes(pool, link.paper_id).await?.markdown;
+    let edited_here = match (&link.note_key, &link.synced_note) {
+        (Some(_), synced) => local != synced.as_deref().unwrap_or_default(),
+        // First time this note is seen; local notes that say something else are a conflict
+        (None, _) => !local.trim().is_empty() && local != remote,
+    };
+
+    link.note_key = Some(note.key.clone());
+    if link.note_conflict_version.is_some() || edited_here {
+        if link.note_conflict_version.is_none() {
+            summary.conflicts += 1;
+        }
+        link.note_conflict_version = Some(note.version);
+        return Ok(db::zotero::save_link(pool, &link).await?);
+    }
+
+    db::notes::save_notes(pool, link.paper_id, &remote).await?;
+    link.note_version = Some(note.version);
+    link.synced_note = Some(remote);
+    db::zotero::save_link(pool, &link).await?;
+    summary.pulled += 1;
+    Ok(())
+}
+
+async fn push(api: &ZoteroApi<'_>, pool: &SqlitePool, summary: &mut SyncSummary) -> Result<(), String> {
+    let unlinked = db::zotero::get_unlinked_paper_ids(pool).await?;
+    for batch in unlinked.chunks(WRITE_BATCH) {
+        let papers = db::get_papers_by_ids(pool, batch).await?;
+        let payload: Vec<Value> = papers.iter().map(new_item).collect();
+        let results = api.create_items(&payload).await?;
+
+        for (paper, result) in papers.iter().zip(results) {
+            match result {
+                Ok(item) => {
+                    db::zotero::save_link(
+                        pool,
+                        &ZoteroLink {
+                            paper_id: paper.id,
+                            item_key: item.key,
+                            item_type: NEW_ITEM_TYPE.to_string(),
+                            version: item.version,
+                            synced_fields: SyncedFields::from_paper(paper).to_json(),
+                            note_key: None,
+                            note_version: None,
+                            synced_note: None,
+                            conflict_version: None,
+                            note_conflict_version: None,
+                        },
+                    )
+                    .await?;
+                    summary.pushed += 1;
+                }
+                Err(e) => summary.errors.push(format!("{}: {}", paper.title, e)),
+            }
+        }
+    }
+
+    // Edits to linked papers, including notes on the ones just created
+    for link in db::zotero::get_links(pool).await? {
+        if let Err(e) = push_changes(api, pool, link, summary).await {
+            summary.errors.push(e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn push_changes(
+    api: &ZoteroApi<'_>,
+    pool: &SqlitePool,
+    mut link: ZoteroLink,
+    summary: &mut SyncSummary,
+) -> Result<(), String> {
+    let paper = db::get_paper(pool, link.paper_id).await?;
+    let mut changed = false;
+
+    let current = SyncedFields::from_paper(&paper);
+    let synced = SyncedFields::parse(&link.synced_fields);
+    if link.conflict_version.is_none() && current != synced {
+        let changes = changed_fields(&link.item_type, &synced, &current);
+        // Edits to fields this item type doesn't have stay local
+        let result = if changes.as_object().is_some_and(|c| c.is_empty()) {
+            UpdateResult::Updated(link.version)
+        } else {
+            api.update_item(&link.item_key, link.version, &changes).await?
+        };
+        match result {
+            UpdateResult::Updated(version) => {
+                link.version = version;
+                link.synced_fields = current.to_json();
+                summary.pushed += 1;
+            }
+            UpdateResult::Conflict => {
+                link.conflict_version = Some(api.get_item(&link.item_key).await?.version);
+                summary.conflicts += 1;
+            }
+        }
+        changed = true;
+    }
+
+    let local_note = paper.notes.unwrap_or_default();
+    if link.note_conflict_version.is_none() && local_note != link.synced_note.clone().unwrap_or_default() {
+        let note_html = html::from_markdown(&local_note);
+        match (&link.note_key, link.note_version) {
+            (Some(note_key), Some(note_version)) => {
+                match api.update_item(note_key, note_version, &json!({ "note": note_html })).await? {
+                    UpdateResult::Updated(version) => {
+                        link.note_version = Some(version);
+                        link.synced_note = Some(local_note);
+                        summary.pushed += 1;
+                    }
+                    UpdateResult::Conflict => {
+                        link.note_conflict_version = Some(api.get_item(note_key).await?.version);
+                        summary.conflicts += 1;
+                    }
+                }
+                changed = true;
+            }
+            _ if !local_note.trim().is_empty() => {
+                let note = json!({
+                    "itemType": "note",
+                    "parentItem": link.item_key,
+                    "note": note_html,
+                    "tags": [],
+                    "collections": [],
+                    "relations": {},
+                });
+                let created = api.create_items(&[note]).await?.remove(0)?;
+                link.note_key = Some(created.key);
+                link.note_version = Some(created.version);
+                link.synced_note = Some(local_note);
+                summary.pushed += 1;
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+
+    if changed {
+        db::zotero::save_link(pool, &link).await?;
+    }
+    Ok(())
+}
+
+async fn resolve(api: &ZoteroApi<'_>, pool: &SqlitePool, paper_id: i64, keep: ConflictSide) -> Result<(), String> {
+    let mut link = db::zotero::get_link(pool, paper_id)
+        .await?
+        .filter(ZoteroLink::has_conflict)
+        .ok_or_else(|| format!("Paper {} has no Zotero conflict", paper_id))?;
+
+    match keep {
+        // Adopting the remote versions lets the next sync push over them
+        ConflictSide::Local => {
+            link.version = link.conflict_version.unwrap_or(link.version);
+            link.note_version = link.note_conflict_version.or(link.note_version);
+        }
+        ConflictSide::Remote => {
+            if link.conflict_version.is_some() {
+                let item = api.get_item(&link.item_key).await?;
+                db::update_paper(pool, paper_id, &to_update(remote_paper(&item), true)).await?;
+                let paper = db::get_paper(pool, paper_id).await?;
+                link.version = item.version;
+                link.synced_fields = SyncedFields::from_paper(&paper).to_json();
+            }
+            if let (Some(note_key), Some(_)) = (&link.note_key, link.note_conflict_version) {
+                let note = api.get_item(note_key).await?;
+                let markdown = html::to_markdown(note.field("note").unwrap_or_default());
+                db::notes::save_notes(pool, paper_id, &markdown).await?;
+                link.note_version = Some(note.version);
+                link.synced_note = Some(markdown);
+            }
+        }
+    }
+
+    link.conflict_version = None;
+    link.note_conflict_version = None;
+    Ok(db::zotero::save_link(pool, &link).await?)
+}
+
+fn remote_paper(item: &ApiItem) -> db::NewPaper {
+    let fields: HashMap<String, String> = item
+        .data
+        .iter()
+        .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+        .collect();
+
+    let creators: Vec<(bool, String)> = item
+        .data
+        .get("creators")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|c| {
+            let text = |name: &str| c.get(name).and_then(Value::as_str).unwrap_or_default().trim();
+            let name = match text("name") {
+                "" => format!("{} {}", text("firstName"), text("lastName")).trim().to_string(),
+                name => name.to_string(),
+            };
+            (text("creatorType") == "author", name)
+        })
+        .collect();
+
+    let tags: Vec<String> = item
+        .data
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t.get("tag").and_then(Value::as_str))
+        .map(|t| t.replace(',', " "))
+        .collect();
+
+    zotero::to_new_paper(&fields, creators, tags)
+}
+
+// `replace_tags` clears local tags the remote item doesn't have; off when linking a paper
+// that already existed here, so its tags aren't thrown away
+fn to_update(remote: db::NewPaper, replace_tags: bool) -> PaperUpdate {
+    PaperUpdate {
+        title: Some(remote.title).filter(|t| !t.trim().is_empty()),
+        authors: remote.authors,
+        journal: remote.journal,
+        year: remote.year,
+        tags: if replace_tags { Some(remote.tags.unwrap_or_default()) } else { remote.tags },
+        doi: remote.doi,
+        r#abstract: remote.r#abstract,
+        ..Default::default()
+    }
+}
+
+// Which Zotero field holds what we call the journal, by item type
+fn journal_field(item_type: &str) -> Option<&'static str> {
+    match item_type {
+        "journalArticle" | "magazineArticle" | "newspaperArticle" => Some("publicationTitle"),
+        "conferencePaper" => Some("proceedingsTitle"),
+        "bookSection" => Some("bookTitle"),
+        "webpage" => Some("websiteTitle"),
+        _ => None,
+    }
+}
+
+fn has_doi(item_type: &str) -> bool {
+    matches!(item_type, "journalArticle" | "conferencePaper" | "preprint")
+}
+
+fn creators(authors: Option<&str>) -> Value {
+    let creators: Vec<Value> = authors
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(|author| match author.rsplit_once(' ') {
+            Some((first, last)) => json!({ "creatorType": "author", "firstName": first, "lastName": last }),
+            None => json!({ "creatorType": "author", "name": author }),
+        })
+        .collect();
+    Value::Array(creators)
+}
+
+fn tag_list(tags: &[String]) -> Value {
+    Value::Array(tags.iter().map(|tag| json!({ "tag": tag })).collect())
+}
+
+fn new_item(paper: &Paper) -> Value {
+    let mut item = changed_fields(NEW_ITEM_TYPE, &SyncedFields::default(), &SyncedFields::from_paper(paper));
+    item["itemType"] = json!(NEW_ITEM_TYPE);
+    item
+}
+
+// Only the fields that differ are sent, so fields we don't model (full dates, extra
+// creators, ...) survive on the Zotero side unless the matching field was edited here
+fn changed_fields(item_type: &str, before: &SyncedFields, after: &SyncedFields) -> Value {
+    let mut changes = serde_json::Map::new();
+    let text = |value: &Option<String>| json!(value.clone().unwrap_or_default());
+
+    if before.title != after.title {
+        changes.insert("title".to_string(), json!(after.title));
+    }
+    if before.authors != after.authors {
+        changes.insert("creators".to_string(), creators(after.authors.as_deref()));
+    }
+    if before.journal != after.journal {
+        if let Some(field) = journal_field(item_type) {
+            changes.insert(field.to_string(), text(&after.journal));
+        }
+    }
+    if before.year != after.year {
+        changes.insert("date".to_string(), json!(after.year.map(|y| y.to_string()).unwrap_or_default()));
+    }
+    if before.doi != after.doi && has_doi(item_type) {
+        changes.insert("DOI".to_string(), text(&after.doi));
+    }
+    if before.r#abstract != after.r#abstract {
+        changes.insert("abstractNote".to_string(), text(&after.r#abstract));
+    }
+    if before.tags != after.tags {
+        changes.insert("tags".to_string(), tag_list(&after.tags));
+    }
+
+    Value::Object(changes)
+}
@@ -0,0 +1,11 @@
+// src/lib/errors.ts
+import type { AppError } from './types';
+
+export function isAppError(err: unknown): err is AppError {
+  return typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+}
+
+// The text to show for anything a command rejected with
+export function errorMessage(err: unknown): string {
+  return isAppError(err) ? err.message : String(err);
+}
@@ -2,6 +2,1059 @@
 export interface Paper {
   id: number;
   title: string;
+  authors: string | null;
+  journal: string | null;
+  // The canonical venue `journal` was matched to
+  venue_id: number | null;
+  year: number | null;
+  item_type: ItemType;
+  // Books
+  isbn: string | null;
+  publisher: string | null;
+  // The university of a thesis or the issuer of a report
+  institution: string | null;
   pdf_path: string;
+  // Comma separated tag names
+  tags: string | null;
+  notes: string | null;
+  doi: string | null;
+  arxiv_id: string | null;
+  pmid: string | null;
+  abstract: string | null;
+  // Comma separated, as given by the authors or publisher
+  keywords: string | null;
+  // BibTeX key, e.g. smith2021attention; stays fixed when metadata changes
+  cite_key: string | null;
+  file_hash: string | null;
+  page_count: number | null;
+  // Bytes
+  file_size: number | null;
+  pdf_version: string | null;
+  file_mode: FileMode;
+  reading_status: ReadingStatus;
+  last_read_page: number | null;
+  // Set when the viewer loads the PDF
+  last_opened_at: string | null;
+  open_count: number;
+  starred: boolean;
+  // 1-5, null when unrated
+  rating: number | null;
+  priority: Priority;
   created_at: string | null;
+  updated_at: string | null;
+  // Set while the paper is in the Trash
+  deleted_at: string | null;
+}
+
+export type ReadingStatus = "unread" | "reading" | "read";
+
+// "article" covers journal and conference papers alike
+export type ItemType = "article" | "book" | "thesis" | "preprint" | "report";
+
+// "stored": copied into app storage; "linked": the original file, left where it is
+export type FileMode = "stored" | "linked";
+
+export type Priority = "none" | "low" | "medium" | "high";
+
+export type SortBy =
+  | "added"
+  | "updated"
+  | "title"
+  | "year"
+  | "rating"
+  | "priority"
+  | "authors"
+  | "journal"
+  | "pages"
+  | "file_size";
+
+export interface SortKey {
+  field: SortBy;
+  // Omitted: text sorts A-Z, everything else newest/highest first
+  descending?: boolean;
+}
+
+export interface PaperFilter {
+  collection_id?: number;
+  // Every spelling of the venue, unlike `journal`
+  venue_id?: number;
+  status?: ReadingStatus;
+  item_type?: ItemType;
+  starred?: boolean;
+  // Papers must carry every one of these tags
+  tags?: string[];
+  year_from?: number;
+  year_to?: number;
+  journal?: string;
+  // Title/full-text words or part of an author name
+  query?: string;
+  // Advanced syntax, e.g. 'author:smith year:2019..2022 tag:transformers -tag:survey "attention"'
+  search?: string;
+  sort?: SortKey[];
+  limit?: number;
+  offset?: number;
+  // Lists the Trash instead; trashed papers are left out otherwise
+  trashed?: boolean;
+}
+
+export interface PaperUpdate {
+  title?: string;
+  authors?: string;
+  journal?: string;
+  year?: number;
+  item_type?: ItemType;
+  isbn?: string;
+  publisher?: string;
+  institution?: string;
+  tags?: string;
+  notes?: string;
+  doi?: string;
+  pmid?: string;
+  abstract?: string;
+  keywords?: string;
+}
+
+export interface BulkUpdate {
+  authors?: string;
+  journal?: string;
+  year?: number;
+  reading_status?: ReadingStatus;
+  priority?: Priority;
+  starred?: boolean;
+  rating?: number;
+  add_tags?: string;
+  remove_tags?: string;
+}
+
+export interface BulkUpdateResult {
+  paper_id: number;
+  paper: Paper | null;
+  error: string | null;
+}
+
+export interface WorkMetadata {
+  title: string;
+  authors: string | null;
+  journal: string | null;
+  year: number | null;
+  doi: string | null;
+  abstract: string | null;
+  keywords: string | null;
+  pdf_url: string | null;
+}
+
+// Identifier kinds are "doi", "arxiv", "pmid", or whatever a script provider declares
+export interface ProviderInfo {
+  name: string;
+  identifiers: string[];
+  can_search: boolean;
+  // Manifest of a script provider; null for the built-in ones
+  manifest: string | null;
+}
+
+export interface ProviderList {
+  providers: ProviderInfo[];
+  errors: string[];
+  directory: string | null;
+}
+
+export interface ImportedWork {
+  paper: Paper;
+  // Set when the PDF was asked for but couldn't be fetched
+  pdf_error: string | null;
+}
+
+export interface SearchResult {
+  id: number;
+  title: string;
+  authors: string | null;
+  year: number | null;
+  pdf_path: string;
+  snippet: string;
+  rank: number;
+  // Best hits in the PDF text; empty when only the title matched
+  matches: PageMatch[];
+  // Found by a title resembling the query rather than containing it; has no snippet
+  fuzzy: boolean;
+}
+
+export interface PageMatch {
+  // 1-based
+  page: number;
+  // Text around the hit, with the hit in <mark>
+  snippet: string;
+  // Where the hit is in the page's text, as JavaScript string indices
+  start: number;
+  end: number;
+}
+
+// In points from the page's top-left corner, like annotations
+export interface MatchRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface InPaperMatch extends PageMatch {
+  // One per line the hit spans
+  rects: MatchRect[];
+}
+
+export interface SimilarPaper {
+  paper: Paper;
+  // Cosine similarity, from 0 to 1
+  score: number;
+  // Word stems that weigh most in the match
+  terms: string[];
+}
+
+export interface SemanticStatus {
+  // Whether this build includes semantic search at all
+  available: boolean;
+  model_dir: string;
+  // Whether the model files are in model_dir
+  model_found: boolean;
+  indexed_papers: number;
+  indexing: boolean;
+}
+
+export interface SemanticMatch {
+  paper: Paper;
+  // Cosine similarity of the best chunk, from -1 to 1
+  score: number;
+  // The chunk that matched best
+  text: string;
+}
+
+// Payload of "semantic:progress"
+export interface SemanticProgress {
+  current: number;
+  total: number;
+  paper_id: number;
+  title: string;
+  error: string | null;
+}
+
+// Payload of "semantic:finished"
+export interface SemanticSummary {
+  indexed: number;
+  failed: number;
+  errors: string[];
+}
+
+export interface ImportSummary {
+  imported: number;
+  skipped: number;
+  errors: string[];
+  // Stopped by cancel_operation before every file was done
+  cancelled: boolean;
+}
+
+// CSV columns; import_csv reads title, authors, journal, year, item_type, isbn, publisher,
+// institution, doi, arxiv_id, pmid, abstract, tags, cite_key and pdf_path, the rest are
+// export-only
+export type CsvField =
+  | "id"
+  | "title"
+  | "authors"
+  | "journal"
+  | "year"
+  | "item_type"
+  | "isbn"
+  | "publisher"
+  | "institution"
+  | "doi"
+  | "arxiv_id"
+  | "pmid"
+  | "abstract"
+  | "tags"
+  | "cite_key"
+  | "notes"
+  | "reading_status"
+  | "starred"
+  | "rating"
+  | "priority"
+  | "pdf_path"
+  | "page_count"
+  | "created_at";
+
+export type DuplicatePolicy = "reject" | "allow";
+
+// import_library_json: what happens to papers already in the library (same DOI, arXiv id,
+// PMID or file hash). "update" takes the document's metadata and adds its tags,
+// collections and annotations; "duplicate" imports them again.
+export type MergeStrategy = "skip" | "update" | "duplicate";
+
+export interface WatchedFolder {
+  id: number;
+  path: string;
+  recursive: boolean;
+  // How PDFs that appear in the folder are imported
+  file_mode: FileMode;
+  created_at: string | null;
+}
+
+export interface ImportProgress {
+  current: number;
+  total: number;
+  path: string;
+  imported: number;
+  skipped: number;
+}
+
+export type DownloadStatus = "queued" | "active" | "retrying" | "done" | "failed" | "cancelled";
+
+// Returned by list_downloads and the payload of "download:progress"
+export interface DownloadInfo {
+  id: number;
+  url: string;
+  // What the download is for, e.g. a paper title or arXiv id
+  label: string;
+  status: DownloadStatus;
+  received: number;
+  // Unknown when the server doesn't send a length
+  total: number | null;
+  attempts: number;
+  error: string | null;
+}
+
+// Payload of "paper:deleted"; "paper:added" and "paper:updated" carry a Paper
+export interface PaperDeleted {
+  id: number;
+}
+
+export interface ThumbnailReady {
+  paper_id: number;
+  path: string;
+}
+
+// An image from a paper's PDF; "figures:ready" carries the paper id once a scan is done
+export interface FigureImage {
+  id: number;
+  paper_id: number;
+  page: number;
+  path: string;
+  // In pixels
+  width: number;
+  height: number;
+}
+
+export interface Collection {
+  id: number;
+  name: string;
+  parent_id: number | null;
+  paper_count: number;
+  created_at: string | null;
+}
+
+// Saved conditions of a smart collection; omitted fields don't narrow it
+export interface SmartQuery {
+  tags?: string[] | null;
+  year_from?: number | null;
+  year_to?: number | null;
+  query?: string | null;
+  status?: ReadingStatus | null;
+  starred?: boolean | null;
+  journal?: string | null;
+  collection_id?: number | null;
+}
+
+export interface SmartCollection {
+  id: number;
+  name: string;
+  query: SmartQuery;
+  paper_count: number;
+  created_at: string | null;
+}
+
+export interface SavedSearch {
+  id: number;
+  name: string;
+  // Advanced search syntax, as in PaperFilter.search
+  query: string;
+  // Papers matching right now, not counting the Trash
+  result_count: number;
+  created_at: string | null;
+}
+
+// Payload of the "saved_searches:counts" event, sent after the library changes
+export interface SavedSearchCount {
+  id: number;
+  result_count: number;
+}
+
+export interface Tag {
+  id: number;
+  name: string;
+  paper_count: number;
+  created_at: string | null;
+}
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'enum';
+
+// A field the library defines for itself; searchable as name:value
+export interface CustomField {
+  id: number;
+  name: string;
+  field_type: CustomFieldType;
+  // Allowed values of an enum field, empty otherwise
+  options: string[];
+  paper_count: number;
+}
+
+export interface CustomValue {
+  field_id: number;
+  name: string;
+  field_type: CustomFieldType;
+  // Dates as YYYY-MM-DD
+  value: string;
+}
+
+export interface TagSuggestion {
+  name: string;
+  // Relative to the paper's best keyword, from 0 to 1
+  score: number;
+  // Whether a tag with this name already exists in the library
+  existing: boolean;
+}
+
+export interface Author {
+  id: number;
+  name: string;
+  paper_count: number;
+  created_at: string | null;
+}
+
+export interface Venue {
+  id: number;
+  name: string;
+  abbreviation: string | null;
+  paper_count: number;
+  created_at: string | null;
+}
+
+export interface Annotation {
+  id: number;
+  paper_id: number;
+  page: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  selected_text: string | null;
+  color: string;
+  comment: string | null;
+  created_at: string | null;
+  updated_at: string | null;
+}
+
+export interface NewAnnotation {
+  paper_id: number;
+  page: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  selected_text?: string;
+  color?: string;
+  comment?: string;
+}
+
+export interface AnnotationUpdate {
+  page?: number;
+  x?: number;
+  y?: number;
+  width?: number;
+  height?: number;
+  color?: string;
+  comment?: string;
+}
+
+export interface Notes {
+  paper_id: number;
+  markdown: string;
+  updated_at: string | null;
+}
+
+export interface NoteRevision {
+  id: number;
+  paper_id: number;
+  markdown: string;
+  created_at: string | null;
+}
+
+export interface SchemaVersion {
+  version: number | null;
+  description: string | null;
+  installed_on: string | null;
+  latest: number;
+}
+
+export interface IndexHealth {
+  // Papers in the library, the Trash included
+  papers: number;
+  unindexed: number;
+  // Index rows of papers that no longer exist
+  orphaned: number;
+  // Papers with a PDF but no per-page text
+  missing_pages: number;
+  integrity_error: string | null;
+}
+
+export interface PoolStatus {
+  connections: number;
+  idle: number;
+  max_connections: number;
+  closed: boolean;
+}
+
+// Result of run_diagnostics; a check that couldn't run is null and explained in `problems`
+export interface Diagnostics {
+  app_version: string;
+  os: string;
+  database_path: string;
+  database_size: number | null;
+  schema: SchemaVersion | null;
+  pool: PoolStatus;
+  papers_dir: string | null;
+  papers_dir_writable: boolean;
+  // Bytes free on the disk holding the papers folder
+  free_space: number | null;
+  missing_files: number | null;
+  index: IndexHealth | null;
+  problems: string[];
+}
+
+export interface BackupInfo {
+  path: string;
+  papers: number;
+  size_bytes: number;
+}
+
+export interface BackupSchedule {
+  enabled: boolean;
+  folder: string | null;
+  interval_hours: number;
+  keep: number;
+}
+
+export interface FileNaming {
+  // Rename stored PDFs when their metadata is edited; off keeps original file names
+  rename_on_edit: boolean;
+  // Placeholders: {title}, {year}, {first_author}, {journal}, {cite_key}
+
+export type SummarizerProvider = "openai" | "ollama";
+
+export interface SummarizerSettings {
+  // Off unless turned on; paper text is sent to the endpoint
+  enabled: boolean;
+  provider: SummarizerProvider;
+  // Base URL; null uses the provider's usual one
+  endpoint: string | null;
+  model: string | null;
+}
+
+export type ProxyMode = "none" | "system" | "manual";
+
+export interface ProxySettings {
+  mode: ProxyMode;
+  // A host name, or a URL to pick the scheme
+  host: string | null;
+  port: number | null;
+  username: string | null;
+  // Comma separated hosts reached directly
+  no_proxy: string | null;
+}
+
+// Proxy and certificate changes take effect after a restart
+export interface NetworkSettings {
+  // Sent with Crossref requests for its polite pool; required for Unpaywall
+  contact_email: string | null;
+  proxy: ProxySettings;
+  // PEM file with extra root certificates
+  ca_certificate: string | null;
+}
+
+export interface NetworkStatus extends NetworkSettings {
+  // The password is kept in the system keychain and never sent to the frontend
+  proxy_password_set: boolean;
+}
+
+export interface SummarizerStatus extends SummarizerSettings {
+  // The key is kept in the system keychain and never sent to the frontend
+  api_key_set: boolean;
+}
+
+export interface Summary {
+  paper_id: number;
+  summary: string;
+  key_points: string[];
+  // The model that wrote it
+  model: string;
+  created_at: string | null;
+}
+  pattern: string;
+}
+
+export interface BackupStatus {
+  schedule: BackupSchedule;
+  last_backup_at: string | null;
+  last_backup_path: string | null;
+  next_backup_at: string | null;
+  last_error: string | null;
+}
+
+export interface MaintenanceSchedule {
+  enabled: boolean;
+  interval_days: number;
+}
+
+// Result of maintain_database, also the payload of "maintenance:finished"
+export interface MaintenanceReport {
+  finished_at: string;
+  // What the integrity check found; empty when the database is sound
+  integrity_errors: string[];
+  // A damaged database is only checked, not vacuumed
+  vacuumed: boolean;
+  size_before: number;
+  size_after: number;
+}
+
+export interface MaintenanceStatus {
+  schedule: MaintenanceSchedule;
+  last_run: MaintenanceReport | null;
+  next_run_at: string | null;
+  running: boolean;
+  last_error: string | null;
+}
+
+// The local endpoint browser extensions save papers through, on 127.0.0.1:<port>
+export interface CompanionSettings {
+  enabled: boolean;
+  port: number;
+}
+
+export interface CompanionStatus {
+  settings: CompanionSettings;
+  // For pasting into the extension; null until the endpoint is first turned on
+  token: string | null;
+  running: boolean;
+  last_error: string | null;
+}
+
+export type HookEvent = "paper_imported" | "paper_updated" | "paper_deleted" | "notes_saved";
+
+// `command` is run by the shell with the event as JSON on stdin
+export interface Hook {
+  event: HookEvent;
+  command: string;
+  enabled: boolean;
+}
+
+export interface HookOutput {
+  exit_code: number | null;
+  stdout: string;
+  stderr: string;
+}
+
+// Defaults for the import commands' file_mode and on_duplicate
+export interface ImportSettings {
+  file_mode: FileMode;
+  on_duplicate: DuplicatePolicy;
+}
+
+// The API key is kept in the system keychain and never sent; set it with set_zotero_sync
+export interface ZoteroSyncSettings {
+  enabled: boolean;
+  has_api_key: boolean;
+  user_id: number | null;
+  username: string | null;
+  interval_minutes: number;
+}
+
+// get_settings / update_settings; update_settings takes any part of it, and null resets a
+// field to its default. database_path, papers_dir and the Zotero account have their own
+// commands.
+export interface AppSettings {
+  database_path: string | null;
+  database_pool_size: number | null;
+  papers_dir: string | null;
+  import: ImportSettings;
+  backup: BackupSchedule;
+  zotero: ZoteroSyncSettings;
+  file_naming: FileNaming;
+  summarizer: SummarizerSettings;
+  network: NetworkSettings;
+  log_level: LogLevel;
+  maintenance: MaintenanceSchedule;
+  watch_clipboard: boolean;
+  companion: CompanionSettings;
+  hooks: Hook[];
+}
+
+export interface ZoteroSyncStatus {
+  enabled: boolean;
+  connected: boolean;
+  username: string | null;
+  interval_minutes: number;
+  library_version: number;
+  last_synced_at: string | null;
+  // Paper ids waiting for resolve_zotero_conflict
+  conflicts: number[];
+  syncing: boolean;
+  last_error: string | null;
+}
+
+export interface SyncSummary {
+  pulled: number;
+  pushed: number;
+  conflicts: number;
+  errors: string[];
+}
+
+export type ConflictSide = "local" | "remote";
+
+export type CitationFormat = "plain" | "html";
+
+// copy_citation: "reference" and "in_text" use a CSL style; "cite" is \cite{key}
+export type CopyFormat = "reference" | "in_text" | "bibtex" | "cite";
+
+export interface FormattedCitation {
+  // In-text form, e.g. "(Smith, 2021)" or "[1]"
+  citation: string;
+  // Reference list entry; null for styles without a bibliography
+  reference: string | null;
+}
+
+// .csl files the user added, usable as `style` by name
+export interface UserStyles {
+  directory: string;
+  styles: string[];
+}
+
+export interface CitationMetrics {
+  paper_id: number;
+  s2_paper_id: string;
+  citation_count: number;
+  influential_citation_count: number;
+  reference_count: number;
+  fetched_at: string | null;
+}
+
+export interface CitedWork {
+  s2_paper_id: string | null;
+  title: string;
+  authors: string | null;
+  year: number | null;
+  doi: string | null;
+  arxiv_id: string | null;
+}
+
+export interface CitationData {
+  // null until fetch_citations has run for the paper
+  metrics: CitationMetrics | null;
+  references: CitedWork[];
+  citations: CitedWork[];
+}
+
+export interface ExtractedReference {
+  id: number;
+  // The entry as printed in the PDF
+  raw: string;
+  title: string | null;
+  authors: string | null;
+  year: number | null;
+  doi: string | null;
+  arxiv_id: string | null;
+  // Library paper the entry was matched to by match_references
+  matched_paper_id: number | null;
+}
+
+export interface OcrText {
+  paper_id: number;
+  // Pages are separated by form feeds ("\f")
+  content: string;
+  // Tesseract language codes, e.g. "eng" or "deu+eng"
+  language: string;
+  page_count: number;
+  created_at: string | null;
+}
+
+export interface OcrProgress {
+  current: number;
+  total: number;
+  paper_id: number;
+  title: string;
+  error: string | null;
+}
+
+export interface OcrSummary {
+  processed: number;
+  failed: number;
+  errors: string[];
+}
+
+export type MatchStatus = "pending" | "applied" | "rejected" | "unmatched";
+
+// A Crossref work found by resolve_missing_metadata for a paper titled after its file;
+// get_metadata_matches lists the pending ones for review
+export interface MetadataMatch {
+  paper_id: number;
+  // The paper's current title, i.e. its file name
+  title: string;
+  pdf_path: string;
+  status: MatchStatus;
+  // 0 to 1
+  confidence: number | null;
+  metadata: WorkMetadata | null;
+  checked_at: string | null;
+}
+
+// Payload of "resolve:finished"
+export interface ResolveSummary {
+  applied: number;
+  queued: number;
+  unmatched: number;
+  failed: number;
+  errors: string[];
+}
+
+export type JobStatus = "queued" | "running" | "done" | "failed" | "cancelled" | "interrupted";
+
+// Returned by list_jobs and the payload of "job:progress"
+export interface Job {
+  id: number;
+  // e.g. "ocr" or "search_index"
+  kind: string;
+  label: string;
+  status: JobStatus;
+  current: number;
+  total: number | null;
+  // What the job is doing right now, then how it ended
+  message: string | null;
+  error: string | null;
+  created_at: string | null;
+  started_at: string | null;
+  finished_at: string | null;
+}
+
+export interface VaultExportSummary {
+  written: number;
+  unchanged: number;
+  // Files of papers renamed or no longer in the library
+  removed: number;
+  errors: string[];
+}
+
+export interface MissingFile {
+  paper_id: number;
+  title: string;
+  pdf_path: string;
+  file_mode: FileMode;
+}
+
+export interface RelinkSummary {
+  relinked: number;
+  still_missing: number;
+  errors: string[];
+}
+
+// An undoable entry in the operation journal
+export interface Operation {
+  id: number;
+  description: string;
+  created_at: string | null;
+}
+
+export interface ArxivUpdate {
+  paper_id: number;
+  title: string;
+  // The version the paper's PDF is, e.g. 1706.03762v5
+  current_id: string;
+  latest_id: string;
+}
+
+// An earlier PDF of a paper, kept when a newer version replaced it
+export interface PaperRevision {
+  id: number;
+  paper_id: number;
+  pdf_path: string;
+  file_hash: string | null;
+  // e.g. "arXiv 1706.03762v5"
+  label: string;
+  created_at: string | null;
+}
+
+export type LookupKind = "doi" | "arxiv";
+
+// Payload of "clipboard:identifier": a DOI or arXiv id just copied that isn't in the library;
+// import it with import_from_doi or import_from_arxiv
+export interface ClipboardIdentifier {
+  kind: LookupKind;
+  identifier: string;
+}
+
+// A metadata lookup waiting for the connection to come back
+export interface PendingLookup {
+  id: number;
+  paper_id: number;
+  kind: LookupKind;
+  identifier: string;
+  attempts: number;
+  last_error: string | null;
+  next_attempt_at: string | null;
+  created_at: string | null;
+}
+
+// Payload of "lookup:completed"; the filled-in paper arrives with "paper:updated"
+export interface LookupCompleted {
+  paper_id: number;
+  kind: LookupKind;
+}
+
+// Payload of "lookup:failed", sent when a queued lookup finally reached a service that
+// doesn't know the identifier
+export interface LookupFailed {
+  paper_id: number;
+  kind: LookupKind;
+  error: string;
+}
+
+export interface Feed {
+  id: number;
+  url: string;
+  title: string;
+  last_polled_at: string | null;
+  // Why the last poll failed
+  last_error: string | null;
+  // Entries neither imported nor dismissed
+  unread_count: number;
+  created_at: string | null;
+}
+
+export interface FeedEntry {
+  id: number;
+  feed_id: number;
+  title: string;
+  // Comma separated
+  authors: string | null;
+  link: string | null;
+  summary: string | null;
+  published: string | null;
+  doi: string | null;
+  arxiv_id: string | null;
+  // The library paper it was imported as
+  paper_id: number | null;
+  dismissed: boolean;
+  created_at: string | null;
+}
+
+// Payload of the "feeds:new_entries" event
+export interface NewFeedEntries {
+  feed_id: number;
+  count: number;
+}
+
+export type AppErrorKind =
+  | 'io'
+  | 'db'
+  | 'not_found'
+  | 'validation'
+  | 'network'
+  | 'conflict'
+  | 'cancelled'
+  | 'other';
+
+// What a failed command rejects with
+export interface AppError {
+  kind: AppErrorKind;
+  message: string;
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export interface YearCount {
+  year: number | null;
+  count: number;
+}
+
+export interface NamedCount {
+  name: string;
+  count: number;
+}
+
+// Result of get_library_stats; papers in the Trash only count towards `trashed`
+export interface LibraryStats {
+  papers: number;
+  with_pdf: number;
+  starred: number;
+  trashed: number;
+  unread: number;
+  reading: number;
+  read: number;
+  // Bytes of PDFs in the papers folder
+  stored_bytes: number;
+  // Bytes of linked files elsewhere on disk
+  linked_bytes: number;
+  per_year: YearCount[];
+  // Top 25 by paper count
+  per_journal: NamedCount[];
+  per_tag: NamedCount[];
+  // name is the month, e.g. "2026-01"
+  added_per_month: NamedCount[];
+}
+
+export interface ReadingSession {
+  id: number;
+  paper_id: number;
+  started_at: string;
+  // null while the paper is still being read
+  ended_at: string | null;
+}
+
+export interface PaperReadingTime {
+  paper_id: number;
+  title: string;
+  seconds: number;
+  sessions: number;
+  last_read_at: string;
+}
+
+export interface WeekReadingTime {
+  // Monday of the week, e.g. "2026-10-12"
+  week_start: string;
+  seconds: number;
+  papers: number;
+}
+
+export interface ReadingStats {
+  per_paper: PaperReadingTime[];
+  // Newest week first
+  per_week: WeekReadingTime[];
+}
+
+export type AnnotationFormat = 'markdown' | 'html';
+
+export interface OutlineItem {
+  title: string;
+  // 1-based; null when the entry points nowhere usable
+  page: number | null;
+  // Points from the top of the page, when the PDF gives one
+  y: number | null;
+  children: OutlineItem[];
+}
+
+export type ViewMode = 'single' | 'continuous' | 'two_page';
+
+export interface ViewState {
+  // 1-based
+  page: number;
+  // 1 is 100%; with `fit` set, the scale it last came to
+  zoom: number;
+  fit: 'width' | 'page' | null;
+  // Fractions from 0 to 1 of the scrollable height and width
+  scroll_top: number;
+  scroll_left: number;
+  view_mode: ViewMode;
 }